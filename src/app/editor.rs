@@ -0,0 +1,144 @@
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::cmd::quote;
+
+/// The user's editor: `$VISUAL`, then `$EDITOR`, then `vi`.
+pub fn editor() -> String {
+    ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// Open `path` in the terminal editor and wait for it to exit.
+///
+/// Runs through `sh` so editors configured with arguments (`code -w`) work.
+/// The caller must have left the TUI first.
+pub fn edit_path(path: &Path) -> std::io::Result<()> {
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$1\"", editor()))
+        .arg("sh")
+        .arg(path)
+        .status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("editor exited with {}", status)));
+    }
+    Ok(())
+}
+
+/// Open `file` at `line` in the terminal editor, from `dir`, and wait.
+pub fn edit_file_at(dir: &Path, file: &str, line: usize) -> std::io::Result<()> {
+    let editor = editor();
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} {}", editor, line_args(&editor, file, line)))
+        .current_dir(dir)
+        .status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("editor exited with {}", status)));
+    }
+    Ok(())
+}
+
+/// Shell arguments that open `file` at `line` in `editor`. Most editors take
+/// `+LINE`; VS Code and its forks want `-g file:line`, Helix, Zed and
+/// Sublime `file:line`.
+fn line_args(editor: &str, file: &str, line: usize) -> String {
+    let program = editor.split_whitespace().next().unwrap_or_default();
+    let name = Path::new(program).file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let at = quote(&format!("{}:{}", file, line));
+    match name {
+        "code" | "code-insiders" | "codium" | "cursor" => format!("-g {}", at),
+        "hx" | "helix" | "zed" | "subl" => at,
+        _ => format!("+{} {}", line, quote(file)),
+    }
+}
+
+/// Write `initial` to `path`, open it in the editor and return the saved text.
+pub fn edit_text(path: &Path, initial: &str) -> std::io::Result<String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, initial)?;
+    edit_path(path)?;
+    std::fs::read_to_string(path)
+}
+
+/// Run an interactive `command` (e.g. a git TUI) in `dir` and wait for it.
+/// The caller must have left the TUI first.
+pub fn run_in_dir(command: &str, dir: &Path) -> std::io::Result<()> {
+    let status = Command::new("sh").arg("-c").arg(command).current_dir(dir).status()?;
+    if !status.success() {
+        return Err(std::io::Error::other(format!("`{}` exited with {}", command, status)));
+    }
+    Ok(())
+}
+
+/// Expand an `editor_command` template for `path`. The path is shell-quoted
+/// and substituted for `{path}`, or appended when the placeholder is missing.
+pub fn command_line(template: &str, path: &str) -> String {
+    let quoted = quote(path);
+    if template.contains("{path}") {
+        template.replace("{path}", &quoted)
+    } else {
+        format!("{} {}", template.trim_end(), quoted)
+    }
+}
+
+/// Expand an `editor_command` template for a file at a line. `{line}` is
+/// replaced by the line number; templates without it open the file at the top.
+pub fn file_command_line(template: &str, file: &str, line: usize) -> String {
+    command_line(&template.replace("{line}", &line.to_string()), file)
+}
+
+/// Launch a GUI editor from `editor_command` on `path` without waiting.
+pub fn launch(template: &str, path: &str) -> std::io::Result<()> {
+    launch_in(&command_line(template, path), Path::new(path))
+}
+
+/// Run an expanded editor command line in `dir` without waiting.
+pub fn launch_in(command_line: &str, dir: &Path) -> std::io::Result<()> {
+    Command::new("sh")
+        .arg("-c")
+        .arg(command_line)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_line_quotes_path() {
+        assert_eq!(command_line("code {path}", "/wt/a b"), "code '/wt/a b'");
+        assert_eq!(command_line("zed", "/wt/it's"), r"zed '/wt/it'\''s'");
+        assert_eq!(
+            command_line("idea --wait {path}/src", "/wt"),
+            "idea --wait '/wt'/src"
+        );
+    }
+
+    #[test]
+    fn test_file_command_line_substitutes_line() {
+        assert_eq!(
+            file_command_line("code -g {path}:{line}", "src/a.rs", 12),
+            "code -g 'src/a.rs':12"
+        );
+        assert_eq!(file_command_line("zed", "src/a.rs", 12), "zed 'src/a.rs'");
+    }
+
+    #[test]
+    fn test_line_args_per_editor() {
+        assert_eq!(line_args("nvim", "src/a.rs", 7), "+7 'src/a.rs'");
+        assert_eq!(line_args("/usr/bin/code -w", "src/a.rs", 7), "-g 'src/a.rs:7'");
+        assert_eq!(line_args("hx", "src/a.rs", 7), "'src/a.rs:7'");
+    }
+}
@@ -7,17 +7,37 @@ pub fn help_text() -> String {
 Navigation:
   j/↓      Move down
   k/↑      Move up
-  Enter    Attach to session
-  Tab      Switch Preview/Diff
+  gg / G   First / last session (5G: the fifth)
+  PgUp/Dn  Page through the list, or the preview while scrolling
+  5j, 3K   Counts repeat a movement
+  Enter    Attach; start an exited agent again; resume paused; retry failed
+           (Diff tab: open the hunk's file in the editor)
+  Tab      Switch Preview/Diff/Activity (S-Tab back, 2 Tab: Diff)
+  R        Refresh diff now
 
 Session Management:
   n        New session
-  N        New session with prompt
-  d        Delete session
-  D        Kill session (force)
+  N        New session with prompt (empty title: from prompt)
+  b        New session from existing branch
+  S        New session like the selected one (program, options; fresh branch)
+  d        Delete session: y list only, c also close tmux + worktree (keeps branch)
+  D        Kill session (force): closes tmux, deletes worktree and branch
+  u        Undo last delete/kill
+  i        Session details (branch, worktree, disk usage)
+  C        Remove worktrees of paused sessions
+  e        Open worktree in editor
+  L        Run lazygit/git TUI in worktree
+  y / Y    Copy worktree path / branch name
+  v        Toggle board view (h/l moves between columns)
+  w        Switch project: all sessions, then each repo's
+  c        In the Diff tab: ask the agent for changes to the hunk picked with J/K
+  o        Open PR/branch in browser (URL copied)
   p        Pause/Resume session
-  P        Push & create PR
-  r        Restart session (options overlay)
+  P        Push & create PR (squash, agent-drafted description)
+  r        Restart session / retry a failed one / repair one
+           missing its worktree or branch
+  A        Toggle auto-yes for session
+  F        Toggle pushing a draft PR once the agent is done
   a        Attach to session
 
 Preview:
@@ -26,14 +46,209 @@ Preview:
   Esc      Reset scroll
 
 General:
+  s        Show scheduled prompts
+  T        Show slow external commands (git, tmux, gh)
+  t        Show teammates' sessions (team_repo)
   ?        Toggle help
   q        Quit
 
+Status (bottom right):
+  daemon ●/○   Daemon running or not: `gana daemon` / `gana stop-daemon`
+  auto-yes N   Sessions answering prompts on their own (A toggles)
+  ⬆ vX.Y.Z     A newer release: `gana update` installs it
+
 Version: {}",
         env!("CARGO_PKG_VERSION")
     )
 }
 
+/// Return the list of configured scheduled prompts.
+pub fn schedules_text(config: &crate::config::Config) -> String {
+    if config.schedules.is_empty() {
+        return "No scheduled prompts.\n\nAdd entries to \"schedules\" in ~/.gana/config.json;\nthe daemon sends them at the configured time.".to_string();
+    }
+    let mut text = String::new();
+    for schedule in &config.schedules {
+        text.push_str(&schedule.describe());
+        if schedule.time_of_day().is_none() {
+            text.push_str("  (invalid time, expected HH:MM)");
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Return the recent external commands that took at least
+/// `SLOW_COMMAND`, newest first, shown with `T`.
+pub fn slow_commands_text(timings: &[crate::cmd::trace::CmdTiming]) -> String {
+    if timings.is_empty() {
+        return format!(
+            "No command took {}ms or longer recently.\n\nEvery command's timing is logged at debug level (gana -v).",
+            crate::cmd::trace::SLOW_COMMAND.as_millis()
+        );
+    }
+    let mut text = String::new();
+    for timing in timings {
+        let status = match timing.exit_code {
+            Some(0) => String::new(),
+            Some(code) => format!("  (exit {})", code),
+            None => "  (no exit code)".to_string(),
+        };
+        text.push_str(&format!(
+            "{}  {:>6.1}s  {}{}\n",
+            timing.at.format("%H:%M:%S"),
+            timing.duration.as_secs_f64(),
+            timing.command,
+            status
+        ));
+    }
+    text
+}
+
+/// Return teammates' sessions from the team repo, shown with `t`.
+/// `error` is why the last sync failed.
+pub fn team_text(
+    config: &crate::config::Config,
+    members: &[crate::session::team::MemberSessions],
+    error: Option<&str>,
+) -> String {
+    if config.team_repo.is_none() {
+        return "No team repo configured.\n\nSet \"team_repo\" in ~/.gana/config.json to a git repo your team\ncan push to; everyone's sessions then show up here (read-only).".to_string();
+    }
+    let mut text = String::new();
+    if let Some(error) = error {
+        text.push_str(&format!("Last sync failed: {}\n\n", error));
+    }
+    if members.is_empty() {
+        text.push_str("No teammates have published sessions yet.");
+    }
+    for member in members {
+        text.push_str(&format!(
+            "{}  (as of {})\n",
+            member.member,
+            member.published_at.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M")
+        ));
+        if member.sessions.is_empty() {
+            text.push_str("  no sessions\n");
+        }
+        for session in &member.sessions {
+            let repo = session.repo.as_deref().map(|r| format!("  [{}]", r)).unwrap_or_default();
+            text.push_str(&format!(
+                "  {}  {}  {}  {}{}\n",
+                session.title, session.branch, session.status, session.program, repo
+            ));
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Return the details shown for a session with `i`.
+/// E.g. "35% CPU, 812.0 MB (limits: 200% CPU, 4096 MB)".
+fn resources_line(
+    resources: Option<&crate::session::resources::ResourceUsage>,
+    limits: Option<&crate::config::ResourceLimits>,
+) -> Option<String> {
+    use crate::session::disk_usage::format_size;
+
+    let limits = limits.map(|limits| {
+        [
+            limits.cpu_percent.map(|p| format!("{}% CPU", p)),
+            limits.memory_mb.map(|mb| format!("{} MB", mb)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+    });
+    let limits = limits.filter(|l| !l.is_empty());
+    match (resources, limits) {
+        (Some(r), Some(l)) => Some(format!(
+            "{:.0}% CPU, {} (limits: {})",
+            r.cpu_percent,
+            format_size(r.memory_bytes),
+            l
+        )),
+        (Some(r), None) => Some(format!("{:.0}% CPU, {}", r.cpu_percent, format_size(r.memory_bytes))),
+        (None, Some(l)) => Some(format!("limits: {}", l)),
+        (None, None) => None,
+    }
+}
+
+pub fn details_text(
+    instance: &crate::session::Instance,
+    usage: &crate::session::disk_usage::DiskUsage,
+    resources: Option<&crate::session::resources::ResourceUsage>,
+    limits: Option<&crate::config::ResourceLimits>,
+) -> String {
+    use crate::session::disk_usage::format_size;
+
+    let worktree = instance
+        .git_worktree
+        .as_ref()
+        .map(|wt| wt.worktree_path().to_string())
+        .unwrap_or_else(|| "-".to_string());
+    let disk = match usage.per_session.get(&instance.title) {
+        Some(bytes) => format_size(*bytes),
+        None if instance.status == crate::session::InstanceStatus::Paused => {
+            "none (paused)".to_string()
+        }
+        None => "not measured yet".to_string(),
+    };
+    let details = format!(
+        "Title:      {}\nStatus:     {}\nProgram:    {}\nBranch:     {}\nRepo:       {}\nWorktree:   {}\nDisk usage: {}\nCreated:    {}\n\nAll worktrees: {}",
+        instance.title,
+        instance.status,
+        instance.program,
+        if instance.branch.is_empty() { "-" } else { &instance.branch },
+        instance.path,
+        worktree,
+        disk,
+        instance.created_at.format("%Y-%m-%d %H:%M"),
+        format_size(usage.total),
+    );
+    let details = match &instance.container {
+        Some(container) => format!(
+            "{}\nContainer:  {} ({}, {})",
+            details, container.name, container.image, container.runtime
+        ),
+        None => details,
+    };
+    let details = match resources_line(resources, limits) {
+        Some(line) => format!("{}\nResources:  {}", details, line),
+        None => details,
+    };
+    let details = match &instance.test_status {
+        Some(crate::session::test_watch::TestStatus::Failed(output)) => {
+            format!("{}\n\nTests failing:\n{}", details, output)
+        }
+        _ => details,
+    };
+    let details = if instance.prompts.is_empty() {
+        details
+    } else {
+        let prompts: Vec<String> = instance
+            .prompts
+            .iter()
+            .map(|p| {
+                format!("{}  {}", p.sent_at.format("%Y-%m-%d %H:%M"), p.text.replace('\n', "\n  "))
+            })
+            .collect();
+        format!("{}\n\nPrompts sent:\n{}", details, prompts.join("\n"))
+    };
+    let details = match &instance.exit_output {
+        Some(output) => format!("{}\n\nLast output before the agent exited:\n{}", details, output),
+        None => details,
+    };
+    match &instance.error {
+        Some(error) if instance.status != crate::session::InstanceStatus::Failed => {
+            format!("{}\n\nNeeds repair:\n{}", details, error)
+        }
+        Some(error) => format!("{}\n\nError:\n{}\n\nPress r to retry.", details, error),
+        None => details,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -45,6 +260,103 @@ mod tests {
         assert!(text.contains(env!("CARGO_PKG_VERSION")));
     }
 
+    #[test]
+    fn test_details_text_shows_disk_usage() {
+        let mut instance = crate::session::Instance::new(crate::session::InstanceOptions {
+            title: "docs".to_string(),
+            path: "/repo".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        let mut usage = crate::session::disk_usage::DiskUsage::default();
+        assert!(details_text(&instance, &usage, None, None).contains("not measured yet"));
+
+        usage.per_session.insert("docs".to_string(), 3 * 1024 * 1024);
+        usage.total = 5 * 1024 * 1024;
+        let text = details_text(&instance, &usage, None, None);
+        assert!(text.contains("Disk usage: 3.0 MB"));
+        assert!(text.contains("All worktrees: 5.0 MB"));
+
+        usage.per_session.clear();
+        instance.status = crate::session::InstanceStatus::Paused;
+        assert!(details_text(&instance, &usage, None, None).contains("none (paused)"));
+    }
+
+    #[test]
+    fn test_details_text_lists_sent_prompts() {
+        let mut instance = crate::session::Instance::new(crate::session::InstanceOptions {
+            title: "docs".to_string(),
+            path: "/repo".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        let usage = crate::session::disk_usage::DiskUsage::default();
+        assert!(!details_text(&instance, &usage, None, None).contains("Prompts sent:"));
+
+        let sent_at = chrono::DateTime::parse_from_rfc3339("2026-03-01T09:30:00Z")
+            .unwrap()
+            .to_utc();
+        instance.prompts.push(crate::session::instance::SentPrompt {
+            text: "fix the docs\nand the README".to_string(),
+            sent_at,
+        });
+        let text = details_text(&instance, &usage, None, None);
+        assert!(text.contains("Prompts sent:\n2026-03-01 09:30  fix the docs\n  and the README"));
+    }
+
+    #[test]
+    fn test_details_text_shows_resources() {
+        let instance = crate::session::Instance::new(crate::session::InstanceOptions {
+            title: "docs".to_string(),
+            path: "/repo".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        let usage = crate::session::disk_usage::DiskUsage::default();
+        assert!(!details_text(&instance, &usage, None, None).contains("Resources:"));
+
+        let sampled = crate::session::resources::ResourceUsage {
+            cpu_percent: 35.2,
+            memory_bytes: 812 * 1024 * 1024,
+        };
+        let limits = crate::config::ResourceLimits {
+            cpu_percent: Some(200),
+            memory_mb: None,
+        };
+        let text = details_text(&instance, &usage, Some(&sampled), Some(&limits));
+        assert!(text.contains("Resources:  35% CPU, 812.0 MB (limits: 200% CPU)"), "{}", text);
+    }
+
+    #[test]
+    fn test_slow_commands_text() {
+        assert!(slow_commands_text(&[]).contains("No command took 500ms"));
+
+        let timing = crate::cmd::trace::CmdTiming {
+            at: chrono::Local::now(),
+            command: "git -C /wt diff".to_string(),
+            duration: std::time::Duration::from_millis(2500),
+            exit_code: Some(128),
+        };
+        let text = slow_commands_text(&[timing]);
+        assert!(text.contains("   2.5s  git -C /wt diff  (exit 128)"), "{}", text);
+    }
+
+    #[test]
+    fn test_schedules_text() {
+        let mut config = crate::config::Config::default();
+        assert!(schedules_text(&config).contains("No scheduled prompts"));
+
+        config.schedules.push(crate::config::schedule::Schedule {
+            time: "09:00".to_string(),
+            days: Vec::new(),
+            session: None,
+            prompt: "update the dependency PRs".to_string(),
+        });
+        let text = schedules_text(&config);
+        assert!(text.contains("09:00 daily"));
+        assert!(text.contains("update the dependency PRs"));
+    }
+
     #[test]
     fn test_help_text_contains_key_bindings() {
         let text = help_text();
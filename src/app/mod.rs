@@ -1,4 +1,7 @@
+mod clipboard;
+mod editor;
 pub mod help;
+mod poll;
 
 use crossterm::event::{self, Event, KeyCode, KeyEvent};
 use ratatui::prelude::*;
@@ -7,19 +10,39 @@ use std::sync::mpsc;
 use std::time::{Duration, Instant};
 
 use crate::cmd::{args, CmdExec, SystemCmdExec};
-use crate::config::Config;
-use crate::session::git::DiffStats;
-use crate::keys::{map_key, KeyAction};
-use crate::session::instance::{Instance, InstanceOptions, InstanceStatus};
+use crate::config::{Config, QuitBehavior};
+use crate::session::attention::AttentionWatcher;
+use crate::session::container::Container;
+use crate::session::disk_usage::DiskUsage;
+use crate::session::expiry::ExpiryPolicy;
+use crate::session::factory::{CreatedSession, CreationStep, SessionFactory};
+use crate::session::git::worktree_git::{CommitOptions, PushOptions, WorktreeSnapshot};
+use crate::session::git::util::GhStatus;
+use crate::session::git::{DiffStats, GitWorktree};
+use crate::keys::{KeyAction, KeyParser};
+use crate::session::instance::{Instance, InstanceOptions, InstanceStatus, Transition};
+use crate::session::journal::{EventKind, Journal};
+use crate::session::reconcile;
 use crate::session::storage::{FileStorage, InstanceStorage};
+use crate::session::team::{MemberSessions, TeamStore};
+use crate::session::test_watch::{TestStatus, TestWatcher};
+use crate::session::tmux::{AgentState, PaneTails, TmuxSession};
+use crate::ui::activity::ActivityView;
+use crate::ui::board::BoardView;
 use crate::ui::diff::DiffView;
 use crate::ui::err::ErrorDisplay;
 use crate::ui::list::ListPane;
-use crate::ui::menu::MenuBar;
-use crate::ui::overlay::{centered_rect, ConfirmationOverlay, TextInputOverlay, TextOverlay};
+use crate::ui::menu::{MenuBar, StatusSegment};
+use crate::ui::overlay::{
+    centered_rect, BranchPickerOverlay, Choice, ConfirmButton, ConfirmationOverlay, PushOverlay,
+    TextInputOverlay, TextOverlay,
+};
+use crate::ui::overlay::text_input::PROMPT_MAX_LEN;
 use crate::ui::preview::PreviewPane;
 use crate::ui::tabbed_window::{Tab, TabbedWindow};
 
+use poll::{PollSchedule, TickRate};
+
 /// Application state machine states.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum AppState {
@@ -28,6 +51,8 @@ enum AppState {
     Confirm,
     Help,
     Restart,
+    BranchPicker,
+    Push,
 }
 
 /// Signal from handle_key that the caller needs to perform an action
@@ -35,24 +60,223 @@ enum AppState {
 enum AppAction {
     None,
     AttachSession(usize),
+    /// Open a worktree in the terminal editor.
+    EditWorktree(String),
+    /// Open a file of a worktree at a line in the terminal editor.
+    EditFile { dir: String, file: String, line: usize },
+    /// Run the git TUI in a worktree.
+    GitTui(String),
 }
 
 /// Background update messages from worker threads.
 enum BackgroundUpdate {
-    PreviewContent(usize, String),
-    DiffComputed(usize, DiffStats),
-    InstanceReady(usize, crate::session::git::GitWorktree),
-    InstanceFailed(usize, String),
-    SessionDied(usize),
-    SessionRestarted(usize),
+    PreviewContent(String, String),
+    DiffComputed(String, DiffStats),
+    // Creation and restart results are keyed by session title: the
+    // index can shift while the worker runs.
+    InstanceReady(String, Box<CreatedSession>),
+    /// A creation worker started another step.
+    CreationProgress(String, CreationStep),
+    InstanceFailed(String, String),
+    SessionDied(String),
+    /// The last lines of a running session's pane, kept in case it dies.
+    PaneTail(String, String),
+    /// What a running session's agent is doing, by its pane.
+    AgentState(String, AgentState),
+    /// A running session's pane was captured, at this Unix second, and
+    /// whether that included the preview.
+    PaneCaptured(String, u64, bool),
+    /// The push remote and `gh` status were looked up for the push options
+    /// of a session.
+    PushChecked(String, String, GhStatus),
+    /// Whether the repo has uncommitted changes, for a session about to be
+    /// created: its title and prompt.
+    DirtyChecked(String, String, bool),
+    /// The repo's local and checked-out branches were listed for the title
+    /// input with this heading.
+    BranchesListed(String, Vec<String>, Vec<(String, String)>),
+    /// A session's PR or branch was opened in the browser: its URL.
+    BrowserOpened(Result<String, String>),
+    /// A running session's agent was measured for its details.
+    ResourcesSampled(String, crate::session::resources::ResourceUsage),
+    /// A session was started again in its worktree, in this tmux session.
+    SessionRestarted(String, Box<crate::session::tmux::TmuxSession>),
+    /// Startup reconnect finished; `None` when the tmux session is gone.
+    SessionRestored(String, Option<Box<crate::session::tmux::TmuxSession>>),
+    /// A loaded session lost its worktree or branch: its new status and why.
+    SessionReconciled(String, InstanceStatus, String),
+    DiskUsage(DiskUsage),
+    /// The agent's PR description for a session waiting to push.
+    PrBodyDrafted(String, Result<String, String>),
+    /// A title generated for an untitled session, with its prompt.
+    TitleGenerated(String, Result<String, String>),
+    /// A `test_command` run finished in a session's worktree.
+    TestsFinished(String, TestStatus),
+    /// A done session's `test_command` run before it is pushed on its own.
+    AutoPushChecked(String, TestStatus),
+    /// Titles of running sessions idle long enough to pause.
+    ExpiryChecked(Vec<String>),
+    /// Titles of running sessions whose agent waits for input.
+    AttentionChecked(std::collections::HashSet<String>),
+    /// A background pause, or worktree cleanup before archiving, finished;
+    /// carries the paused copy of the session.
+    PauseCompleted(Box<Instance>, Result<(), String>),
+    /// A background resume finished; carries the resumed copy of the session.
+    ResumeCompleted(Box<Instance>, Result<(), String>),
+    /// A started session's trust prompt was answered (`true`) or never
+    /// showed.
+    TrustAnswered(String, Result<bool, String>),
+    /// A background kill finished, with the worktree and the snapshot taken
+    /// for undo.
+    KillCompleted(Box<KilledSession>, Result<(), String>),
+    /// A push finished: the request, with the URL to open a PR at for
+    /// plain git pushes.
+    PushFinished(PushRequest, Result<Option<String>, String>),
+    /// Notes of the release installed since the last launch.
+    ReleaseNotes(String, Result<String, String>),
+    /// Teammates' sessions, after publishing ours to the team repo.
+    TeamSynced(Result<Vec<MemberSessions>, String>),
+}
+
+/// Scrollback lines fetched from tmux at a time in scroll mode.
+const SCROLLBACK_CHUNK: usize = 500;
+
+/// How long to wait for an agent to draft a PR description.
+const PR_DRAFT_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Quiet period after a worktree change before tests are rerun.
+const TEST_DEBOUNCE: Duration = Duration::from_secs(2);
+
+/// How often pending pipeline steps check on their upstream session.
+const PIPELINE_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often the board re-checks which agents wait for input.
+const ATTENTION_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// How often the menu bar's daemon and update status is refreshed.
+const STATUS_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Events kept in the Activity tab.
+const ACTIVITY_EVENTS: usize = 200;
+
+/// How often idle and long-paused sessions are looked for.
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(300);
+
+/// How often worktree disk usage is re-measured.
+const DISK_SCAN_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How often sessions are exchanged with the team repo.
+const TEAM_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Sessions moved by PgUp/PgDn in the list.
+const LIST_PAGE: usize = 10;
+
+/// Largest count a movement key is repeated by.
+const MAX_REPEAT: usize = 500;
+
+/// Number of deleted/killed sessions kept for undo.
+const UNDO_LIMIT: usize = 5;
+
+/// A removed session that can be brought back with `u`.
+enum UndoEntry {
+    /// Removed from the list only; tmux session and worktree untouched.
+    Deleted { instance: Instance, index: usize },
+    /// Killed: tmux, worktree and branch were cleaned up.
+    Killed {
+        instance: Instance,
+        index: usize,
+        worktree: Option<Box<GitWorktree>>,
+        snapshot: Option<WorktreeSnapshot>,
+    },
+}
+
+/// A session killed on a background thread, with what undo needs.
+struct KilledSession {
+    instance: Instance,
+    worktree: Option<GitWorktree>,
+    snapshot: Option<WorktreeSnapshot>,
+}
+
+/// A confirmed push, kept while the agent drafts the PR description.
+struct PushRequest {
+    title: String,
+    squash: Option<String>,
+    push: PushOptions,
+    /// Open a draft PR if `gh` is ready, else push with plain git; `gh` is
+    /// checked on the push's worker thread, as it asks GitHub.
+    by_gh_status: bool,
+}
+
+/// A running session due for a poll, checked on the tick's tmux thread.
+struct PaneCheck {
+    title: String,
+    /// Capture the preview too: the session is selected.
+    capture: bool,
+    /// When the pane was last captured, if it has been.
+    captured_at: Option<u64>,
+    prompts: crate::config::PromptPatterns,
+}
+
+impl PaneCheck {
+    /// Report the session dead if tmux doesn't list it, otherwise capture
+    /// its pane unless nothing was printed since the last capture.
+    fn run(
+        self,
+        activity: &std::collections::HashMap<String, u64>,
+        cmd: &dyn CmdExec,
+        sender: &mpsc::Sender<BackgroundUpdate>,
+    ) {
+        let sanitized = crate::session::tmux::sanitize_name(&self.title);
+        let Some(&active_at) = activity.get(&sanitized) else {
+            let _ = sender.send(BackgroundUpdate::SessionDied(self.title));
+            return;
+        };
+        // Activity has one-second resolution: output in the second of the
+        // last capture may have come after it, so that one isn't skipped
+        if self.captured_at.is_some_and(|at| active_at < at) {
+            return;
+        }
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        // The selected session's preview keeps its colors; the text is
+        // read from the same capture
+        let flags = if self.capture { "-peJ" } else { "-pJ" };
+        let Ok(content) = cmd.output("tmux", &args(&["capture-pane", flags, "-t", &sanitized]))
+        else {
+            return;
+        };
+        let text = if self.capture {
+            let text = crate::ui::preview::strip_ansi(&content);
+            let _ = sender.send(BackgroundUpdate::PreviewContent(self.title.clone(), content));
+            text
+        } else {
+            content
+        };
+
+        // Keep the pane's last lines: once the session is gone they
+        // can't be captured, and they usually say why it exited
+        if let Some(state) = crate::session::tmux::agent_state(&text, &self.prompts) {
+            let _ = sender.send(BackgroundUpdate::AgentState(self.title.clone(), state));
+        }
+        let tail = crate::session::tmux::last_lines(&text, crate::session::tmux::EXIT_OUTPUT_LINES);
+        let _ = sender.send(BackgroundUpdate::PaneTail(self.title.clone(), tail));
+        let _ = sender.send(BackgroundUpdate::PaneCaptured(self.title, now, self.capture));
+    }
 }
 
 /// Action pending confirmation.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 enum PendingAction {
-    KillSession(usize),
-    DeleteSession(usize),
-    PushSession(usize),
+    Kill(usize),
+    Delete(usize),
+    /// Remove the on-disk worktrees of paused sessions.
+    CleanupWorktrees,
+    /// Quit; confirming pauses running sessions first.
+    Quit,
+    /// Create a session from a dirty repo; confirming carries the changes over.
+    Create { title: String, prompt: String },
 }
 
 pub struct App {
@@ -60,36 +284,108 @@ pub struct App {
     state: AppState,
     instances: Vec<Instance>,
     running: bool,
+    // Titles of the sessions as last loaded or saved; stored ones not in
+    // here were added by someone else, e.g. `gana new`
+    known_titles: std::collections::HashSet<String>,
 
     // Config
     config: Config,
     config_dir: std::path::PathBuf,
+    // Runs the commands of work done on background threads; a mock in tests
+    cmd: std::sync::Arc<dyn CmdExec>,
+    // Root of the repo whose sessions are shown; all sessions when `None`
+    project: Option<std::path::PathBuf>,
 
     // UI components
     list: ListPane,
     preview: PreviewPane,
     diff_view: DiffView,
+    activity: ActivityView,
+    // Board view (v) in place of the list and preview
+    board: BoardView,
+    show_board: bool,
+    waiting: std::collections::HashSet<String>,
+    journal: Journal,
     tabbed_window: TabbedWindow,
     menu: MenuBar,
     error: ErrorDisplay,
+    // Counts (5j) and gg typed in the default state
+    keys: KeyParser,
 
     // Overlays
     confirmation: Option<ConfirmationOverlay>,
     text_input: Option<TextInputOverlay>,
     help_overlay: Option<TextOverlay>,
+    /// Session whose details the help overlay shows, while it does.
+    details_for: Option<String>,
     restart_overlay: Option<crate::ui::overlay::RestartOverlay>,
     restart_idx: Option<usize>,
+    branch_picker: Option<BranchPickerOverlay>,
+    /// The repo's remotes, listed with the branches in the picker to tell
+    /// remote-tracking ones apart.
+    branch_remotes: Vec<String>,
+    push_overlay: Option<PushOverlay>,
+
+    // Pushes waiting for a drafted PR description, and a draft to edit
+    pending_pushes: std::collections::HashMap<String, PushRequest>,
+    pr_body_to_edit: Option<(PushRequest, String)>,
 
     // Pending action after confirmation
     pending_action: Option<PendingAction>,
+    undo_buffer: std::collections::VecDeque<UndoEntry>,
+
+    // Titles of sessions whose worktree + tmux setup is in flight, with the
+    // step it's at once known
+    creating: std::collections::HashSet<String>,
+    /// Titles of sessions whose diff is being computed.
+    diffing: std::collections::HashSet<String>,
+    creation_steps: std::collections::HashMap<String, CreationStep>,
+
+    // Titles of started sessions still watched for a trust prompt
+    answering_trust: std::collections::HashSet<String>,
+
+    // Worktree disk usage from the last background scan
+    disk_usage: DiskUsage,
+    disk_scan_in_flight: bool,
+    expiry_check_in_flight: bool,
+    budget_warned: bool,
+
+    // Teammates' sessions from the last `team_repo` sync, and why it failed
+    team: Vec<MemberSessions>,
+    team_error: Option<String>,
+    team_sync_in_flight: bool,
+
+    // Worktree watching for `watch_tests`: titles arrive on `test_changes`
+    // and run once quiet for TEST_DEBOUNCE
+    test_watcher: TestWatcher,
+    test_changes: mpsc::Receiver<String>,
+    tests_due: std::collections::HashMap<String, Instant>,
+    tests_running: std::collections::HashSet<String>,
+
+    // When each running session's preview and diff are refreshed next;
+    // `polled_selection` is the selected session as of the last round
+    poll: PollSchedule,
+    tick_rate: TickRate,
+    polled_selection: Option<String>,
+    // Last lines of each polled session's pane, by title
+    pane_tails: PaneTails,
+    /// When each running session's pane was last captured, in Unix
+    /// seconds; it isn't captured again until tmux reports new output.
+    pane_activity: std::collections::HashMap<String, u64>,
+    // Sessions whose agent was seen working since it last went idle; the
+    // next idle pane marks them done
+    agents_working: std::collections::HashSet<String>,
+
+    // Change request being written for a diff hunk: (session title, hunk reference)
+    review_target: Option<(String, String)>,
+
+    // Session whose setup the title being typed will copy (`S`)
+    like_target: Option<String>,
 
     // Prompt flow state (N key: new session with initial prompt)
     creating_with_prompt: bool,
     pending_instance_title: Option<String>,
 
-    // Prompts waiting for async session creation to complete
-    pending_prompts: std::collections::HashMap<usize, String>,
-
     // Background update channels (async tick to prevent TUI freezing)
     bg_sender: mpsc::Sender<BackgroundUpdate>,
     bg_receiver: mpsc::Receiver<BackgroundUpdate>,
@@ -99,27 +395,73 @@ impl App {
     /// Create a new App with real config.
     pub fn new(config: Config, config_dir: std::path::PathBuf) -> Self {
         let (bg_sender, bg_receiver) = mpsc::channel();
+        let mut preview = PreviewPane::new();
+        preview.set_max_scrollback(config.scrollback_lines);
+        let journal = Journal::new(&config_dir);
+        let (test_sender, test_changes) = mpsc::channel();
+        let poll = PollSchedule::new(
+            Duration::from_millis(config.preview_poll_interval),
+            Duration::from_millis(config.background_poll_interval),
+        );
         Self {
             state: AppState::Default,
             instances: Vec::new(),
             running: true,
+            known_titles: std::collections::HashSet::new(),
             config,
             config_dir,
+            cmd: std::sync::Arc::new(SystemCmdExec),
+            project: None,
             list: ListPane::new(),
-            preview: PreviewPane::new(),
+            preview,
             diff_view: DiffView::new(),
+            activity: ActivityView::new(),
+            board: BoardView::new(),
+            show_board: false,
+            waiting: std::collections::HashSet::new(),
+            journal,
             tabbed_window: TabbedWindow::new(),
             menu: MenuBar::new(),
             error: ErrorDisplay::new(),
+            keys: KeyParser::new(),
             confirmation: None,
             text_input: None,
             help_overlay: None,
+            details_for: None,
             restart_overlay: None,
             restart_idx: None,
+            branch_picker: None,
+            branch_remotes: Vec::new(),
+            push_overlay: None,
+            pending_pushes: std::collections::HashMap::new(),
+            pr_body_to_edit: None,
             pending_action: None,
+            undo_buffer: std::collections::VecDeque::new(),
+            creating: std::collections::HashSet::new(),
+            diffing: std::collections::HashSet::new(),
+            creation_steps: std::collections::HashMap::new(),
+            answering_trust: std::collections::HashSet::new(),
+            disk_usage: DiskUsage::default(),
+            disk_scan_in_flight: false,
+            expiry_check_in_flight: false,
+            budget_warned: false,
+            team: Vec::new(),
+            team_error: None,
+            team_sync_in_flight: false,
+            test_watcher: TestWatcher::new(test_sender, std::sync::Arc::new(SystemCmdExec)),
+            test_changes,
+            tests_due: std::collections::HashMap::new(),
+            tests_running: std::collections::HashSet::new(),
+            poll,
+            tick_rate: TickRate::new(),
+            polled_selection: None,
+            pane_tails: PaneTails::default(),
+            pane_activity: std::collections::HashMap::new(),
+            agents_working: std::collections::HashSet::new(),
+            review_target: None,
+            like_target: None,
             creating_with_prompt: false,
             pending_instance_title: None,
-            pending_prompts: std::collections::HashMap::new(),
             bg_sender,
             bg_receiver,
         }
@@ -146,90 +488,233 @@ impl App {
             let _ = persistent_state.save(&self.config_dir);
         }
 
+        if let Some(error) = crate::update::take_failure(&self.config_dir) {
+            self.error.set_error(format!("Update failed: {}", error));
+        } else if !self.config.auto_update
+            && let Some(version) = crate::update::available_version(&self.config_dir)
+        {
+            self.error.set_info(format!(
+                "gana v{} is available: run `gana update` to install it",
+                version
+            ));
+        }
+
         let mut last_bg_tick = Instant::now();
+        let mut last_disk_scan: Option<Instant> = None;
+        let mut last_team_sync: Option<Instant> = None;
+        let mut last_expiry_check: Option<Instant> = None;
+        let mut last_pipeline_check: Option<Instant> = None;
+        let mut last_attention_check: Option<Instant> = None;
+        let mut last_status_check: Option<Instant> = None;
 
         while self.running {
+            self.board.set_selected(self.list.selected_index());
             terminal.draw(|frame| self.draw(frame))?;
 
             // Process background results (non-blocking)
             self.process_background_updates();
 
+            if let Some((request, draft)) = self.pr_body_to_edit.take() {
+                self.edit_pr_body_and_push(terminal, request, draft)?;
+            }
+
             // Advance spinner animation for Loading sessions
-            let has_loading = self.instances.iter().any(|i| i.status == InstanceStatus::Loading);
+            let has_loading = self
+                .instances
+                .iter()
+                .any(|i| {
+                    i.status == InstanceStatus::Loading || i.restoring || i.transition.is_some()
+                });
             if has_loading {
                 self.list.advance_spinner();
                 self.refresh_list();
             }
+            if has_loading || self.menu.is_busy() {
+                self.tick_rate.wake();
+            }
 
             // Show loading animation or fallback in preview pane
             let sel_idx = self.list.selected_index();
+            match self.instances.get(sel_idx) {
+                Some(instance) => self.preview.set_header(crate::ui::preview::session_header(instance)),
+                None => self.preview.set_header(""),
+            }
             if sel_idx < self.instances.len() {
                 if self.instances[sel_idx].status == InstanceStatus::Loading {
                     let tick = self.list.spinner_tick();
                     let name = self.instances[sel_idx].title.clone();
-                    self.preview.set_loading(tick, &name);
-                }
-            } else if self.instances.is_empty() {
-                if self.preview.is_empty() {
-                    self.preview.set_fallback();
+                    let step = self.creation_steps.get(&name).map(|s| s.label());
+                    self.preview.set_loading(tick, &name, step);
+                } else if self.instances[sel_idx].restoring {
+                    let name = self.instances[sel_idx].title.clone();
+                    self.preview
+                        .set_content(&format!("\n   Reconnecting to '{}'…\n", name));
+                } else if let Some(ref error) = self.instances[sel_idx].error {
+                    let name = self.instances[sel_idx].title.clone();
+                    self.preview.set_content(&format!(
+                        "\n   '{}' failed:\n\n   {}\n\n   Press r to retry\n",
+                        name,
+                        error.replace('\n', "\n   ")
+                    ));
+                } else if self.instances[sel_idx].status == InstanceStatus::Queued {
+                    let name = self.instances[sel_idx].title.clone();
+                    self.preview.set_content(&format!(
+                        "\n   Queued: '{}' starts once another session finishes setting up\n",
+                        name
+                    ));
                 }
+            } else if self.instances.is_empty()
+                && self.preview.is_empty()
+            {
+                self.preview.set_fallback();
             }
 
-            // Poll for key events with short timeout for responsiveness
-            if event::poll(Duration::from_millis(100))?
-                && let Event::Key(key) = event::read()?
-            {
+            // Poll for key events; the timeout stretches while idle
+            let timeout = self.tick_rate.event_timeout(Instant::now());
+            let event = if event::poll(timeout)? {
+                Some(event::read()?)
+            } else {
+                None
+            };
+            if event.is_some() {
+                self.tick_rate.wake();
+            }
+            if let Some(Event::Paste(text)) = &event {
+                self.handle_paste(text);
+            }
+            if let Some(Event::Key(key)) = event {
                 let action = self.handle_key(key)?;
 
-                if let AppAction::AttachSession(idx) = action {
-                    if idx < self.instances.len() {
-                        // 1. Leave TUI FIRST so terminal is back to normal
-                        crossterm::terminal::disable_raw_mode()?;
-                        crossterm::execute!(
-                            std::io::stdout(),
-                            crossterm::terminal::LeaveAlternateScreen
-                        )?;
-
-                        // 2. NOW get the real terminal size (not TUI size)
-                        //    and resize both tmux window + PTY
-                        if let Ok((tw, th)) = crossterm::terminal::size() {
-                            if let Some(ref mut tmux) =
-                                self.instances[idx].tmux_session
-                            {
-                                let _ = tmux.set_size(tw, th);
-                                tmux.resize_pty(tw, th);
-                            }
-                        }
+                if let AppAction::EditWorktree(dir) = &action {
+                    let result = Self::with_tui_suspended(terminal, || {
+                        editor::edit_path(std::path::Path::new(dir))
+                    })?;
+                    if let Err(e) = result {
+                        self.error.set_error(format!("Failed to open editor: {}", e));
+                    }
+                }
+                if let AppAction::EditFile { dir, file, line } = &action {
+                    let result = Self::with_tui_suspended(terminal, || {
+                        editor::edit_file_at(std::path::Path::new(dir), file, *line)
+                    })?;
+                    if let Err(e) = result {
+                        self.error.set_error(format!("Failed to open editor: {}", e));
+                    }
+                }
+                if let AppAction::GitTui(dir) = &action {
+                    let command = self.config.git_tui_command.clone();
+                    let result = Self::with_tui_suspended(terminal, || {
+                        editor::run_in_dir(&command, std::path::Path::new(dir))
+                    })?;
+                    if let Err(e) = result {
+                        self.error.set_error(format!("Failed to run git TUI: {}", e));
+                    }
+                }
 
-                        // 3. Enable raw mode for Ctrl+Q detection
-                        crossterm::terminal::enable_raw_mode()?;
+                if let AppAction::AttachSession(idx) = action
+                    && idx < self.instances.len()
+                {
+                    // 1. Leave TUI FIRST so terminal is back to normal
+                    crossterm::terminal::disable_raw_mode()?;
+                    crossterm::execute!(
+                        std::io::stdout(),
+                        crossterm::event::DisableBracketedPaste,
+                        crossterm::terminal::LeaveAlternateScreen
+                    )?;
+
+                    // 2. NOW get the real terminal size (not TUI size)
+                    //    and resize both tmux window + PTY
+                    if let Ok((tw, th)) = crossterm::terminal::size()
+                        && let Some(ref mut tmux) = self.instances[idx].tmux_session
+                    {
+                        let _ = tmux.set_size(tw, th);
+                        tmux.resize_pty(tw, th);
+                    }
 
-                        // 4. Attach: pipes stdin/stdout directly to tmux PTY.
-                        //    Blocks until user presses Ctrl+Q.
-                        let result = self.instances[idx].attach();
+                    // 3. Enable raw mode for Ctrl+Q detection
+                    crossterm::terminal::enable_raw_mode()?;
 
-                        // Restore TUI
-                        crossterm::terminal::disable_raw_mode()?;
-                        crossterm::terminal::enable_raw_mode()?;
-                        crossterm::execute!(
-                            std::io::stdout(),
-                            crossterm::terminal::EnterAlternateScreen
-                        )?;
-                        terminal.clear()?;
+                    // 4. Watch the other sessions, so one that starts
+                    //    waiting for input rings the bell
+                    let others = self
+                        .instances
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, inst)| *i != idx && inst.status.is_running())
+                        .map(|(_, inst)| {
+                            crate::session::tmux::TmuxSession::new(
+                                &inst.title,
+                                &inst.program,
+                                Box::new(SystemCmdExec),
+                                Box::new(crate::session::tmux::pty::SystemPtyFactory),
+                            )
+                            .with_prompts(self.config.prompt_patterns(&inst.program))
+                        })
+                        .collect();
+                    let watcher = AttentionWatcher::start(
+                        &self.instances[idx].title,
+                        others,
+                        self.config.attention_alert,
+                    );
 
-                        if let Err(e) = result {
-                            self.error
-                                .set_error(format!("Failed to attach: {}", e));
-                        }
+                    // 5. Attach: pipes stdin/stdout directly to tmux PTY.
+                    //    Blocks until user presses Ctrl+Q.
+                    let result = self.instances[idx].attach();
+                    watcher.stop();
+
+                    // Restore TUI
+                    crossterm::terminal::disable_raw_mode()?;
+                    crossterm::terminal::enable_raw_mode()?;
+                    crossterm::execute!(
+                        std::io::stdout(),
+                        crossterm::terminal::EnterAlternateScreen,
+                        crossterm::event::EnableBracketedPaste
+                    )?;
+                    terminal.clear()?;
+
+                    if let Err(e) = result {
+                        self.error
+                            .set_error(format!("Failed to attach: {}", e));
                     }
                 }
             }
 
-            // Schedule background updates every 500ms
-            if last_bg_tick.elapsed() >= Duration::from_millis(500) {
-                self.schedule_background_updates();
+            self.schedule_background_updates();
+            if last_bg_tick.elapsed() >= self.tick_rate.background_interval(Instant::now()) {
+                self.run_watched_tests();
+                if self.tabbed_window.active_tab() == Tab::Activity {
+                    self.refresh_activity();
+                }
                 last_bg_tick = Instant::now();
             }
+            if last_disk_scan.is_none_or(|t| t.elapsed() >= DISK_SCAN_INTERVAL) {
+                self.schedule_disk_scan();
+                last_disk_scan = Some(Instant::now());
+            }
+            if self.config.team_repo.is_some()
+                && last_team_sync.is_none_or(|t| t.elapsed() >= TEAM_SYNC_INTERVAL)
+            {
+                self.schedule_team_sync();
+                last_team_sync = Some(Instant::now());
+            }
+            if last_expiry_check.is_none_or(|t| t.elapsed() >= EXPIRY_CHECK_INTERVAL) {
+                self.apply_expiry_policy();
+                last_expiry_check = Some(Instant::now());
+            }
+            if self.show_board
+                && last_attention_check.is_none_or(|t| t.elapsed() >= ATTENTION_CHECK_INTERVAL)
+            {
+                self.schedule_attention_check();
+                last_attention_check = Some(Instant::now());
+            }
+            if last_pipeline_check.is_none_or(|t| t.elapsed() >= PIPELINE_CHECK_INTERVAL) {
+                self.release_pipeline_steps(None);
+                last_pipeline_check = Some(Instant::now());
+            }
+            if last_status_check.is_none_or(|t| t.elapsed() >= STATUS_CHECK_INTERVAL) {
+                self.refresh_status();
+                last_status_check = Some(Instant::now());
+            }
         }
 
         // Save state on exit so sessions persist across restarts
@@ -237,6 +722,29 @@ impl App {
         Ok(())
     }
 
+    /// Insert a bracketed paste into whichever input has focus, in one go,
+    /// instead of as keys that could submit or trigger actions.
+    fn handle_paste(&mut self, text: &str) {
+        match self.state {
+            AppState::TextInput => {
+                if let Some(ref mut input) = self.text_input {
+                    input.paste(text);
+                }
+            }
+            AppState::BranchPicker => {
+                if let Some(ref mut picker) = self.branch_picker {
+                    picker.paste(text);
+                }
+            }
+            AppState::Push => {
+                if let Some(ref mut overlay) = self.push_overlay {
+                    overlay.paste(text);
+                }
+            }
+            _ => {}
+        }
+    }
+
     /// Handle a raw key event by routing to the current state.
     /// Returns an AppAction if the caller needs to do something outside the TUI.
     fn handle_key(&mut self, key: KeyEvent) -> anyhow::Result<AppAction> {
@@ -257,178 +765,491 @@ impl App {
                 self.handle_restart_key(key)?;
                 Ok(AppAction::None)
             }
+            AppState::BranchPicker => {
+                self.handle_branch_picker_key(key);
+                Ok(AppAction::None)
+            }
+            AppState::Push => {
+                self.handle_push_key(key);
+                Ok(AppAction::None)
+            }
             AppState::Default => {
-                if let Some(action) = map_key(key) {
-                    return Ok(self.handle_key_action(action));
+                if let Some((action, count)) = self.keys.feed(key) {
+                    return Ok(self.handle_counted_action(action, count));
                 }
                 Ok(AppAction::None)
             }
         }
     }
 
+    /// Handle a key action typed with an optional count: movement is
+    /// repeated, and `5gg` / `5G` go to the fifth session.
+    fn handle_counted_action(&mut self, action: KeyAction, count: Option<usize>) -> AppAction {
+        let Some(count) = count else {
+            return self.handle_key_action(action);
+        };
+        match action {
+            KeyAction::Top | KeyAction::Bottom if !self.preview.is_scrolling() => {
+                self.select_session(count.saturating_sub(1));
+                AppAction::None
+            }
+            KeyAction::Tab => {
+                // `2<Tab>` shows the second tab
+                if self.tabbed_window.select_number(count) {
+                    self.menu.highlight_key("Tab");
+                    self.tab_shown();
+                }
+                AppAction::None
+            }
+            KeyAction::Up
+            | KeyAction::Down
+            | KeyAction::Left
+            | KeyAction::Right
+            | KeyAction::ScrollUp
+            | KeyAction::ScrollDown
+            | KeyAction::PageUp
+            | KeyAction::PageDown => {
+                for _ in 0..count.clamp(1, MAX_REPEAT) {
+                    self.handle_key_action(action);
+                }
+                AppAction::None
+            }
+            _ => self.handle_key_action(action),
+        }
+    }
+
+    /// Bring the newly shown tab up to date.
+    fn tab_shown(&mut self) {
+        match self.tabbed_window.active_tab() {
+            Tab::Activity => self.refresh_activity(),
+            Tab::Diff => self.refresh_diff(),
+            Tab::Preview => {}
+        }
+    }
+
+    /// Compute the selected session's diff now rather than on its next poll.
+    fn refresh_diff(&mut self) {
+        let idx = self.list.selected_index();
+        if let Some(instance) = self.instances.get(idx)
+            && let Some(ref worktree) = instance.git_worktree
+        {
+            let (title, worktree) = (instance.title.clone(), worktree.clone());
+            self.spawn_diff(title, worktree);
+        }
+    }
+
+    /// Compute a session's diff on a worker, unless one is already running
+    /// for it: its result will be just as fresh.
+    fn spawn_diff(&mut self, title: String, worktree: GitWorktree) {
+        if !self.diffing.insert(title.clone()) {
+            return;
+        }
+        let sender = self.bg_sender.clone();
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            let stats = worktree.diff(&*cmd);
+            let _ = sender.send(BackgroundUpdate::DiffComputed(title, stats));
+        });
+    }
+
+    /// Select the session at `idx` (clamped to the list).
+    fn select_session(&mut self, idx: usize) {
+        self.list.set_selected(idx);
+        self.board.set_selected(self.list.selected_index());
+        self.selection_changed();
+    }
+
+    /// Reset what belongs to the previously selected session.
+    fn selection_changed(&mut self) {
+        self.diff_view.clear_selection();
+        self.update_diff_badge();
+    }
+
+    /// Show the selected session's last known +/- counts on the Diff tab.
+    fn update_diff_badge(&mut self) {
+        let badge = self
+            .instances
+            .get(self.list.selected_index())
+            .and_then(|i| i.diff_stats.as_ref())
+            .filter(|stats| stats.added_lines + stats.removed_lines > 0)
+            .map(|stats| format!("+{} -{}", stats.added_lines, stats.removed_lines))
+            .unwrap_or_default();
+        self.tabbed_window.set_badge(Tab::Diff, badge);
+    }
+
     /// Handle a mapped key action in Default state.
     fn handle_key_action(&mut self, action: KeyAction) -> AppAction {
         match action {
-            KeyAction::Up => self.list.select_previous(),
-            KeyAction::Down => self.list.select_next(),
-            KeyAction::Enter | KeyAction::Attach => {
-                if !self.instances.is_empty() {
-                    let idx = self.list.selected_index();
-                    if idx < self.instances.len() {
-                        let status = self.instances[idx].status;
-                        if status == InstanceStatus::Running {
-                            return AppAction::AttachSession(idx);
-                        } else if status == InstanceStatus::Ready {
-                            // Session died — restart Claude in existing worktree
-                            if let Some(ref wt) = self.instances[idx].git_worktree {
-                                let worktree_path = wt.worktree_path().to_string();
-                                let title = self.instances[idx].title.clone();
-                                let program = self.instances[idx].program.clone();
-                                let sender = self.bg_sender.clone();
-
-                                self.instances[idx].status = InstanceStatus::Loading;
-                                self.refresh_list();
-
-                                std::thread::spawn(move || {
-                                    let cmd = SystemCmdExec;
-                                    let sanitized =
-                                        crate::session::tmux::sanitize_name(&title);
-                                    let _ = cmd.run(
-                                        "tmux",
-                                        &args(&["kill-session", "-t", &sanitized]),
-                                    );
-                                    if let Err(e) = cmd.run(
-                                        "tmux",
-                                        &args(&[
-                                            "new-session", "-d", "-s", &sanitized,
-                                            "-c", &worktree_path, &program,
-                                        ]),
-                                    ) {
-                                        let _ = sender.send(
-                                            BackgroundUpdate::InstanceFailed(
-                                                idx,
-                                                e.to_string(),
-                                            ),
-                                        );
-                                        return;
-                                    }
-                                    // Re-use existing worktree — just signal ready
-                                    // (InstanceReady expects a GitWorktree but we
-                                    // already have one; send a RestartReady instead)
-                                    let _ = sender.send(
-                                        BackgroundUpdate::SessionRestarted(idx),
-                                    );
-                                });
-                            }
+            KeyAction::Up | KeyAction::Down | KeyAction::Left | KeyAction::Right
+                if self.show_board =>
+            {
+                self.board.set_selected(self.list.selected_index());
+                let idx = match action {
+                    KeyAction::Up => self.board.move_vertical(-1),
+                    KeyAction::Down => self.board.move_vertical(1),
+                    KeyAction::Left => self.board.move_horizontal(-1),
+                    _ => self.board.move_horizontal(1),
+                };
+                self.list.set_selected(idx);
+                self.board.set_selected(idx);
+                self.selection_changed();
+            }
+            KeyAction::Up => {
+                self.list.select_previous();
+                self.selection_changed();
+            }
+            KeyAction::Down => {
+                self.list.select_next();
+                self.selection_changed();
+            }
+            // While scrolling, paging and gg/G move the preview
+            KeyAction::Top if self.preview.is_scrolling() => self.scroll_preview_to_top(),
+            KeyAction::Bottom if self.preview.is_scrolling() => self.preview.reset_scroll(),
+            KeyAction::PageUp if self.preview.is_scrolling() => {
+                self.scroll_preview_up(self.preview.page_size());
+            }
+            KeyAction::PageDown if self.preview.is_scrolling() => {
+                self.preview.scroll_down(self.preview.page_size());
+            }
+            KeyAction::Top => self.select_session(0),
+            KeyAction::Bottom => self.select_session(self.instances.len().saturating_sub(1)),
+            KeyAction::PageUp => {
+                self.select_session(self.list.selected_index().saturating_sub(LIST_PAGE));
+            }
+            KeyAction::PageDown => {
+                self.select_session(self.list.selected_index().saturating_add(LIST_PAGE));
+            }
+            KeyAction::ToggleBoard => {
+                self.menu.highlight_key("v");
+                self.show_board = !self.show_board;
+                self.refresh_list();
+            }
+            KeyAction::SwitchProject => {
+                self.menu.highlight_key("w");
+                self.switch_project();
+            }
+            KeyAction::Enter
+                if !self.instances.is_empty()
+                    && self.tabbed_window.active_tab() == Tab::Diff
+                    && self.diff_view.selected_hunk().is_some() =>
+            {
+                let hunk = self.diff_view.selected_hunk().cloned().unwrap_or_default();
+                let line = hunk.start.max(1);
+                match (self.selected_worktree_dir(), &self.config.editor_command) {
+                    (None, _) => {}
+                    (Some(dir), Some(command)) => {
+                        let command = editor::file_command_line(command, &hunk.file, line);
+                        if let Err(e) = editor::launch_in(&command, std::path::Path::new(&dir)) {
+                            self.error.set_error(format!("Failed to run editor_command: {}", e));
                         }
                     }
+                    (Some(dir), None) => {
+                        return AppAction::EditFile { dir, file: hunk.file, line };
+                    }
+                }
+            }
+            KeyAction::Enter | KeyAction::Attach if !self.instances.is_empty() => {
+                let idx = self.list.selected_index();
+                if idx < self.instances.len() {
+                    return self.open_session(idx);
                 }
             }
             KeyAction::New => {
                 self.menu.highlight_key("n");
                 self.state = AppState::TextInput;
-                self.text_input = Some(TextInputOverlay::new("New Session"));
+                self.text_input = Some(self.title_input("New Session"));
                 self.creating_with_prompt = false;
             }
             KeyAction::Prompt => {
                 self.menu.highlight_key("N");
                 self.state = AppState::TextInput;
-                self.text_input = Some(TextInputOverlay::new("New Session (with prompt)"));
+                self.text_input = Some(self.title_input("New Session (with prompt)"));
                 self.creating_with_prompt = true;
             }
-            KeyAction::Delete => {
-                if !self.instances.is_empty() {
-                    self.menu.highlight_key("d");
-                    let idx = self.list.selected_index();
-                    let name = &self.instances[idx].title;
-                    let msg = format!("Delete session '{}'? (y/n)", name);
-                    self.confirmation = Some(ConfirmationOverlay::new(msg));
-                    self.pending_action = Some(PendingAction::DeleteSession(idx));
-                    self.state = AppState::Confirm;
+            KeyAction::NewLike if !self.instances.is_empty() => {
+                self.menu.highlight_key("S");
+                let source = self.instances[self.list.selected_index()].title.clone();
+                let heading = format!("New Session like '{}' (empty: its title, numbered)", source);
+                self.text_input = Some(self.title_input(&heading));
+                self.like_target = Some(source);
+                self.state = AppState::TextInput;
+            }
+            KeyAction::NewFromBranch => {
+                self.menu.highlight_key("b");
+                let branches = self
+                    .repo_dir()
+                    .map_err(|e| e.to_string())
+                    .and_then(|cwd| {
+                        use crate::session::git::util::{list_branches, remotes};
+                        let cwd = cwd.to_string_lossy();
+                        let branches =
+                            list_branches(&cwd, &*self.cmd).map_err(|e| e.to_string())?;
+                        Ok((branches, remotes(&cwd, &*self.cmd).unwrap_or_default()))
+                    });
+                match branches {
+                    Ok((branches, remotes)) => {
+                        self.branch_remotes = remotes;
+                        self.branch_picker = Some(BranchPickerOverlay::new(branches));
+                        self.state = AppState::BranchPicker;
+                    }
+                    Err(e) => self.error.set_error(format!("Failed to list branches: {}", e)),
                 }
             }
-            KeyAction::Kill => {
-                if !self.instances.is_empty() {
-                    self.menu.highlight_key("D");
-                    let idx = self.list.selected_index();
-                    let name = &self.instances[idx].title;
-                    let msg = format!("[!] Kill session '{}'? (y/n)", name);
-                    self.confirmation = Some(ConfirmationOverlay::new(msg));
-                    self.pending_action = Some(PendingAction::KillSession(idx));
-                    self.state = AppState::Confirm;
+            KeyAction::Delete if !self.instances.is_empty() => {
+                self.menu.highlight_key("d");
+                let idx = self.list.selected_index();
+                if self.reject_while_creating(idx) {
+                    return AppAction::None;
                 }
+                let name = &self.instances[idx].title;
+                let msg = format!(
+                    "Delete session '{}'? Removing it from the list leaves its tmux session and worktree running.",
+                    name
+                );
+                self.confirmation = Some(ConfirmationOverlay::with_buttons(
+                    msg,
+                    vec![
+                        ConfirmButton::new('y', "List only", Choice::Yes),
+                        ConfirmButton::new('c', "Also close tmux + worktree (keep branch)", Choice::YesAndClose),
+                    ],
+                ));
+                self.pending_action = Some(PendingAction::Delete(idx));
+                self.state = AppState::Confirm;
             }
-            KeyAction::Pause => {
-                if !self.instances.is_empty() {
-                    let idx = self.list.selected_index();
-                    let cmd = crate::cmd::SystemCmdExec;
-                    if self.instances[idx].status == InstanceStatus::Paused {
-                        if let Err(e) = self.instances[idx].resume(&cmd) {
-                            self.error.set_error(format!("Resume failed: {}", e));
-                        }
-                    } else if self.instances[idx].status == InstanceStatus::Running {
-                        if let Err(e) = self.instances[idx].pause(&cmd) {
-                            self.error.set_error(format!("Pause failed: {}", e));
-                        }
-                    }
-                    self.refresh_list();
-                    let _ = self.save_instances();
+            KeyAction::Kill if !self.instances.is_empty() => {
+                self.menu.highlight_key("D");
+                let idx = self.list.selected_index();
+                if self.reject_while_creating(idx) {
+                    return AppAction::None;
                 }
+                let name = &self.instances[idx].title;
+                let msg = format!("[!] Kill session '{}'? (y/n)", name);
+                self.confirmation = Some(ConfirmationOverlay::new(msg));
+                self.pending_action = Some(PendingAction::Kill(idx));
+                self.state = AppState::Confirm;
             }
-            KeyAction::Restart => {
-                if !self.instances.is_empty() {
-                    let idx = self.list.selected_index();
-                    let status = self.instances[idx].status;
-                    if status == InstanceStatus::Running || status == InstanceStatus::Ready {
-                        self.menu.highlight_key("r");
-                        self.restart_overlay = Some(crate::ui::overlay::RestartOverlay::new());
-                        self.restart_idx = Some(idx);
-                        self.state = AppState::Restart;
+            KeyAction::Pause if !self.instances.is_empty() => {
+                let idx = self.list.selected_index();
+                match self.instances[idx].status {
+                    _ if self.instances[idx].transition.is_some() => {}
+                    InstanceStatus::Paused => self.start_transition(idx, Transition::Resuming),
+                    InstanceStatus::Running | InstanceStatus::Done => {
+                        self.start_transition(idx, Transition::Pausing)
                     }
+                    _ => {}
+                }
+            }
+            KeyAction::Restart if !self.instances.is_empty() => {
+                let idx = self.list.selected_index();
+                let status = self.instances[idx].status;
+                let failed = status == InstanceStatus::Failed;
+                if failed && self.instances[idx].git_worktree.is_none() {
+                    // Creation never finished: run it again
+                    self.menu.highlight_key("r");
+                    self.retry_creation(idx);
+                } else if matches!(status, InstanceStatus::WorktreeMissing | InstanceStatus::Orphaned)
+                    && self.instances[idx].transition.is_none()
+                {
+                    // Check the branch out again (from the base commit if it
+                    // is gone) and start the agent, as resuming does
+                    self.menu.highlight_key("r");
+                    self.instances[idx].error = None;
+                    self.start_transition(idx, Transition::Resuming);
+                } else if status.is_running()
+                    || status == InstanceStatus::Ready
+                    || failed
+                {
+                    self.menu.highlight_key("r");
+                    self.restart_overlay = Some(crate::ui::overlay::RestartOverlay::new());
+                    self.restart_idx = Some(idx);
+                    self.state = AppState::Restart;
+                }
+            }
+            KeyAction::Push if !self.instances.is_empty() => {
+                let idx = self.list.selected_index();
+                if self.instances[idx].status.is_running() {
+                    self.menu.highlight_key("P");
+                    self.check_push(idx);
                 }
             }
-            KeyAction::Push => {
-                if !self.instances.is_empty() {
-                    let idx = self.list.selected_index();
-                    if self.instances[idx].status == InstanceStatus::Running {
-                        self.menu.highlight_key("P");
-                        let name = &self.instances[idx].title;
-                        let msg = format!("Push & create PR for '{}'? (y/n)", name);
-                        self.confirmation = Some(ConfirmationOverlay::new(msg));
-                        self.pending_action = Some(PendingAction::PushSession(idx));
-                        self.state = AppState::Confirm;
+            KeyAction::OpenInEditor if !self.instances.is_empty() => {
+                self.menu.highlight_key("e");
+                match (self.selected_worktree_dir(), &self.config.editor_command) {
+                    (None, _) => {}
+                    (Some(dir), Some(command)) => {
+                        if let Err(e) = editor::launch(command, &dir) {
+                            self.error.set_error(format!("Failed to run editor_command: {}", e));
+                        }
                     }
+                    (Some(dir), None) => return AppAction::EditWorktree(dir),
+                }
+            }
+            KeyAction::GitTui if !self.instances.is_empty() => {
+                self.menu.highlight_key("L");
+                if let Some(dir) = self.selected_worktree_dir() {
+                    return AppAction::GitTui(dir);
                 }
             }
+            KeyAction::CopyPath if !self.instances.is_empty() => {
+                self.menu.highlight_key("y");
+                if let Some(dir) = self.selected_worktree_dir() {
+                    clipboard::copy(&dir);
+                    self.error.set_info(format!("Copied {}", dir));
+                }
+            }
+            KeyAction::CopyBranch if !self.instances.is_empty() => {
+                self.menu.highlight_key("Y");
+                let branch = self.instances[self.list.selected_index()].branch.clone();
+                if branch.is_empty() {
+                    self.error.set_error("Session has no branch yet");
+                } else {
+                    clipboard::copy(&branch);
+                    self.error.set_info(format!("Copied {}", branch));
+                }
+            }
+            KeyAction::OpenInBrowser if !self.instances.is_empty() => {
+                self.menu.highlight_key("o");
+                let instance = self.instances[self.list.selected_index()].clone();
+                let remote = self.config.push_remote.clone();
+                let sender = self.bg_sender.clone();
+                let cmd = std::sync::Arc::clone(&self.cmd);
+                // gh and git can take seconds to answer
+                std::thread::spawn(move || {
+                    let gh = crate::session::git::util::gh_status(&*cmd);
+                    let result = instance
+                        .open_in_browser(gh, remote.as_deref(), &*cmd)
+                        .map_err(|e| e.to_string());
+                    let _ = sender.send(BackgroundUpdate::BrowserOpened(result));
+                });
+            }
+            KeyAction::Undo => {
+                self.menu.highlight_key("u");
+                if let Err(e) = self.undo_last_removal() {
+                    self.error.set_error(format!("Undo failed: {}", e));
+                }
+            }
+            KeyAction::Details if !self.instances.is_empty() => {
+                self.menu.highlight_key("i");
+                let idx = self.list.selected_index();
+                let instance = &self.instances[idx];
+                if instance.status.is_running() {
+                    self.sample_resources(instance.clone());
+                }
+                self.details_for = Some(instance.title.clone());
+                let text = self.details_text(instance, None);
+                self.state = AppState::Help;
+                self.help_overlay = Some(TextOverlay::new("Session Details", text));
+            }
+            KeyAction::CleanupWorktrees => {
+                self.menu.highlight_key("C");
+                let candidates = self.paused_worktrees();
+                if candidates.is_empty() {
+                    self.error.set_error("No paused session has a worktree on disk");
+                } else {
+                    let bytes: u64 = candidates
+                        .iter()
+                        .filter_map(|&i| self.disk_usage.per_session.get(&self.instances[i].title))
+                        .sum();
+                    let msg = format!(
+                        "Remove {} worktree(s) of paused sessions ({})? Branches are kept. (y/n)",
+                        candidates.len(),
+                        crate::session::disk_usage::format_size(bytes)
+                    );
+                    self.confirmation = Some(ConfirmationOverlay::new(msg));
+                    self.pending_action = Some(PendingAction::CleanupWorktrees);
+                    self.state = AppState::Confirm;
+                }
+            }
+            KeyAction::ToggleAutoYes if !self.instances.is_empty() => {
+                self.menu.highlight_key("A");
+                let idx = self.list.selected_index();
+                let instance = &mut self.instances[idx];
+                instance.auto_yes = !instance.auto_yes;
+                self.refresh_list();
+                let _ = self.save_instances();
+            }
+            KeyAction::ToggleAutoPush if !self.instances.is_empty() => {
+                self.menu.highlight_key("F");
+                let idx = self.list.selected_index();
+                let instance = &mut self.instances[idx];
+                instance.auto_push = !instance.auto_push;
+                let message = if instance.auto_push {
+                    format!("'{}' will be pushed as a draft PR once its agent is done", instance.title)
+                } else {
+                    format!("'{}' won't be pushed on its own", instance.title)
+                };
+                self.error.set_info(message);
+                self.refresh_list();
+                let _ = self.save_instances();
+            }
             KeyAction::Quit => {
                 self.menu.highlight_key("q");
-                self.running = false;
+                self.request_quit();
             }
             KeyAction::Help => {
                 self.menu.highlight_key("?");
                 self.state = AppState::Help;
                 self.help_overlay = Some(TextOverlay::new("Help", help::help_text()));
             }
+            KeyAction::SlowCommands => {
+                let timings = crate::cmd::trace::recent(crate::cmd::trace::SLOW_COMMAND);
+                self.state = AppState::Help;
+                self.help_overlay =
+                    Some(TextOverlay::new("Slow Commands", help::slow_commands_text(&timings)));
+            }
+            KeyAction::Team => {
+                self.state = AppState::Help;
+                self.help_overlay = Some(TextOverlay::new(
+                    "Team Sessions",
+                    help::team_text(&self.config, &self.team, self.team_error.as_deref()),
+                ));
+            }
+            KeyAction::Schedules => {
+                self.state = AppState::Help;
+                self.help_overlay =
+                    Some(TextOverlay::new("Scheduled Prompts", help::schedules_text(&self.config)));
+            }
             KeyAction::Tab => {
                 self.menu.highlight_key("Tab");
-                self.tabbed_window.switch_tab();
+                self.tabbed_window.next_tab();
+                self.tab_shown();
             }
-            KeyAction::ScrollUp => {
-                if !self.preview.is_scrolling() {
-                    // Entering scroll mode: fetch full history
-                    let history = self
-                        .instances
-                        .get(self.list.selected_index())
-                        .and_then(|inst| inst.preview_full_history());
-                    if let Some(history) = history {
-                        self.preview.enter_scroll_mode(&history);
-                    } else {
-                        // No full history available; enter scroll mode with current content
-                        self.preview.enter_scroll_mode("");
+            KeyAction::PreviousTab => {
+                self.tabbed_window.previous_tab();
+                self.tab_shown();
+            }
+            KeyAction::RefreshDiff if !self.instances.is_empty() => {
+                self.menu.highlight_key("R");
+                self.refresh_diff();
+            }
+            // In the Diff tab J/K step through hunks for change requests
+            KeyAction::ScrollUp if self.tabbed_window.active_tab() == Tab::Diff => {
+                self.diff_view.select_previous_hunk();
+            }
+            KeyAction::ScrollDown if self.tabbed_window.active_tab() == Tab::Diff => {
+                self.diff_view.select_next_hunk();
+            }
+            KeyAction::RequestChanges if !self.instances.is_empty() => {
+                let instance = &self.instances[self.list.selected_index()];
+                match self.diff_view.selected_hunk() {
+                    _ if instance.tmux_session.is_none() => {
+                        self.error.set_error("Session isn't running; resume it first");
                     }
+                    Some(hunk) if self.tabbed_window.active_tab() == Tab::Diff => {
+                        let reference = hunk.reference();
+                        self.text_input = Some(
+                            TextInputOverlay::new(format!("Changes for {}", reference))
+                                .with_max_len(PROMPT_MAX_LEN),
+                        );
+                        self.review_target = Some((instance.title.clone(), reference));
+                        self.state = AppState::TextInput;
+                    }
+                    _ => self
+                        .error
+                        .set_error("Pick a hunk in the Diff tab with J/K first"),
                 }
-                self.preview.scroll_up(3);
             }
+            KeyAction::ScrollUp => self.scroll_preview_up(3),
             KeyAction::ScrollDown => {
                 self.preview.scroll_down(3);
             }
@@ -440,6 +1261,60 @@ impl App {
         AppAction::None
     }
 
+    /// Scroll the preview up by `lines`, entering scroll mode first and
+    /// loading older scrollback as the top of what's loaded comes near.
+    fn scroll_preview_up(&mut self, lines: usize) {
+        if !self.preview.is_scrolling() {
+            // Entering scroll mode: fetch the most recent chunk of
+            // history; older lines are loaded as the user scrolls up
+            let instance = self.instances.get(self.list.selected_index());
+            let history_size = instance.and_then(|i| i.history_size()).unwrap_or(0);
+            let loaded = history_size.min(SCROLLBACK_CHUNK);
+            let history = instance.and_then(|inst| inst.preview_history(-(loaded as i64), None));
+            if let Some(history) = history {
+                self.preview
+                    .enter_scroll_mode_partial(&history, loaded, history_size - loaded);
+            } else {
+                // No history available; enter scroll mode with current content
+                self.preview.enter_scroll_mode("");
+            }
+        }
+        self.preview.scroll_up(lines);
+        self.load_older_history();
+    }
+
+    /// Scroll to the oldest scrollback line kept (up to `scrollback_lines`).
+    fn scroll_preview_to_top(&mut self) {
+        loop {
+            self.preview.scroll_up(usize::MAX);
+            if !self.load_older_history() {
+                break;
+            }
+        }
+    }
+
+    /// Prepend the next chunk of scrollback if the preview wants it.
+    /// Returns whether any was loaded.
+    fn load_older_history(&mut self) -> bool {
+        let wanted = self.preview.wants_older_history(SCROLLBACK_CHUNK);
+        if wanted == 0 {
+            return false;
+        }
+        // Lines above what's loaded: -(loaded + wanted) ..= -(loaded + 1)
+        let loaded = self.preview.loaded_history() as i64;
+        let older = self
+            .instances
+            .get(self.list.selected_index())
+            .and_then(|inst| inst.preview_history(-(loaded + wanted as i64), Some(-(loaded + 1))));
+        match older {
+            Some(older) => {
+                self.preview.prepend_history(&older, wanted);
+                true
+            }
+            None => false,
+        }
+    }
+
     /// Handle key events while the text input overlay is active.
     fn handle_text_input_key(&mut self, key: KeyEvent) -> anyhow::Result<()> {
         if let Some(ref mut input) = self.text_input {
@@ -449,31 +1324,42 @@ impl App {
                 let text = input.input().to_string();
                 self.text_input = None;
 
-                if self.creating_with_prompt && self.pending_instance_title.is_none() {
-                    // First input was the title, now get the prompt
-                    if !text.is_empty() {
-                        self.pending_instance_title = Some(text);
-                        self.text_input = Some(TextInputOverlay::new("Enter prompt"));
-                        // Stay in TextInput state
-                    } else {
-                        self.state = AppState::Default;
-                        self.creating_with_prompt = false;
+                if let Some((title, reference)) = self.review_target.take() {
+                    self.state = AppState::Default;
+                    if !text.trim().is_empty() {
+                        self.request_changes(&title, &reference, &text);
+                    }
+                } else if let Some(source) = self.like_target.take() {
+                    self.state = AppState::Default;
+                    if let Err(e) = self.create_sibling(&source, text) {
+                        self.error.set_error(e.to_string());
                     }
+                } else if self.creating_with_prompt && self.pending_instance_title.is_none() {
+                    // First input was the title (empty derives it from the
+                    // prompt), now get the prompt
+                    self.pending_instance_title = Some(text);
+                    self.text_input = Some(
+                        TextInputOverlay::new("Enter prompt (@path loads a file)")
+                            .with_max_len(PROMPT_MAX_LEN),
+                    );
+                    // Stay in TextInput state
                 } else if self.creating_with_prompt && self.pending_instance_title.is_some() {
                     // Second input was the prompt
                     let title = self.pending_instance_title.take().unwrap();
                     self.state = AppState::Default;
                     self.creating_with_prompt = false;
-                    if let Err(e) = self.create_instance_with_prompt(title, text) {
-                        self.error.set_error(e.to_string());
+                    match resolve_prompt_input(&text) {
+                        Ok(prompt) if title.trim().is_empty() => self.create_untitled(prompt),
+                        Ok(prompt) => self.request_create(title, prompt),
+                        Err(e) => {
+                            self.error.set_error(format!("Failed to read prompt file: {}", e));
+                        }
                     }
                 } else {
                     // Normal new session (no prompt)
                     self.state = AppState::Default;
                     if !text.is_empty() {
-                        if let Err(e) = self.create_instance(text) {
-                            self.error.set_error(e.to_string());
-                        }
+                        self.request_create(text, String::new());
                     }
                 }
             } else if input.is_cancelled() {
@@ -481,42 +1367,82 @@ impl App {
                 self.state = AppState::Default;
                 self.creating_with_prompt = false;
                 self.pending_instance_title = None;
+                self.review_target = None;
+                self.like_target = None;
             }
         }
         Ok(())
     }
 
+    /// Send a follow-up prompt about part of the diff to the session's agent.
+    fn request_changes(&mut self, title: &str, reference: &str, request: &str) {
+        let Some(idx) = self.instance_index(title) else {
+            return;
+        };
+        let prompt = format!("In {}: {}", reference, request.trim());
+        if let Err(e) = self.instances[idx].send_prompt(&prompt) {
+            self.error.set_error(format!("Couldn't send to '{}': {}", title, e));
+            return;
+        }
+        self.journal.record(title, EventKind::PromptSent, Some(&prompt));
+        let _ = self.save_instances();
+        self.error.set_info(format!("Sent to '{}': {}", title, prompt));
+    }
+
     /// Handle key events while the confirmation overlay is active.
     fn handle_confirm_key(&mut self, key: KeyCode) -> anyhow::Result<()> {
         if let Some(ref mut overlay) = self.confirmation {
             overlay.handle_key(key);
 
             if overlay.is_dismissed() {
+                let choice = overlay.choice();
                 let confirmed = overlay.is_confirmed();
                 let action = self.pending_action.take();
                 self.confirmation = None;
                 self.state = AppState::Default;
 
-                if confirmed
+                if let Some(PendingAction::Quit) = action {
+                    // y pauses then quits, n quits leaving sessions running, Esc stays
+                    if let Some(choice) = choice {
+                        if choice == Choice::Yes {
+                            self.pause_all_running();
+                        }
+                        self.running = false;
+                    }
+                } else if let Some(PendingAction::Create { title, prompt }) = action {
+                    // y carries local changes, n branches clean, Esc cancels
+                    if let Some(choice) = choice
+                        && let Err(e) = self.create_instance_full(title, prompt, choice == Choice::Yes)
+                    {
+                        self.error.set_error(e.to_string());
+                    }
+                } else if let Some(PendingAction::Delete(idx)) = action {
+                    // y removes from the list only, c closes tmux and the worktree first, Esc cancels
+                    let result = match choice {
+                        // Its setup may have started while the dialog was open
+                        Some(_) if self.reject_while_creating(idx) => Ok(()),
+                        Some(Choice::Yes) => self.delete_instance(idx),
+                        Some(Choice::YesAndClose) => self.close_and_delete(idx),
+                        _ => Ok(()),
+                    };
+                    if let Err(e) = result {
+                        self.error.set_error(e.to_string());
+                    }
+                } else if confirmed
                     && let Some(pending) = action
                 {
                     match pending {
-                        PendingAction::KillSession(idx) => {
-                            if let Err(e) = self.kill_instance(idx) {
-                                self.error.set_error(e.to_string());
-                            }
-                        }
-                        PendingAction::DeleteSession(idx) => {
-                            if let Err(e) = self.delete_instance(idx) {
-                                self.error.set_error(e.to_string());
-                            }
-                        }
-                        PendingAction::PushSession(idx) => {
-                            let cmd = SystemCmdExec;
-                            if let Err(e) = self.instances[idx].push_and_pr(&cmd) {
-                                self.error.set_error(format!("Push failed: {}", e));
+                        PendingAction::Kill(idx) => {
+                            if self.instances.get(idx).is_some_and(|i| i.transition.is_none())
+                                && !self.reject_while_creating(idx)
+                            {
+                                self.start_transition(idx, Transition::Killing);
                             }
                         }
+                        PendingAction::CleanupWorktrees => self.cleanup_paused_worktrees(),
+                        PendingAction::Quit
+                        | PendingAction::Create { .. }
+                        | PendingAction::Delete(_) => {}
                     }
                 }
             }
@@ -524,6 +1450,46 @@ impl App {
         Ok(())
     }
 
+    /// Look up the session's push remote and whether `gh` works on a
+    /// worker, then open its push options.
+    fn check_push(&self, idx: usize) {
+        let instance = &self.instances[idx];
+        let title = instance.title.clone();
+        let worktree = instance.git_worktree.clone();
+        let remote = self.config.push_remote.clone();
+        let sender = self.bg_sender.clone();
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            let remote = worktree
+                .map(|wt| wt.push_remote(remote.as_deref(), &*cmd))
+                .unwrap_or_else(|| "origin".to_string());
+            let gh = crate::session::git::util::gh_status(&*cmd);
+            let _ = sender.send(BackgroundUpdate::PushChecked(title, remote, gh));
+        });
+    }
+
+    /// The details shown with `i`, with the agent's usage once sampled.
+    fn details_text(
+        &self,
+        instance: &Instance,
+        resources: Option<&crate::session::resources::ResourceUsage>,
+    ) -> String {
+        let limits = crate::config::per_program(&self.config.resource_limits, &instance.program);
+        help::details_text(instance, &self.disk_usage, resources, limits)
+    }
+
+    /// Measure a running session's CPU and memory on a worker; `ps` and
+    /// `docker stats` can take a second.
+    fn sample_resources(&self, instance: Instance) {
+        let sender = self.bg_sender.clone();
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            if let Some(usage) = crate::session::resources::sample(&instance, &*cmd) {
+                let _ = sender.send(BackgroundUpdate::ResourcesSampled(instance.title, usage));
+            }
+        });
+    }
+
     /// Handle key events while the help overlay is active.
     fn handle_help_key(&mut self, key: KeyCode) -> anyhow::Result<()> {
         if let Some(ref mut overlay) = self.help_overlay {
@@ -531,6 +1497,7 @@ impl App {
 
             if overlay.is_dismissed() {
                 self.help_overlay = None;
+                self.details_for = None;
                 self.state = AppState::Default;
             }
         }
@@ -553,75 +1520,416 @@ impl App {
                 self.state = AppState::Default;
 
                 if idx < self.instances.len() {
-                    if let Some(ref wt) = self.instances[idx].git_worktree {
-                        let worktree_path = wt.worktree_path().to_string();
-                        let title = self.instances[idx].title.clone();
-                        let program = self.instances[idx].program.clone();
-                        let sender = self.bg_sender.clone();
-
-                        // Kill existing tmux session
-                        self.instances[idx].tmux_session = None;
-                        self.instances[idx].status = InstanceStatus::Loading;
-                        self.refresh_list();
-
-                        // Build program command with flags
-                        let program_cmd = if skip_perms && program == "claude" {
-                            format!("{} --dangerously-skip-permissions", program)
-                        } else {
-                            program
-                        };
-
-                        std::thread::spawn(move || {
-                            let cmd = SystemCmdExec;
-                            let sanitized =
-                                crate::session::tmux::sanitize_name(&title);
-                            let _ = cmd.run(
-                                "tmux",
-                                &args(&["kill-session", "-t", &sanitized]),
-                            );
-
-                            // Start new session with program + flags
-                            if let Err(e) = cmd.run(
-                                "tmux",
-                                &args(&[
-                                    "new-session", "-d", "-s", &sanitized,
-                                    "-c", &worktree_path, &program_cmd,
-                                ]),
-                            ) {
-                                let _ = sender.send(
-                                    BackgroundUpdate::InstanceFailed(idx, e.to_string()),
-                                );
-                                return;
-                            }
+                    let program = self.instances[idx].program.clone();
+                    let resume_cmd = crate::session::launch::resume_command(&program, &self.config)
+                        .filter(|_| resume);
+                    // Build program command with flags
+                    let program_cmd = resume_cmd.clone().unwrap_or_else(|| program.clone());
+                    let program_cmd = if skip_perms && program == "claude" {
+                        format!("{} --dangerously-skip-permissions", program_cmd)
+                    } else {
+                        program_cmd
+                    };
+                    // Without a resume flag, ask the agent with /resume
+                    self.relaunch(idx, program_cmd, resume && resume_cmd.is_none());
+                }
+            }
+        }
+        Ok(())
+    }
 
-                            // If resume requested, wait a bit then send /resume
-                            if resume {
-                                // Wait for Claude to start up
-                                std::thread::sleep(std::time::Duration::from_secs(3));
-                                let _ = cmd.run(
-                                    "tmux",
-                                    &args(&[
-                                        "send-keys", "-t", &sanitized,
-                                        "/resume", "Enter",
-                                    ]),
-                                );
-                            }
+    /// What Enter does for the session at `idx`, by status: attach to a
+    /// running agent, start an exited one again, resume a paused session,
+    /// retry a failed one, or say why there is nothing to open yet.
+    fn open_session(&mut self, idx: usize) -> AppAction {
+        let instance = &self.instances[idx];
+        let title = instance.title.clone();
+        if let Some(transition) = instance.transition {
+            self.error.set_error(format!(
+                "'{}' is {}; wait for it to finish",
+                title,
+                transition.label().to_lowercase()
+            ));
+            return AppAction::None;
+        }
+        let has_worktree = instance.git_worktree.is_some();
+        match instance.status {
+            InstanceStatus::Running | InstanceStatus::Done => return AppAction::AttachSession(idx),
+            InstanceStatus::Ready | InstanceStatus::Failed if has_worktree => {
+                // Agent exited or failed to restart: start it again in the
+                // existing worktree, continuing its conversation if it can
+                let program = self.instances[idx].program.clone();
+                let program_cmd = crate::session::launch::resume_command(&program, &self.config)
+                    .unwrap_or(program);
+                self.instances[idx].error = None;
+                self.relaunch(idx, program_cmd, false);
+            }
+            // Creation never finished: run it again
+            InstanceStatus::Failed => self.retry_creation(idx),
+            InstanceStatus::Paused => self.start_transition(idx, Transition::Resuming),
+            InstanceStatus::Loading | InstanceStatus::Queued => {
+                self.error.set_error(format!("'{}' is still starting", title));
+            }
+            InstanceStatus::Pending => {
+                self.error.set_error(format!("'{}' starts once the session it waits for finishes", title));
+            }
+            InstanceStatus::WorktreeMissing | InstanceStatus::Orphaned => {
+                self.error.set_error(format!("'{}' needs repair first (r: repair, i: details)", title));
+            }
+            InstanceStatus::Ready => {}
+        }
+        AppAction::None
+    }
 
-                            let _ = sender.send(
-                                BackgroundUpdate::SessionRestarted(idx),
-                            );
-                        });
-                    }
+    /// Start `program_cmd` in a fresh tmux session in the session's existing
+    /// worktree, replacing any session left over. With `send_resume`, the
+    /// agent is sent `/resume` once it has started.
+    fn relaunch(&mut self, idx: usize, program_cmd: String, send_resume: bool) {
+        let Some(ref wt) = self.instances[idx].git_worktree else {
+            return;
+        };
+        let worktree = wt.clone();
+        let title = self.instances[idx].title.clone();
+        let mut factory = SessionFactory::new(&self.instances[idx], self.config.clone());
+        factory.program = program_cmd;
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        let sender = self.bg_sender.clone();
+
+        // The launch replaces the old tmux session
+        self.instances[idx].tmux_session = None;
+        self.instances[idx].status = InstanceStatus::Loading;
+        self.refresh_list();
+
+        std::thread::spawn(move || {
+            let tmux = match factory.launch(&worktree, &cmd) {
+                Ok(tmux) => tmux,
+                Err(e) => {
+                    let _ = sender.send(BackgroundUpdate::InstanceFailed(title, e.to_string()));
+                    return;
                 }
+            };
+            if let Some(prompt) = tmux.trust_prompt()
+                && let Err(e) = prompt.answer(&*cmd)
+            {
+                tracing::warn!(session = %title, "Failed to answer the trust prompt: {}", e);
+            }
+
+            if send_resume {
+                // Wait for the agent to start up
+                std::thread::sleep(std::time::Duration::from_secs(3));
+                let _ = tmux.send_keys("/resume");
+                let _ = tmux.send_keys("Enter");
+            }
+
+            let _ = sender.send(BackgroundUpdate::SessionRestarted(title, Box::new(tmux)));
+        });
+    }
+
+    fn handle_branch_picker_key(&mut self, key: KeyEvent) {
+        let Some(ref mut overlay) = self.branch_picker else {
+            return;
+        };
+        overlay.handle_key(key);
+
+        if overlay.is_cancelled() {
+            self.branch_picker = None;
+            self.state = AppState::Default;
+        } else if overlay.is_submitted() {
+            let branch = overlay.selected_branch().map(str::to_string);
+            self.branch_picker = None;
+            self.state = AppState::Default;
+            if let Some(branch) = branch
+                && let Err(e) = self.create_instance_on_branch(branch)
+            {
+                self.error.set_error(e.to_string());
+            }
+        }
+    }
+
+    fn handle_push_key(&mut self, key: KeyEvent) {
+        let Some(ref mut overlay) = self.push_overlay else {
+            return;
+        };
+        overlay.handle_key(key);
+
+        if overlay.is_cancelled() {
+            self.push_overlay = None;
+            self.state = AppState::Default;
+        } else if overlay.is_submitted() {
+            let request = PushRequest {
+                title: overlay.session().to_string(),
+                squash: overlay.squash_message().map(str::to_string),
+                push: PushOptions {
+                    remote: self.config.push_remote.clone(),
+                    force_with_lease: overlay.force_with_lease,
+                    plain_git: overlay.plain_git(),
+                    draft: false,
+                },
+                by_gh_status: false,
+            };
+            // Without gh there is no PR to put a drafted description in
+            let draft_body = overlay.draft_body && !overlay.plain_git();
+            self.push_overlay = None;
+            self.state = AppState::Default;
+
+            if draft_body {
+                self.request_pr_draft(request);
+            } else {
+                self.push_session(request, None);
+            }
+        }
+    }
+
+    /// Ask the session's agent for a PR description in the background; the
+    /// push continues once it arrives and the user has edited it.
+    fn request_pr_draft(&mut self, request: PushRequest) {
+        let Some(idx) = self.instance_index(&request.title) else {
+            return;
+        };
+        let instance = &self.instances[idx];
+        if !instance.status.is_running() {
+            self.error
+                .set_error(format!("'{}' is not running, so its agent can't draft a PR description", instance.title));
+            return;
+        }
+        let base_commit = instance
+            .git_worktree
+            .as_ref()
+            .map(|wt| wt.base_commit_sha().to_string())
+            .unwrap_or_default();
+        let title = request.title.clone();
+        let sender = self.bg_sender.clone();
+        self.error
+            .set_info(format!("Waiting for '{}' to draft the PR description…", title));
+        self.pending_pushes.insert(title.clone(), request);
+
+        std::thread::spawn(move || {
+            let result = crate::session::pr_draft::request_draft(
+                &title,
+                &base_commit,
+                PR_DRAFT_TIMEOUT,
+                &SystemCmdExec,
+            )
+            .map_err(|e| e.to_string());
+            let _ = sender.send(BackgroundUpdate::PrBodyDrafted(title, result));
+        });
+    }
+
+    /// Let the user edit a drafted PR description in their editor, then push.
+    /// Saving an empty description cancels the push.
+    fn edit_pr_body_and_push<B: Backend>(
+        &mut self,
+        terminal: &mut Terminal<B>,
+        request: PushRequest,
+        draft: String,
+    ) -> anyhow::Result<()>
+    where
+        B::Error: Send + Sync + 'static,
+    {
+        let path = self
+            .config_dir
+            .join("pr-drafts")
+            .join(format!("{}.md", crate::session::tmux::sanitize_name(&request.title)));
+
+        let edited = Self::with_tui_suspended(terminal, || editor::edit_text(&path, &draft))?;
+        let _ = std::fs::remove_file(&path);
+
+        match edited {
+            Ok(body) if body.trim().is_empty() => {
+                self.error.set_info("Empty PR description, push cancelled");
             }
+            Ok(body) => {
+                self.error.clear();
+                self.push_session(request, Some(body.trim().to_string()));
+            }
+            Err(e) => self.error.set_error(format!("Editing the PR description failed: {}", e)),
         }
         Ok(())
     }
 
+    /// Worktree directory of the selected session, or `None` (with an error
+    /// shown) when it has none on disk, e.g. while paused.
+    fn selected_worktree_dir(&mut self) -> Option<String> {
+        let instance = &self.instances[self.list.selected_index()];
+        let dir = instance
+            .git_worktree
+            .as_ref()
+            .map(|wt| wt.worktree_path().to_string())
+            .filter(|dir| std::path::Path::new(dir).is_dir());
+        if dir.is_none() {
+            self.error
+                .set_error(format!("'{}' has no worktree on disk", instance.title));
+        }
+        dir
+    }
+
+    /// Show whether the daemon runs, how many sessions have auto-yes on and
+    /// any newer release in the menu bar.
+    fn refresh_status(&mut self) {
+        self.menu.set_status(StatusSegment {
+            daemon_running: crate::daemon::is_daemon_running(&self.config_dir),
+            auto_yes: self.instances.iter().filter(|i| i.auto_yes).count(),
+            update: crate::update::available_version(&self.config_dir),
+        });
+    }
+
+    /// Leave the TUI, run `f` with the normal terminal, then restore the TUI.
+    fn with_tui_suspended<B: Backend, T>(
+        terminal: &mut Terminal<B>,
+        f: impl FnOnce() -> T,
+    ) -> anyhow::Result<T>
+    where
+        B::Error: Send + Sync + 'static,
+    {
+        crossterm::terminal::disable_raw_mode()?;
+        crossterm::execute!(
+            std::io::stdout(),
+            crossterm::event::DisableBracketedPaste,
+            crossterm::terminal::LeaveAlternateScreen
+        )?;
+        let result = f();
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(
+            std::io::stdout(),
+            crossterm::terminal::EnterAlternateScreen,
+            crossterm::event::EnableBracketedPaste
+        )?;
+        terminal.clear()?;
+        Ok(result)
+    }
+
+    /// Push a session on a background thread; the result arrives as
+    /// `BackgroundUpdate::PushFinished`.
+    fn push_session(&mut self, request: PushRequest, pr_body: Option<String>) {
+        let opts = self.commit_options();
+        let Some(idx) = self.instance_index(&request.title) else {
+            return;
+        };
+        let label = Self::push_label(&request.title);
+        if self.menu.is_running(&label) {
+            self.error
+                .set_error(format!("'{}' is already being pushed", request.title));
+            return;
+        }
+        self.menu.start_operation(&label);
+        let mut instance = self.instances[idx].clone();
+        let sender = self.bg_sender.clone();
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            let mut request = request;
+            if request.by_gh_status {
+                let ready = crate::session::git::util::gh_status(&*cmd) == GhStatus::Ready;
+                request.push.plain_git = !ready;
+                request.push.draft = ready;
+            }
+            let result = instance
+                .push_and_pr(
+                    &opts,
+                    &request.push,
+                    request.squash.as_deref(),
+                    pr_body.as_deref(),
+                    &*cmd,
+                )
+                .map_err(|e| e.to_string());
+            let _ = sender.send(BackgroundUpdate::PushFinished(request, result));
+        });
+    }
+
+    /// Fetch the notes of the release installed since the last launch;
+    /// they're shown once they arrive.
+    fn fetch_release_notes(&self, version: String) {
+        let sender = self.bg_sender.clone();
+        std::thread::spawn(move || {
+            let notes = crate::update::release_notes(&version).map_err(|e| format!("{:#}", e));
+            let _ = sender.send(BackgroundUpdate::ReleaseNotes(version, notes));
+        });
+    }
+
+    fn show_release_notes(&mut self, version: &str, notes: Result<String, String>) {
+        match notes {
+            // Don't cover a dialog the user is in
+            Ok(notes) if self.state == AppState::Default => {
+                self.state = AppState::Help;
+                self.help_overlay =
+                    Some(TextOverlay::new(format!("Updated to gana v{}", version), notes));
+            }
+            Ok(_) => self.error.set_info(format!("Updated to gana v{}", version)),
+            Err(e) => {
+                tracing::warn!("Release notes for v{} unavailable: {}", version, e);
+                self.error.set_info(format!(
+                    "Updated to gana v{} (release notes unavailable: {})",
+                    version, e
+                ));
+            }
+        }
+    }
+
+    fn push_label(title: &str) -> String {
+        format!("Pushing '{}'", title)
+    }
+
+    fn finish_push(&mut self, request: PushRequest, result: Result<Option<String>, String>) {
+        self.menu.finish_operation(&Self::push_label(&request.title));
+        let branch = self
+            .instance_index(&request.title)
+            .map(|idx| self.instances[idx].branch.clone())
+            .unwrap_or_default();
+        if result.is_ok() {
+            self.journal.record(&request.title, EventKind::Pushed, Some(&branch));
+            // A pushed PR completes the session for pipeline purposes
+            self.release_pipeline_steps(Some(&request.title));
+        }
+        match result {
+            Ok(Some(url)) if request.push.draft => self
+                .error
+                .set_info(format!("Pushed '{}' as a draft PR:\n{}", request.title, url)),
+            Ok(None) if request.push.draft => self
+                .error
+                .set_info(format!("Pushed '{}' as a draft PR", request.title)),
+            Ok(_) if !request.push.plain_git => {}
+            Ok(Some(url)) => self
+                .error
+                .set_info(format!("Pushed '{}'. Open the PR at:\n{}", request.title, url)),
+            Ok(None) => self.error.set_info(format!(
+                "Pushed '{}'. Open a PR from branch '{}' on your git host",
+                request.title, branch
+            )),
+            Err(e) => {
+                tracing::error!(session = %request.title, "Push failed: {}", e);
+                self.error.set_error(format!("Push failed: {}", e));
+            }
+        }
+    }
+
     /// Draw all UI components.
     fn draw(&self, frame: &mut Frame) {
         let area = frame.area();
+        if self.show_board {
+            self.draw_board(frame, area);
+        } else {
+            self.draw_panes(frame, area);
+        }
+        self.draw_overlay(frame, area);
+    }
+
+    /// The board across the full width, with the error and menu below.
+    fn draw_board(&self, frame: &mut Frame, area: Rect) {
+        let mut constraints = vec![Constraint::Min(1)];
+        if self.error.has_message() {
+            constraints.push(Constraint::Length(self.error.height()));
+        }
+        constraints.push(Constraint::Length(1));
+        let layout = Layout::vertical(constraints).split(area);
+
+        frame.render_widget(&self.board, layout[0]);
+        if self.error.has_message() {
+            frame.render_widget(&self.error, layout[1]);
+        }
+        frame.render_widget(&self.menu, layout[layout.len() - 1]);
+    }
 
+    /// The session list beside the tabbed preview pane.
+    fn draw_panes(&self, frame: &mut Frame, area: Rect) {
         // Main layout: horizontal split [list | right_pane]
         let main_layout = Layout::horizontal([
             Constraint::Percentage(30),
@@ -630,11 +1938,11 @@ impl App {
         .split(area);
 
         // Right pane: vertical split [tabs | content | error? | menu]
-        let right_constraints = if self.error.has_error() {
+        let right_constraints = if self.error.has_message() {
             vec![
                 Constraint::Length(1),  // tab bar
                 Constraint::Min(1),     // content
-                Constraint::Length(3),  // error
+                Constraint::Length(self.error.height()), // error
                 Constraint::Length(1),  // menu bar
             ]
         } else {
@@ -656,17 +1964,20 @@ impl App {
         match self.tabbed_window.active_tab() {
             Tab::Preview => frame.render_widget(&self.preview, right_layout[1]),
             Tab::Diff => frame.render_widget(&self.diff_view, right_layout[1]),
+            Tab::Activity => frame.render_widget(&self.activity, right_layout[1]),
         }
 
         // Render error if present
-        if self.error.has_error() {
+        if self.error.has_message() {
             frame.render_widget(&self.error, right_layout[2]);
             frame.render_widget(&self.menu, right_layout[3]);
         } else {
             frame.render_widget(&self.menu, right_layout[2]);
         }
+    }
 
-        // Render overlays on top
+    /// Render the overlay of the current state on top.
+    fn draw_overlay(&self, frame: &mut Frame, area: Rect) {
         match self.state {
             AppState::Confirm => {
                 if let Some(ref overlay) = self.confirmation {
@@ -677,7 +1988,8 @@ impl App {
             }
             AppState::TextInput => {
                 if let Some(ref overlay) = self.text_input {
-                    let popup_area = centered_rect(50, 20, area);
+                    // Room for the branch and worktree notes under the input
+                    let popup_area = centered_rect(50, 30, area);
                     frame.render_widget(Clear, popup_area);
                     overlay.render_content(popup_area, frame.buffer_mut());
                 }
@@ -696,6 +2008,20 @@ impl App {
                     overlay.render_content(popup_area, frame.buffer_mut());
                 }
             }
+            AppState::Push => {
+                if let Some(ref overlay) = self.push_overlay {
+                    let popup_area = centered_rect(50, 50, area);
+                    frame.render_widget(Clear, popup_area);
+                    overlay.render_content(popup_area, frame.buffer_mut());
+                }
+            }
+            AppState::BranchPicker => {
+                if let Some(ref overlay) = self.branch_picker {
+                    let popup_area = centered_rect(50, 60, area);
+                    frame.render_widget(Clear, popup_area);
+                    overlay.render_content(popup_area, frame.buffer_mut());
+                }
+            }
             AppState::Default => {}
         }
     }
@@ -703,539 +2029,3053 @@ impl App {
     // ── Instance management ─────────────────────────────────────────
 
     fn create_instance(&mut self, title: String) -> anyhow::Result<()> {
-        let cwd = std::env::current_dir()?.to_string_lossy().to_string();
+        self.create_instance_with_prompt(title, String::new())
+    }
 
-        // Create placeholder instance with Loading status
-        let mut instance = Instance::new(InstanceOptions {
-            title: title.clone(),
-            path: cwd.clone(),
-            program: self.config.default_program.clone(),
-            auto_yes: self.config.auto_yes,
-        });
-        instance.status = InstanceStatus::Loading;
-        self.instances.push(instance);
-        let idx = self.instances.len() - 1;
-        self.refresh_list();
+    fn create_instance_with_prompt(
+        &mut self,
+        title: String,
+        prompt: String,
+    ) -> anyhow::Result<()> {
+        self.create_instance_full(title, prompt, false)
+    }
+
+    /// Create a session the user left untitled, titling it from the prompt.
+    /// With `title_command` set the agent is asked for a title first.
+    fn create_untitled(&mut self, prompt: String) {
+        let Some(fallback) = crate::session::title::title_from_prompt(&prompt) else {
+            self.error.set_error("Enter a title or a prompt to name the session after");
+            return;
+        };
+        let Some(command) = self.config.title_command.clone() else {
+            self.request_create(fallback, prompt);
+            return;
+        };
 
-        // Spawn background thread for slow git worktree + tmux creation
+        self.error.set_info("Generating a session title…");
         let sender = self.bg_sender.clone();
-        let program = self.config.default_program.clone();
         std::thread::spawn(move || {
-            let cmd = SystemCmdExec;
-
-            // Create worktree (slow: 0.5-5s)
-            let worktree = match crate::session::git::GitWorktree::new(&title, &cwd, &program, &title, &cmd) {
-                Ok(wt) => wt,
-                Err(e) => {
-                    let _ = sender.send(BackgroundUpdate::InstanceFailed(idx, e.to_string()));
-                    return;
-                }
-            };
-
-            // Setup worktree on disk (slow: git worktree add)
-            if let Err(e) = worktree.setup(&cmd) {
-                let _ = sender.send(BackgroundUpdate::InstanceFailed(idx, e.to_string()));
-                return;
-            }
+            let title = crate::session::title::agent_title(&command, &prompt, &SystemCmdExec)
+                .map_err(|e| e.to_string());
+            let _ = sender.send(BackgroundUpdate::TitleGenerated(prompt, title));
+        });
+    }
 
-            // Create tmux session (medium: 50-500ms)
-            let sanitized = crate::session::tmux::sanitize_name(&title);
-            // Kill existing session if any
-            let _ = cmd.run("tmux", &args(&["kill-session", "-t", &sanitized]));
-            // Create new detached session
-            let worktree_path = worktree.worktree_path().to_string();
-            if let Err(e) = cmd.run("tmux", &args(&[
-                "new-session", "-d", "-s", &sanitized, "-c", &worktree_path, &program,
-            ])) {
-                let _ = sender.send(BackgroundUpdate::InstanceFailed(idx, e.to_string()));
-                return;
-            }
+    /// Input for a new session's title, showing the branch and worktree it
+    /// leads to and refusing titles git can't make a branch of.
+    fn title_input(&self, heading: &str) -> TextInputOverlay {
+        use crate::session::git::util::{checked_out_branches, local_branches};
 
-            // Handle trust prompt (slow: 0-45s polling)
-            let timeout_secs: u64 = match program.as_str() {
-                "claude" => 30,
-                "aider" | "gemini" => 45,
-                _ => 0,
-            };
-            if timeout_secs > 0 {
-                let start = std::time::Instant::now();
-                let mut interval = std::time::Duration::from_millis(100);
-                let (trust_string, response_keys): (&str, Vec<&str>) = if program == "claude" {
-                    ("Do you trust the files in this folder?", vec!["Enter"])
-                } else {
-                    ("Open documentation url", vec!["d", "Enter"])
-                };
+        // The branches are listed on a worker and the check filled in with
+        // them as `BackgroundUpdate::BranchesListed`.
+        if let Ok(dir) = self.repo_dir() {
+            let sender = self.bg_sender.clone();
+            let cmd = std::sync::Arc::clone(&self.cmd);
+            let heading = heading.to_string();
+            std::thread::spawn(move || {
+                let dir = dir.to_string_lossy();
+                let branches = local_branches(&dir, &*cmd).unwrap_or_default();
+                let checked_out = checked_out_branches(&dir, &*cmd).unwrap_or_default();
+                let _ =
+                    sender.send(BackgroundUpdate::BranchesListed(heading, branches, checked_out));
+            });
+        }
+        let check = self.title_check(Vec::new(), Vec::new());
+        TextInputOverlay::new(heading)
+            .with_max_len(self.config.title_max_length)
+            .with_check(move |title| check.check(title))
+    }
 
-                while start.elapsed().as_secs() < timeout_secs {
-                    std::thread::sleep(interval);
-                    if let Ok(content) = cmd.output("tmux", &args(&[
-                        "capture-pane", "-p", "-t", &sanitized,
-                    ])) {
-                        if content.contains(trust_string) {
-                            for key in &response_keys {
-                                let _ = cmd.run("tmux", &args(&["send-keys", "-t", &sanitized, key]));
-                            }
-                            break;
-                        }
-                    }
-                    interval = std::cmp::min(
-                        std::time::Duration::from_millis((interval.as_millis() as f64 * 1.2) as u64),
-                        std::time::Duration::from_secs(1),
-                    );
-                }
-            }
+    fn title_check(
+        &self,
+        branches: Vec<String>,
+        checked_out: Vec<(String, String)>,
+    ) -> crate::session::title::TitleCheck {
+        crate::session::title::TitleCheck {
+            branch_prefix: self.config.branch_prefix.clone(),
+            taken: self.instances.iter().map(|i| i.title.clone()).collect(),
+            branches,
+            checked_out,
+            worktrees_dir: home_relative(&self.config_dir.join("worktrees")),
+            time_suffix: self.config.branch_unique_suffix,
+        }
+    }
 
-            // Success -- send worktree back to main thread
-            let _ = sender.send(BackgroundUpdate::InstanceReady(idx, worktree));
+    /// Create a session, first asking whether to carry over uncommitted
+    /// changes when the current repo is dirty. The repo is checked on a
+    /// worker; the answer arrives as `BackgroundUpdate::DirtyChecked`.
+    fn request_create(&mut self, title: String, prompt: String) {
+        let Ok(cwd) = self.repo_dir() else {
+            // Reports why there's no repo
+            self.confirm_create(title, prompt, false);
+            return;
+        };
+        let sender = self.bg_sender.clone();
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            let dirty = crate::session::git::util::is_repo_dirty(&cwd.to_string_lossy(), &*cmd)
+                .unwrap_or(false);
+            let _ = sender.send(BackgroundUpdate::DirtyChecked(title, prompt, dirty));
         });
+    }
 
-        Ok(())
+    /// Create a session, asking first when the repo is `dirty` and nothing
+    /// else took over the screen meanwhile.
+    fn confirm_create(&mut self, title: String, prompt: String, dirty: bool) {
+        if dirty && self.state == AppState::Default {
+            let msg = format!(
+                "The repo has uncommitted changes. Carry them into '{}'?",
+                title
+            );
+            self.confirmation = Some(ConfirmationOverlay::with_buttons(
+                msg,
+                vec![
+                    ConfirmButton::new('y', "Carry them", Choice::Yes),
+                    ConfirmButton::new('n', "Branch clean", Choice::No),
+                ],
+            ));
+            self.pending_action = Some(PendingAction::Create { title, prompt });
+            self.state = AppState::Confirm;
+        } else if let Err(e) = self.create_instance_with_prompt(title, prompt) {
+            self.error.set_error(e.to_string());
+        }
     }
 
-    fn create_instance_with_prompt(
+    fn create_instance_full(
         &mut self,
         title: String,
         prompt: String,
+        carry_changes: bool,
     ) -> anyhow::Result<()> {
-        // Store the prompt for delivery after InstanceReady arrives
-        let idx = self.instances.len(); // will be the index after create_instance pushes
-        if !prompt.is_empty() {
-            self.pending_prompts.insert(idx, prompt);
+        let cwd = self.repo_dir()?.to_string_lossy().to_string();
+        let title = self.suffixed(title);
+        // Sessions sharing a tmux name or branch would clobber each other
+        let unique = crate::session::title::unique_title(&title, &self.instances);
+        if unique != title {
+            self.error
+                .set_info(format!("A session named '{}' exists; created '{}'", title, unique));
         }
-        self.create_instance(title)
+        let title = unique;
+
+        // Create a placeholder instance; it waits in the queue until a
+        // creation slot is free. The prompt rides on the instance until
+        // InstanceReady arrives.
+        let mut instance = Instance::new(InstanceOptions {
+            title,
+            path: cwd,
+            program: self.config.default_program.clone(),
+            auto_yes: self.config.auto_yes,
+            git_identity: self.config.git_identity.clone(),
+            shared_dirs: self.config.shared_dirs.clone(),
+            shared_dirs_mode: self.config.shared_dirs_mode,
+        });
+        instance.status = InstanceStatus::Queued;
+        instance.pending_prompt = (!prompt.is_empty()).then_some(prompt);
+        instance.carry_changes = carry_changes;
+        instance.container = self.new_container(&instance)?;
+        self.instances.push(instance);
+        self.start_queued_creations();
+
+        Ok(())
     }
 
-    fn kill_instance(&mut self, idx: usize) -> anyhow::Result<()> {
-        let cmd = SystemCmdExec;
-        if idx < self.instances.len() {
-            self.instances[idx].kill(&cmd)?;
-            self.instances.remove(idx);
-            self.refresh_list();
-            self.save_instances()?;
+    /// Create a session that checks out an existing local or remote branch,
+    /// e.g. to continue someone else's PR. The session is named after the
+    /// branch, without the remote prefix.
+    fn create_instance_on_branch(&mut self, branch: String) -> anyhow::Result<()> {
+        let cwd = self.repo_dir()?.to_string_lossy().to_string();
+        let title = crate::session::git::util::strip_remote(&branch, &self.branch_remotes)
+            .unwrap_or(&branch)
+            .to_string();
+        if let Some(existing) = crate::session::title::clashing(&title, &self.instances) {
+            anyhow::bail!("A session named '{}' already exists", existing.title);
         }
+
+        let mut instance = Instance::new(InstanceOptions {
+            title,
+            path: cwd,
+            program: self.config.default_program.clone(),
+            auto_yes: self.config.auto_yes,
+            git_identity: self.config.git_identity.clone(),
+            shared_dirs: self.config.shared_dirs.clone(),
+            shared_dirs_mode: self.config.shared_dirs_mode,
+        });
+        instance.status = InstanceStatus::Queued;
+        instance.existing_branch = Some(branch);
+        instance.container = self.new_container(&instance)?;
+        self.instances.push(instance);
+        self.start_queued_creations();
+
         Ok(())
     }
 
-    fn delete_instance(&mut self, idx: usize) -> anyhow::Result<()> {
-        if idx < self.instances.len() {
-            self.instances.remove(idx);
-            self.refresh_list();
-            self.save_instances()?;
+    /// A new session's title with the creation time appended when
+    /// `branch_unique_suffix` is on.
+    fn suffixed(&self, title: String) -> String {
+        if self.config.branch_unique_suffix {
+            crate::session::title::with_time_suffix(&title, chrono::Local::now())
+        } else {
+            title
         }
+    }
+
+    /// Create a session set up like `source` on a fresh branch, titled
+    /// `title` or, left empty, after `source`.
+    fn create_sibling(&mut self, source: &str, title: String) -> anyhow::Result<()> {
+        let Some(idx) = self.instance_index(source) else {
+            anyhow::bail!("Session '{}' is gone", source);
+        };
+        let title = if title.trim().is_empty() { source.to_string() } else { title };
+        let title = crate::session::title::unique_title(&self.suffixed(title), &self.instances);
+        let mut instance = self.instances[idx].sibling(title);
+        instance.status = InstanceStatus::Queued;
+        self.instances.push(instance);
+        self.start_queued_creations();
         Ok(())
     }
 
-    fn refresh_list(&mut self) {
-        self.list.set_items(&self.instances);
+    /// The container a new session runs in when `container_sessions` is on.
+    fn new_container(&self, instance: &Instance) -> anyhow::Result<Option<Container>> {
+        if !self.config.container_sessions {
+            return Ok(None);
+        }
+        Ok(Some(Container::for_session(&instance.title, &instance.program, &self.config)?))
     }
 
-    /// Reconnect loaded instances to their still-running tmux sessions.
-    /// If a tmux session no longer exists, mark the instance as Ready.
-    fn restore_loaded_instances(&mut self) {
-        use crate::session::InstanceStatus;
-        for instance in &mut self.instances {
-            if instance.status == InstanceStatus::Running {
-                if instance.restore_session().is_err() {
-                    // tmux session is gone — mark as not running
-                    instance.status = InstanceStatus::Ready;
-                    instance.started = false;
-                }
-            }
+    /// Queue a failed session for creation again with its original options
+    /// and prompt.
+    fn retry_creation(&mut self, idx: usize) {
+        let instance = &mut self.instances[idx];
+        instance.error = None;
+        instance.status = InstanceStatus::Queued;
+        self.error.clear();
+        self.start_queued_creations();
+    }
+
+    /// Start queued sessions while fewer than `max_concurrent_creations`
+    /// are being set up.
+    fn start_queued_creations(&mut self) {
+        for job in self.promote_queued() {
+            self.spawn_creation(job);
         }
         self.refresh_list();
+        // Record new and queued sessions so their prompts survive an exit
+        let _ = self.save_instances();
     }
 
-    fn load_instances(&mut self) -> anyhow::Result<()> {
-        let storage = FileStorage::new(&self.config_dir);
-        match storage.load_instances() {
-            Ok(instances) => {
-                self.instances = instances;
-                self.refresh_list();
+    /// Mark queued sessions as Loading, oldest first, up to the free
+    /// creation slots. Returns what each background setup needs.
+    fn promote_queued(&mut self) -> Vec<SessionFactory> {
+        let limit = self.config.max_concurrent_creations.max(1);
+        let mut promoted = Vec::new();
+        for instance in &mut self.instances {
+            if self.creating.len() >= limit {
+                break;
             }
-            Err(e) => {
-                self.error.set_error(format!("Failed to load sessions: {}", e));
+            if instance.status == InstanceStatus::Queued {
+                instance.status = InstanceStatus::Loading;
+                self.creating.insert(instance.title.clone());
+                promoted.push(SessionFactory::new(instance, self.config.clone()));
             }
         }
-        Ok(())
+        promoted
     }
 
-    fn save_instances(&self) -> anyhow::Result<()> {
-        let storage = FileStorage::new(&self.config_dir);
-        storage.save_instances(&self.instances)?;
-        Ok(())
+    /// Spawn a background thread for slow git worktree + tmux creation.
+    fn spawn_creation(&self, factory: SessionFactory) {
+        let sender = self.bg_sender.clone();
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            let title = factory.title.clone();
+            let mut progress = |step| {
+                let _ = sender.send(BackgroundUpdate::CreationProgress(title.clone(), step));
+            };
+            let update = match factory.create(&cmd, &mut progress) {
+                // The main thread answers the trust prompt
+                Ok(created) => BackgroundUpdate::InstanceReady(title, Box::new(created)),
+                Err(e) => BackgroundUpdate::InstanceFailed(title, e.to_string()),
+            };
+            let _ = sender.send(update);
+        });
     }
 
-    /// Spawn background threads to fetch preview content and diff stats.
-    /// Results arrive via `bg_sender` channel and are processed by
-    /// `process_background_updates()`.
-    fn schedule_background_updates(&self) {
-        let idx = self.list.selected_index();
-        if let Some(instance) = self.instances.get(idx) {
-            if instance.status != InstanceStatus::Running || !instance.started {
-                return;
-            }
+    fn commit_options(&self) -> CommitOptions {
+        CommitOptions::from_config(&self.config)
+    }
 
-            // Preview: check session exists, then capture pane content
-            let title = instance.title.clone();
-            let sender = self.bg_sender.clone();
-            let s1 = sender.clone();
-            std::thread::spawn(move || {
-                let sanitized = crate::session::tmux::sanitize_name(&title);
-                let cmd = SystemCmdExec;
+    /// Indices of paused sessions whose worktree directory still exists.
+    fn paused_worktrees(&self) -> Vec<usize> {
+        self.instances
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| {
+                i.status == InstanceStatus::Paused
+                    && i.transition.is_none()
+                    && i.git_worktree
+                        .as_ref()
+                        .is_some_and(|wt| std::path::Path::new(wt.worktree_path()).exists())
+            })
+            .map(|(idx, _)| idx)
+            .collect()
+    }
 
-                // Check if tmux session still exists
-                if cmd.run("tmux", &args(&["has-session", "-t", &sanitized])).is_err() {
-                    let _ = s1.send(BackgroundUpdate::SessionDied(idx));
-                    return;
-                }
+    /// Remove the worktrees of paused sessions in the background, committing
+    /// leftover changes to their branches first. Branches are kept so `p`
+    /// can resume them.
+    fn cleanup_paused_worktrees(&mut self) {
+        for idx in self.paused_worktrees() {
+            self.start_transition(idx, Transition::CleaningUp);
+        }
+    }
 
-                if let Ok(content) = cmd.output(
-                    "tmux",
-                    &args(&["capture-pane", "-p", "-e", "-J", "-t", &sanitized]),
-                ) {
-                    let _ = s1.send(BackgroundUpdate::PreviewContent(idx, content));
-                }
-            });
+    /// Pause idle sessions and archive long-paused ones, per
+    /// `idle_pause_hours` and `archive_paused_days`. Work is committed and
+    /// branches are kept, in the background; what happened is reported in
+    /// the status bar.
+    ///
+    /// Idle sessions are found on a worker, since that asks tmux about each
+    /// one; see `ExpiryChecked`.
+    fn apply_expiry_policy(&mut self) {
+        let policy = ExpiryPolicy::from_config(&self.config);
+        if !policy.is_enabled() {
+            return;
+        }
+        let now = chrono::Utc::now();
+        let to_archive: Vec<usize> = self
+            .instances
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| i.transition.is_none() && policy.should_archive(i, now))
+            .map(|(idx, _)| idx)
+            .collect();
+        if !to_archive.is_empty() {
+            let titles: Vec<&str> = to_archive.iter().map(|&i| self.instances[i].title.as_str()).collect();
+            self.error.set_info(format!(
+                "Archiving (branches kept, see `gana archived`): {}",
+                titles.join(", ")
+            ));
+        }
+        for idx in to_archive {
+            self.start_transition(idx, Transition::Archiving);
+        }
 
-            // Diff: compute git diff in background
-            if let Some(ref worktree) = instance.git_worktree {
-                let wt = worktree.clone();
-                std::thread::spawn(move || {
-                    let cmd = SystemCmdExec;
-                    let stats = wt.diff(&cmd);
-                    let _ = sender.send(BackgroundUpdate::DiffComputed(idx, stats));
-                });
+        if policy.pause_idle_after.is_none() || self.expiry_check_in_flight {
+            return;
+        }
+        let running: Vec<Instance> = self
+            .instances
+            .iter()
+            .filter(|i| i.transition.is_none() && i.status.is_running() && i.tmux_session.is_some())
+            .cloned()
+            .collect();
+        if running.is_empty() {
+            return;
+        }
+        self.expiry_check_in_flight = true;
+        let sender = self.bg_sender.clone();
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            let idle = running
+                .into_iter()
+                .filter(|instance| {
+                    TmuxSession::activity(&instance.title, &*cmd)
+                        .is_ok_and(|at| policy.should_pause(instance, at, now))
+                })
+                .map(|instance| instance.title)
+                .collect();
+            let _ = sender.send(BackgroundUpdate::ExpiryChecked(idle));
+        });
+    }
+
+    /// Pause the sessions found idle, unless they changed meanwhile.
+    fn pause_idle(&mut self, titles: Vec<String>) {
+        self.expiry_check_in_flight = false;
+        let mut paused = Vec::new();
+        for title in titles {
+            let Some(idx) = self.instance_index(&title) else {
+                continue;
+            };
+            let instance = &self.instances[idx];
+            if instance.transition.is_none() && instance.status.is_running() {
+                self.start_transition(idx, Transition::Pausing);
+                paused.push(title);
             }
         }
+        if !paused.is_empty() {
+            self.error.set_info(format!("Pausing idle: {}", paused.join(", ")));
+        }
     }
 
-    /// Drain the background update channel and apply results to the UI.
-    /// This is non-blocking — `try_recv()` returns immediately if empty.
-    fn process_background_updates(&mut self) {
-        while let Ok(update) = self.bg_receiver.try_recv() {
-            match update {
-                BackgroundUpdate::PreviewContent(idx, content) => {
-                    if idx == self.list.selected_index() {
-                        self.preview.set_content(&content);
-                    }
+    /// Move a session whose worktree was cleaned up to the archive.
+    fn archive_instance(&mut self, idx: usize) {
+        let title = self.instances[idx].title.clone();
+        // The archive is the session's only record once it leaves the list
+        if let Err(e) = FileStorage::new(&self.config_dir).archive(vec![self.instances[idx].clone()]) {
+            self.error
+                .set_error(format!("Saving '{}' to the archive failed: {}", title, e));
+            return;
+        }
+        self.instances.remove(idx);
+        self.refresh_list();
+        let _ = self.save_instances();
+        self.error
+            .set_info(format!("Archived '{}' (branch kept, see `gana archived`)", title));
+    }
+
+    /// Keep worktree watches in line with the running sessions and start
+    /// `test_command` for sessions whose files settled after a change.
+    fn run_watched_tests(&mut self) {
+        let command = match self.config.test_command {
+            Some(ref command) if self.config.watch_tests => command.clone(),
+            _ => return,
+        };
+
+        let watched: Vec<(String, String)> = self
+            .instances
+            .iter()
+            .filter(|i| i.status.is_running())
+            .filter_map(|i| {
+                let wt = i.git_worktree.as_ref()?;
+                let dir = wt.worktree_path();
+                std::path::Path::new(dir)
+                    .exists()
+                    .then(|| (i.title.clone(), dir.to_string()))
+            })
+            .collect();
+        self.test_watcher
+            .retain(|title| watched.iter().any(|(t, _)| t == title));
+        for (title, dir) in &watched {
+            if self.test_watcher.is_watching(title) {
+                continue;
+            }
+            match self.test_watcher.watch(title, std::path::Path::new(dir)) {
+                // Run once up front so the list shows a status straight away
+                Ok(()) => {
+                    self.tests_due.insert(title.clone(), Instant::now());
                 }
-                BackgroundUpdate::DiffComputed(idx, stats) => {
-                    if idx == self.list.selected_index() {
-                        self.diff_view.set_diff(&stats);
-                    }
-                    if let Some(instance) = self.instances.get_mut(idx) {
-                        instance.diff_stats = Some(stats);
-                        self.refresh_list();
-                    }
+                Err(e) => tracing::warn!(session = %title, "Failed to watch the worktree: {}", e),
+            }
+        }
+
+        while let Ok(title) = self.test_changes.try_recv() {
+            self.tests_due.insert(title, Instant::now());
+        }
+        let ready: Vec<String> = self
+            .tests_due
+            .iter()
+            .filter(|(title, changed)| {
+                changed.elapsed() >= TEST_DEBOUNCE && !self.tests_running.contains(*title)
+            })
+            .map(|(title, _)| title.clone())
+            .collect();
+        for title in ready {
+            self.tests_due.remove(&title);
+            let Some((_, dir)) = watched.iter().find(|(t, _)| *t == title) else {
+                continue;
+            };
+            if let Some(idx) = self.instance_index(&title) {
+                self.instances[idx].test_status = Some(TestStatus::Running);
+            }
+            self.tests_running.insert(title.clone());
+            let sender = self.bg_sender.clone();
+            let command = command.clone();
+            let dir = dir.clone();
+            std::thread::spawn(move || {
+                let status = crate::session::test_watch::run_tests(&command, &dir, &SystemCmdExec);
+                let _ = sender.send(BackgroundUpdate::TestsFinished(title, status));
+            });
+            self.refresh_list();
+        }
+    }
+
+    /// Measure worktree disk usage on a background thread.
+    fn schedule_disk_scan(&mut self) {
+        if self.disk_scan_in_flight {
+            return;
+        }
+        self.disk_scan_in_flight = true;
+        let worktrees: Vec<(String, String)> = self
+            .instances
+            .iter()
+            .filter_map(|i| {
+                let wt = i.git_worktree.as_ref()?;
+                Some((i.title.clone(), wt.worktree_path().to_string()))
+            })
+            .collect();
+        let config_dir = self.config_dir.clone();
+        let sender = self.bg_sender.clone();
+        std::thread::spawn(move || {
+            let usage = DiskUsage::scan(
+                worktrees.iter().map(|(t, d)| (t.as_str(), d.as_str())),
+                &config_dir,
+            );
+            let _ = sender.send(BackgroundUpdate::DiskUsage(usage));
+        });
+    }
+
+    /// Publish this machine's sessions to the team repo and fetch the
+    /// teammates' on a background thread. All sessions are published, not
+    /// only the current project's.
+    fn schedule_team_sync(&mut self) {
+        if self.team_sync_in_flight {
+            return;
+        }
+        self.team_sync_in_flight = true;
+        let config = self.config.clone();
+        let config_dir = self.config_dir.clone();
+        let sender = self.bg_sender.clone();
+        std::thread::spawn(move || {
+            let result = FileStorage::new(&config_dir)
+                .load_instances()
+                .map_err(|e| e.to_string())
+                .and_then(|instances| {
+                    TeamStore::from_config(&config, &config_dir, &SystemCmdExec)
+                        .and_then(|store| store.sync(&instances, &SystemCmdExec))
+                        .map_err(|e| e.to_string())
+                });
+            let _ = sender.send(BackgroundUpdate::TeamSynced(result));
+        });
+    }
+
+    /// Store a finished disk scan and warn once each time the worktrees
+    /// directory goes over `worktree_budget_mb`.
+    fn apply_disk_usage(&mut self, usage: DiskUsage) {
+        self.disk_scan_in_flight = false;
+        let over_budget = self
+            .config
+            .worktree_budget_mb
+            .is_some_and(|mb| usage.total > mb.saturating_mul(1024 * 1024));
+        if over_budget && !self.budget_warned {
+            self.error.set_error(format!(
+                "Worktrees use {}, over the {} MB budget. Press C to remove worktrees of paused sessions.",
+                crate::session::disk_usage::format_size(usage.total),
+                self.config.worktree_budget_mb.unwrap_or_default()
+            ));
+        }
+        self.budget_warned = over_budget;
+        self.disk_usage = usage;
+    }
+
+    /// Quit, handling running sessions according to `config.on_quit`.
+    fn request_quit(&mut self) {
+        let running = self
+            .instances
+            .iter()
+            .filter(|i| i.status.is_running())
+            .count();
+        if running == 0 {
+            self.running = false;
+            return;
+        }
+
+        match self.config.on_quit {
+            QuitBehavior::Leave => self.running = false,
+            QuitBehavior::Pause => {
+                self.pause_all_running();
+                self.running = false;
+            }
+            QuitBehavior::Ask => {
+                let msg = format!(
+                    "{} session(s) still running. Pause before quitting?",
+                    running
+                );
+                self.confirmation = Some(ConfirmationOverlay::with_buttons(
+                    msg,
+                    vec![
+                        ConfirmButton::new('y', "Pause", Choice::Yes),
+                        ConfirmButton::new('n', "Leave running", Choice::No),
+                    ],
+                ));
+                self.pending_action = Some(PendingAction::Quit);
+                self.state = AppState::Confirm;
+            }
+        }
+    }
+
+    /// Pause every running session (auto-commit + remove worktree).
+    fn pause_all_running(&mut self) {
+        let cmd = SystemCmdExec;
+        let opts = self.commit_options();
+        for instance in &mut self.instances {
+            if instance.status.is_running()
+                && let Err(e) = instance.pause(&opts, &cmd)
+            {
+                self.error
+                    .set_error(format!("Pause of '{}' failed: {}", instance.title, e));
+            }
+        }
+        self.refresh_list();
+        let _ = self.save_instances();
+    }
+
+    /// Summary of sessions left running, printed after the TUI exits.
+    fn background_summary(&self) -> Option<String> {
+        let running: Vec<&Instance> = self
+            .instances
+            .iter()
+            .filter(|i| i.status.is_running())
+            .collect();
+        if running.is_empty() {
+            return None;
+        }
+        let mut summary = format!(
+            "{} session(s) still running in the background:",
+            running.len()
+        );
+        for instance in running {
+            summary.push_str(&format!("\n  ● {}", instance.title));
+            if !instance.branch.is_empty() {
+                summary.push_str(&format!(" [{}]", instance.branch));
+            }
+        }
+        summary.push_str("\nRun `gana` to check on them.");
+        Some(summary)
+    }
+
+    /// Pause, resume or kill a session on a background thread.
+    ///
+    /// The worker gets a copy of the session along with its tmux session;
+    /// until the result arrives the session shows the transition and isn't
+    /// polled.
+    fn start_transition(&mut self, idx: usize, transition: Transition) {
+        let opts = self.commit_options();
+        let instance = &mut self.instances[idx];
+        instance.transition = Some(transition);
+        let mut worker = instance.clone();
+        worker.tmux_session = instance.tmux_session.take();
+        self.menu
+            .start_operation(&Self::transition_label(transition, &worker.title));
+        self.refresh_list();
+
+        let config = self.config.clone();
+        let sender = self.bg_sender.clone();
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            let update = match transition {
+                Transition::Pausing | Transition::Closing => {
+                    let result = worker.pause(&opts, &cmd).map_err(|e| e.to_string());
+                    BackgroundUpdate::PauseCompleted(Box::new(worker), result)
                 }
-                BackgroundUpdate::InstanceReady(idx, worktree) => {
-                    if let Some(instance) = self.instances.get_mut(idx) {
-                        instance.branch = worktree.branch().to_string();
-                        instance.git_worktree = Some(worktree);
-
-                        // Attach to the tmux session (fast -- just opens PTY)
-                        if instance.restore_session().is_ok() {
-                            instance.status = InstanceStatus::Running;
-                        } else {
-                            instance.status = InstanceStatus::Ready;
-                            self.error.set_error("Failed to attach to session".to_string());
+                Transition::Resuming => {
+                    let result = worker.resume(&cmd, &config).map_err(|e| e.to_string());
+                    BackgroundUpdate::ResumeCompleted(Box::new(worker), result)
+                }
+                Transition::CleaningUp | Transition::Archiving => {
+                    // Paused sessions normally have no worktree left; commit
+                    // and remove any that predate that
+                    let result = match worker.git_worktree {
+                        Some(ref wt) if std::path::Path::new(wt.worktree_path()).exists() => {
+                            let msg = opts.autosave_message(&worker.message_context());
+                            wt.commit_changes(&msg, &opts, &cmd)
+                                .and_then(|_| wt.remove(&cmd))
+                                .map_err(|e| e.to_string())
                         }
+                        _ => Ok(()),
+                    };
+                    BackgroundUpdate::PauseCompleted(Box::new(worker), result)
+                }
+                Transition::Killing => {
+                    // Snapshot the branch and uncommitted work so the kill can be undone
+                    let worktree = worker.git_worktree.clone();
+                    let snapshot = worktree.as_ref().and_then(|wt| wt.snapshot(&cmd).ok());
+                    let result = worker.kill(&cmd).map_err(|e| e.to_string());
+                    let killed = KilledSession { instance: worker, worktree, snapshot };
+                    BackgroundUpdate::KillCompleted(Box::new(killed), result)
+                }
+            };
+            let _ = sender.send(update);
+        });
+    }
+
+    fn transition_label(transition: Transition, title: &str) -> String {
+        format!("{} '{}'", transition.label(), title)
+    }
+
+    /// Apply a finished pause, resume or kill. Returns the session's index
+    /// when it still exists.
+    ///
+    /// The state the worker left behind is copied back even on failure: a
+    /// pause can fail after closing tmux, a kill after removing the worktree.
+    fn finish_transition(
+        &mut self,
+        worker: Instance,
+        result: &Result<(), String>,
+    ) -> Option<usize> {
+        let transition = worker.transition?;
+        self.menu
+            .finish_operation(&Self::transition_label(transition, &worker.title));
+        let idx = self.instance_index(&worker.title)?;
+        let instance = &mut self.instances[idx];
+        instance.transition = None;
+        instance.status = worker.status;
+        instance.started = worker.started;
+        instance.tmux_session = worker.tmux_session;
+        instance.git_worktree = worker.git_worktree;
+        instance.updated_at = worker.updated_at;
+        match result {
+            Ok(()) => tracing::info!(session = %worker.title, "{} finished", transition.label()),
+            Err(e) => {
+                tracing::error!(session = %worker.title, "{} failed: {}", transition.label(), e);
+                self.error
+                    .set_error(format!("{} '{}' failed: {}", transition.label(), worker.title, e));
+            }
+        }
+        self.refresh_list();
+        let _ = self.save_instances();
+        Some(idx)
+    }
+
+    fn finish_kill(&mut self, killed: KilledSession, result: Result<(), String>) {
+        let KilledSession { instance, worktree, snapshot } = killed;
+        let Some(idx) = self.finish_transition(instance, &result) else {
+            return;
+        };
+        if result.is_ok() {
+            let instance = self.instances.remove(idx);
+            let worktree = worktree.map(Box::new);
+            self.push_undo(UndoEntry::Killed { instance, index: idx, worktree, snapshot });
+            self.refresh_list();
+            if let Err(e) = self.save_instances() {
+                self.error.set_error(e.to_string());
+            }
+        }
+    }
+
+    /// Delete a session after closing its tmux session and removing its
+    /// worktree. Work is committed to the branch as on pause, so undo brings
+    /// the session back paused.
+    fn close_and_delete(&mut self, idx: usize) -> anyhow::Result<()> {
+        let Some(instance) = self.instances.get(idx) else {
+            return Ok(());
+        };
+        if instance.transition.is_some() {
+            return Ok(());
+        }
+        let worktree_on_disk = instance
+            .git_worktree
+            .as_ref()
+            .is_some_and(|wt| std::path::Path::new(wt.worktree_path()).exists());
+        if instance.tmux_session.is_some() || worktree_on_disk {
+            self.start_transition(idx, Transition::Closing);
+            Ok(())
+        } else {
+            self.delete_instance(idx)
+        }
+    }
+
+    /// Refuse to remove the session at `idx` while its worktree and tmux
+    /// session are being set up in the background, which would leave them
+    /// behind. Returns whether it was refused.
+    fn reject_while_creating(&mut self, idx: usize) -> bool {
+        let Some(instance) = self.instances.get(idx) else {
+            return false;
+        };
+        if !self.creating.contains(&instance.title) {
+            return false;
+        }
+        self.error.set_error(format!(
+            "'{}' is still being set up; wait for it to finish before removing it",
+            instance.title
+        ));
+        true
+    }
+
+    /// Close the tmux session and remove the worktree of a session that
+    /// was set up after it left the list, in the background. Its branch
+    /// goes too when the setup created it.
+    fn discard_created(&self, title: String, created: CreatedSession) {
+        let cmd = std::sync::Arc::clone(&self.cmd);
+        std::thread::spawn(move || {
+            let CreatedSession { worktree, mut tmux, owns_branch, .. } = created;
+            if let Err(e) = tmux.close() {
+                tracing::warn!(session = %title, "Failed to close the tmux session: {}", e);
+            }
+            let removed = if owns_branch { worktree.cleanup(&cmd) } else { worktree.remove(&cmd) };
+            if let Err(e) = removed {
+                tracing::warn!(session = %title, "Failed to remove the worktree: {}", e);
+            }
+        });
+    }
+
+    fn delete_instance(&mut self, idx: usize) -> anyhow::Result<()> {
+        if idx < self.instances.len() {
+            let instance = self.instances.remove(idx);
+            self.push_undo(UndoEntry::Deleted { instance, index: idx });
+            self.refresh_list();
+            self.save_instances()?;
+        }
+        Ok(())
+    }
+
+    fn push_undo(&mut self, entry: UndoEntry) {
+        if self.undo_buffer.len() == UNDO_LIMIT {
+            self.undo_buffer.pop_front();
+        }
+        self.undo_buffer.push_back(entry);
+    }
+
+    /// Restore the most recently deleted or killed session.
+    fn undo_last_removal(&mut self) -> anyhow::Result<()> {
+        let Some(entry) = self.undo_buffer.pop_back() else {
+            return Ok(());
+        };
+
+        let (mut instance, index) = match entry {
+            UndoEntry::Deleted { instance, index } => (instance, index),
+            UndoEntry::Killed { mut instance, index, worktree, snapshot } => {
+                // Recreate the branch, then bring the worktree and agent back
+                if let (Some(wt), Some(snap)) = (worktree, snapshot) {
+                    let cmd = std::sync::Arc::clone(&self.cmd);
+                    wt.restore_branch(&snap, &cmd)?;
+                    instance.git_worktree = Some(*wt);
+                    instance.resume(&cmd, &self.config)?;
+                    instance.started = true;
+                    if let Some(ref wt) = instance.git_worktree {
+                        wt.apply_snapshot(&snap, &cmd)?;
+                    }
+                }
+                (instance, index)
+            }
+        };
+
+        let index = index.min(self.instances.len());
+        instance.touch();
+        self.instances.insert(index, instance);
+        self.answer_trust_prompt(index);
+        self.refresh_list();
+        self.list.set_selected(index);
+        self.selection_changed();
+        self.save_instances()?;
+        Ok(())
+    }
+
+    /// Watch for a started session's trust prompt on a background thread
+    /// and answer it; the result arrives as `BackgroundUpdate::TrustAnswered`.
+    /// Returns false when there is nothing to watch for.
+    fn answer_trust_prompt(&mut self, idx: usize) -> bool {
+        let Some(instance) = self.instances.get(idx) else {
+            return false;
+        };
+        let Some(trust) = instance.trust_prompt() else {
+            return false;
+        };
+        let title = instance.title.clone();
+        if !self.answering_trust.insert(title.clone()) {
+            return true;
+        }
+        let sender = self.bg_sender.clone();
+        std::thread::spawn(move || {
+            let result = trust.answer(&SystemCmdExec).map_err(|e| e.to_string());
+            let _ = sender.send(BackgroundUpdate::TrustAnswered(title, result));
+        });
+        true
+    }
+
+    /// Send a started session's initial prompt, if it has one left.
+    fn deliver_pending_prompt(&mut self, idx: usize) -> bool {
+        let Some(instance) = self.instances.get_mut(idx) else {
+            return false;
+        };
+        let Some(prompt) = instance.deliver_pending_prompt() else {
+            return false;
+        };
+        self.journal.record(&instance.title, EventKind::PromptSent, Some(&prompt));
+        true
+    }
+
+    fn refresh_list(&mut self) {
+        self.list.set_items(&self.instances);
+        if self.show_board {
+            self.board.set_items(&self.instances, &self.waiting);
+        }
+        // Removals and reordering can select another session
+        self.update_diff_badge();
+    }
+
+    /// Find which running agents wait for input, for the board's Waiting
+    /// column, on a background thread.
+    fn schedule_attention_check(&self) {
+        let sessions: Vec<crate::session::tmux::TmuxSession> = self
+            .instances
+            .iter()
+            .filter(|i| i.status.is_running())
+            .map(|i| {
+                crate::session::tmux::TmuxSession::new(
+                    &i.title,
+                    &i.program,
+                    Box::new(SystemCmdExec),
+                    Box::new(crate::session::tmux::pty::SystemPtyFactory),
+                )
+                .with_prompts(self.config.prompt_patterns(&i.program))
+            })
+            .collect();
+        let sender = self.bg_sender.clone();
+        std::thread::spawn(move || {
+            let waiting = sessions
+                .into_iter()
+                .filter(|tmux| tmux.needs_attention().unwrap_or(false))
+                .map(|tmux| tmux.session_name().to_string())
+                .collect();
+            let _ = sender.send(BackgroundUpdate::AttentionChecked(waiting));
+        });
+    }
+
+    /// Check loaded instances against the repo and reconnect them to their
+    /// still-running tmux sessions.
+    ///
+    /// Runs on a background thread so the list shows up immediately. Sessions
+    /// whose worktree or branch has gone arrive as `SessionReconciled`; the
+    /// others' reconnects as `SessionRestored`. Instances whose tmux session
+    /// no longer exists are marked Ready. Interrupted creations start again.
+    fn restore_loaded_instances(&mut self) {
+        if self.requeue_interrupted_creations() {
+            self.start_queued_creations();
+        }
+
+        let pending = Self::mark_restoring(&mut self.instances);
+        self.refresh_list();
+        self.restore_in_background(pending);
+    }
+
+    /// Flag the running sessions among freshly loaded `instances` as
+    /// restoring. Returns copies of those to check on, with any others that
+    /// still have a worktree.
+    fn mark_restoring(instances: &mut [Instance]) -> Vec<Instance> {
+        let mut pending = Vec::new();
+        for instance in instances.iter_mut() {
+            if instance.status.is_running() {
+                instance.restoring = true;
+            }
+            if instance.status.is_running() || instance.git_worktree.is_some() {
+                pending.push(instance.clone());
+            }
+        }
+        pending
+    }
+
+    /// Reconcile `pending` with what's on disk and reconnect the running
+    /// ones to tmux on a background thread.
+    fn restore_in_background(&self, pending: Vec<Instance>) {
+        if pending.is_empty() {
+            return;
+        }
+
+        let config = self.config.clone();
+        let sender = self.bg_sender.clone();
+        std::thread::spawn(move || {
+            for instance in pending {
+                if let Some((status, reason)) = reconcile::check(&instance, &SystemCmdExec) {
+                    let _ = sender.send(BackgroundUpdate::SessionReconciled(instance.title, status, reason));
+                } else if instance.status.is_running() {
+                    let prompts = config.prompt_patterns(&instance.program);
+                    let tmux = Instance::connect_tmux(&instance.title, &instance.program, prompts)
+                        .ok()
+                        .map(Box::new);
+                    let _ = sender.send(BackgroundUpdate::SessionRestored(instance.title, tmux));
+                }
+            }
+        });
+    }
+
+    /// Queue sessions whose creation was cut short by the last exit again,
+    /// so they get set up and receive their prompt. Returns whether any were.
+    fn requeue_interrupted_creations(&mut self) -> bool {
+        let mut requeued = false;
+        for instance in &mut self.instances {
+            if !instance.started
+                && matches!(instance.status, InstanceStatus::Loading | InstanceStatus::Queued)
+            {
+                instance.status = InstanceStatus::Queued;
+                requeued = true;
+            }
+        }
+        requeued
+    }
+
+    /// Queue pending pipeline steps whose upstream session has finished, or
+    /// that wait on `pushed`, which just had its PR pushed.
+    fn release_pipeline_steps(&mut self, pushed: Option<&str>) {
+        let now = chrono::Utc::now();
+        let finished = |upstream: &str| {
+            if pushed == Some(upstream) {
+                return true;
+            }
+            let upstream = self.instances.iter().find(|u| u.title == upstream);
+            let last_activity = upstream
+                .and_then(|u| u.tmux_session.as_ref())
+                .and_then(|tmux| tmux.last_activity().ok());
+            crate::session::pipeline::upstream_finished(upstream, last_activity, now)
+        };
+        let released: Vec<usize> = self
+            .instances
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| {
+                i.status == InstanceStatus::Pending && finished(i.after.as_deref().unwrap_or_default())
+            })
+            .map(|(idx, _)| idx)
+            .collect();
+        if released.is_empty() {
+            return;
+        }
+
+        let mut started = Vec::new();
+        for idx in released {
+            let instance = &mut self.instances[idx];
+            instance.status = InstanceStatus::Queued;
+            started.push(format!(
+                "'{}' (after '{}')",
+                instance.title,
+                instance.after.as_deref().unwrap_or_default()
+            ));
+        }
+        self.error
+            .set_info(format!("Starting pipeline steps: {}", started.join(", ")));
+        self.start_queued_creations();
+    }
+
+    /// Reload the Activity tab from the event journal.
+    fn refresh_activity(&mut self) {
+        self.activity.set_events(self.journal.recent(ACTIVITY_EVENTS));
+    }
+
+    /// Current index of the session with the given title.
+    fn instance_index(&self, title: &str) -> Option<usize> {
+        self.instances.iter().position(|i| i.title == title)
+    }
+
+    /// Storage for the sessions of the current project.
+    fn storage(&self) -> FileStorage {
+        FileStorage::scoped(&self.config_dir, self.project.as_deref())
+    }
+
+    /// Repo new sessions are created in: the project's, else the one gana
+    /// was started in.
+    fn repo_dir(&self) -> std::io::Result<std::path::PathBuf> {
+        match self.project {
+            Some(ref root) => Ok(root.clone()),
+            None => std::env::current_dir(),
+        }
+    }
+
+    /// Show only the sessions of the repo rooted at `project`, or all
+    /// sessions when `None`. Takes effect on the next load.
+    pub fn set_project(&mut self, project: Option<std::path::PathBuf>) {
+        let scope = project.as_ref().map(|root| {
+            root.file_name()
+                .unwrap_or(root.as_os_str())
+                .to_string_lossy()
+                .to_string()
+        });
+        self.list.set_scope(scope);
+        self.project = project;
+    }
+
+    /// Cycle the list through all sessions and each project's. The
+    /// current sessions are saved first and the new project's restored.
+    fn switch_project(&mut self) {
+        if !self.creating.is_empty() || self.instances.iter().any(|i| i.transition.is_some()) {
+            self.error
+                .set_error("Wait for sessions being created, paused or killed before switching projects");
+            return;
+        }
+        if let Err(e) = self.save_instances() {
+            self.error.set_error(format!("Failed to save sessions: {}", e));
+            return;
+        }
+        let projects = match FileStorage::new(&self.config_dir).projects() {
+            Ok(projects) => projects,
+            Err(e) => {
+                self.error.set_error(format!("Failed to load sessions: {}", e));
+                return;
+            }
+        };
+        let next = match self.project {
+            None => projects.first().cloned(),
+            Some(ref current) => projects.iter().skip_while(|p| *p != current).nth(1).cloned(),
+        };
+
+        self.set_project(next);
+        let _ = self.load_instances();
+        self.list.set_selected(0);
+        self.selection_changed();
+        self.restore_loaded_instances();
+        let notice = match self.project {
+            Some(ref root) => format!("Project: {}", root.display()),
+            None => "All projects".to_string(),
+        };
+        self.error.set_info(notice);
+    }
+
+    fn load_instances(&mut self) -> anyhow::Result<()> {
+        let storage = self.storage();
+        match storage.load_instances() {
+            Ok(instances) => {
+                self.known_titles = instances.iter().map(|i| i.title.clone()).collect();
+                self.instances = instances;
+                self.refresh_list();
+            }
+            Err(e) => {
+                self.error.set_error(format!("Failed to load sessions: {}", e));
+            }
+        }
+        Ok(())
+    }
+
+    /// Save the session list, taking in sessions other processes stored
+    /// since it was loaded rather than overwriting them.
+    fn save_instances(&mut self) -> anyhow::Result<()> {
+        let storage = self.storage();
+        let known = &self.known_titles;
+        let added = storage.save_merged(&self.instances, |title| known.contains(title))?;
+        if !added.is_empty() {
+            let start = self.instances.len();
+            self.instances.extend(added);
+            let pending = Self::mark_restoring(&mut self.instances[start..]);
+            self.refresh_list();
+            self.restore_in_background(pending);
+        }
+        self.known_titles = self.instances.iter().map(|i| i.title.clone()).collect();
+        Ok(())
+    }
+
+    /// Spawn background threads to fetch preview content and diff stats.
+    /// Results arrive via `bg_sender` channel and are processed by
+    /// `process_background_updates()`.
+    fn schedule_background_updates(&mut self) {
+        let now = Instant::now();
+        let selected = self.list.selected_index();
+        let selected_title = self.instances.get(selected).map(|i| i.title.clone());
+
+        // Paused and stopped sessions aren't polled at all
+        let polled = |i: &Instance| {
+            i.status.is_running() && i.started && i.transition.is_none()
+        };
+
+        // A newly selected session is captured right away instead of on its
+        // next tick, and its predecessor's output isn't left on screen
+        if selected_title != self.polled_selection {
+            if let Some(ref title) = selected_title {
+                self.poll.reset(title);
+                self.pane_activity.remove(title);
+                if self.instances.get(selected).is_some_and(polled) {
+                    self.preview.set_content(&format!("\n   Capturing '{}'…\n", title));
+                }
+            }
+            self.polled_selection = selected_title;
+        }
+
+        let instances = &self.instances;
+        self.poll
+            .retain(|title| instances.iter().any(|i| i.title == title && polled(i)));
+        self.pane_tails
+            .retain(|title| instances.iter().any(|i| i.title == title && polled(i)));
+        self.agents_working
+            .retain(|title| instances.iter().any(|i| i.title == *title && polled(i)));
+        self.pane_activity
+            .retain(|title, _| instances.iter().any(|i| i.title == *title && polled(i)));
+
+        // One thread per tick asks tmux which sessions are alive and when
+        // their panes last changed, then captures only those that did
+        let mut due = Vec::new();
+        let mut diffs = Vec::new();
+        for (idx, instance) in self.instances.iter().enumerate() {
+            if !polled(instance) || !self.poll.due(&instance.title, idx == selected, now) {
+                continue;
+            }
+            due.push(PaneCheck {
+                title: instance.title.clone(),
+                capture: idx == selected,
+                captured_at: self.pane_activity.get(&instance.title).copied(),
+                prompts: self.config.prompt_patterns(&instance.program),
+            });
+
+            // Diff: compute git diff in background
+            if let Some(ref worktree) = instance.git_worktree {
+                diffs.push((instance.title.clone(), worktree.clone()));
+            }
+        }
+        for (title, worktree) in diffs {
+            self.spawn_diff(title, worktree);
+        }
+        if due.is_empty() {
+            return;
+        }
+        let sender = self.bg_sender.clone();
+        std::thread::spawn(move || {
+            let cmd = SystemCmdExec;
+            // tmux didn't answer: try again next tick rather than take every
+            // session for dead
+            let activity = match crate::session::tmux::TmuxSession::pane_activity(&cmd) {
+                Ok(activity) => activity,
+                Err(e) => {
+                    tracing::warn!("Failed to list tmux panes: {}", e);
+                    return;
+                }
+            };
+            for check in due {
+                check.run(&activity, &cmd, &sender);
+            }
+        });
+    }
+
+    /// Mark a session done once its agent, seen working before, goes idle,
+    /// and running again when it picks up more work.
+    fn apply_agent_state(&mut self, title: String, state: AgentState) {
+        let Some(idx) = self.instance_index(&title) else {
+            return;
+        };
+        if !self.instances[idx].status.is_running() {
+            return;
+        }
+        let status = match state {
+            AgentState::Working => {
+                self.agents_working.insert(title.clone());
+                InstanceStatus::Running
+            }
+            AgentState::Waiting => InstanceStatus::Running,
+            AgentState::Idle if self.agents_working.remove(&title) => InstanceStatus::Done,
+            AgentState::Idle => return,
+        };
+        let instance = &mut self.instances[idx];
+        if instance.status == status {
+            return;
+        }
+        instance.status = status;
+        let push = status == InstanceStatus::Done && instance.auto_push;
+        if status == InstanceStatus::Done {
+            self.journal.record(&title, EventKind::Done, None);
+        }
+        self.refresh_list();
+        let _ = self.save_instances();
+        if push {
+            self.verify_and_auto_push(&title);
+        }
+    }
+
+    /// Run `test_command` in a done session's worktree, then push it if it
+    /// passes; without a `test_command`, push straight away.
+    fn verify_and_auto_push(&mut self, title: &str) {
+        let Some(command) = self.config.test_command.clone() else {
+            self.auto_push(title);
+            return;
+        };
+        let Some(idx) = self.instance_index(title) else {
+            return;
+        };
+        let Some(dir) = self.instances[idx]
+            .git_worktree
+            .as_ref()
+            .map(|wt| wt.worktree_path().to_string())
+        else {
+            return;
+        };
+        self.instances[idx].test_status = Some(TestStatus::Running);
+        self.refresh_list();
+        let title = title.to_string();
+        let sender = self.bg_sender.clone();
+        std::thread::spawn(move || {
+            let status = crate::session::test_watch::run_tests(&command, &dir, &SystemCmdExec);
+            let _ = sender.send(BackgroundUpdate::AutoPushChecked(title, status));
+        });
+    }
+
+    /// Commit and push a done session and open a draft PR, or with git
+    /// alone when `gh` isn't usable.
+    fn auto_push(&mut self, title: &str) {
+        let request = PushRequest {
+            title: title.to_string(),
+            squash: None,
+            push: PushOptions {
+                remote: self.config.push_remote.clone(),
+                ..PushOptions::default()
+            },
+            by_gh_status: true,
+        };
+        self.push_session(request, None);
+    }
+
+    /// Drain the background update channel and apply results to the UI.
+    /// This is non-blocking — `try_recv()` returns immediately if empty.
+    fn process_background_updates(&mut self) {
+        while let Ok(update) = self.bg_receiver.try_recv() {
+            match update {
+                BackgroundUpdate::DiskUsage(usage) => self.apply_disk_usage(usage),
+                BackgroundUpdate::TeamSynced(result) => {
+                    self.team_sync_in_flight = false;
+                    match result {
+                        Ok(members) => {
+                            self.team = members;
+                            self.team_error = None;
+                        }
+                        Err(e) => {
+                            tracing::warn!("Team sync failed: {}", e);
+                            self.team_error = Some(e);
+                        }
+                    }
+                }
+                BackgroundUpdate::PushFinished(request, result) => self.finish_push(request, result),
+                BackgroundUpdate::ReleaseNotes(version, notes) => {
+                    self.show_release_notes(&version, notes);
+                }
+                BackgroundUpdate::PauseCompleted(worker, result)
+                | BackgroundUpdate::ResumeCompleted(worker, result) => {
+                    let transition = worker.transition;
+                    let idx = self.finish_transition(*worker, &result);
+                    match (idx, transition) {
+                        (Some(idx), Some(Transition::Closing)) if result.is_ok() => {
+                            if let Err(e) = self.delete_instance(idx) {
+                                self.error.set_error(e.to_string());
+                            }
+                        }
+                        (Some(idx), Some(Transition::Resuming)) if result.is_ok() => {
+                            self.answer_trust_prompt(idx);
+                        }
+                        (Some(idx), Some(Transition::Archiving)) if result.is_ok() => {
+                            self.archive_instance(idx);
+                        }
+                        (_, Some(Transition::CleaningUp)) => self.schedule_disk_scan(),
+                        _ => {}
+                    }
+                }
+                BackgroundUpdate::KillCompleted(killed, result) => self.finish_kill(*killed, result),
+                BackgroundUpdate::ExpiryChecked(idle) => self.pause_idle(idle),
+                BackgroundUpdate::AttentionChecked(waiting) => {
+                    if waiting != self.waiting {
+                        self.waiting = waiting;
+                        self.refresh_list();
+                    }
+                }
+                BackgroundUpdate::TestsFinished(title, status) => {
+                    self.tests_running.remove(&title);
+                    if let Some(idx) = self.instance_index(&title) {
+                        let instance = &mut self.instances[idx];
+                        let was_failing = matches!(instance.test_status, Some(TestStatus::Failed(_)));
+                        if !was_failing && matches!(status, TestStatus::Failed(_)) {
+                            self.error
+                                .set_error(format!("Tests started failing in '{}' (i: output)", title));
+                        }
+                        instance.test_status = Some(status);
+                        self.refresh_list();
+                    }
+                }
+                BackgroundUpdate::TitleGenerated(prompt, result) => {
+                    self.error.clear();
+                    let title = result.unwrap_or_else(|e| {
+                        tracing::warn!("title_command failed: {}", e);
+                        crate::session::title::title_from_prompt(&prompt).unwrap_or_default()
+                    });
+                    self.request_create(title, prompt);
+                }
+                BackgroundUpdate::PrBodyDrafted(title, result) => {
+                    if let Some(request) = self.pending_pushes.remove(&title) {
+                        match result {
+                            Ok(body) => self.pr_body_to_edit = Some((request, body)),
+                            Err(e) => self.error.set_error(format!(
+                                "No PR description from '{}', push cancelled: {}",
+                                title, e
+                            )),
+                        }
+                    }
+                }
+                BackgroundUpdate::PreviewContent(title, content) => {
+                    if self.instance_index(&title) == Some(self.list.selected_index()) {
+                        self.preview.set_content(&content);
+                        if self.poll.observe(&title, &content) {
+                            self.tick_rate.wake();
+                        }
+                    }
+                }
+                BackgroundUpdate::DiffComputed(title, stats) => {
+                    self.diffing.remove(&title);
+                    let Some(idx) = self.instance_index(&title) else {
+                        continue;
+                    };
+                    if idx == self.list.selected_index() {
+                        self.diff_view.set_diff(&stats);
+                    } else if let Some(instance) = self.instances.get(idx) {
+                        // The selected session backs off on its preview instead
+                        if self.poll.observe(&instance.title, &stats.content) {
+                            self.tick_rate.wake();
+                        }
+                    }
+                    if let Some(instance) = self.instances.get_mut(idx) {
+                        instance.diff_stats = Some(stats);
+                        self.refresh_list();
+                    }
+                }
+                BackgroundUpdate::CreationProgress(title, step) => {
+                    if self.creating.contains(&title) {
+                        self.creation_steps.insert(title, step);
+                    }
+                }
+                BackgroundUpdate::InstanceReady(title, created) => {
+                    self.creation_steps.remove(&title);
+                    if self.creating.remove(&title) {
+                        self.start_queued_creations();
+                    }
+                    let Some(idx) = self.instance_index(&title) else {
+                        // Gone from the list while it was set up
+                        self.discard_created(title, *created);
+                        continue;
+                    };
+                    if let Some(ref e) = created.carry_error {
+                        self.error.set_error(format!(
+                            "'{}' was created without your uncommitted changes, which \
+                             couldn't be carried over: {}",
+                            title, e
+                        ));
+                    }
+                    self.instances[idx].attach_created(*created);
+                    self.journal.record(&title, EventKind::Created, None);
+                    // The initial prompt waits until the trust prompt is
+                    // answered, or it would land in the trust dialog
+                    if !self.answer_trust_prompt(idx) {
+                        self.deliver_pending_prompt(idx);
+                    }
+                    self.refresh_list();
+                    let _ = self.save_instances();
+                }
+                BackgroundUpdate::TrustAnswered(title, result) => {
+                    self.answering_trust.remove(&title);
+                    if let Err(e) = result {
+                        tracing::warn!(session = %title, "Failed to answer the trust prompt: {}", e);
+                    }
+                    if let Some(idx) = self.instance_index(&title)
+                        && self.deliver_pending_prompt(idx)
+                    {
+                        let _ = self.save_instances();
+                    }
+                }
+                BackgroundUpdate::InstanceFailed(title, msg) => {
+                    tracing::error!(session = %title, "Setup failed: {}", msg);
+                    self.creation_steps.remove(&title);
+                    if self.creating.remove(&title) {
+                        self.start_queued_creations();
+                    }
+                    // Keep the session, with its prompt, so it can be retried
+                    if let Some(idx) = self.instance_index(&title) {
+                        let instance = &mut self.instances[idx];
+                        instance.status = InstanceStatus::Failed;
+                        instance.tmux_session = None;
+                        instance.error = Some(msg.clone());
+                        self.refresh_list();
+                        let _ = self.save_instances();
+                        self.error.set_error(format!(
+                            "Session '{}' failed: {} (r: retry, i: details)",
+                            title, msg
+                        ));
+                    }
+                }
+                BackgroundUpdate::PushChecked(title, remote, gh) => {
+                    // Unless something else was opened meanwhile
+                    if self.state == AppState::Default
+                        && self
+                            .instance_index(&title)
+                            .is_some_and(|idx| self.instances[idx].status.is_running())
+                    {
+                        self.push_overlay = Some(PushOverlay::new(title, remote, gh));
+                        self.state = AppState::Push;
+                    }
+                }
+                BackgroundUpdate::DirtyChecked(title, prompt, dirty) => {
+                    self.confirm_create(title, prompt, dirty);
+                }
+                BackgroundUpdate::BranchesListed(heading, branches, checked_out) => {
+                    let check = self.title_check(branches, checked_out);
+                    if let Some(input) = self.text_input.as_mut()
+                        && input.title() == heading
+                    {
+                        input.set_check(move |title| check.check(title));
+                    }
+                }
+                BackgroundUpdate::BrowserOpened(result) => match result {
+                    Ok(url) if !url.is_empty() => {
+                        clipboard::copy(&url);
+                        self.error.set_info(format!("Opened and copied {}", url));
+                    }
+                    Ok(_) => {}
+                    Err(e) => self.error.set_error(format!("Open in browser failed: {}", e)),
+                },
+                BackgroundUpdate::ResourcesSampled(title, usage) => {
+                    if self.details_for.as_deref() == Some(title.as_str())
+                        && let Some(idx) = self.instance_index(&title)
+                    {
+                        let text = self.details_text(&self.instances[idx], Some(&usage));
+                        if let Some(ref mut overlay) = self.help_overlay {
+                            overlay.set_content(text);
+                        }
+                    }
+                }
+                BackgroundUpdate::PaneCaptured(title, at, preview) => {
+                    // Captured before it was selected: the preview still
+                    // needs a capture of its own
+                    let selected = self.polled_selection.as_deref() == Some(title.as_str());
+                    if preview || !selected {
+                        self.pane_activity.insert(title, at);
+                    }
+                }
+                BackgroundUpdate::SessionDied(title) => {
+                    if let Some(idx) = self.instance_index(&title)
+                        && self.instances[idx].status.is_running()
+                    {
+                        let instance = &mut self.instances[idx];
+                        instance.mark_exited(self.pane_tails.take(&instance.title));
+                        self.journal.record(&instance.title, EventKind::Died, None);
+                        self.refresh_list();
+                        let _ = self.save_instances();
+                    }
+                }
+                BackgroundUpdate::PaneTail(title, tail) => {
+                    self.pane_tails.record(&title, &tail);
+                }
+                BackgroundUpdate::AgentState(title, state) => self.apply_agent_state(title, state),
+                BackgroundUpdate::AutoPushChecked(title, status) => {
+                    let Some(idx) = self.instance_index(&title) else {
+                        continue;
+                    };
+                    self.instances[idx].test_status = Some(status.clone());
+                    self.refresh_list();
+                    if status == TestStatus::Passed {
+                        self.auto_push(&title);
+                    } else {
+                        self.error.set_error(format!(
+                            "'{}' is done but its tests fail, so it wasn't pushed (i: output)",
+                            title
+                        ));
+                    }
+                }
+                BackgroundUpdate::SessionRestarted(title, tmux) => {
+                    let idx = self.instance_index(&title);
+                    if let Some(instance) = idx.and_then(|i| self.instances.get_mut(i)) {
+                        instance.error = None;
+                        instance.attach_tmux(*tmux);
+                        if let Some(prompt) = instance.deliver_pending_prompt() {
+                            self.journal.record(&title, EventKind::PromptSent, Some(&prompt));
+                        }
+                        self.refresh_list();
+                        let _ = self.save_instances();
+                    }
+                }
+                BackgroundUpdate::SessionReconciled(title, status, reason) => {
+                    let idx = self.instance_index(&title);
+                    if let Some(instance) = idx.and_then(|i| self.instances.get_mut(i)) {
+                        tracing::warn!(session = %title, "{}", reason);
+                        instance.restoring = false;
+                        instance.tmux_session = None;
+                        instance.status = status;
+                        instance.error = Some(reason);
+                        self.error.set_error(format!(
+                            "Session '{}': {} (r: repair, i: details)",
+                            title, status
+                        ));
+                        self.refresh_list();
+                        let _ = self.save_instances();
+                    }
+                }
+                BackgroundUpdate::SessionRestored(title, tmux) => {
+                    let idx = self.instance_index(&title);
+                    if let Some(instance) = idx.and_then(|i| self.instances.get_mut(i)) {
+                        instance.restoring = false;
+                        match tmux {
+                            Some(tmux) => {
+                                instance.attach_tmux(*tmux);
+                                // A prompt queued before the last exit is still owed
+                                if let Some(prompt) = instance.deliver_pending_prompt() {
+                                    self.journal.record(&title, EventKind::PromptSent, Some(&prompt));
+                                    let _ = self.save_instances();
+                                }
+                            }
+                            None => {
+                                // tmux session is gone — mark as not running
+                                instance.status = InstanceStatus::Ready;
+                                instance.started = false;
+                            }
+                        }
+                        self.refresh_list();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Resolve the text typed into the prompt overlay.
+///
+/// Input that is just `@path/to/file`, naming an existing file, loads the
+/// prompt from it (a leading `~/` expands to the home directory). Anything
+/// else, like the agent's own `@file` mentions, is used as-is.
+fn resolve_prompt_input(text: &str) -> anyhow::Result<String> {
+    let Some(path) = text.trim().strip_prefix('@') else {
+        return Ok(text.to_string());
+    };
+    let path = match path.strip_prefix("~/") {
+        Some(rest) => match dirs::home_dir() {
+            Some(home) => home.join(rest),
+            None => return Ok(text.to_string()),
+        },
+        None => std::path::PathBuf::from(path),
+    };
+    if !path.is_file() {
+        return Ok(text.to_string());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    Ok(contents.trim_end().to_string())
+}
+
+/// `path` with the home directory shown as `~`.
+fn home_relative(path: &std::path::Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(|p| p.to_owned())) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Set up terminal, run the TUI app, and restore terminal on exit.
+/// `project` limits the list to one repo's sessions; `updated_to` is a
+/// version installed since the last launch, whose release notes are shown.
+pub fn run(
+    config: Config,
+    config_dir: std::path::PathBuf,
+    project: Option<std::path::PathBuf>,
+    updated_to: Option<String>,
+) -> anyhow::Result<()> {
+    crossterm::terminal::enable_raw_mode()?;
+    let mut stdout = std::io::stdout();
+    // Pastes then arrive as one event rather than as keys
+    crossterm::execute!(
+        stdout,
+        crossterm::terminal::EnterAlternateScreen,
+        crossterm::event::EnableBracketedPaste
+    )?;
+    let backend = ratatui::backend::CrosstermBackend::new(stdout);
+    let mut terminal = ratatui::Terminal::new(backend)?;
+
+    let mut app = App::new(config, config_dir);
+    app.set_project(project);
+    if let Some(version) = updated_to {
+        app.fetch_release_notes(version);
+    }
+    let result = app.run(&mut terminal);
+
+    crossterm::terminal::disable_raw_mode()?;
+    crossterm::execute!(
+        terminal.backend_mut(),
+        crossterm::event::DisableBracketedPaste,
+        crossterm::terminal::LeaveAlternateScreen
+    )?;
+
+    if let Some(summary) = app.background_summary() {
+        println!("{}", summary);
+    }
+
+    result
+}
+
+// ── Test support ────────────────────────────────────────────────────
+
+#[cfg(test)]
+impl App {
+    /// Create an App suitable for unit testing (no real config dir). Each
+    /// gets a config dir of its own, so tests don't see each other's sessions.
+    fn new_for_test() -> Self {
+        static NEXT: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let dir = std::env::temp_dir().join(format!("gana-test-{}-{}", std::process::id(), n));
+        let _ = std::fs::remove_dir_all(&dir);
+        Self::new(Config::default(), dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keys::map_key;
+    use crossterm::event::{KeyEvent, KeyModifiers};
+
+    fn test_app() -> App {
+        App::new_for_test()
+    }
+
+    fn make_test_instance(title: &str) -> Instance {
+        Instance::new(InstanceOptions {
+            title: title.into(),
+            path: "/tmp".into(),
+            program: "bash".into(),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_initial_state_is_default() {
+        let app = test_app();
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.running);
+        assert!(app.instances.is_empty());
+    }
+
+    #[test]
+    fn test_quit_without_running_sessions_exits() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("idle"));
+        app.handle_key_action(KeyAction::Quit);
+        assert!(!app.running);
+        assert!(app.background_summary().is_none());
+    }
+
+    #[test]
+    fn test_quit_with_running_sessions_asks() {
+        let mut app = test_app();
+        let mut inst = make_test_instance("busy");
+        inst.status = InstanceStatus::Running;
+        inst.branch = "busy".to_string();
+        app.instances.push(inst);
+
+        app.handle_key_action(KeyAction::Quit);
+        assert!(app.running);
+        assert_eq!(app.state, AppState::Confirm);
+
+        // Esc stays in the app
+        app.handle_confirm_key(KeyCode::Esc).unwrap();
+        assert!(app.running);
+        assert_eq!(app.state, AppState::Default);
+
+        // n quits and leaves the session running
+        app.handle_key_action(KeyAction::Quit);
+        app.handle_confirm_key(KeyCode::Char('n')).unwrap();
+        assert!(!app.running);
+        assert_eq!(app.instances[0].status, InstanceStatus::Running);
+        let summary = app.background_summary().unwrap();
+        assert!(summary.contains("busy [busy]"));
+    }
+
+    #[test]
+    fn test_quit_leave_config_skips_prompt() {
+        let mut app = test_app();
+        app.config.on_quit = QuitBehavior::Leave;
+        let mut inst = make_test_instance("busy");
+        inst.status = InstanceStatus::Running;
+        app.instances.push(inst);
+
+        app.handle_key_action(KeyAction::Quit);
+        assert!(!app.running);
+        assert_eq!(app.state, AppState::Default);
+    }
+
+    #[test]
+    fn test_undo_delete_restores_position() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("a"));
+        app.instances.push(make_test_instance("b"));
+        app.instances.push(make_test_instance("c"));
+        app.refresh_list();
+
+        app.delete_instance(1).unwrap();
+        assert_eq!(app.instances.len(), 2);
+
+        app.handle_key_action(KeyAction::Undo);
+        let titles: Vec<&str> = app.instances.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, vec!["a", "b", "c"]);
+        assert_eq!(app.list.selected_index(), 1);
+
+        // Nothing left to undo
+        app.handle_key_action(KeyAction::Undo);
+        assert_eq!(app.instances.len(), 3);
+    }
+
+    #[test]
+    fn test_undo_buffer_is_bounded() {
+        let mut app = test_app();
+        for i in 0..(UNDO_LIMIT + 2) {
+            app.instances.push(make_test_instance(&format!("s{}", i)));
+        }
+        for _ in 0..(UNDO_LIMIT + 2) {
+            app.delete_instance(0).unwrap();
+        }
+        assert_eq!(app.undo_buffer.len(), UNDO_LIMIT);
+    }
+
+    #[test]
+    fn test_toggle_auto_yes() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("auto"));
+        app.refresh_list();
+        assert!(!app.instances[0].auto_yes);
+
+        app.handle_key_action(KeyAction::ToggleAutoYes);
+        assert!(app.instances[0].auto_yes);
+
+        app.handle_key_action(KeyAction::ToggleAutoYes);
+        assert!(!app.instances[0].auto_yes);
+    }
+
+    #[test]
+    fn test_confirmation_modal_state_transitions() {
+        let mut app = test_app();
+        assert_eq!(app.state, AppState::Default);
+
+        // Add an instance so kill has something to target
+        app.instances.push(make_test_instance("test"));
+        app.refresh_list();
+
+        // Press D to kill -> should enter Confirm state
+        app.handle_key_action(KeyAction::Kill);
+        assert_eq!(app.state, AppState::Confirm);
+        assert!(app.confirmation.is_some());
+
+        // Press Esc to cancel -> back to Default
+        app.handle_confirm_key(KeyCode::Esc).unwrap();
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.confirmation.is_none());
+    }
+
+    #[test]
+    fn test_counts_and_jumps_in_list() {
+        let mut app = test_app();
+        for i in 0..30 {
+            app.instances.push(make_test_instance(&format!("s{}", i)));
+        }
+        app.refresh_list();
+        let press = |app: &mut App, code: KeyCode| {
+            app.handle_key(KeyEvent::new(code, KeyModifiers::NONE)).unwrap();
+        };
+
+        press(&mut app, KeyCode::Char('5'));
+        press(&mut app, KeyCode::Char('j'));
+        assert_eq!(app.list.selected_index(), 5);
+        press(&mut app, KeyCode::Char('G'));
+        assert_eq!(app.list.selected_index(), 29);
+        press(&mut app, KeyCode::Char('g'));
+        press(&mut app, KeyCode::Char('g'));
+        assert_eq!(app.list.selected_index(), 0);
+        press(&mut app, KeyCode::PageDown);
+        press(&mut app, KeyCode::PageDown);
+        assert_eq!(app.list.selected_index(), 20);
+        press(&mut app, KeyCode::Char('2'));
+        press(&mut app, KeyCode::PageUp);
+        assert_eq!(app.list.selected_index(), 0);
+        press(&mut app, KeyCode::Char('1'));
+        press(&mut app, KeyCode::Char('2'));
+        press(&mut app, KeyCode::Char('G'));
+        assert_eq!(app.list.selected_index(), 11);
+    }
+
+    #[test]
+    fn test_confirmation_key_handling() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("sess1"));
+        app.refresh_list();
+
+        // Enter confirm state for kill
+        app.handle_key_action(KeyAction::Kill);
+        assert_eq!(app.state, AppState::Confirm);
+
+        // Random key should not dismiss
+        app.handle_confirm_key(KeyCode::Char('x')).unwrap();
+        assert_eq!(app.state, AppState::Confirm);
+
+        // 'n' cancels
+        app.handle_confirm_key(KeyCode::Char('n')).unwrap();
+        assert_eq!(app.state, AppState::Default);
+        // Instance should still be there (not killed)
+        assert_eq!(app.instances.len(), 1);
+    }
+
+    #[test]
+    fn test_confirmation_y_confirms_delete() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("to-delete"));
+        app.refresh_list();
+
+        // Enter confirm state for delete
+        app.handle_key_action(KeyAction::Delete);
+        assert_eq!(app.state, AppState::Confirm);
+
+        // 'y' confirms -> instance removed
+        app.handle_confirm_key(KeyCode::Char('y')).unwrap();
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.instances.is_empty());
+    }
+
+    #[test]
+    fn test_text_input_flow() {
+        let mut app = test_app();
+        assert_eq!(app.state, AppState::Default);
+
+        // Press 'n' for new session -> TextInput state
+        app.handle_key_action(KeyAction::New);
+        assert_eq!(app.state, AppState::TextInput);
+        assert!(app.text_input.is_some());
+
+        // Type some characters
+        app.handle_text_input_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE))
+            .unwrap();
+        app.handle_text_input_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE))
+            .unwrap();
+
+        assert_eq!(app.text_input.as_ref().unwrap().input(), "te");
+
+        // Cancel with Esc
+        app.handle_text_input_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+            .unwrap();
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.text_input.is_none());
+    }
+
+    #[test]
+    fn test_new_like_copies_selected_session() {
+        let mut app = test_app();
+        let mut instance = make_test_instance("api");
+        instance.program = "aider".to_string();
+        instance.status = InstanceStatus::Paused;
+        app.instances.push(instance);
+        app.refresh_list();
+
+        app.handle_key_action(KeyAction::NewLike);
+        assert_eq!(app.state, AppState::TextInput);
+        assert_eq!(app.like_target.as_deref(), Some("api"));
+        app.handle_text_input_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            .unwrap();
+
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.like_target.is_none());
+        let sibling = app.instances.iter().find(|i| i.title == "api-2").unwrap();
+        assert_eq!(sibling.program, "aider");
+        assert_ne!(sibling.status, InstanceStatus::Paused);
+    }
+
+    #[test]
+    fn test_paste_goes_to_text_input() {
+        let mut app = test_app();
+        // Ignored outside of an input
+        app.handle_paste("n");
+        assert_eq!(app.state, AppState::Default);
+
+        app.handle_key_action(KeyAction::Prompt);
+        app.handle_text_input_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            .unwrap();
+        app.handle_paste("Refactor the parser\nand add tests\n");
+        assert_eq!(
+            app.text_input.as_ref().unwrap().input(),
+            "Refactor the parser and add tests"
+        );
+        assert_eq!(app.state, AppState::TextInput);
+    }
+
+    #[test]
+    fn test_help_toggle() {
+        let mut app = test_app();
+        assert_eq!(app.state, AppState::Default);
+
+        // Press ? for help
+        app.handle_key_action(KeyAction::Help);
+        assert_eq!(app.state, AppState::Help);
+        assert!(app.help_overlay.is_some());
+
+        // Press Esc to dismiss
+        app.handle_help_key(KeyCode::Esc).unwrap();
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.help_overlay.is_none());
+    }
+
+    #[test]
+    fn test_navigation_updates_selection() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("first"));
+        app.instances.push(make_test_instance("second"));
+        app.instances.push(make_test_instance("third"));
+        app.refresh_list();
+
+        assert_eq!(app.list.selected_index(), 0);
+
+        app.handle_key_action(KeyAction::Down);
+        assert_eq!(app.list.selected_index(), 1);
+
+        app.handle_key_action(KeyAction::Down);
+        assert_eq!(app.list.selected_index(), 2);
+
+        app.handle_key_action(KeyAction::Up);
+        assert_eq!(app.list.selected_index(), 1);
+    }
+
+    #[test]
+    fn test_save_takes_in_sessions_stored_meanwhile() {
+        let mut app = test_app();
+        let mut mine = make_test_instance("mine");
+        mine.started = true;
+        app.instances.push(mine);
+        app.save_instances().unwrap();
+
+        // `gana new` stores a session while the TUI is open
+        let mut added = make_test_instance("from-cli");
+        added.started = true;
+        added.status = InstanceStatus::Paused;
+        app.storage().update_instances(|stored| stored.push(added)).unwrap();
+
+        app.instances[0].auto_push = true;
+        app.save_instances().unwrap();
+        let titles: Vec<String> = app.instances.iter().map(|i| i.title.clone()).collect();
+        assert_eq!(titles, vec!["mine", "from-cli"]);
+        let stored = app.storage().load_instances().unwrap();
+        assert_eq!(stored.len(), 2);
+
+        // Once taken in, deleting it from the TUI sticks
+        app.instances.pop();
+        app.save_instances().unwrap();
+        assert_eq!(app.storage().load_instances().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_failed_archive_keeps_the_session() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("old"));
+        app.refresh_list();
+        // The archive can't be written over a directory
+        std::fs::create_dir_all(app.config_dir.join("archive.json")).unwrap();
+
+        app.archive_instance(0);
+        assert_eq!(app.instances.len(), 1);
+        assert!(app.error.has_error());
+
+        std::fs::remove_dir(app.config_dir.join("archive.json")).unwrap();
+        app.archive_instance(0);
+        assert!(app.instances.is_empty());
+        assert_eq!(FileStorage::new(&app.config_dir).load_archive().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_quit_sets_running_false() {
+        let mut app = test_app();
+        assert!(app.running);
+
+        app.handle_key_action(KeyAction::Quit);
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_diff_badge_follows_selection() {
+        let mut app = test_app();
+        let mut changed = make_test_instance("changed");
+        changed.diff_stats = Some(DiffStats::from_diff("@@ -1 +1,2 @@\n-a\n+b\n+c\n".to_string()));
+        app.instances.push(changed);
+        app.instances.push(make_test_instance("clean"));
+        app.refresh_list();
+        assert_eq!(app.tabbed_window.badge(Tab::Diff), Some("+2 -1"));
+
+        app.handle_key_action(KeyAction::Down);
+        assert_eq!(app.tabbed_window.badge(Tab::Diff), None);
+        app.handle_key_action(KeyAction::Up);
+        assert_eq!(app.tabbed_window.badge(Tab::Diff), Some("+2 -1"));
+    }
+
+    #[test]
+    fn test_switching_to_diff_computes_it_now() {
+        let mut app = test_app();
+        let mut inst = make_test_instance("fresh");
+        inst.git_worktree = Some(GitWorktree::from_storage(
+            "/tmp".to_string(),
+            "/nonexistent/gana-wt".to_string(),
+            "fresh".to_string(),
+            "gana/fresh".to_string(),
+            String::new(),
+        ));
+        app.instances.push(inst);
+        app.refresh_list();
+
+        app.handle_key_action(KeyAction::Tab);
+        assert_eq!(app.tabbed_window.active_tab(), Tab::Diff);
+        // Asking again while it runs doesn't start another
+        app.handle_key_action(KeyAction::RefreshDiff);
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(update, BackgroundUpdate::DiffComputed(ref t, _) if t == "fresh"));
+        assert!(app.bg_receiver.recv_timeout(Duration::from_millis(200)).is_err());
+        app.bg_sender.send(update).unwrap();
+        app.process_background_updates();
+        assert!(app.diffing.is_empty());
+
+        app.handle_key_action(KeyAction::RefreshDiff);
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(update, BackgroundUpdate::DiffComputed(ref t, _) if t == "fresh"));
+    }
+
+    #[test]
+    fn test_tab_switches_view() {
+        let mut app = test_app();
+        assert_eq!(app.tabbed_window.active_tab(), Tab::Preview);
+
+        app.handle_key_action(KeyAction::Tab);
+        assert_eq!(app.tabbed_window.active_tab(), Tab::Diff);
+
+        app.handle_key_action(KeyAction::Tab);
+        assert_eq!(app.tabbed_window.active_tab(), Tab::Activity);
+
+        app.handle_key_action(KeyAction::Tab);
+        assert_eq!(app.tabbed_window.active_tab(), Tab::Preview);
+
+        app.handle_key(KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT)).unwrap();
+        assert_eq!(app.tabbed_window.active_tab(), Tab::Activity);
 
-                        // Send pending prompt if any
-                        if let Some(prompt) = self.pending_prompts.remove(&idx) {
-                            if !prompt.is_empty() {
-                                instance.send_prompt(&prompt);
-                            }
-                        }
+        // A count picks the tab by number
+        app.handle_key(KeyEvent::new(KeyCode::Char('2'), KeyModifiers::NONE)).unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE)).unwrap();
+        assert_eq!(app.tabbed_window.active_tab(), Tab::Diff);
+    }
 
-                        self.refresh_list();
-                        let _ = self.save_instances();
-                    }
-                }
-                BackgroundUpdate::InstanceFailed(idx, msg) => {
-                    if idx < self.instances.len() {
-                        self.instances.remove(idx);
-                        self.pending_prompts.remove(&idx);
-                        self.refresh_list();
-                    }
-                    self.error.set_error(format!("Session creation failed: {}", msg));
-                }
-                BackgroundUpdate::SessionDied(idx) => {
-                    if let Some(instance) = self.instances.get_mut(idx) {
-                        if instance.status == InstanceStatus::Running {
-                            instance.status = InstanceStatus::Ready;
-                            instance.tmux_session = None;
-                            instance.started = false;
-                            self.refresh_list();
-                            let _ = self.save_instances();
-                        }
-                    }
-                }
-                BackgroundUpdate::SessionRestarted(idx) => {
-                    if let Some(instance) = self.instances.get_mut(idx) {
-                        // Attach PTY to the restarted tmux session
-                        if instance.restore_session().is_ok() {
-                            instance.status = InstanceStatus::Running;
-                        } else {
-                            instance.status = InstanceStatus::Ready;
-                        }
-                        self.refresh_list();
-                        let _ = self.save_instances();
-                    }
-                }
-            }
+    #[test]
+    fn test_board_navigates_columns() {
+        let mut app = test_app();
+        for (title, status) in [
+            ("a", InstanceStatus::Running),
+            ("b", InstanceStatus::Paused),
+            ("c", InstanceStatus::Running),
+        ] {
+            let mut instance = make_test_instance(title);
+            instance.status = status;
+            app.instances.push(instance);
         }
-    }
-}
+        app.refresh_list();
 
-/// Set up terminal, run the TUI app, and restore terminal on exit.
-pub fn run(config: Config, config_dir: std::path::PathBuf) -> anyhow::Result<()> {
-    crossterm::terminal::enable_raw_mode()?;
-    let mut stdout = std::io::stdout();
-    crossterm::execute!(stdout, crossterm::terminal::EnterAlternateScreen)?;
-    let backend = ratatui::backend::CrosstermBackend::new(stdout);
-    let mut terminal = ratatui::Terminal::new(backend)?;
+        app.handle_key_action(KeyAction::ToggleBoard);
+        assert!(app.show_board);
+        // j stays within the Working column
+        app.handle_key_action(KeyAction::Down);
+        assert_eq!(app.list.selected_index(), 2);
+        // l jumps over the empty Waiting column to Paused
+        app.handle_key_action(KeyAction::Right);
+        assert_eq!(app.list.selected_index(), 1);
 
-    let mut app = App::new(config, config_dir);
-    let result = app.run(&mut terminal);
+        app.handle_key_action(KeyAction::ToggleBoard);
+        assert!(!app.show_board);
+        app.handle_key_action(KeyAction::Down);
+        assert_eq!(app.list.selected_index(), 2);
+    }
 
-    crossterm::terminal::disable_raw_mode()?;
-    crossterm::execute!(
-        terminal.backend_mut(),
-        crossterm::terminal::LeaveAlternateScreen
-    )?;
+    #[test]
+    fn test_request_changes_on_diff_hunk() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(Config::default(), dir.path().to_path_buf());
+        let sent = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = std::sync::Arc::clone(&sent);
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run().returning(move |_, a| {
+            log.lock().unwrap().push(a[3].clone());
+            Ok(())
+        });
+        let mut instance = make_test_instance("review");
+        instance.status = InstanceStatus::Running;
+        instance.tmux_session = Some(crate::session::tmux::TmuxSession::new(
+            "review",
+            "claude",
+            Box::new(mock),
+            Box::new(crate::session::tmux::pty::SystemPtyFactory),
+        ));
+        app.instances.push(instance);
+        app.refresh_list();
+        app.diff_view.set_diff(&DiffStats::from_diff(
+            "+++ b/src/foo.rs\n@@ -10,3 +10,21 @@\n+x\n".to_string(),
+        ));
 
-    result
-}
+        // Outside the Diff tab there is no hunk to refer to
+        app.handle_key_action(KeyAction::RequestChanges);
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.error.has_error());
 
-// ── Test support ────────────────────────────────────────────────────
+        app.tabbed_window.set_tab(Tab::Diff);
+        app.handle_key_action(KeyAction::ScrollDown);
+        app.handle_key_action(KeyAction::RequestChanges);
+        assert_eq!(app.state, AppState::TextInput);
+        for c in "use X instead".chars() {
+            app.handle_text_input_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE))
+                .unwrap();
+        }
+        app.handle_text_input_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            .unwrap();
 
-#[cfg(test)]
-impl App {
-    /// Create an App suitable for unit testing (no real config dir).
-    fn new_for_test() -> Self {
-        Self::new(Config::default(), std::path::PathBuf::from("/tmp/gana-test"))
+        assert_eq!(app.state, AppState::Default);
+        assert_eq!(
+            *sent.lock().unwrap(),
+            ["In src/foo.rs lines 10-30: use X instead", "Enter"]
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crossterm::event::{KeyEvent, KeyModifiers};
+    #[test]
+    fn test_scroll_in_default_state() {
+        let mut app = test_app();
 
-    fn test_app() -> App {
-        App::new_for_test()
-    }
+        app.handle_key_action(KeyAction::ScrollUp);
+        assert!(app.preview.is_scrolling());
 
-    fn make_test_instance(title: &str) -> Instance {
-        Instance::new(InstanceOptions {
-            title: title.into(),
-            path: "/tmp".into(),
-            program: "bash".into(),
-            auto_yes: false,
-        })
+        app.handle_key_action(KeyAction::Cancel);
+        assert!(!app.preview.is_scrolling());
     }
 
     #[test]
-    fn test_initial_state_is_default() {
-        let app = test_app();
+    fn test_kill_on_empty_list_does_nothing() {
+        let mut app = test_app();
+        assert!(app.instances.is_empty());
+
+        // Kill on empty list should stay in Default state
+        app.handle_key_action(KeyAction::Kill);
         assert_eq!(app.state, AppState::Default);
-        assert!(app.running);
+    }
+
+    #[test]
+    fn test_delete_on_empty_list_does_nothing() {
+        let mut app = test_app();
         assert!(app.instances.is_empty());
+
+        app.handle_key_action(KeyAction::Delete);
+        assert_eq!(app.state, AppState::Default);
     }
 
     #[test]
-    fn test_confirmation_modal_state_transitions() {
+    fn test_key_routing_in_text_input_state() {
+        let mut app = test_app();
+        app.handle_key_action(KeyAction::New);
+        assert_eq!(app.state, AppState::TextInput);
+
+        // In TextInput state, normal keys go to the input, not to actions
+        // 'q' should not quit — it should type 'q'
+        app.handle_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE))
+            .unwrap();
+        assert!(app.running); // still running
+        assert_eq!(app.text_input.as_ref().unwrap().input(), "q");
+    }
+
+    #[test]
+    fn test_key_routing_pause_and_restart() {
         let mut app = test_app();
+        let mut inst = make_test_instance("keys");
+        inst.status = InstanceStatus::Running;
+        app.instances.push(inst);
+        app.refresh_list();
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE))
+            .unwrap();
+        assert_eq!(app.state, AppState::Restart);
+        app.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
+            .unwrap();
         assert_eq!(app.state, AppState::Default);
 
-        // Add an instance so kill has something to target
-        app.instances.push(make_test_instance("test"));
+        app.handle_key(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE))
+            .unwrap();
+        assert_eq!(app.instances[0].transition, Some(Transition::Pausing));
+        wait_for_transitions(&mut app);
+    }
+
+    #[test]
+    fn test_confirmation_message_format_kill() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("my-feature"));
         app.refresh_list();
 
-        // Press D to kill -> should enter Confirm state
         app.handle_key_action(KeyAction::Kill);
+        let msg = app.confirmation.as_ref().unwrap().message();
+        assert_eq!(msg, "[!] Kill session 'my-feature'? (y/n)");
+    }
+
+    #[test]
+    fn test_reconciled_session_offers_repair() {
+        let mut app = test_app();
+        let mut lost = make_test_instance("lost");
+        lost.status = InstanceStatus::Running;
+        lost.started = true;
+        lost.restoring = true;
+        lost.git_worktree = Some(GitWorktree::from_storage(
+            "/tmp".to_string(),
+            "/nonexistent/gana-wt".to_string(),
+            "lost".to_string(),
+            "gana/lost".to_string(),
+            String::new(),
+        ));
+        app.instances.push(lost);
+        app.refresh_list();
+
+        let reason = "The worktree /nonexistent/gana-wt no longer exists.".to_string();
+        app.bg_sender
+            .send(BackgroundUpdate::SessionReconciled("lost".to_string(), InstanceStatus::WorktreeMissing, reason))
+            .unwrap();
+        app.process_background_updates();
+
+        let instance = &app.instances[0];
+        assert!(!instance.restoring);
+        assert_eq!(instance.status, InstanceStatus::WorktreeMissing);
+        let details = help::details_text(instance, &app.disk_usage, None, None);
+        assert!(details.contains("Needs repair:\nThe worktree /nonexistent/gana-wt no longer exists."));
+
+        app.handle_key_action(KeyAction::Restart);
+        assert_eq!(app.instances[0].transition, Some(Transition::Resuming));
+        assert!(app.instances[0].error.is_none());
+    }
+
+    #[test]
+    fn test_restore_result_applied_by_title() {
+        let mut app = test_app();
+        let mut gone = make_test_instance("gone");
+        gone.status = InstanceStatus::Running;
+        gone.started = true;
+        gone.restoring = true;
+        app.instances.push(gone);
+        app.refresh_list();
+
+        app.bg_sender
+            .send(BackgroundUpdate::SessionRestored("gone".to_string(), None))
+            .unwrap();
+        app.process_background_updates();
+
+        let instance = &app.instances[0];
+        assert!(!instance.restoring);
+        assert_eq!(instance.status, InstanceStatus::Ready);
+        assert!(!instance.started);
+    }
+
+    #[test]
+    fn test_dirty_repo_is_checked_off_the_ui_thread() {
+        let mut app = test_app();
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_output()
+            .withf(|name, a| name == "git" && a.contains(&"status".to_string()))
+            .returning(|_, _| Ok(" M src/main.rs\n".to_string()));
+        app.cmd = std::sync::Arc::new(mock);
+
+        app.request_create("carry".to_string(), "go".to_string());
+        assert!(app.confirmation.is_none());
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(update, BackgroundUpdate::DirtyChecked(ref t, _, true) if t == "carry"));
+        app.bg_sender.send(update).unwrap();
+        app.process_background_updates();
+
         assert_eq!(app.state, AppState::Confirm);
-        assert!(app.confirmation.is_some());
+        assert!(matches!(app.pending_action, Some(PendingAction::Create { ref title, .. }) if title == "carry"));
+    }
 
-        // Press Esc to cancel -> back to Default
+    #[test]
+    fn test_title_input_lists_branches_off_the_ui_thread() {
+        let mut app = test_app();
+        app.config.branch_prefix = String::new();
+        app.config.branch_unique_suffix = false;
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_output()
+            .withf(|name, a| name == "git" && a.contains(&"for-each-ref".to_string()))
+            .returning(|_, _| Ok("main\ntaken\n".to_string()));
+        mock.expect_output()
+            .withf(|name, a| name == "git" && a.contains(&"worktree".to_string()))
+            .returning(|_, _| Ok(String::new()));
+        app.cmd = std::sync::Arc::new(mock);
+
+        app.handle_key_action(KeyAction::New);
+        app.text_input.as_mut().unwrap().paste("taken");
+        assert!(app.text_input.as_ref().unwrap().feedback().is_ok());
+
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(update, BackgroundUpdate::BranchesListed(..)));
+        app.bg_sender.send(update).unwrap();
+        app.process_background_updates();
+
+        let feedback = app.text_input.as_ref().unwrap().feedback();
+        assert!(feedback.unwrap_err().contains("already exists"));
+    }
+
+    #[test]
+    fn test_dirty_repo_confirmation_creates_with_carry() {
+        let mut app = test_app();
+        // Keep the new session queued so no worker thread is spawned
+        app.config.max_concurrent_creations = 1;
+        app.creating.insert("busy".to_string());
+
+        app.confirmation = Some(ConfirmationOverlay::new("dirty"));
+        app.pending_action = Some(PendingAction::Create {
+            title: "carry".to_string(),
+            prompt: "go".to_string(),
+        });
+        app.state = AppState::Confirm;
+        app.handle_confirm_key(KeyCode::Char('y')).unwrap();
+
+        assert_eq!(app.state, AppState::Default);
+        assert_eq!(app.instances.len(), 1);
+        assert!(app.instances[0].carry_changes);
+        assert_eq!(app.instances[0].pending_prompt.as_deref(), Some("go"));
+    }
+
+    #[test]
+    fn test_dirty_repo_confirmation_clean_and_cancel() {
+        let mut app = test_app();
+        app.config.max_concurrent_creations = 1;
+        app.creating.insert("busy".to_string());
+
+        app.confirmation = Some(ConfirmationOverlay::new("dirty"));
+        app.pending_action = Some(PendingAction::Create {
+            title: "clean".to_string(),
+            prompt: String::new(),
+        });
+        app.handle_confirm_key(KeyCode::Char('n')).unwrap();
+        assert_eq!(app.instances.len(), 1);
+        assert!(!app.instances[0].carry_changes);
+
+        app.confirmation = Some(ConfirmationOverlay::new("dirty"));
+        app.pending_action = Some(PendingAction::Create {
+            title: "never".to_string(),
+            prompt: String::new(),
+        });
         app.handle_confirm_key(KeyCode::Esc).unwrap();
+        assert_eq!(app.instances.len(), 1);
+    }
+
+    #[test]
+    fn test_creation_queue_respects_limit() {
+        let mut app = test_app();
+        app.config.max_concurrent_creations = 2;
+        // One creation already in flight
+        app.creating.insert("busy".to_string());
+        for title in ["a", "b", "c"] {
+            let mut instance = make_test_instance(title);
+            instance.status = InstanceStatus::Queued;
+            app.instances.push(instance);
+        }
+
+        let promoted = app.promote_queued();
+        assert_eq!(promoted.len(), 1);
+        assert_eq!(promoted[0].title, "a");
+        assert_eq!(app.instances[0].status, InstanceStatus::Loading);
+        assert_eq!(app.instances[1].status, InstanceStatus::Queued);
+        assert_eq!(app.instances[2].status, InstanceStatus::Queued);
+
+        // Slots are full: nothing else starts
+        assert!(app.promote_queued().is_empty());
+
+        // A finished creation frees a slot for the next queued session
+        app.creating.remove("busy");
+        let promoted = app.promote_queued();
+        assert_eq!(promoted.len(), 1);
+        assert_eq!(promoted[0].title, "b");
+        assert_eq!(app.instances[2].status, InstanceStatus::Queued);
+    }
+
+    #[test]
+    fn test_branch_picker_queues_session_on_branch() {
+        let mut app = test_app();
+        app.config.max_concurrent_creations = 1;
+        app.creating.insert("busy".to_string());
+        app.branch_picker = Some(BranchPickerOverlay::new(vec![
+            "main".to_string(),
+            "origin/pr-42".to_string(),
+        ]));
+        app.branch_remotes = vec!["origin".to_string()];
+        app.state = AppState::BranchPicker;
+
+        app.handle_key(KeyEvent::new(KeyCode::Char('4'), KeyModifiers::NONE)).unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)).unwrap();
+
         assert_eq!(app.state, AppState::Default);
-        assert!(app.confirmation.is_none());
+        assert!(app.branch_picker.is_none());
+        assert_eq!(app.instances.len(), 1);
+        assert_eq!(app.instances[0].title, "pr-42");
+        assert_eq!(app.instances[0].status, InstanceStatus::Queued);
+        assert_eq!(app.instances[0].existing_branch.as_deref(), Some("origin/pr-42"));
+
+        app.creating.remove("busy");
+        let promoted = app.promote_queued();
+        assert_eq!(promoted[0].existing_branch.as_deref(), Some("origin/pr-42"));
     }
 
     #[test]
-    fn test_confirmation_key_handling() {
+    fn test_branch_title_drops_any_remote_but_keeps_local_prefixes() {
         let mut app = test_app();
-        app.instances.push(make_test_instance("sess1"));
+        app.config.max_concurrent_creations = 1;
+        app.creating.insert("busy".to_string());
+        app.branch_remotes = vec!["origin".to_string(), "upstream".to_string()];
+
+        app.create_instance_on_branch("upstream/pr-7".to_string()).unwrap();
+        app.create_instance_on_branch("feature/login".to_string()).unwrap();
+
+        assert_eq!(app.instances[0].title, "pr-7");
+        assert_eq!(app.instances[0].existing_branch.as_deref(), Some("upstream/pr-7"));
+        assert_eq!(app.instances[1].title, "feature/login");
+    }
+
+    #[test]
+    fn test_disk_budget_warns_once_per_crossing() {
+        let mut app = test_app();
+        app.config.worktree_budget_mb = Some(1);
+        app.disk_scan_in_flight = true;
+        let usage = |total| DiskUsage { total, ..Default::default() };
+
+        app.apply_disk_usage(usage(2 * 1024 * 1024));
+        assert!(!app.disk_scan_in_flight);
+        assert!(app.error.has_error());
+
+        // Still over budget: no repeated warning
+        app.error.clear();
+        app.apply_disk_usage(usage(3 * 1024 * 1024));
+        assert!(!app.error.has_error());
+
+        // Drop below and cross again
+        app.apply_disk_usage(usage(1024));
+        app.apply_disk_usage(usage(2 * 1024 * 1024));
+        assert!(app.error.has_error());
+    }
+
+    #[test]
+    fn test_cleanup_targets_paused_worktrees_on_disk() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = test_app();
+        for (title, status, exists) in [
+            ("paused", InstanceStatus::Paused, true),
+            ("paused-gone", InstanceStatus::Paused, false),
+            ("running", InstanceStatus::Running, true),
+        ] {
+            let wt_dir = dir.path().join(title);
+            if exists {
+                std::fs::create_dir(&wt_dir).unwrap();
+            }
+            let mut instance = make_test_instance(title);
+            instance.status = status;
+            instance.git_worktree = Some(GitWorktree::from_storage(
+                "/repo".into(),
+                wt_dir.to_string_lossy().to_string(),
+                title.into(),
+                title.into(),
+                String::new(),
+            ));
+            app.instances.push(instance);
+        }
+
+        assert_eq!(app.paused_worktrees(), vec![0]);
+        app.handle_key_action(KeyAction::CleanupWorktrees);
+        assert_eq!(app.state, AppState::Confirm);
+        assert!(matches!(app.pending_action, Some(PendingAction::CleanupWorktrees)));
+    }
+
+    #[test]
+    fn test_cleanup_without_paused_worktrees_reports() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("idle"));
+        app.handle_key_action(KeyAction::CleanupWorktrees);
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.error.has_error());
+    }
+
+    #[test]
+    fn test_duplicate_title_gets_suffix() {
+        let mut app = test_app();
+        app.config.max_concurrent_creations = 1;
+        // Occupies the only creation slot, so new sessions stay queued
+        app.creating.insert("busy".to_string());
+        app.instances.push(make_test_instance("fix-bug"));
+
+        app.create_instance_with_prompt("fix bug".to_string(), String::new()).unwrap();
+        assert_eq!(app.instances[1].title, "fix bug-2");
+        assert_eq!(app.instances[1].status, InstanceStatus::Queued);
+        assert!(app.error.has_info());
+    }
+
+    #[test]
+    fn test_creation_limit_is_at_least_one() {
+        let mut app = test_app();
+        app.config.max_concurrent_creations = 0;
+        let mut instance = make_test_instance("only");
+        instance.status = InstanceStatus::Queued;
+        app.instances.push(instance);
+
+        assert_eq!(app.promote_queued().len(), 1);
+        assert!(app.creating.contains("only"));
+    }
+
+    #[test]
+    fn test_background_results_follow_title_not_index() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("first"));
+        let mut second = make_test_instance("second");
+        second.status = InstanceStatus::Loading;
+        second.pending_prompt = Some("fix the build".to_string());
+        app.instances.push(second);
+        let mut third = make_test_instance("third");
+        third.status = InstanceStatus::Loading;
+        app.instances.push(third);
         app.refresh_list();
 
-        // Enter confirm state for kill
+        // An earlier session disappears while creation is in flight
+        app.instances.remove(0);
+
+        app.bg_sender
+            .send(BackgroundUpdate::InstanceFailed("third".to_string(), "boom".to_string()))
+            .unwrap();
+        let worktree = crate::session::git::GitWorktree::from_storage(
+            "/repo".to_string(),
+            "/worktree".to_string(),
+            "second".to_string(),
+            "gana/second".to_string(),
+            "abc123".to_string(),
+        );
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run().returning(|_, _| Ok(()));
+        // A program without a trust prompt gets its prompt right away
+        let tmux = crate::session::tmux::TmuxSession::new(
+            "second",
+            "vim",
+            Box::new(mock),
+            Box::new(crate::session::tmux::pty::SystemPtyFactory),
+        );
+        app.bg_sender
+            .send(BackgroundUpdate::CreationProgress("second".to_string(), CreationStep::Launch))
+            .unwrap();
+        app.bg_sender
+            .send(BackgroundUpdate::InstanceReady(
+                "second".to_string(),
+                Box::new(CreatedSession {
+                    worktree,
+                    tmux,
+                    owns_branch: true,
+                    carry_error: Some("patch does not apply".to_string()),
+                }),
+            ))
+            .unwrap();
+        app.process_background_updates();
+
+        assert!(app.error.message().is_some_and(|m| m.contains("patch does not apply")));
+        assert_eq!(app.instances.len(), 2);
+        let second = &app.instances[0];
+        assert_eq!(second.title, "second");
+        assert_eq!(second.branch, "gana/second");
+        assert_eq!(second.status, InstanceStatus::Running);
+        assert_eq!(second.pending_prompt, None);
+        assert!(app.creation_steps.is_empty());
+        assert_eq!(app.instances[1].status, InstanceStatus::Failed);
+    }
+
+    #[test]
+    fn test_sessions_being_set_up_are_not_removed() {
+        let mut app = test_app();
+        let sent = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = std::sync::Arc::clone(&sent);
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run().returning(move |_, a| {
+            log.lock().unwrap().push(a.join(" "));
+            Ok(())
+        });
+        app.cmd = std::sync::Arc::new(mock);
+        let mut loading = make_test_instance("loading");
+        loading.status = InstanceStatus::Loading;
+        app.instances.push(loading);
+        app.creating.insert("loading".to_string());
+        app.refresh_list();
+
+        app.handle_key_action(KeyAction::Delete);
+        assert_eq!(app.state, AppState::Default);
+        assert!(app.error.has_error());
         app.handle_key_action(KeyAction::Kill);
-        assert_eq!(app.state, AppState::Confirm);
+        assert_eq!(app.state, AppState::Default);
 
-        // Random key should not dismiss
-        app.handle_confirm_key(KeyCode::Char('x')).unwrap();
-        assert_eq!(app.state, AppState::Confirm);
+        // Should it leave the list anyway, what its setup made is removed
+        app.instances.clear();
+        let worktree = crate::session::git::GitWorktree::from_storage(
+            "/repo".to_string(),
+            "/nonexistent/worktree".to_string(),
+            "loading".to_string(),
+            "gana/loading".to_string(),
+            "abc123".to_string(),
+        );
+        let tmux = crate::session::tmux::TmuxSession::new(
+            "loading",
+            "claude",
+            Box::new(std::sync::Arc::clone(&app.cmd)),
+            Box::new(crate::session::tmux::pty::SystemPtyFactory),
+        );
+        app.bg_sender
+            .send(BackgroundUpdate::InstanceReady(
+                "loading".to_string(),
+                Box::new(CreatedSession { worktree, tmux, owns_branch: true, carry_error: None }),
+            ))
+            .unwrap();
+        app.process_background_updates();
+        assert!(app.instances.is_empty());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !sent.lock().unwrap().iter().any(|a| a.contains("worktree prune")) {
+            assert!(Instant::now() < deadline, "worktree never removed");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let sent = sent.lock().unwrap();
+        assert!(sent.iter().any(|a| a.contains("kill-session")));
+        assert!(sent.iter().any(|a| a.contains("branch -D gana/loading")));
+    }
 
-        // 'n' cancels
-        app.handle_confirm_key(KeyCode::Char('n')).unwrap();
-        assert_eq!(app.state, AppState::Default);
-        // Instance should still be there (not killed)
-        assert_eq!(app.instances.len(), 1);
+    #[test]
+    fn test_initial_prompt_waits_for_trust_prompt() {
+        let mut app = test_app();
+        let sent = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let log = std::sync::Arc::clone(&sent);
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run().returning(move |_, a| {
+            log.lock().unwrap().push(a.join(" "));
+            Ok(())
+        });
+        let mut instance = make_test_instance("trusting");
+        instance.status = InstanceStatus::Running;
+        instance.pending_prompt = Some("fix the build".to_string());
+        instance.tmux_session = Some(crate::session::tmux::TmuxSession::new(
+            "trusting",
+            "claude",
+            Box::new(mock),
+            Box::new(crate::session::tmux::pty::SystemPtyFactory),
+        ));
+        assert!(instance.trust_prompt().is_some());
+        app.instances.push(instance);
+        app.answering_trust.insert("trusting".to_string());
+
+        app.bg_sender
+            .send(BackgroundUpdate::TrustAnswered("trusting".to_string(), Ok(true)))
+            .unwrap();
+        app.process_background_updates();
+
+        assert!(app.answering_trust.is_empty());
+        assert_eq!(app.instances[0].pending_prompt, None);
+        assert!(sent.lock().unwrap().iter().any(|a| a.contains("fix the build")));
     }
 
     #[test]
-    fn test_confirmation_y_confirms_delete() {
+    fn test_interrupted_creations_are_requeued() {
         let mut app = test_app();
-        app.instances.push(make_test_instance("to-delete"));
+        let mut loading = make_test_instance("loading");
+        loading.status = InstanceStatus::Loading;
+        loading.pending_prompt = Some("refactor".to_string());
+        let mut failed = make_test_instance("failed");
+        failed.status = InstanceStatus::Failed;
+        let mut running = make_test_instance("running");
+        running.status = InstanceStatus::Running;
+        running.started = true;
+        app.instances = vec![loading, failed, running];
+
+        assert!(app.requeue_interrupted_creations());
+        assert_eq!(app.instances[0].status, InstanceStatus::Queued);
+        assert_eq!(app.instances[0].pending_prompt.as_deref(), Some("refactor"));
+        assert_eq!(app.instances[1].status, InstanceStatus::Failed);
+        assert_eq!(app.instances[2].status, InstanceStatus::Running);
+    }
+
+    #[test]
+    fn test_expiry_archives_long_paused_sessions() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(Config::default(), dir.path().to_path_buf());
+        app.config.archive_paused_days = Some(14);
+        for (title, status, days_ago) in [
+            ("old", InstanceStatus::Paused, 30),
+            ("recent", InstanceStatus::Paused, 2),
+            ("ready", InstanceStatus::Ready, 30),
+        ] {
+            let mut instance = make_test_instance(title);
+            instance.status = status;
+            instance.updated_at = chrono::Utc::now() - chrono::Duration::days(days_ago);
+            app.instances.push(instance);
+        }
         app.refresh_list();
 
-        // Enter confirm state for delete
-        app.handle_key_action(KeyAction::Delete);
-        assert_eq!(app.state, AppState::Confirm);
+        app.apply_expiry_policy();
+        assert_eq!(app.instances[0].transition, Some(Transition::Archiving));
+        wait_for_transitions(&mut app);
 
-        // 'y' confirms -> instance removed
-        app.handle_confirm_key(KeyCode::Char('y')).unwrap();
-        assert_eq!(app.state, AppState::Default);
-        assert!(app.instances.is_empty());
+        let titles: Vec<&str> = app.instances.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, ["recent", "ready"]);
+        let archived = FileStorage::new(dir.path()).load_archive().unwrap();
+        assert_eq!(archived.len(), 1);
+        assert_eq!(archived[0].title, "old");
+        assert!(app.error.has_info());
     }
 
     #[test]
-    fn test_text_input_flow() {
-        let mut app = test_app();
-        assert_eq!(app.state, AppState::Default);
-
-        // Press 'n' for new session -> TextInput state
-        app.handle_key_action(KeyAction::New);
-        assert_eq!(app.state, AppState::TextInput);
-        assert!(app.text_input.is_some());
+    fn test_switch_project_cycles_scopes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(Config::default(), dir.path().to_path_buf());
+        for (title, path) in [("api", "/src/api"), ("web", "/src/web")] {
+            let mut instance = make_test_instance(title);
+            instance.path = path.to_string();
+            instance.started = true;
+            instance.status = InstanceStatus::Paused;
+            app.instances.push(instance);
+        }
+        app.refresh_list();
+        let titles = |app: &App| app.instances.iter().map(|i| i.title.clone()).collect::<Vec<_>>();
 
-        // Type some characters
-        app.handle_text_input_key(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE))
-            .unwrap();
-        app.handle_text_input_key(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE))
-            .unwrap();
+        app.handle_key_action(KeyAction::SwitchProject);
+        assert_eq!(app.project.as_deref(), Some(std::path::Path::new("/src/api")));
+        assert_eq!(titles(&app), ["api"]);
+        assert_eq!(app.repo_dir().unwrap(), std::path::PathBuf::from("/src/api"));
 
-        assert_eq!(app.text_input.as_ref().unwrap().input(), "te");
+        app.handle_key_action(KeyAction::SwitchProject);
+        assert_eq!(titles(&app), ["web"]);
 
-        // Cancel with Esc
-        app.handle_text_input_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE))
-            .unwrap();
-        assert_eq!(app.state, AppState::Default);
-        assert!(app.text_input.is_none());
+        app.handle_key_action(KeyAction::SwitchProject);
+        assert_eq!(app.project, None);
+        assert_eq!(titles(&app), ["api", "web"]);
     }
 
     #[test]
-    fn test_help_toggle() {
+    fn test_team_overlay_shows_synced_sessions() {
         let mut app = test_app();
-        assert_eq!(app.state, AppState::Default);
-
-        // Press ? for help
-        app.handle_key_action(KeyAction::Help);
+        app.config.team_repo = Some("git@example.com:team/gana.git".to_string());
+        app.team_sync_in_flight = true;
+        let mut session = make_test_instance("search");
+        session.branch = "gana/search".to_string();
+        let members = vec![MemberSessions {
+            member: "bob".to_string(),
+            published_at: chrono::Utc::now(),
+            sessions: vec![(&session).into()],
+        }];
+        app.bg_sender.send(BackgroundUpdate::TeamSynced(Ok(members))).unwrap();
+        app.process_background_updates();
+        assert!(!app.team_sync_in_flight);
+
+        app.handle_key_action(KeyAction::Team);
         assert_eq!(app.state, AppState::Help);
         assert!(app.help_overlay.is_some());
+        let text = help::team_text(&app.config, &app.team, None);
+        assert!(text.contains("bob"));
+        assert!(text.contains("search  gana/search  ready"));
 
-        // Press Esc to dismiss
-        app.handle_help_key(KeyCode::Esc).unwrap();
-        assert_eq!(app.state, AppState::Default);
-        assert!(app.help_overlay.is_none());
+        // A failed sync keeps the last sessions and says why
+        app.bg_sender
+            .send(BackgroundUpdate::TeamSynced(Err("push rejected".to_string())))
+            .unwrap();
+        app.process_background_updates();
+        assert_eq!(app.team.len(), 1);
+        assert_eq!(app.team_error.as_deref(), Some("push rejected"));
     }
 
     #[test]
-    fn test_navigation_updates_selection() {
+    fn test_tests_finished_updates_status() {
         let mut app = test_app();
-        app.instances.push(make_test_instance("first"));
-        app.instances.push(make_test_instance("second"));
-        app.instances.push(make_test_instance("third"));
+        app.instances.push(make_test_instance("watched"));
+        app.tests_running.insert("watched".to_string());
         app.refresh_list();
 
-        assert_eq!(app.list.selected_index(), 0);
+        app.bg_sender
+            .send(BackgroundUpdate::TestsFinished("watched".to_string(), TestStatus::Passed))
+            .unwrap();
+        app.process_background_updates();
+        assert_eq!(app.instances[0].test_status, Some(TestStatus::Passed));
+        assert!(app.tests_running.is_empty());
+        assert!(!app.error.has_error());
+
+        // Passing to failing is called out once
+        let failed = TestStatus::Failed("assertion failed".to_string());
+        app.bg_sender
+            .send(BackgroundUpdate::TestsFinished("watched".to_string(), failed.clone()))
+            .unwrap();
+        app.process_background_updates();
+        assert_eq!(app.instances[0].test_status, Some(failed));
+        assert!(app.error.has_error());
+    }
 
-        app.handle_key_action(KeyAction::Down);
-        assert_eq!(app.list.selected_index(), 1);
+    #[test]
+    fn test_pipeline_step_waits_for_upstream() {
+        let mut app = test_app();
+        app.config.max_concurrent_creations = 1;
+        // Occupies the only creation slot, so released steps stay queued
+        app.creating.insert("busy".to_string());
+        let mut upstream = make_test_instance("refactor-core");
+        upstream.status = InstanceStatus::Loading;
+        app.instances.push(upstream);
+        for (title, after) in [("docs-update", "refactor-core"), ("changelog", "docs-update")] {
+            let mut step = make_test_instance(title);
+            step.status = InstanceStatus::Pending;
+            step.after = Some(after.to_string());
+            app.instances.push(step);
+        }
 
-        app.handle_key_action(KeyAction::Down);
-        assert_eq!(app.list.selected_index(), 2);
+        app.release_pipeline_steps(None);
+        assert_eq!(app.instances[1].status, InstanceStatus::Pending);
 
-        app.handle_key_action(KeyAction::Up);
-        assert_eq!(app.list.selected_index(), 1);
+        // The agent exited
+        app.instances[0].status = InstanceStatus::Ready;
+        app.instances[0].started = true;
+        app.release_pipeline_steps(None);
+        assert_eq!(app.instances[1].status, InstanceStatus::Queued);
+        assert_eq!(app.instances[2].status, InstanceStatus::Pending);
+
+        // Pushing a PR releases the next step straight away
+        app.release_pipeline_steps(Some("docs-update"));
+        assert_eq!(app.instances[2].status, InstanceStatus::Queued);
     }
 
     #[test]
-    fn test_quit_sets_running_false() {
+    fn test_agent_idle_after_working_is_done() {
         let mut app = test_app();
-        assert!(app.running);
+        let mut instance = make_test_instance("chore");
+        instance.status = InstanceStatus::Running;
+        instance.started = true;
+        app.instances.push(instance);
+        app.refresh_list();
+        let state = |app: &mut App, state: AgentState| {
+            app.bg_sender
+                .send(BackgroundUpdate::AgentState("chore".to_string(), state))
+                .unwrap();
+            app.process_background_updates();
+            app.instances[0].status
+        };
 
-        app.handle_key_action(KeyAction::Quit);
-        assert!(!app.running);
+        // Idle before it started on anything isn't done
+        assert_eq!(state(&mut app, AgentState::Idle), InstanceStatus::Running);
+        assert_eq!(state(&mut app, AgentState::Working), InstanceStatus::Running);
+        assert_eq!(state(&mut app, AgentState::Waiting), InstanceStatus::Running);
+        assert_eq!(state(&mut app, AgentState::Idle), InstanceStatus::Done);
+        assert_eq!(state(&mut app, AgentState::Idle), InstanceStatus::Done);
+        // More work makes it running again
+        assert_eq!(state(&mut app, AgentState::Working), InstanceStatus::Running);
     }
 
     #[test]
-    fn test_tab_switches_view() {
+    fn test_done_session_with_failing_tests_is_not_pushed() {
         let mut app = test_app();
-        assert_eq!(app.tabbed_window.active_tab(), Tab::Preview);
-
-        app.handle_key_action(KeyAction::Tab);
-        assert_eq!(app.tabbed_window.active_tab(), Tab::Diff);
+        // Slow enough that the result isn't handled in the same round
+        app.config.test_command = Some("sleep 0.5; exit 1".to_string());
+        let mut instance = make_test_instance("chore");
+        instance.status = InstanceStatus::Running;
+        instance.started = true;
+        instance.git_worktree = Some(GitWorktree::from_storage(
+            "/tmp".to_string(),
+            "/tmp".to_string(),
+            "chore".to_string(),
+            "gana/chore".to_string(),
+            String::new(),
+        ));
+        app.instances.push(instance);
+        app.refresh_list();
+        app.handle_key_action(KeyAction::ToggleAutoPush);
+        assert!(app.instances[0].auto_push);
 
-        app.handle_key_action(KeyAction::Tab);
-        assert_eq!(app.tabbed_window.active_tab(), Tab::Preview);
+        for state in [AgentState::Working, AgentState::Idle] {
+            app.bg_sender
+                .send(BackgroundUpdate::AgentState("chore".to_string(), state))
+                .unwrap();
+        }
+        app.process_background_updates();
+        assert_eq!(app.instances[0].status, InstanceStatus::Done);
+        assert_eq!(app.instances[0].test_status, Some(TestStatus::Running));
+
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(update, BackgroundUpdate::AutoPushChecked(_, TestStatus::Failed(_))));
+        app.bg_sender.send(update).unwrap();
+        app.process_background_updates();
+        assert!(matches!(app.instances[0].test_status, Some(TestStatus::Failed(_))));
+        assert!(!app.menu.is_running("Pushing 'chore'"));
     }
 
     #[test]
-    fn test_scroll_in_default_state() {
+    fn test_selected_pane_needs_its_own_preview_capture() {
         let mut app = test_app();
+        let mut instance = make_test_instance("api");
+        instance.status = InstanceStatus::Running;
+        instance.started = true;
+        app.instances.push(instance);
+        app.refresh_list();
+        app.polled_selection = Some("api".to_string());
 
-        app.handle_key_action(KeyAction::ScrollUp);
-        assert!(app.preview.is_scrolling());
+        // Captured while another session was selected: no preview yet
+        app.bg_sender
+            .send(BackgroundUpdate::PaneCaptured("api".to_string(), 100, false))
+            .unwrap();
+        app.process_background_updates();
+        assert!(!app.pane_activity.contains_key("api"));
 
-        app.handle_key_action(KeyAction::Cancel);
-        assert!(!app.preview.is_scrolling());
+        app.bg_sender
+            .send(BackgroundUpdate::PaneCaptured("api".to_string(), 101, true))
+            .unwrap();
+        app.process_background_updates();
+        assert_eq!(app.pane_activity.get("api"), Some(&101));
     }
 
     #[test]
-    fn test_kill_on_empty_list_does_nothing() {
+    fn test_died_session_keeps_last_output() {
         let mut app = test_app();
-        assert!(app.instances.is_empty());
+        let mut instance = make_test_instance("api");
+        instance.status = InstanceStatus::Running;
+        instance.started = true;
+        app.instances.push(instance);
+        app.refresh_list();
 
-        // Kill on empty list should stay in Default state
-        app.handle_key_action(KeyAction::Kill);
-        assert_eq!(app.state, AppState::Default);
+        app.bg_sender
+            .send(BackgroundUpdate::PaneTail("api".to_string(), "Error: API key expired".to_string()))
+            .unwrap();
+        app.bg_sender.send(BackgroundUpdate::SessionDied("api".to_string())).unwrap();
+        app.process_background_updates();
+
+        let died = &app.instances[0];
+        assert_eq!(died.status, InstanceStatus::Ready);
+        assert_eq!(died.exit_output.as_deref(), Some("Error: API key expired"));
+        let details = help::details_text(died, &app.disk_usage, None, None);
+        assert!(details.contains("Last output before the agent exited:\nError: API key expired"));
+        assert_eq!(app.pane_tails.take("api"), None);
+        // Still stored, so the output survives a restart
+        let stored = app.storage().load_instances().unwrap();
+        assert_eq!(stored[0].exit_output.as_deref(), Some("Error: API key expired"));
     }
 
     #[test]
-    fn test_delete_on_empty_list_does_nothing() {
+    fn test_died_session_is_found_by_title() {
         let mut app = test_app();
-        assert!(app.instances.is_empty());
+        for title in ["gone", "dead", "live"] {
+            let mut instance = make_test_instance(title);
+            instance.status = InstanceStatus::Running;
+            instance.started = true;
+            app.instances.push(instance);
+        }
+        app.refresh_list();
 
-        app.handle_key_action(KeyAction::Delete);
-        assert_eq!(app.state, AppState::Default);
+        // "dead" was checked at index 1, then "gone" left the list
+        app.instances.remove(0);
+        app.bg_sender.send(BackgroundUpdate::SessionDied("dead".to_string())).unwrap();
+        app.process_background_updates();
+
+        assert_eq!(app.instances[0].status, InstanceStatus::Ready);
+        assert_eq!(app.instances[1].status, InstanceStatus::Running);
     }
 
     #[test]
-    fn test_key_routing_in_text_input_state() {
+    fn test_failed_creation_is_kept_and_retried() {
         let mut app = test_app();
-        app.handle_key_action(KeyAction::New);
-        assert_eq!(app.state, AppState::TextInput);
+        app.config.max_concurrent_creations = 1;
+        let mut failing = make_test_instance("failing");
+        failing.status = InstanceStatus::Loading;
+        failing.pending_prompt = Some("add tests".to_string());
+        app.instances.push(failing);
+        // Occupies the only creation slot, so a retry waits in the queue
+        app.creating.insert("busy".to_string());
+        app.creating.insert("failing".to_string());
+        app.refresh_list();
 
-        // In TextInput state, normal keys go to the input, not to actions
-        // 'q' should not quit — it should type 'q'
-        app.handle_key(KeyEvent::new(KeyCode::Char('q'), KeyModifiers::NONE))
+        app.bg_sender
+            .send(BackgroundUpdate::InstanceFailed("failing".to_string(), "worktree add failed".to_string()))
             .unwrap();
-        assert!(app.running); // still running
-        assert_eq!(app.text_input.as_ref().unwrap().input(), "q");
+        app.process_background_updates();
+
+        let failed = &app.instances[0];
+        assert_eq!(failed.status, InstanceStatus::Failed);
+        assert_eq!(failed.error.as_deref(), Some("worktree add failed"));
+        assert_eq!(failed.pending_prompt.as_deref(), Some("add tests"));
+        let details = help::details_text(failed, &app.disk_usage, None, None);
+        assert!(details.contains("worktree add failed"));
+
+        app.handle_key_action(KeyAction::Restart);
+        assert_eq!(app.state, AppState::Default);
+        let retried = &app.instances[0];
+        assert_eq!(retried.status, InstanceStatus::Queued);
+        assert!(retried.error.is_none());
+        assert_eq!(retried.pending_prompt.as_deref(), Some("add tests"));
     }
 
     #[test]
-    fn test_confirmation_message_format_kill() {
+    fn test_updates_for_unknown_session_are_ignored() {
         let mut app = test_app();
-        app.instances.push(make_test_instance("my-feature"));
+        app.instances.push(make_test_instance("kept"));
         app.refresh_list();
 
-        app.handle_key_action(KeyAction::Kill);
-        let msg = app.confirmation.as_ref().unwrap().message();
-        assert_eq!(msg, "[!] Kill session 'my-feature'? (y/n)");
+        app.bg_sender
+            .send(BackgroundUpdate::InstanceFailed("gone".to_string(), "boom".to_string()))
+            .unwrap();
+        app.process_background_updates();
+
+        assert_eq!(app.instances.len(), 1);
+        assert_eq!(app.instances[0].title, "kept");
     }
 
     #[test]
@@ -1307,18 +5147,56 @@ mod tests {
     }
 
     #[test]
-    fn test_prompt_flow_empty_title_cancels() {
+    fn test_resolve_prompt_input_plain_text() {
+        assert_eq!(resolve_prompt_input("fix the bug").unwrap(), "fix the bug");
+    }
+
+    #[test]
+    fn test_resolve_prompt_input_from_file() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("plan.md");
+        std::fs::write(&path, "# Plan\n\nDo the thing.\n").unwrap();
+
+        let prompt = resolve_prompt_input(&format!("@{}", path.display())).unwrap();
+        assert_eq!(prompt, "# Plan\n\nDo the thing.");
+    }
+
+    #[test]
+    fn test_resolve_prompt_input_missing_file_is_text() {
+        let prompt = resolve_prompt_input("@/nonexistent/gana-prompt.md").unwrap();
+        assert_eq!(prompt, "@/nonexistent/gana-prompt.md");
+    }
+
+    #[test]
+    fn test_resolve_prompt_input_keeps_mentions() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("main.rs");
+        std::fs::write(&path, "fn main() {}\n").unwrap();
+
+        let text = format!("@{} add error handling", path.display());
+        assert_eq!(resolve_prompt_input(&text).unwrap(), text);
+        assert_eq!(resolve_prompt_input("@src/app.rs fix it").unwrap(), "@src/app.rs fix it");
+    }
+
+    #[test]
+    fn test_prompt_flow_empty_title_asks_for_prompt() {
         let mut app = test_app();
 
         app.handle_key_action(KeyAction::Prompt);
 
-        // Submit empty title
+        // Submit empty title: the title will come from the prompt
         app.handle_text_input_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
             .unwrap();
+        assert_eq!(app.state, AppState::TextInput);
+        assert_eq!(app.pending_instance_title.as_deref(), Some(""));
 
-        // Should return to default (empty title cancels flow)
+        // An empty prompt leaves nothing to name the session after
+        app.handle_text_input_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE))
+            .unwrap();
         assert_eq!(app.state, AppState::Default);
         assert!(!app.creating_with_prompt);
+        assert!(app.instances.is_empty());
+        assert!(app.error.has_error());
     }
 
     #[test]
@@ -1363,6 +5241,33 @@ mod tests {
         assert_eq!(map_key(event), Some(KeyAction::Prompt));
     }
 
+    #[test]
+    fn test_enter_depends_on_status() {
+        let mut app = test_app();
+        let mut loading = make_test_instance("loading");
+        loading.status = InstanceStatus::Loading;
+        let mut paused = make_test_instance("paused");
+        paused.status = InstanceStatus::Paused;
+        paused.started = true;
+        paused.git_worktree = Some(GitWorktree::from_storage(
+            "/tmp".to_string(),
+            "/nonexistent/gana-wt".to_string(),
+            "paused".to_string(),
+            "gana/paused".to_string(),
+            String::new(),
+        ));
+        app.instances.push(loading);
+        app.instances.push(paused);
+        app.refresh_list();
+
+        assert!(matches!(app.handle_key_action(KeyAction::Enter), AppAction::None));
+        assert!(app.error.has_error());
+
+        app.handle_key_action(KeyAction::Down);
+        app.handle_key_action(KeyAction::Enter);
+        assert_eq!(app.instances[1].transition, Some(Transition::Resuming));
+    }
+
     #[test]
     fn test_pause_running_session() {
         let mut app = test_app();
@@ -1371,12 +5276,78 @@ mod tests {
         app.instances.push(inst);
         app.refresh_list();
 
-        // Pause action on a Running instance triggers pause.
-        // Since there's no real tmux/git, pause() will fail, but
-        // the code path is exercised and an error is set.
+        // Pause runs in the background; the session shows it meanwhile
         app.handle_key_action(KeyAction::Pause);
+        assert_eq!(app.instances[0].transition, Some(Transition::Pausing));
+        assert!(app.menu.is_running("Pausing 'pause-test'"));
+        // A second press while pausing does nothing
+        app.handle_key_action(KeyAction::Pause);
+
+        wait_for_transitions(&mut app);
         // The instance should still exist (pause doesn't remove it)
         assert_eq!(app.instances.len(), 1);
+        assert_eq!(app.instances[0].status, InstanceStatus::Paused);
+        assert!(!app.menu.is_running("Pausing 'pause-test'"));
+    }
+
+    #[test]
+    fn test_kill_runs_in_background() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(Config::default(), dir.path().to_path_buf());
+        let mut inst = make_test_instance("doomed");
+        inst.status = InstanceStatus::Running;
+        app.instances.push(inst);
+        app.refresh_list();
+
+        app.handle_key_action(KeyAction::Kill);
+        app.handle_confirm_key(KeyCode::Char('y')).unwrap();
+        assert_eq!(app.instances[0].transition, Some(Transition::Killing));
+
+        wait_for_transitions(&mut app);
+        assert!(app.instances.is_empty());
+        assert_eq!(app.undo_buffer.len(), 1);
+    }
+
+    #[test]
+    fn test_delete_can_close_tmux_first() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(Config::default(), dir.path().to_path_buf());
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run()
+            .withf(|_, a| a[0] == "kill-session" && a[2] == "gana_closing")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let mut inst = make_test_instance("closing");
+        inst.status = InstanceStatus::Running;
+        inst.tmux_session = Some(crate::session::tmux::TmuxSession::new(
+            "closing",
+            "claude",
+            Box::new(mock),
+            Box::new(crate::session::tmux::pty::SystemPtyFactory),
+        ));
+        app.instances.push(inst);
+        app.refresh_list();
+
+        app.handle_key_action(KeyAction::Delete);
+        app.handle_confirm_key(KeyCode::Char('c')).unwrap();
+        assert_eq!(app.instances[0].transition, Some(Transition::Closing));
+
+        wait_for_transitions(&mut app);
+        assert!(app.instances.is_empty());
+
+        // Undo brings it back paused, since its tmux session is gone
+        app.handle_key_action(KeyAction::Undo);
+        assert_eq!(app.instances[0].status, InstanceStatus::Paused);
+    }
+
+    /// Process background updates until no session is mid-transition.
+    fn wait_for_transitions(app: &mut App) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while app.instances.iter().any(|i| i.transition.is_some()) {
+            assert!(Instant::now() < deadline, "transition never finished");
+            std::thread::sleep(Duration::from_millis(10));
+            app.process_background_updates();
+        }
     }
 
     #[test]
@@ -1387,15 +5358,267 @@ mod tests {
         app.instances.push(inst);
         app.refresh_list();
 
-        // Push should enter confirmation state
+        // Push should open the push options for the session, once gh was
+        // checked on a worker
         app.handle_key_action(KeyAction::Push);
-        assert_eq!(app.state, AppState::Confirm);
-        assert!(app.confirmation.is_some());
-        let msg = app.confirmation.as_ref().unwrap().message();
-        assert!(msg.contains("push-test"));
+        assert_eq!(app.state, AppState::Default);
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(update, BackgroundUpdate::PushChecked(ref t, _, _) if t == "push-test"));
+        app.bg_sender.send(update).unwrap();
+        app.process_background_updates();
+        assert_eq!(app.state, AppState::Push);
+        assert_eq!(app.push_overlay.as_ref().unwrap().session(), "push-test");
 
         // Cancel with 'n'
-        app.handle_confirm_key(KeyCode::Char('n')).unwrap();
+        app.handle_key(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE)).unwrap();
         assert_eq!(app.state, AppState::Default);
+        assert!(app.push_overlay.is_none());
+    }
+
+    #[test]
+    fn test_worktree_tools_need_worktree_on_disk() {
+        let mut app = test_app();
+        let mut inst = make_test_instance("edit-me");
+        app.instances.push(inst.clone());
+        app.refresh_list();
+
+        let action = app.handle_key_action(KeyAction::OpenInEditor);
+        assert!(matches!(action, AppAction::None));
+        assert!(app.error.has_error());
+
+        let dir = tempfile::TempDir::new().unwrap();
+        let dir_str = dir.path().to_string_lossy().to_string();
+        inst.git_worktree = Some(GitWorktree::from_storage(
+            "/repo".into(),
+            dir_str.clone(),
+            "edit-me".into(),
+            "gana/edit-me".into(),
+            String::new(),
+        ));
+        app.instances[0] = inst;
+        let action = app.handle_key_action(KeyAction::OpenInEditor);
+        assert!(matches!(action, AppAction::EditWorktree(ref d) if *d == dir_str));
+        let action = app.handle_key_action(KeyAction::GitTui);
+        assert!(matches!(action, AppAction::GitTui(ref d) if *d == dir_str));
+    }
+
+    #[test]
+    fn test_enter_on_diff_hunk_opens_file_at_line() {
+        let mut app = test_app();
+        let dir = tempfile::TempDir::new().unwrap();
+        let dir_str = dir.path().to_string_lossy().to_string();
+        let mut inst = make_test_instance("hunks");
+        inst.git_worktree = Some(GitWorktree::from_storage(
+            "/repo".into(),
+            dir_str.clone(),
+            "hunks".into(),
+            "gana/hunks".into(),
+            String::new(),
+        ));
+        app.instances.push(inst);
+        app.refresh_list();
+        app.diff_view.set_diff(&DiffStats::from_diff(
+            "+++ b/src/foo.rs\n@@ -10,3 +12,4 @@\n+x\n".to_string(),
+        ));
+        app.tabbed_window.set_tab(Tab::Diff);
+        app.handle_key_action(KeyAction::ScrollDown);
+
+        let action = app.handle_key_action(KeyAction::Enter);
+        assert!(matches!(
+            action,
+            AppAction::EditFile { ref dir, ref file, line: 12 } if *dir == dir_str && file == "src/foo.rs"
+        ));
+    }
+
+    #[test]
+    fn test_push_finishes_in_background() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut app = App::new(Config::default(), dir.path().to_path_buf());
+        let mut instance = make_test_instance("pushed");
+        instance.branch = "gana/pushed".to_string();
+        app.instances.push(instance);
+        app.refresh_list();
+        let request = |plain_git| PushRequest {
+            title: "pushed".to_string(),
+            squash: None,
+            push: PushOptions {
+                plain_git,
+                ..PushOptions::default()
+            },
+            by_gh_status: false,
+        };
+
+        app.menu.start_operation("Pushing 'pushed'");
+        app.bg_sender
+            .send(BackgroundUpdate::PushFinished(request(true), Ok(None)))
+            .unwrap();
+        app.process_background_updates();
+        assert!(!app.menu.is_running("Pushing 'pushed'"));
+        assert!(app.error.has_info());
+        let events = app.journal.recent(10);
+        assert_eq!(events.last().unwrap().kind, EventKind::Pushed);
+
+        app.error.clear();
+        app.bg_sender
+            .send(BackgroundUpdate::PushFinished(request(false), Err("rejected".to_string())))
+            .unwrap();
+        app.process_background_updates();
+        assert!(app.error.has_error());
+        assert_eq!(app.journal.recent(10).len(), 1);
+
+        let draft = PushRequest {
+            title: "pushed".to_string(),
+            squash: None,
+            push: PushOptions {
+                draft: true,
+                ..PushOptions::default()
+            },
+            by_gh_status: false,
+        };
+        let url = "https://github.com/o/r/pull/7".to_string();
+        app.error.clear();
+        app.bg_sender
+            .send(BackgroundUpdate::PushFinished(draft, Ok(Some(url))))
+            .unwrap();
+        app.process_background_updates();
+        assert!(app.error.has_info());
+        assert_eq!(app.journal.recent(10).len(), 2);
+    }
+
+    #[test]
+    fn test_auto_push_checks_gh_on_the_worker() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("done"));
+        app.refresh_list();
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run()
+            .withf(|name, a| name == "gh" && a[0] == "--version")
+            .times(1)
+            .returning(|_, _| Err(crate::cmd::CmdError::NotFound("gh".to_string())));
+        app.cmd = std::sync::Arc::new(mock);
+
+        app.auto_push("done");
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        let BackgroundUpdate::PushFinished(request, result) = update else {
+            panic!("expected the push to finish");
+        };
+        assert!(request.push.plain_git);
+        assert!(!request.push.draft);
+        assert_eq!(result, Ok(None));
+    }
+
+    #[test]
+    fn test_relaunch_goes_through_the_app_runner() {
+        let mut app = test_app();
+        let mut inst = make_test_instance("again");
+        inst.git_worktree = Some(GitWorktree::from_storage(
+            "/tmp".to_string(),
+            "/nonexistent/gana-wt".to_string(),
+            "again".to_string(),
+            "gana/again".to_string(),
+            String::new(),
+        ));
+        app.instances.push(inst);
+        app.refresh_list();
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run()
+            .withf(|name, a| name == "tmux" && a[0] == "has-session")
+            .returning(|_, _| Ok(()));
+        mock.expect_run()
+            .withf(|name, a| name == "tmux" && a[0] == "kill-session")
+            .times(1)
+            .returning(|_, _| Err(crate::cmd::CmdError::NotFound("tmux".to_string())));
+        app.cmd = std::sync::Arc::new(mock);
+
+        app.relaunch(0, "bash".to_string(), false);
+        assert_eq!(app.instances[0].status, InstanceStatus::Loading);
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(matches!(update, BackgroundUpdate::InstanceFailed(ref t, _) if t == "again"));
+    }
+
+    #[test]
+    fn test_details_sample_resources_on_a_worker() {
+        let mut app = test_app();
+        let mut inst = make_test_instance("busy");
+        inst.status = InstanceStatus::Running;
+        app.instances.push(inst);
+        app.refresh_list();
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_output().returning(|name, _| match name {
+            "tmux" => Ok("42\n".to_string()),
+            _ => Ok("42 1 12.5 1024\n".to_string()),
+        });
+        app.cmd = std::sync::Arc::new(mock);
+
+        app.handle_key_action(KeyAction::Details);
+        assert!(app.help_overlay.is_some());
+        let update = app.bg_receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+        let BackgroundUpdate::ResourcesSampled(ref title, usage) = update else {
+            panic!("expected the usage");
+        };
+        assert_eq!(title, "busy");
+        assert_eq!(usage.memory_bytes, 1024 * 1024);
+
+        app.bg_sender.send(update).unwrap();
+        app.process_background_updates();
+        assert!(app.help_overlay.is_some());
+        app.handle_help_key(KeyCode::Esc).unwrap();
+        assert!(app.details_for.is_none());
+    }
+
+    #[test]
+    fn test_release_notes_shown_after_update() {
+        let mut app = test_app();
+        app.bg_sender
+            .send(BackgroundUpdate::ReleaseNotes(
+                "0.2.0".to_string(),
+                Ok("- Faster startup".to_string()),
+            ))
+            .unwrap();
+        app.process_background_updates();
+        assert_eq!(app.state, AppState::Help);
+        assert!(app.help_overlay.is_some());
+
+        // Not over another dialog, and failures become a notice
+        app.handle_help_key(KeyCode::Esc).unwrap();
+        app.state = AppState::TextInput;
+        app.show_release_notes("0.2.0", Ok("- Faster startup".to_string()));
+        assert!(app.help_overlay.is_none());
+        assert!(app.error.has_info());
+        app.error.clear();
+        app.state = AppState::Default;
+        app.show_release_notes("0.2.0", Err("offline".to_string()));
+        assert!(app.help_overlay.is_none());
+        assert!(app.error.has_info());
+    }
+
+    #[test]
+    fn test_drafted_pr_body_resumes_pending_push() {
+        let mut app = test_app();
+        app.instances.push(make_test_instance("drafted"));
+        app.refresh_list();
+        for title in ["drafted", "failed"] {
+            let request = PushRequest {
+                title: title.to_string(),
+                squash: None,
+                push: PushOptions::default(),
+                by_gh_status: false,
+            };
+            app.pending_pushes.insert(title.to_string(), request);
+        }
+
+        app.bg_sender
+            .send(BackgroundUpdate::PrBodyDrafted("drafted".to_string(), Ok("## Summary".to_string())))
+            .unwrap();
+        app.bg_sender
+            .send(BackgroundUpdate::PrBodyDrafted("failed".to_string(), Err("timed out".to_string())))
+            .unwrap();
+        app.process_background_updates();
+
+        assert!(app.pending_pushes.is_empty());
+        let (request, body) = app.pr_body_to_edit.as_ref().unwrap();
+        assert_eq!(request.title, "drafted");
+        assert_eq!(body, "## Summary");
+        assert!(app.error.has_error());
     }
 }
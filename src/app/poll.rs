@@ -0,0 +1,224 @@
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::time::{Duration, Instant};
+
+/// Polls with unchanged output before the interval starts growing.
+const BACKOFF_AFTER: u32 = 4;
+
+/// The interval grows to at most this multiple of the base interval.
+const MAX_BACKOFF: u32 = 8;
+
+#[derive(Default)]
+struct PollState {
+    last_poll: Option<Instant>,
+    fingerprint: Option<u64>,
+    unchanged: u32,
+}
+
+/// Decides when each running session's preview and diff are refreshed.
+///
+/// The selected session is polled every `selected` interval, the others
+/// every `background` interval. A session whose output stays the same is
+/// polled less and less often, up to [`MAX_BACKOFF`] times its interval,
+/// until its output changes again.
+pub struct PollSchedule {
+    selected: Duration,
+    background: Duration,
+    sessions: HashMap<String, PollState>,
+}
+
+impl PollSchedule {
+    pub fn new(selected: Duration, background: Duration) -> Self {
+        Self {
+            selected,
+            background,
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// Current polling interval of a session.
+    pub fn interval(&self, title: &str, selected: bool) -> Duration {
+        let base = if selected { self.selected } else { self.background };
+        let unchanged = self.sessions.get(title).map_or(0, |s| s.unchanged);
+        let factor = 1u32
+            .checked_shl(unchanged.saturating_sub(BACKOFF_AFTER))
+            .unwrap_or(MAX_BACKOFF)
+            .min(MAX_BACKOFF);
+        base * factor
+    }
+
+    /// Whether the session is due for a poll at `now`. Marks it polled if so.
+    pub fn due(&mut self, title: &str, selected: bool, now: Instant) -> bool {
+        let interval = self.interval(title, selected);
+        let state = self.sessions.entry(title.to_string()).or_default();
+        if state.last_poll.is_some_and(|at| now.duration_since(at) < interval) {
+            return false;
+        }
+        state.last_poll = Some(now);
+        true
+    }
+
+    /// Record what a poll saw; unchanged output grows the interval, new
+    /// output resets it. Returns whether the output changed.
+    pub fn observe(&mut self, title: &str, output: &str) -> bool {
+        let mut hasher = DefaultHasher::new();
+        output.hash(&mut hasher);
+        let fingerprint = hasher.finish();
+        let state = self.sessions.entry(title.to_string()).or_default();
+        if state.fingerprint == Some(fingerprint) {
+            state.unchanged = state.unchanged.saturating_add(1);
+            false
+        } else {
+            state.fingerprint = Some(fingerprint);
+            state.unchanged = 0;
+            true
+        }
+    }
+
+    /// Drop any backoff and make the session due right away, e.g. when
+    /// the user selects it.
+    pub fn reset(&mut self, title: &str) {
+        if let Some(state) = self.sessions.get_mut(title) {
+            state.unchanged = 0;
+            state.last_poll = None;
+        }
+    }
+
+    /// Forget sessions for which `keep` returns false.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.sessions.retain(|title, _| keep(title));
+    }
+}
+
+/// Without activity for this long the main loop slows down.
+const IDLE_AFTER: Duration = Duration::from_secs(3);
+
+/// How long the main loop waits for a key, and how often its background
+/// tick runs. Both stretch once nothing has happened for [`IDLE_AFTER`],
+/// so an idle TUI doesn't keep the CPU awake.
+pub struct TickRate {
+    last_activity: Instant,
+}
+
+impl TickRate {
+    const EVENT_POLL: Duration = Duration::from_millis(100);
+    const IDLE_EVENT_POLL: Duration = Duration::from_secs(1);
+    const BACKGROUND: Duration = Duration::from_millis(500);
+    const IDLE_BACKGROUND: Duration = Duration::from_secs(5);
+
+    pub fn new() -> Self {
+        Self {
+            last_activity: Instant::now(),
+        }
+    }
+
+    /// Something happened (a key, new output, a session loading): run at
+    /// full speed again.
+    pub fn wake(&mut self) {
+        self.last_activity = Instant::now();
+    }
+
+    pub fn is_idle(&self, now: Instant) -> bool {
+        now.duration_since(self.last_activity) >= IDLE_AFTER
+    }
+
+    /// How long to wait for a terminal event before redrawing.
+    pub fn event_timeout(&self, now: Instant) -> Duration {
+        if self.is_idle(now) {
+            Self::IDLE_EVENT_POLL
+        } else {
+            Self::EVENT_POLL
+        }
+    }
+
+    /// How often the background tick (watched tests, activity) runs.
+    pub fn background_interval(&self, now: Instant) -> Duration {
+        if self.is_idle(now) {
+            Self::IDLE_BACKGROUND
+        } else {
+            Self::BACKGROUND
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> PollSchedule {
+        PollSchedule::new(Duration::from_millis(500), Duration::from_secs(5))
+    }
+
+    #[test]
+    fn test_selected_polls_faster() {
+        let mut schedule = schedule();
+        let start = Instant::now();
+        assert!(schedule.due("a", true, start));
+        assert!(!schedule.due("a", true, start + Duration::from_millis(100)));
+        assert!(schedule.due("a", true, start + Duration::from_millis(500)));
+
+        assert!(schedule.due("b", false, start));
+        assert!(!schedule.due("b", false, start + Duration::from_secs(1)));
+        assert!(schedule.due("b", false, start + Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_unchanged_output_backs_off() {
+        let mut schedule = schedule();
+        for _ in 0..=BACKOFF_AFTER {
+            schedule.observe("a", "same");
+        }
+        assert_eq!(schedule.interval("a", true), Duration::from_millis(500));
+        schedule.observe("a", "same");
+        assert_eq!(schedule.interval("a", true), Duration::from_secs(1));
+        for _ in 0..20 {
+            schedule.observe("a", "same");
+        }
+        assert_eq!(schedule.interval("a", true), Duration::from_secs(4));
+        assert_eq!(schedule.interval("a", false), Duration::from_secs(40));
+
+        schedule.observe("a", "new output");
+        assert_eq!(schedule.interval("a", true), Duration::from_millis(500));
+
+        for _ in 0..10 {
+            schedule.observe("a", "new output");
+        }
+        schedule.reset("a");
+        assert_eq!(schedule.interval("a", true), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn test_reset_makes_session_due_now() {
+        let mut schedule = schedule();
+        let start = Instant::now();
+        assert!(schedule.due("a", false, start));
+        assert!(!schedule.due("a", true, start + Duration::from_millis(100)));
+        schedule.reset("a");
+        assert!(schedule.due("a", true, start + Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn test_tick_rate_slows_when_idle_and_snaps_back() {
+        let mut rate = TickRate::new();
+        let start = Instant::now();
+        assert_eq!(rate.event_timeout(start), Duration::from_millis(100));
+        assert_eq!(rate.background_interval(start), Duration::from_millis(500));
+
+        let later = start + IDLE_AFTER;
+        assert!(rate.is_idle(later));
+        assert_eq!(rate.event_timeout(later), Duration::from_secs(1));
+        assert_eq!(rate.background_interval(later), Duration::from_secs(5));
+
+        rate.wake();
+        assert!(!rate.is_idle(Instant::now()));
+        assert_eq!(rate.event_timeout(Instant::now()), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn test_observe_reports_changes() {
+        let mut schedule = schedule();
+        assert!(schedule.observe("a", "one"));
+        assert!(!schedule.observe("a", "one"));
+        assert!(schedule.observe("a", "two"));
+    }
+}
@@ -1,6 +1,9 @@
-use std::process::Command;
+use std::process::{Command, Output};
+use std::time::Instant;
 use thiserror::Error;
 
+pub mod trace;
+
 #[derive(Debug, Error)]
 pub enum CmdError {
     #[error("command failed: {0}")]
@@ -17,13 +20,39 @@ pub trait CmdExec: Send + Sync {
     fn output(&self, name: &str, args: &[String]) -> Result<String, CmdError>;
 }
 
+/// A shared runner, e.g. the app's, handed to what keeps its own.
+impl<T: CmdExec + ?Sized> CmdExec for std::sync::Arc<T> {
+    fn run(&self, name: &str, args: &[String]) -> Result<(), CmdError> {
+        (**self).run(name, args)
+    }
+
+    fn output(&self, name: &str, args: &[String]) -> Result<String, CmdError> {
+        (**self).output(name, args)
+    }
+}
+
 pub struct SystemCmdExec;
 
+impl SystemCmdExec {
+    /// Run a command to completion inside a `cmd` span, recording its
+    /// duration and exit status (see [`trace`]).
+    fn exec(name: &str, args: &[String]) -> std::io::Result<Output> {
+        let command = trace::redact(name, args);
+        let span = tracing::debug_span!("cmd", command = %command);
+        let _entered = span.enter();
+        let started = Instant::now();
+        let output = Command::new(name).args(args).output();
+        let exit_code = output.as_ref().ok().and_then(|o| o.status.code());
+        trace::record(command, started.elapsed(), exit_code);
+        output
+    }
+}
+
 impl CmdExec for SystemCmdExec {
     fn run(&self, name: &str, args: &[String]) -> Result<(), CmdError> {
         // Use .output() instead of .status() to suppress stdout/stderr.
         // Without this, git error messages leak through the TUI.
-        let output = Command::new(name).args(args).output()?;
+        let output = Self::exec(name, args)?;
         if output.status.success() {
             Ok(())
         } else {
@@ -43,7 +72,7 @@ impl CmdExec for SystemCmdExec {
     }
 
     fn output(&self, name: &str, args: &[String]) -> Result<String, CmdError> {
-        let output = Command::new(name).args(args).output()?;
+        let output = Self::exec(name, args)?;
         if output.status.success() {
             Ok(String::from_utf8_lossy(&output.stdout).to_string())
         } else {
@@ -63,6 +92,11 @@ pub fn args(strs: &[&str]) -> Vec<String> {
     strs.iter().map(|s| s.to_string()).collect()
 }
 
+/// Single-quote `s` for `sh`.
+pub fn quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
+
 /// Convert a Command to a string representation for debugging/testing.
 #[allow(dead_code)]
 pub fn command_to_string(cmd: &Command) -> String {
@@ -0,0 +1,135 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+
+/// Commands taking at least this long are logged at info level and listed
+/// in the TUI's slow command overlay.
+pub const SLOW_COMMAND: Duration = Duration::from_millis(500);
+
+/// Timings kept in memory for the overlay.
+const KEPT_TIMINGS: usize = 200;
+
+/// Arguments longer than this are shown by length only.
+const MAX_SHOWN_ARG: usize = 80;
+
+/// Flags whose value may be a prompt or other user text.
+const TEXT_FLAGS: [&str; 6] = ["-p", "--prompt", "-m", "--message", "--body", "--title"];
+
+/// One finished external command.
+#[derive(Debug, Clone)]
+pub struct CmdTiming {
+    pub at: DateTime<Local>,
+    /// The command line, redacted.
+    pub command: String,
+    pub duration: Duration,
+    /// Exit code; `None` when killed by a signal or not started.
+    pub exit_code: Option<i32>,
+}
+
+static TIMINGS: Mutex<VecDeque<CmdTiming>> = Mutex::new(VecDeque::new());
+
+fn redacted(arg: &str) -> String {
+    format!("<{} chars>", arg.chars().count())
+}
+
+/// The command line with prompts and other user text replaced by their
+/// length: keys sent with `tmux send-keys`, values of flags like `-p` and
+/// `--body`, and anything long or multi-line.
+pub fn redact(name: &str, args: &[String]) -> String {
+    let send_keys = name == "tmux" && args.first().is_some_and(|a| a == "send-keys");
+    let mut shown = vec![name.to_string()];
+    let mut after_target = false;
+    let mut redact_next = false;
+    for (i, arg) in args.iter().enumerate() {
+        let text = if redact_next {
+            redacted(arg)
+        } else if send_keys && after_target && !arg.starts_with('-') {
+            // Key names like Enter or C-c are fine to show
+            let key_name = arg.len() <= 8 && !arg.contains(char::is_whitespace);
+            if key_name { arg.clone() } else { redacted(arg) }
+        } else if arg.contains('\n') || arg.chars().count() > MAX_SHOWN_ARG {
+            redacted(arg)
+        } else {
+            arg.clone()
+        };
+        redact_next = TEXT_FLAGS.contains(&arg.as_str());
+        if send_keys && i > 0 && args[i - 1] == "-t" {
+            after_target = true;
+        }
+        shown.push(text);
+    }
+    shown.join(" ")
+}
+
+/// Log a finished command and keep its timing for the overlay.
+pub fn record(command: String, duration: Duration, exit_code: Option<i32>) {
+    let duration_ms = duration.as_millis() as u64;
+    if duration >= SLOW_COMMAND {
+        tracing::info!(duration_ms, exit_code, "slow command");
+    } else {
+        tracing::debug!(duration_ms, exit_code, "command finished");
+    }
+
+    let Ok(mut timings) = TIMINGS.lock() else {
+        return;
+    };
+    if timings.len() == KEPT_TIMINGS {
+        timings.pop_front();
+    }
+    timings.push_back(CmdTiming {
+        at: Local::now(),
+        command,
+        duration,
+        exit_code,
+    });
+}
+
+/// Recent commands that took at least `min`, newest first.
+pub fn recent(min: Duration) -> Vec<CmdTiming> {
+    let Ok(timings) = TIMINGS.lock() else {
+        return Vec::new();
+    };
+    timings.iter().rev().filter(|t| t.duration >= min).cloned().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::args;
+
+    #[test]
+    fn test_redact_prompts() {
+        assert_eq!(
+            redact("tmux", &args(&["send-keys", "-t", "gana_x", "-l", "fix the login bug"])),
+            "tmux send-keys -t gana_x -l <17 chars>"
+        );
+        assert_eq!(
+            redact("tmux", &args(&["send-keys", "-t", "gana_x", "Enter"])),
+            "tmux send-keys -t gana_x Enter"
+        );
+        assert_eq!(
+            redact("claude", &args(&["-p", "name this session"])),
+            "claude -p <17 chars>"
+        );
+        assert_eq!(
+            redact("git", &args(&["-C", "/wt", "commit", "-m", "wip"])),
+            "git -C /wt commit -m <3 chars>"
+        );
+        assert_eq!(
+            redact("sh", &args(&["-c", "one\ntwo"])),
+            "sh -c <7 chars>"
+        );
+        assert_eq!(redact("git", &args(&["status"])), "git status");
+    }
+
+    #[test]
+    fn test_recent_filters_by_duration() {
+        record("test-fast".to_string(), Duration::from_millis(1), Some(0));
+        record("test-slow".to_string(), Duration::from_secs(2), Some(1));
+        let slow: Vec<String> = recent(SLOW_COMMAND).into_iter().map(|t| t.command).collect();
+        assert!(slow.contains(&"test-slow".to_string()));
+        assert!(!slow.contains(&"test-fast".to_string()));
+    }
+}
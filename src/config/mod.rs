@@ -1,7 +1,9 @@
+pub mod schedule;
 #[allow(dead_code)]
 pub mod state;
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
@@ -30,34 +32,523 @@ pub struct Config {
     #[serde(default)]
     pub auto_yes: bool,
 
+    /// Prompts containing any of these patterns (case-insensitive) are never
+    /// auto-approved by the daemon.
+    #[serde(default = "default_auto_yes_deny_patterns")]
+    pub auto_yes_deny_patterns: Vec<String>,
+
+    /// When set, auto-yes only answers prompts containing one of these
+    /// patterns (case-insensitive), e.g. `["read file"]`; any other prompt
+    /// is left waiting for the user.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub auto_yes_allow_patterns: Vec<String>,
+
+    /// Prompt text each agent shows, by program name or full command line,
+    /// merged over the built-in patterns field by field, e.g.
+    /// `{"claude": {"attention": ["Do you want to proceed?"]}}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub prompt_patterns: BTreeMap<String, PromptPatterns>,
+
     /// Daemon polling interval in milliseconds.
     #[serde(default = "default_poll_interval")]
     pub daemon_poll_interval: u64,
 
+    /// How often the selected session's preview and diff are refreshed, in
+    /// milliseconds.
+    #[serde(default = "default_preview_poll_interval")]
+    pub preview_poll_interval: u64,
+
+    /// How often other running sessions' diff stats are refreshed, in
+    /// milliseconds. Paused sessions are not polled.
+    #[serde(default = "default_background_poll_interval")]
+    pub background_poll_interval: u64,
+
+    /// Log level or filter (e.g. "debug" or "gana=trace") used when neither
+    /// `--log-level` nor `RUST_LOG` is given. Defaults to "info".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<String>,
+
+    /// Download and install new releases in the background. When off, the
+    /// TUI only says that one is available; `gana update` installs it. Off
+    /// by default in builds made with `GANA_PACKAGED` set, which a package
+    /// manager keeps up to date.
+    #[serde(default = "default_auto_update")]
+    pub auto_update: bool,
+
+    /// Which releases updates come from: "stable" or "prerelease", which
+    /// also offers pre-releases when they're the newest.
+    #[serde(default)]
+    pub update_channel: UpdateChannel,
+
     /// Prefix for git branch names created by gana.
     #[serde(default = "default_branch_prefix")]
     pub branch_prefix: String,
+
+    /// Append the creation time to new sessions' titles, and so to their
+    /// branches and tmux sessions, e.g. `fix-20261017-093012`, so reused titles
+    /// never collide with old branches.
+    #[serde(default)]
+    pub branch_unique_suffix: bool,
+
+    /// Fetch `origin` and branch new worktrees from its default branch
+    /// instead of the local HEAD.
+    #[serde(default)]
+    pub branch_from_origin: bool,
+
+    /// Run the repo's git hooks on commits gana makes (auto-save, push)
+    /// instead of skipping them with `--no-verify`.
+    #[serde(default)]
+    pub run_git_hooks: bool,
+
+    /// Remote session branches are pushed to. A repo can override it with
+    /// `git config gana.pushRemote <name>`; falls back to git's
+    /// `remote.pushDefault`, then `origin`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_remote: Option<String>,
+
+    /// Message for auto-save commits made on pause. Supports `{session}`,
+    /// `{title}`, `{program}`, `{branch}` and `{timestamp}` placeholders.
+    #[serde(default = "default_autosave_message_template")]
+    pub autosave_message_template: String,
+
+    /// Message for the commit made when pushing. Same placeholders as
+    /// `autosave_message_template`; `{title}` is the squash message if given.
+    #[serde(default = "default_push_message_template")]
+    pub push_message_template: String,
+
+    /// Sign commits in session worktrees by setting `commit.gpgsign` in each
+    /// worktree, for both gana's commits and the agent's.
+    #[serde(default)]
+    pub sign_commits: bool,
+
+    /// Key used for signed commits (`user.signingkey`). The repo's own
+    /// setting applies when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_key: Option<String>,
+
+    /// Signature format (`gpg.format`): `openpgp`, `ssh` or `x509`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signing_format: Option<String>,
+
+    /// Author of commits made in new sessions' worktrees, by gana and the
+    /// agent alike, e.g. a bot account. The repo's own identity applies
+    /// when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_identity: Option<GitIdentity>,
+
+    /// Command that titles sessions created without a title, given a request
+    /// as its last argument, e.g. `"claude -p"`. When unset, the title is
+    /// taken from the first words of the prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title_command: Option<String>,
+
+    /// Command that opens a session's worktree in an editor or IDE, e.g.
+    /// `"code {path}"`. Runs in the background; when unset, `$VISUAL` or
+    /// `$EDITOR` opens in the terminal instead.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub editor_command: Option<String>,
+
+    /// Git TUI run in the session's worktree with `L`, e.g. `"tig"`.
+    #[serde(default = "default_git_tui_command")]
+    pub git_tui_command: String,
+
+    /// Command that checks a session's worktree, e.g. `"cargo test"`. Run
+    /// through `sh` in the worktree; exit status 0 means passing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_command: Option<String>,
+
+    /// Rerun `test_command` whenever files in a running session's worktree
+    /// change.
+    #[serde(default)]
+    pub watch_tests: bool,
+
+    /// Directories (relative to the repo root) shared from the main checkout
+    /// into each new worktree, e.g. `node_modules`, `target` or `.venv`.
+    #[serde(default)]
+    pub shared_dirs: Vec<String>,
+
+    /// How `shared_dirs` are shared with new worktrees.
+    #[serde(default)]
+    pub shared_dirs_mode: LinkMode,
+
+    /// Run new sessions' agents in a container with only the worktree
+    /// mounted (`gana new --container` does it for one session).
+    #[serde(default)]
+    pub container_sessions: bool,
+
+    /// Container runtime for container sessions: "docker" or "podman".
+    #[serde(default = "default_container_runtime")]
+    pub container_runtime: String,
+
+    /// Image each program runs in, by program name or full command line,
+    /// e.g. `{"claude": "ghcr.io/me/claude-sandbox"}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub container_images: BTreeMap<String, String>,
+
+    /// Extra arguments for the runtime's `run`, e.g. `["-e", "ANTHROPIC_API_KEY"]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub container_args: Vec<String>,
+
+    /// Command each program is launched through, by program name or full
+    /// command line, e.g. `{"claude": "bwrap --ro-bind / / --bind {worktree}
+    /// {worktree} --"}`. Supports `{worktree}` and `{git_dir}` placeholders.
+    /// Not used for container sessions.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub sandbox_commands: BTreeMap<String, String>,
+
+    /// CPU and memory limits for each program's agent, by program name or
+    /// full command line, e.g. `{"claude": {"cpu_percent": 200, "memory_mb": 4096}}`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resource_limits: BTreeMap<String, ResourceLimits>,
+
+    /// Flag that makes each program continue its last conversation, by
+    /// program name or full command line, e.g. `{"claude": "--continue"}`.
+    /// Added when an exited agent is started again in its worktree.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub resume_flags: BTreeMap<String, String>,
+
+    /// Template for the initial prompt of sessions created with
+    /// `gana new --from-issue`. Supports `{number}`, `{title}`, `{body}`
+    /// and `{url}` placeholders.
+    #[serde(default = "default_issue_prompt_template")]
+    pub issue_prompt_template: String,
+
+    /// Address for the daemon's Prometheus `/metrics` endpoint
+    /// (e.g. "127.0.0.1:9464"). Disabled when unset.
+    #[serde(default)]
+    pub metrics_addr: Option<String>,
+
+    /// Let the daemon restart agents whose process exited.
+    #[serde(default)]
+    pub auto_restart: bool,
+
+    /// Maximum number of automatic restarts per session.
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+
+    /// Prompts the daemon sends at fixed times of day.
+    #[serde(default)]
+    pub schedules: Vec<schedule::Schedule>,
+
+    /// Pause running sessions (committing their work) after this many hours
+    /// without terminal activity. Checked while the TUI is open.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idle_pause_hours: Option<u64>,
+
+    /// Move sessions paused for this many days from the list to
+    /// `archive.json`. Their branches are kept; `gana archived --restore`
+    /// brings them back.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub archive_paused_days: Option<u64>,
+
+    /// What to do with running sessions when quitting the TUI.
+    #[serde(default)]
+    pub on_quit: QuitBehavior,
+
+    /// How to say another session is waiting for input while attached.
+    #[serde(default)]
+    pub attention_alert: AttentionAlert,
+
+    /// Maximum number of sessions set up (worktree + tmux) at the same time.
+    /// Further sessions wait in a queue.
+    #[serde(default = "default_max_concurrent_creations")]
+    pub max_concurrent_creations: usize,
+
+    /// Maximum number of preview scrollback lines held in memory. Older
+    /// lines are fetched from tmux in chunks while scrolling up.
+    #[serde(default = "default_scrollback_lines")]
+    pub scrollback_lines: usize,
+
+    /// Longest session title the TUI accepts, in characters.
+    #[serde(default = "default_title_max_length")]
+    pub title_max_length: usize,
+
+    /// Warn when the worktrees directory grows beyond this many megabytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub worktree_budget_mb: Option<u64>,
+
+    /// Git repo (URL or path) the team publishes its sessions to, so
+    /// members see each other's. Disabled when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_repo: Option<String>,
+
+    /// Name this machine's sessions are published under; defaults to git's
+    /// `user.name`, then `$USER`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team_member: Option<String>,
+}
+
+/// What happens to running sessions when the TUI quits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QuitBehavior {
+    /// Ask whether to pause running sessions.
+    #[default]
+    Ask,
+    /// Leave sessions running in the background.
+    Leave,
+    /// Pause all running sessions (auto-commit + remove worktrees).
+    Pause,
+}
+
+/// How other sessions needing input are announced while attached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AttentionAlert {
+    /// Ring the terminal bell and show a tmux message.
+    #[default]
+    Both,
+    Bell,
+    /// A tmux `display-message` in the attached session.
+    Message,
+    Off,
+}
+
+/// Which releases the updater installs.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum UpdateChannel {
+    /// Full releases only.
+    #[default]
+    Stable,
+    /// The newest release, pre-releases included.
+    Prerelease,
+}
+
+/// How a shared directory is brought into a worktree.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkMode {
+    /// Symlink the directory; every session writes to the same files.
+    #[default]
+    Symlink,
+    /// Recreate the directory tree with hardlinked files. Tools that replace
+    /// files keep their changes local to the worktree.
+    Hardlink,
+}
+
+/// A commit author: `user.name` and `user.email`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GitIdentity {
+    pub name: String,
+    pub email: String,
+}
+
+/// Caps on an agent's CPU and memory use.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceLimits {
+    /// CPU time as a percentage of one core, e.g. 200 for two cores.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_percent: Option<u32>,
+    /// Memory in megabytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<u64>,
+}
+
+/// How gana recognizes and answers an agent's prompts. Unset fields fall
+/// back to the built-in patterns for the program.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PromptPatterns {
+    /// Text of the trust prompt shown when the agent first starts in a folder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust: Option<String>,
+    /// Keys that accept the trust prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust_keys: Option<Vec<String>>,
+    /// How long to watch for the trust prompt after starting, in seconds.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trust_timeout_secs: Option<u64>,
+    /// Text that, all appearing near the bottom of the pane, means the agent
+    /// is waiting for an answer.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attention: Option<Vec<String>>,
+    /// Keys that accept a permission prompt when auto-approving.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approve_keys: Option<Vec<String>>,
+    /// Text near the bottom of the pane while the agent is working. Once it
+    /// is gone and no prompt waits, the agent has finished its task.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub busy: Option<Vec<String>>,
+}
+
+impl PromptPatterns {
+    /// The patterns gana ships with for `program` (a program name).
+    ///
+    /// Claude, Gemini and Amp pre-select the "allow" option, so Enter
+    /// confirms it; Aider asks a (Y)es/(N)o question. Unknown programs have
+    /// none, so they are never auto-approved.
+    pub fn builtin(program: &str) -> Self {
+        let strings = |s: &[&str]| Some(s.iter().map(|s| s.to_string()).collect());
+        match program {
+            "claude" => Self {
+                trust: Some("Do you trust the files in this folder?".to_string()),
+                trust_keys: strings(&["Enter"]),
+                trust_timeout_secs: Some(30),
+                attention: strings(&["No, and tell Claude what to do differently"]),
+                approve_keys: strings(&["Enter"]),
+                busy: strings(&["esc to interrupt"]),
+            },
+            "aider" => Self {
+                trust: Some("Open documentation url".to_string()),
+                trust_keys: strings(&["d", "Enter"]),
+                trust_timeout_secs: Some(45),
+                attention: strings(&["(Y)es/(N)o/(D)on't ask again"]),
+                approve_keys: strings(&["y", "Enter"]),
+                busy: None,
+            },
+            "gemini" => Self {
+                trust: Some("Open documentation url".to_string()),
+                trust_keys: strings(&["d", "Enter"]),
+                trust_timeout_secs: Some(45),
+                attention: strings(&["Yes, allow once"]),
+                approve_keys: strings(&["Enter"]),
+                busy: strings(&["esc to cancel"]),
+            },
+            "amp" => Self {
+                attention: strings(&["Allow", "Deny"]),
+                approve_keys: strings(&["Enter"]),
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// These patterns, with unset fields taken from `base`.
+    pub fn or(self, base: Self) -> Self {
+        Self {
+            trust: self.trust.or(base.trust),
+            trust_keys: self.trust_keys.or(base.trust_keys),
+            trust_timeout_secs: self.trust_timeout_secs.or(base.trust_timeout_secs),
+            attention: self.attention.or(base.attention),
+            approve_keys: self.approve_keys.or(base.approve_keys),
+            busy: self.busy.or(base.busy),
+        }
+    }
 }
 
 fn default_program() -> String {
     "claude".to_string()
 }
 
+fn default_auto_yes_deny_patterns() -> Vec<String> {
+    ["delete", "rm -rf", "force push", "push --force", "push -f", "reset --hard", "drop table"]
+        .iter()
+        .map(|p| p.to_string())
+        .collect()
+}
+
 fn default_poll_interval() -> u64 {
     1000
 }
 
+fn default_preview_poll_interval() -> u64 {
+    500
+}
+
+fn default_background_poll_interval() -> u64 {
+    5000
+}
+
+fn default_container_runtime() -> String {
+    "docker".to_string()
+}
+
+fn default_auto_update() -> bool {
+    option_env!("GANA_PACKAGED").is_none()
+}
+
 fn default_branch_prefix() -> String {
     String::new()
 }
 
+fn default_max_restarts() -> u32 {
+    3
+}
+
+fn default_max_concurrent_creations() -> usize {
+    2
+}
+
+fn default_scrollback_lines() -> usize {
+    crate::ui::preview::DEFAULT_MAX_SCROLLBACK
+}
+
+fn default_title_max_length() -> usize {
+    crate::ui::overlay::text_input::DEFAULT_MAX_LEN
+}
+
+fn default_autosave_message_template() -> String {
+    "gana: auto-save {timestamp}".to_string()
+}
+
+fn default_push_message_template() -> String {
+    "{title}".to_string()
+}
+
+fn default_git_tui_command() -> String {
+    "lazygit".to_string()
+}
+
+fn default_issue_prompt_template() -> String {
+    "Resolve GitHub issue #{number}: {title}\n\n{body}\n\nIssue: {url}".to_string()
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
             default_program: default_program(),
             auto_yes: false,
+            auto_yes_deny_patterns: default_auto_yes_deny_patterns(),
+            auto_yes_allow_patterns: Vec::new(),
+            prompt_patterns: BTreeMap::new(),
             daemon_poll_interval: default_poll_interval(),
+            preview_poll_interval: default_preview_poll_interval(),
+            background_poll_interval: default_background_poll_interval(),
+            log_level: None,
+            auto_update: default_auto_update(),
+            update_channel: UpdateChannel::default(),
             branch_prefix: default_branch_prefix(),
+            branch_unique_suffix: false,
+            branch_from_origin: false,
+            run_git_hooks: false,
+            push_remote: None,
+            autosave_message_template: default_autosave_message_template(),
+            push_message_template: default_push_message_template(),
+            sign_commits: false,
+            signing_key: None,
+            signing_format: None,
+            git_identity: None,
+            title_command: None,
+            editor_command: None,
+            git_tui_command: default_git_tui_command(),
+            test_command: None,
+            watch_tests: false,
+            shared_dirs: Vec::new(),
+            shared_dirs_mode: LinkMode::default(),
+            container_sessions: false,
+            container_runtime: default_container_runtime(),
+            container_images: BTreeMap::new(),
+            container_args: Vec::new(),
+            sandbox_commands: BTreeMap::new(),
+            resource_limits: BTreeMap::new(),
+            resume_flags: BTreeMap::new(),
+            issue_prompt_template: default_issue_prompt_template(),
+            metrics_addr: None,
+            auto_restart: false,
+            max_restarts: default_max_restarts(),
+            schedules: Vec::new(),
+            on_quit: QuitBehavior::default(),
+            attention_alert: AttentionAlert::default(),
+            max_concurrent_creations: default_max_concurrent_creations(),
+            scrollback_lines: default_scrollback_lines(),
+            title_max_length: default_title_max_length(),
+            worktree_budget_mb: None,
+            team_repo: None,
+            team_member: None,
+            idle_pause_hours: None,
+            archive_paused_days: None,
         }
     }
 }
@@ -101,6 +592,29 @@ impl Config {
         std::fs::write(&path, contents)?;
         Ok(())
     }
+
+    /// Prompt patterns for `program`: the configured ones over the built-in.
+    pub fn prompt_patterns(&self, program: &str) -> PromptPatterns {
+        let builtin = PromptPatterns::builtin(program_name(program));
+        match per_program(&self.prompt_patterns, program) {
+            Some(custom) => custom.clone().or(builtin),
+            None => builtin,
+        }
+    }
+}
+
+/// Name of the program a command line runs, e.g. "claude" for
+/// "claude --dangerously-skip-permissions".
+pub fn program_name(program: &str) -> &str {
+    program.split_whitespace().next().unwrap_or(program)
+}
+
+/// The entry for `program` in a per-program setting like
+/// `container_images`: by full command line, else by program name.
+pub fn per_program<'a, T>(setting: &'a BTreeMap<String, T>, program: &str) -> Option<&'a T> {
+    setting
+        .get(program)
+        .or_else(|| setting.get(program_name(program)))
 }
 
 /// Discover the claude command by searching PATH.
@@ -158,6 +672,23 @@ mod tests {
         assert_eq!(config.daemon_poll_interval, 1000);
         // Default prefix is empty (user types the branch name directly)
         assert!(config.branch_prefix.is_empty());
+        assert_eq!(config.max_concurrent_creations, 2);
+        assert_eq!(config.scrollback_lines, 10_000);
+        assert_eq!(config.title_max_length, 64);
+    }
+
+    #[test]
+    fn test_prompt_patterns_merge_over_builtin() {
+        let config: Config = serde_json::from_str(
+            r#"{"prompt_patterns": {"claude": {"attention": ["Do you want to proceed?"]}, "mybot": {"approve_keys": ["y"]}}}"#,
+        )
+        .unwrap();
+        let claude = config.prompt_patterns("claude --model opus");
+        assert_eq!(claude.attention, Some(vec!["Do you want to proceed?".to_string()]));
+        assert_eq!(claude.trust.as_deref(), Some("Do you trust the files in this folder?"));
+        assert_eq!(config.prompt_patterns("mybot").approve_keys, Some(vec!["y".to_string()]));
+        assert_eq!(config.prompt_patterns("aider"), PromptPatterns::builtin("aider"));
+        assert_eq!(config.prompt_patterns("bash"), PromptPatterns::default());
     }
 
     #[test]
@@ -186,7 +717,10 @@ mod tests {
             "default_program": "test-claude",
             "auto_yes": true,
             "daemon_poll_interval": 2000,
-            "branch_prefix": "test/"
+            "branch_prefix": "test/",
+            "shared_dirs": ["node_modules"],
+            "shared_dirs_mode": "hardlink",
+            "update_channel": "prerelease"
         }"#;
         std::fs::write(tmp.path().join(CONFIG_FILE_NAME), json).unwrap();
 
@@ -195,6 +729,9 @@ mod tests {
         assert!(config.auto_yes);
         assert_eq!(config.daemon_poll_interval, 2000);
         assert_eq!(config.branch_prefix, "test/");
+        assert_eq!(config.shared_dirs, vec!["node_modules"]);
+        assert_eq!(config.shared_dirs_mode, LinkMode::Hardlink);
+        assert_eq!(config.update_channel, UpdateChannel::Prerelease);
     }
 
     #[test]
@@ -214,6 +751,7 @@ mod tests {
             auto_yes: true,
             daemon_poll_interval: 500,
             branch_prefix: "custom/".to_string(),
+            ..Config::default()
         };
 
         config.save(tmp.path()).expect("should save config");
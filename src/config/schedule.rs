@@ -0,0 +1,112 @@
+use chrono::{Datelike, NaiveDateTime, NaiveTime, Timelike};
+use serde::{Deserialize, Serialize};
+
+const WEEKDAYS: [&str; 7] = ["mon", "tue", "wed", "thu", "fri", "sat", "sun"];
+
+/// A prompt sent by the daemon at a fixed local time of day.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Local time of day to send the prompt, as "HH:MM".
+    pub time: String,
+
+    /// Days of the week ("mon" … "sun"); empty means every day.
+    #[serde(default)]
+    pub days: Vec<String>,
+
+    /// Title of the session to send to; all running sessions when unset.
+    #[serde(default)]
+    pub session: Option<String>,
+
+    /// Prompt text to send.
+    pub prompt: String,
+}
+
+impl Schedule {
+    /// Parse the configured time of day, if valid.
+    pub fn time_of_day(&self) -> Option<NaiveTime> {
+        NaiveTime::parse_from_str(self.time.trim(), "%H:%M").ok()
+    }
+
+    /// Whether the schedule fires during the minute containing `now`.
+    pub fn is_due(&self, now: NaiveDateTime) -> bool {
+        let Some(at) = self.time_of_day() else {
+            return false;
+        };
+        if now.hour() != at.hour() || now.minute() != at.minute() {
+            return false;
+        }
+        let today = WEEKDAYS[now.weekday().num_days_from_monday() as usize];
+        self.days.is_empty() || self.days.iter().any(|d| d.trim().eq_ignore_ascii_case(today))
+    }
+
+    /// Whether the schedule targets the session with the given title.
+    pub fn targets(&self, title: &str) -> bool {
+        self.session.as_deref().is_none_or(|s| s == title)
+    }
+
+    /// One-line human-readable description for the TUI.
+    pub fn describe(&self) -> String {
+        let days = if self.days.is_empty() {
+            "daily".to_string()
+        } else {
+            self.days.join(",")
+        };
+        let target = self.session.as_deref().unwrap_or("all sessions");
+        format!("{} {} → {}: {}", self.time, days, target, self.prompt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn at(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .unwrap()
+            .and_hms_opt(h, min, 30)
+            .unwrap()
+    }
+
+    fn schedule(time: &str, days: &[&str]) -> Schedule {
+        Schedule {
+            time: time.to_string(),
+            days: days.iter().map(|d| d.to_string()).collect(),
+            session: None,
+            prompt: "update the dependency PRs".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_is_due_daily() {
+        let s = schedule("09:00", &[]);
+        assert!(s.is_due(at(2025, 1, 6, 9, 0)));
+        assert!(!s.is_due(at(2025, 1, 6, 9, 1)));
+        assert!(!s.is_due(at(2025, 1, 6, 21, 0)));
+    }
+
+    #[test]
+    fn test_is_due_respects_days() {
+        // 2025-01-06 is a Monday
+        let s = schedule("09:00", &["mon", "Wed"]);
+        assert!(s.is_due(at(2025, 1, 6, 9, 0)));
+        assert!(!s.is_due(at(2025, 1, 7, 9, 0)));
+        assert!(s.is_due(at(2025, 1, 8, 9, 0)));
+    }
+
+    #[test]
+    fn test_invalid_time_never_due() {
+        let s = schedule("9am", &[]);
+        assert!(s.time_of_day().is_none());
+        assert!(!s.is_due(at(2025, 1, 6, 9, 0)));
+    }
+
+    #[test]
+    fn test_targets() {
+        let mut s = schedule("09:00", &[]);
+        assert!(s.targets("anything"));
+        s.session = Some("deps".to_string());
+        assert!(s.targets("deps"));
+        assert!(!s.targets("other"));
+    }
+}
@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// How long a metrics client gets to send its request and read the reply.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+use crate::session::{Instance, InstanceStatus};
+
+/// Daemon metrics exposed in Prometheus text format on `/metrics`.
+#[derive(Default)]
+pub struct Metrics {
+    state: Mutex<MetricsState>,
+}
+
+#[derive(Default)]
+struct MetricsState {
+    running: usize,
+    done: usize,
+    loading: usize,
+    paused: usize,
+    ready: usize,
+    failed: usize,
+    auto_responses: u64,
+    prompt_wait_sum: f64,
+    prompt_wait_count: u64,
+    diff_added: usize,
+    diff_removed: usize,
+    // Session title -> when its current prompt was first seen
+    waiting_since: HashMap<String, Instant>,
+}
+
+impl Metrics {
+    /// Record the current number of sessions per status.
+    pub fn set_sessions(&self, instances: &[Instance]) {
+        let mut state = self.state.lock().unwrap();
+        state.running = 0;
+        state.done = 0;
+        state.loading = 0;
+        state.paused = 0;
+        state.ready = 0;
+        state.failed = 0;
+        for instance in instances {
+            match instance.status {
+                InstanceStatus::Running => state.running += 1,
+                InstanceStatus::Done => state.done += 1,
+                InstanceStatus::Loading | InstanceStatus::Queued | InstanceStatus::Pending => {
+                    state.loading += 1
+                }
+                InstanceStatus::Paused => state.paused += 1,
+                InstanceStatus::Ready => state.ready += 1,
+                InstanceStatus::Failed
+                | InstanceStatus::WorktreeMissing
+                | InstanceStatus::Orphaned => state.failed += 1,
+            }
+        }
+    }
+
+    /// Count an automatic response sent by the daemon.
+    pub fn record_auto_response(&self) {
+        self.state.lock().unwrap().auto_responses += 1;
+    }
+
+    /// Track whether a session is currently waiting on a prompt.
+    ///
+    /// The wait duration is observed once the prompt goes away.
+    pub fn observe_prompt(&self, title: &str, waiting: bool) {
+        self.observe_prompt_at(title, waiting, Instant::now());
+    }
+
+    fn observe_prompt_at(&self, title: &str, waiting: bool, now: Instant) {
+        let mut state = self.state.lock().unwrap();
+        if waiting {
+            state.waiting_since.entry(title.to_string()).or_insert(now);
+        } else if let Some(since) = state.waiting_since.remove(title) {
+            state.prompt_wait_sum += now.duration_since(since).as_secs_f64();
+            state.prompt_wait_count += 1;
+        }
+    }
+
+    /// Record the total added/removed diff lines across all sessions.
+    pub fn set_diff_totals(&self, added: usize, removed: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.diff_added = added;
+        state.diff_removed = removed;
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let state = self.state.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# HELP gana_sessions Number of sessions by status.\n");
+        out.push_str("# TYPE gana_sessions gauge\n");
+        for (status, count) in [
+            ("running", state.running),
+            ("done", state.done),
+            ("loading", state.loading),
+            ("paused", state.paused),
+            ("ready", state.ready),
+            ("failed", state.failed),
+        ] {
+            out.push_str(&format!("gana_sessions{{status=\"{}\"}} {}\n", status, count));
+        }
+
+        out.push_str("# HELP gana_auto_responses_total Prompts answered automatically by the daemon.\n");
+        out.push_str("# TYPE gana_auto_responses_total counter\n");
+        out.push_str(&format!("gana_auto_responses_total {}\n", state.auto_responses));
+
+        out.push_str("# HELP gana_prompt_wait_seconds Time sessions spent waiting on a prompt.\n");
+        out.push_str("# TYPE gana_prompt_wait_seconds summary\n");
+        out.push_str(&format!("gana_prompt_wait_seconds_sum {}\n", state.prompt_wait_sum));
+        out.push_str(&format!("gana_prompt_wait_seconds_count {}\n", state.prompt_wait_count));
+
+        out.push_str("# HELP gana_sessions_waiting Sessions currently waiting on a prompt.\n");
+        out.push_str("# TYPE gana_sessions_waiting gauge\n");
+        out.push_str(&format!("gana_sessions_waiting {}\n", state.waiting_since.len()));
+
+        out.push_str("# HELP gana_diff_lines Diff lines across all sessions.\n");
+        out.push_str("# TYPE gana_diff_lines gauge\n");
+        out.push_str(&format!("gana_diff_lines{{kind=\"added\"}} {}\n", state.diff_added));
+        out.push_str(&format!("gana_diff_lines{{kind=\"removed\"}} {}\n", state.diff_removed));
+
+        out
+    }
+}
+
+/// Serve `/metrics` on `addr` from a background thread.
+pub fn serve(addr: &str, metrics: Arc<Metrics>) -> std::io::Result<()> {
+    let listener = TcpListener::bind(addr)?;
+    tracing::info!("Metrics endpoint listening on http://{}/metrics", addr);
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            // A client that never sends its request doesn't hold up the rest
+            let metrics = Arc::clone(&metrics);
+            std::thread::spawn(move || respond(stream, &metrics));
+        }
+    });
+
+    Ok(())
+}
+
+/// Answer one request on `stream`.
+fn respond(mut stream: TcpStream, metrics: &Metrics) {
+    if stream.set_read_timeout(Some(CLIENT_TIMEOUT)).is_err()
+        || stream.set_write_timeout(Some(CLIENT_TIMEOUT)).is_err()
+    {
+        return;
+    }
+    let mut request_line = String::new();
+    if BufReader::new(&stream).read_line(&mut request_line).is_err() {
+        return;
+    }
+
+    let response = match request_line.split_whitespace().nth(1) {
+        Some("/metrics") => {
+            let body = metrics.render();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            .to_string(),
+    };
+    let _ = stream.write_all(response.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::InstanceOptions;
+    use std::io::Read;
+
+    fn make_instance(title: &str, status: InstanceStatus) -> Instance {
+        let mut inst = Instance::new(InstanceOptions {
+            title: title.to_string(),
+            path: "/tmp".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        inst.status = status;
+        inst
+    }
+
+    #[test]
+    fn test_render_sessions_by_status() {
+        let metrics = Metrics::default();
+        metrics.set_sessions(&[
+            make_instance("a", InstanceStatus::Running),
+            make_instance("b", InstanceStatus::Running),
+            make_instance("c", InstanceStatus::Paused),
+        ]);
+        metrics.record_auto_response();
+        metrics.set_diff_totals(10, 4);
+
+        let text = metrics.render();
+        assert!(text.contains("gana_sessions{status=\"running\"} 2"));
+        assert!(text.contains("gana_sessions{status=\"paused\"} 1"));
+        assert!(text.contains("gana_auto_responses_total 1"));
+        assert!(text.contains("gana_diff_lines{kind=\"added\"} 10"));
+        assert!(text.contains("gana_diff_lines{kind=\"removed\"} 4"));
+    }
+
+    #[test]
+    fn test_prompt_wait_observed_when_prompt_clears() {
+        let metrics = Metrics::default();
+        let start = Instant::now();
+
+        metrics.observe_prompt_at("a", true, start);
+        metrics.observe_prompt_at("a", true, start + Duration::from_secs(1));
+        assert!(metrics.render().contains("gana_sessions_waiting 1"));
+
+        metrics.observe_prompt_at("a", false, start + Duration::from_secs(3));
+        let text = metrics.render();
+        assert!(text.contains("gana_prompt_wait_seconds_sum 3"));
+        assert!(text.contains("gana_prompt_wait_seconds_count 1"));
+        assert!(text.contains("gana_sessions_waiting 0"));
+    }
+
+    #[test]
+    fn test_serve_metrics_endpoint() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let metrics = Arc::new(Metrics::default());
+        serve(&addr, metrics).unwrap();
+
+        let mut stream = std::net::TcpStream::connect(&addr).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("gana_sessions"));
+    }
+
+    #[test]
+    fn test_idle_client_does_not_block_scrapes() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        drop(listener);
+
+        let metrics = Arc::new(Metrics::default());
+        serve(&addr, metrics).unwrap();
+
+        let _idle = TcpStream::connect(&addr).unwrap();
+        let mut stream = TcpStream::connect(&addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(2))).unwrap();
+        stream.write_all(b"GET /metrics HTTP/1.1\r\n\r\n").unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+    }
+}
@@ -1,27 +1,55 @@
+pub mod metrics;
 pub mod platform;
+pub mod scheduler;
+pub mod service;
 
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
 
+use crate::cmd::SystemCmdExec;
 use crate::config::Config;
-use crate::session::InstanceStatus;
+use crate::session::instance::SentPrompt;
+use crate::session::journal::{EventKind, Journal};
+use crate::session::{Instance, InstanceStatus};
 use crate::session::storage::{FileStorage, InstanceStorage};
+use crate::session::tmux::pty::SystemPtyFactory;
+use crate::session::tmux::{PaneTails, TmuxSession};
 
 const PID_FILE: &str = "daemon.pid";
 
+/// How often diff line totals are recomputed for metrics.
+const METRICS_DIFF_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Builds the tmux handle for a session loaded from storage. Loaded
+/// instances have no live `tmux_session`, so the daemon addresses each
+/// session's tmux by name on every poll.
+type TmuxFor<'a> = dyn Fn(&Instance) -> TmuxSession + 'a;
+
 /// Global shutdown flag, set by signal handlers.
 static SHUTDOWN: AtomicBool = AtomicBool::new(false);
 
 /// Run the daemon loop: poll sessions, auto-respond to prompts.
 pub fn run_daemon(config_dir: &Path, config: &Config) -> anyhow::Result<()> {
     let storage = FileStorage::new(config_dir);
+    let journal = Journal::new(config_dir);
     let poll_interval = std::time::Duration::from_millis(config.daemon_poll_interval);
 
-    // Write PID file
+    // Lock the PID file for the daemon's lifetime, then record our PID
     let pid = std::process::id();
     let pid_path = config_dir.join(PID_FILE);
     fs::create_dir_all(config_dir)?;
+    let pid_file = fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&pid_path)?;
+    let Some(_lock) = platform::try_lock(pid_file) else {
+        anyhow::bail!("daemon already running (lock held on {})", pid_path.display());
+    };
     fs::write(&pid_path, pid.to_string())?;
 
     // Install signal handlers for graceful shutdown
@@ -29,16 +57,54 @@ pub fn run_daemon(config_dir: &Path, config: &Config) -> anyhow::Result<()> {
 
     tracing::info!("Daemon started with PID {}", pid);
 
+    let metrics = match config.metrics_addr {
+        Some(ref addr) => {
+            let metrics = Arc::new(metrics::Metrics::default());
+            metrics::serve(addr, Arc::clone(&metrics))?;
+            Some(metrics)
+        }
+        None => None,
+    };
+    let mut last_diff_refresh: Option<std::time::Instant> = None;
+    let mut scheduler = scheduler::Scheduler::default();
+    let mut waiting = HashSet::new();
+    let mut tails = PaneTails::default();
+    let tmux = |instance: &Instance| tmux_for(instance, config);
+
     while !SHUTDOWN.load(Ordering::SeqCst) {
         if let Ok(mut instances) = storage.load_instances() {
+            let mut changed = Vec::new();
+            tails.retain(|title| instances.iter().any(|i| i.title == title && i.status.is_running()));
             for instance in instances.iter_mut() {
-                if instance.status == InstanceStatus::Running
-                    && instance.auto_yes
-                    && instance.has_updated()
+                if instance.status.is_running()
+                    && check_health(instance, &mut tmux(instance), config, &mut tails)
                 {
-                    instance.send_keys("y\n");
+                    changed.push(instance.clone());
+                    let detail = (instance.status == InstanceStatus::Running).then(|| {
+                        format!("restarted ({}/{})", instance.restart_count, config.max_restarts)
+                    });
+                    journal.record(&instance.title, EventKind::Died, detail.as_deref());
                 }
             }
+            if !changed.is_empty() {
+                let _ = storage.update_instances(|stored| record_health(stored, &changed));
+            }
+
+            waiting =
+                watch_prompts(&instances, config, &journal, metrics.as_deref(), &waiting, &tmux);
+
+            let now = chrono::Local::now().naive_local();
+            let mut sent = Vec::new();
+            for schedule in scheduler.due(&config.schedules, now) {
+                sent.extend(send_scheduled_prompt(schedule, &instances, &journal, &tmux));
+            }
+            if !sent.is_empty() {
+                let _ = storage.update_instances(|stored| record_prompts(stored, sent));
+            }
+
+            if let Some(ref metrics) = metrics {
+                update_metrics(metrics, &instances, &waiting, &mut last_diff_refresh);
+            }
         }
 
         std::thread::sleep(poll_interval);
@@ -50,6 +116,197 @@ pub fn run_daemon(config_dir: &Path, config: &Config) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Build a detached tmux handle for an instance loaded from storage.
+fn tmux_for(instance: &Instance, config: &Config) -> TmuxSession {
+    TmuxSession::new(
+        &instance.title,
+        &instance.program,
+        Box::new(SystemCmdExec),
+        Box::new(SystemPtyFactory),
+    )
+    .with_prompts(config.prompt_patterns(&instance.program))
+}
+
+/// Auto-approve prompts in auto-yes sessions and journal prompts left for
+/// the user. Returns the titles of sessions now waiting for input; a session
+/// is journaled once per wait, not on every poll.
+fn watch_prompts(
+    instances: &[Instance],
+    config: &Config,
+    journal: &Journal,
+    metrics: Option<&metrics::Metrics>,
+    was_waiting: &HashSet<String>,
+    tmux_for: &TmuxFor,
+) -> HashSet<String> {
+    let mut waiting = HashSet::new();
+    for instance in instances {
+        if !instance.status.is_running() {
+            continue;
+        }
+        let tmux = tmux_for(instance);
+        let approved = instance.auto_yes
+            && tmux
+                .auto_approve(&config.auto_yes_allow_patterns, &config.auto_yes_deny_patterns)
+                .unwrap_or(false);
+        if approved {
+            journal.record(&instance.title, EventKind::AutoResponse, None);
+            if let Some(metrics) = metrics {
+                metrics.record_auto_response();
+            }
+        } else if tmux.needs_attention().unwrap_or(false) {
+            if !was_waiting.contains(&instance.title) {
+                journal.record(&instance.title, EventKind::AttentionNeeded, None);
+            }
+            waiting.insert(instance.title.clone());
+        }
+    }
+    waiting
+}
+
+/// Restart the agent of a running instance whose process has exited, or
+/// mark it Ready with its last output from `tails`.
+///
+/// Restarts happen in the instance's existing worktree when `auto_restart`
+/// is enabled, up to `max_restarts` times. Once restarts are exhausted (or
+/// disabled) the instance is marked Ready. Returns true if the instance
+/// changed and should be saved.
+fn check_health(
+    instance: &mut Instance,
+    tmux: &mut TmuxSession,
+    config: &Config,
+    tails: &mut PaneTails,
+) -> bool {
+    if tmux.is_alive() {
+        // Gone with the session once the agent exits
+        if let Ok(content) = tmux.capture_pane_content(false) {
+            tails.record(&instance.title, &content);
+        }
+        return false;
+    }
+
+    if config.auto_restart
+        && instance.restart_count < config.max_restarts
+        && let Some(ref wt) = instance.git_worktree
+    {
+        let respawned = crate::session::launch::pane_command(
+            instance.container.as_ref(),
+            wt,
+            &instance.program,
+            instance.git_identity.as_ref(),
+            config,
+            &SystemCmdExec,
+        )
+        .map_err(|e| e.to_string())
+        .and_then(|launch| {
+            tmux.set_command(launch);
+            tmux.respawn(wt.worktree_path()).map_err(|e| e.to_string())
+        });
+        match respawned {
+            Ok(()) => {
+                instance.restart_count += 1;
+                tracing::warn!(
+                    session = %instance.title,
+                    "Agent exited; restarted ({}/{})",
+                    instance.restart_count,
+                    config.max_restarts
+                );
+                return true;
+            }
+            Err(e) => {
+                tracing::error!(session = %instance.title, "Failed to restart the agent: {}", e);
+            }
+        }
+    }
+
+    // Keep `started` so the session (and its worktree) stays in storage
+    tracing::warn!(session = %instance.title, "Agent exited; marking as ready");
+    instance.mark_exited(tails.take(&instance.title));
+    true
+}
+
+/// Write what `check_health` changed in the `changed` sessions into the
+/// stored ones. Only the health fields (status, restart count, exit output)
+/// are touched, and only while they're still running: the TUI may have
+/// saved other changes, or paused a session, since the daemon loaded them.
+fn record_health(stored: &mut [Instance], changed: &[Instance]) {
+    for checked in changed {
+        if let Some(instance) = stored
+            .iter_mut()
+            .find(|i| i.title == checked.title && i.status.is_running())
+        {
+            instance.status = checked.status;
+            instance.restart_count = checked.restart_count;
+            instance.exit_output = checked.exit_output.clone();
+        }
+    }
+}
+
+/// Send a scheduled prompt to every running session it targets. Returns
+/// the sessions it reached, with the prompt as their history records it.
+fn send_scheduled_prompt(
+    schedule: &crate::config::schedule::Schedule,
+    instances: &[Instance],
+    journal: &Journal,
+    tmux_for: &TmuxFor,
+) -> Vec<(String, SentPrompt)> {
+    let mut sent = Vec::new();
+    for instance in instances {
+        if instance.status.is_running() && schedule.targets(&instance.title) {
+            let mut session = instance.clone();
+            session.tmux_session = Some(tmux_for(instance));
+            match session.send_prompt(&schedule.prompt) {
+                Ok(()) => {
+                    journal.record(&instance.title, EventKind::PromptSent, Some(&schedule.prompt));
+                    sent.extend(session.prompts.pop().map(|p| (session.title, p)));
+                }
+                Err(e) => {
+                    tracing::warn!(session = %instance.title, "Failed to send scheduled prompt: {}", e);
+                }
+            }
+        }
+    }
+    sent
+}
+
+/// Add prompts the daemon sent to the stored sessions' history.
+fn record_prompts(stored: &mut [Instance], sent: Vec<(String, SentPrompt)>) {
+    for (title, prompt) in sent {
+        if let Some(instance) = stored.iter_mut().find(|i| i.title == title) {
+            instance.prompts.push(prompt);
+        }
+    }
+}
+
+/// Refresh session gauges, prompt-wait tracking and (periodically) diff totals.
+fn update_metrics(
+    metrics: &metrics::Metrics,
+    instances: &[Instance],
+    waiting: &HashSet<String>,
+    last_diff_refresh: &mut Option<std::time::Instant>,
+) {
+    metrics.set_sessions(instances);
+
+    for instance in instances {
+        metrics.observe_prompt(&instance.title, waiting.contains(&instance.title));
+    }
+
+    if last_diff_refresh.is_none_or(|t| t.elapsed() >= METRICS_DIFF_INTERVAL) {
+        let cmd = SystemCmdExec;
+        let (mut added, mut removed) = (0, 0);
+        for instance in instances {
+            if instance.status.is_running()
+                && let Some(ref wt) = instance.git_worktree
+            {
+                let stats = wt.diff(&cmd);
+                added += stats.added_lines;
+                removed += stats.removed_lines;
+            }
+        }
+        metrics.set_diff_totals(added, removed);
+        *last_diff_refresh = Some(std::time::Instant::now());
+    }
+}
+
 #[cfg(unix)]
 extern "C" fn handle_shutdown(_: std::ffi::c_int) {
     SHUTDOWN.store(true, Ordering::SeqCst);
@@ -90,6 +347,10 @@ pub fn launch_daemon(config_dir: &Path) -> anyhow::Result<()> {
 }
 
 /// Stop a running daemon.
+///
+/// Only signals the PID recorded in the PID file while a daemon holds the
+/// file's lock, so a stale file can never kill an unrelated process that
+/// reused the PID.
 pub fn stop_daemon(config_dir: &Path) -> anyhow::Result<()> {
     let pid_path = config_dir.join(PID_FILE);
     if !pid_path.exists() {
@@ -97,30 +358,26 @@ pub fn stop_daemon(config_dir: &Path) -> anyhow::Result<()> {
         return Ok(());
     }
 
+    if !platform::is_locked(&pid_path) {
+        let _ = fs::remove_file(&pid_path);
+        println!("No daemon running (removed stale PID file)");
+        return Ok(());
+    }
+
     let pid_str = fs::read_to_string(&pid_path)?;
     let pid: i32 = pid_str.trim().parse()?;
 
-    // Send SIGTERM
+    // Send SIGTERM; the daemon removes its PID file on shutdown
     platform::kill_process(pid)?;
 
-    // Remove PID file
-    let _ = fs::remove_file(&pid_path);
     println!("Daemon stopped (PID {})", pid);
     Ok(())
 }
 
-/// Check if daemon is running.
+/// Check if daemon is running, i.e. some process holds the PID file lock.
 pub fn is_daemon_running(config_dir: &Path) -> bool {
     let pid_path = config_dir.join(PID_FILE);
-    if !pid_path.exists() {
-        return false;
-    }
-    if let Ok(pid_str) = fs::read_to_string(&pid_path) {
-        if let Ok(pid) = pid_str.trim().parse::<i32>() {
-            return platform::is_process_running(pid);
-        }
-    }
-    false
+    pid_path.exists() && platform::is_locked(&pid_path)
 }
 
 #[cfg(test)]
@@ -149,6 +406,245 @@ mod tests {
         assert!(!is_daemon_running(tmp.path()));
     }
 
+    fn make_running_instance() -> Instance {
+        let mut instance = Instance::new(crate::session::InstanceOptions {
+            title: "health".to_string(),
+            path: "/repo".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        instance.status = InstanceStatus::Running;
+        instance.started = true;
+        instance.git_worktree = Some(crate::session::git::GitWorktree::from_storage(
+            "/repo".to_string(),
+            "/worktree".to_string(),
+            "health".to_string(),
+            "health".to_string(),
+            "abc123".to_string(),
+        ));
+        instance
+    }
+
+    fn dead_session_tmux() -> TmuxSession {
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run().returning(|_, cmd_args| {
+            if cmd_args[0] == "has-session" {
+                Err(crate::cmd::CmdError::Failed("no session".to_string()))
+            } else {
+                Ok(())
+            }
+        });
+        TmuxSession::new("health", "claude", Box::new(mock), Box::new(SystemPtyFactory))
+    }
+
+    #[test]
+    fn test_record_health_keeps_other_changes() {
+        let tmp = TempDir::new().unwrap();
+        let storage = FileStorage::new(tmp.path());
+        let mut paused = make_running_instance();
+        paused.title = "paused".to_string();
+        storage.save_instances(&[make_running_instance(), paused.clone()]).unwrap();
+
+        // The TUI pauses one session and sends a prompt to the other after
+        // the daemon's load
+        let mut running = make_running_instance();
+        running.pending_prompt = Some("next".to_string());
+        paused.status = InstanceStatus::Paused;
+        storage.save_instances(&[running, paused]).unwrap();
+
+        let mut health = make_running_instance();
+        health.status = InstanceStatus::Ready;
+        health.restart_count = 2;
+        health.exit_output = Some("Error: API key expired".to_string());
+        let mut died = health.clone();
+        died.title = "paused".to_string();
+        died.restart_count = 1;
+        let changed = [health, died];
+        storage.update_instances(|stored| record_health(stored, &changed)).unwrap();
+
+        let stored = storage.load_instances().unwrap();
+        assert_eq!(stored[0].status, InstanceStatus::Ready);
+        assert_eq!(stored[0].restart_count, 2);
+        assert_eq!(stored[0].pending_prompt.as_deref(), Some("next"));
+        assert_eq!(stored[0].exit_output.as_deref(), Some("Error: API key expired"));
+        assert_eq!(stored[1].status, InstanceStatus::Paused);
+        assert_eq!(stored[1].restart_count, 0);
+        assert_eq!(stored[1].exit_output, None);
+    }
+
+    #[test]
+    fn test_check_health_restarts_dead_agent() {
+        let mut instance = make_running_instance();
+        let config = Config {
+            auto_restart: true,
+            max_restarts: 1,
+            ..Config::default()
+        };
+
+        assert!(check_health(&mut instance, &mut dead_session_tmux(), &config, &mut PaneTails::default()));
+        assert_eq!(instance.restart_count, 1);
+        assert_eq!(instance.status, InstanceStatus::Running);
+
+        // Restart budget exhausted: falls back to Ready
+        assert!(check_health(&mut instance, &mut dead_session_tmux(), &config, &mut PaneTails::default()));
+        assert_eq!(instance.restart_count, 1);
+        assert_eq!(instance.status, InstanceStatus::Ready);
+    }
+
+    #[test]
+    fn test_check_health_without_auto_restart_marks_ready() {
+        let mut instance = make_running_instance();
+        let config = Config::default();
+
+        assert!(check_health(&mut instance, &mut dead_session_tmux(), &config, &mut PaneTails::default()));
+        assert_eq!(instance.restart_count, 0);
+        assert_eq!(instance.status, InstanceStatus::Ready);
+        assert!(instance.started);
+    }
+
+    #[test]
+    fn test_check_health_keeps_last_output_of_dead_agent() {
+        let mut instance = make_running_instance();
+        let config = Config::default();
+        let mut tails = PaneTails::default();
+
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run().returning(|_, _| Ok(()));
+        mock.expect_output()
+            .returning(|_, _| Ok("working\nError: API key expired\n".to_string()));
+        let mut alive =
+            TmuxSession::new("health", "claude", Box::new(mock), Box::new(SystemPtyFactory));
+        assert!(!check_health(&mut instance, &mut alive, &config, &mut tails));
+        assert_eq!(instance.exit_output, None);
+
+        assert!(check_health(&mut instance, &mut dead_session_tmux(), &config, &mut tails));
+        assert_eq!(instance.status, InstanceStatus::Ready);
+        assert!(instance.exit_output.as_deref().unwrap().contains("API key expired"));
+    }
+
+    /// `instances` as the daemon sees them: saved and loaded back, with no
+    /// live tmux handles.
+    fn load_fresh(dir: &Path, instances: &[Instance]) -> Vec<Instance> {
+        let storage = FileStorage::new(dir);
+        storage.save_instances(instances).unwrap();
+        let loaded = storage.load_instances().unwrap();
+        assert!(loaded.iter().all(|i| i.tmux_session.is_none()));
+        loaded
+    }
+
+    type SentKeys = Arc<std::sync::Mutex<Vec<Vec<String>>>>;
+
+    /// tmux handles whose panes all show `pane`, recording what's run.
+    fn tmux_showing(pane: &'static str, sent: &SentKeys) -> impl Fn(&Instance) -> TmuxSession {
+        let sent = Arc::clone(sent);
+        move |instance: &Instance| {
+            let mut mock = crate::cmd::MockCmdExec::new();
+            let log = Arc::clone(&sent);
+            mock.expect_run().returning(move |_, cmd_args| {
+                log.lock().unwrap().push(cmd_args.to_vec());
+                Ok(())
+            });
+            mock.expect_output().returning(move |_, _| Ok(pane.to_string()));
+            TmuxSession::new(
+                &instance.title,
+                &instance.program,
+                Box::new(mock),
+                Box::new(SystemPtyFactory),
+            )
+        }
+    }
+
+    #[test]
+    fn test_watch_prompts_on_freshly_loaded_sessions() {
+        let tmp = TempDir::new().unwrap();
+        let mut auto = make_running_instance();
+        auto.title = "auto".to_string();
+        auto.auto_yes = true;
+        let mut manual = make_running_instance();
+        manual.title = "manual".to_string();
+        let instances = load_fresh(tmp.path(), &[auto, manual]);
+
+        let sent = SentKeys::default();
+        let pane = "Edit src/main.rs?\n> Yes\nNo, and tell Claude what to do differently\n";
+        let tmux = tmux_showing(pane, &sent);
+        let journal = Journal::new(tmp.path());
+        let waiting = watch_prompts(
+            &instances,
+            &Config::default(),
+            &journal,
+            None,
+            &HashSet::new(),
+            &tmux,
+        );
+
+        // The auto-yes session was approved, the other one waits
+        assert_eq!(waiting, HashSet::from(["manual".to_string()]));
+        let sent = sent.lock().unwrap();
+        assert!(sent.iter().any(|a| a[0] == "send-keys" && a[2] == "gana_auto"));
+        assert!(!sent.iter().any(|a| a[0] == "send-keys" && a[2] == "gana_manual"));
+        let kinds: Vec<_> = journal.recent(10).into_iter().map(|e| (e.session, e.kind)).collect();
+        assert!(kinds.contains(&("auto".to_string(), EventKind::AutoResponse)));
+        assert!(kinds.contains(&("manual".to_string(), EventKind::AttentionNeeded)));
+    }
+
+    #[test]
+    fn test_scheduled_prompt_reaches_freshly_loaded_session() {
+        let tmp = TempDir::new().unwrap();
+        let mut paused = make_running_instance();
+        paused.title = "paused".to_string();
+        paused.status = InstanceStatus::Paused;
+        let instances = load_fresh(tmp.path(), &[make_running_instance(), paused]);
+
+        let sent = SentKeys::default();
+        let tmux = tmux_showing("", &sent);
+        let schedule = crate::config::schedule::Schedule {
+            time: "09:00".to_string(),
+            days: Vec::new(),
+            session: None,
+            prompt: "run the tests".to_string(),
+        };
+        let reached = send_scheduled_prompt(&schedule, &instances, &Journal::new(tmp.path()), &tmux);
+        assert_eq!(reached.len(), 1);
+        assert_eq!(reached[0].1.text, "run the tests");
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(
+            *sent,
+            [
+                crate::cmd::args(&["send-keys", "-t", "gana_health", "run the tests"]),
+                crate::cmd::args(&["send-keys", "-t", "gana_health", "Enter"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_daemon_running_while_locked() {
+        let tmp = TempDir::new().unwrap();
+        let pid_path = tmp.path().join(PID_FILE);
+        fs::write(&pid_path, std::process::id().to_string()).unwrap();
+        // A live PID alone is not enough
+        assert!(!is_daemon_running(tmp.path()));
+
+        let lock = platform::try_lock(fs::File::open(&pid_path).unwrap()).unwrap();
+        assert!(is_daemon_running(tmp.path()));
+        // A second lock attempt fails while the first is held
+        assert!(platform::try_lock(fs::File::open(&pid_path).unwrap()).is_none());
+
+        drop(lock);
+        assert!(!is_daemon_running(tmp.path()));
+    }
+
+    #[test]
+    fn test_stop_daemon_stale_pid_file_does_not_kill() {
+        let tmp = TempDir::new().unwrap();
+        let pid_path = tmp.path().join(PID_FILE);
+        // Our own PID: killing it would abort the test run
+        fs::write(&pid_path, std::process::id().to_string()).unwrap();
+
+        stop_daemon(tmp.path()).unwrap();
+        assert!(!pid_path.exists());
+    }
+
     #[test]
     fn test_is_daemon_running_invalid_pid() {
         let tmp = TempDir::new().unwrap();
@@ -7,6 +7,7 @@ pub fn kill_process(pid: i32) -> anyhow::Result<()> {
 }
 
 #[cfg(unix)]
+#[allow(dead_code)] // Unix uses the PID file lock instead
 pub fn is_process_running(pid: i32) -> bool {
     use nix::sys::signal::kill;
     use nix::unistd::Pid;
@@ -30,3 +31,53 @@ pub fn is_process_running(pid: i32) -> bool {
         .map(|o| String::from_utf8_lossy(&o.stdout).contains(&pid.to_string()))
         .unwrap_or(false)
 }
+
+/// Exclusive lock on the daemon PID file, released when dropped.
+#[cfg(unix)]
+pub type PidLock = nix::fcntl::Flock<std::fs::File>;
+
+/// Try to take an exclusive, non-blocking lock on `file`.
+/// Returns `None` if another process holds the lock.
+#[cfg(unix)]
+pub fn try_lock(file: std::fs::File) -> Option<PidLock> {
+    use nix::fcntl::{Flock, FlockArg};
+    Flock::lock(file, FlockArg::LockExclusiveNonblock).ok()
+}
+
+/// Take an exclusive lock on `file`, waiting while another process holds it.
+#[cfg(unix)]
+pub fn lock(file: std::fs::File) -> Option<PidLock> {
+    use nix::fcntl::{Flock, FlockArg};
+    Flock::lock(file, FlockArg::LockExclusive).ok()
+}
+
+/// Check whether some process holds the lock on the file at `path`.
+#[cfg(unix)]
+pub fn is_locked(path: &std::path::Path) -> bool {
+    match std::fs::File::open(path) {
+        Ok(file) => try_lock(file).is_none(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(windows)]
+pub struct PidLock(#[allow(dead_code)] std::fs::File);
+
+#[cfg(windows)]
+pub fn try_lock(file: std::fs::File) -> Option<PidLock> {
+    // No advisory locking on Windows; fall back to PID liveness checks.
+    Some(PidLock(file))
+}
+
+#[cfg(windows)]
+pub fn lock(file: std::fs::File) -> Option<PidLock> {
+    Some(PidLock(file))
+}
+
+#[cfg(windows)]
+pub fn is_locked(path: &std::path::Path) -> bool {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|s| s.trim().parse::<i32>().ok())
+        .is_some_and(is_process_running)
+}
@@ -0,0 +1,52 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::config::schedule::Schedule;
+
+/// Tracks which schedules already fired today so each fires once per day.
+#[derive(Default)]
+pub struct Scheduler {
+    // Schedule index -> date it last fired
+    last_fired: HashMap<usize, NaiveDate>,
+}
+
+impl Scheduler {
+    /// Return the schedules that should fire at `now`, marking them as fired.
+    pub fn due<'a>(&mut self, schedules: &'a [Schedule], now: NaiveDateTime) -> Vec<&'a Schedule> {
+        let today = now.date();
+        let mut due = Vec::new();
+        for (idx, schedule) in schedules.iter().enumerate() {
+            if schedule.is_due(now) && self.last_fired.get(&idx) != Some(&today) {
+                self.last_fired.insert(idx, today);
+                due.push(schedule);
+            }
+        }
+        due
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fires_once_per_day() {
+        let schedules = vec![Schedule {
+            time: "09:00".to_string(),
+            days: Vec::new(),
+            session: None,
+            prompt: "hi".to_string(),
+        }];
+        let mut scheduler = Scheduler::default();
+        let day1 = NaiveDate::from_ymd_opt(2025, 1, 6).unwrap();
+        let day2 = NaiveDate::from_ymd_opt(2025, 1, 7).unwrap();
+
+        assert_eq!(scheduler.due(&schedules, day1.and_hms_opt(9, 0, 1).unwrap()).len(), 1);
+        // Later polls within the same minute don't fire again
+        assert!(scheduler.due(&schedules, day1.and_hms_opt(9, 0, 2).unwrap()).is_empty());
+        assert!(scheduler.due(&schedules, day1.and_hms_opt(10, 0, 0).unwrap()).is_empty());
+        // Next day fires again
+        assert_eq!(scheduler.due(&schedules, day2.and_hms_opt(9, 0, 0).unwrap()).len(), 1);
+    }
+}
@@ -0,0 +1,174 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::cmd::{args, CmdExec};
+
+const SYSTEMD_UNIT: &str = "gana-daemon.service";
+const LAUNCHD_LABEL: &str = "com.gana.daemon";
+
+/// Render a user systemd unit that runs the daemon with `search_path` as
+/// its PATH, so it finds tmux, git and gh where the installing shell did.
+pub fn systemd_unit(exe: &Path, config_dir: &Path, search_path: &str) -> String {
+    let environment = search_path
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%");
+    format!(
+        "[Unit]
+Description=gana daemon
+After=default.target
+
+[Service]
+Environment=\"PATH={}\"
+ExecStart=\"{}\" daemon --config-dir \"{}\"
+Restart=on-failure
+RestartSec=5
+
+[Install]
+WantedBy=default.target
+",
+        environment,
+        exe.display(),
+        config_dir.display()
+    )
+}
+
+/// Render a launchd agent plist that runs the daemon at login with
+/// `search_path` as its PATH: launchd's own leaves out Homebrew's tools.
+pub fn launchd_plist(exe: &Path, config_dir: &Path, search_path: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+        <string>daemon</string>
+        <string>--config-dir</string>
+        <string>{}</string>
+    </array>
+    <key>EnvironmentVariables</key>
+    <dict>
+        <key>PATH</key>
+        <string>{}</string>
+    </dict>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        LAUNCHD_LABEL,
+        xml_escape(&exe.display().to_string()),
+        xml_escape(&config_dir.display().to_string()),
+        xml_escape(search_path)
+    )
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// Path of the service file for the current platform.
+fn service_path() -> anyhow::Result<PathBuf> {
+    let home = dirs::home_dir().ok_or_else(|| anyhow::anyhow!("could not determine home directory"))?;
+    if cfg!(target_os = "macos") {
+        Ok(home
+            .join("Library/LaunchAgents")
+            .join(format!("{}.plist", LAUNCHD_LABEL)))
+    } else {
+        Ok(home.join(".config/systemd/user").join(SYSTEMD_UNIT))
+    }
+}
+
+/// Write and enable a login service for the daemon.
+pub fn install(config_dir: &Path, cmd: &dyn CmdExec) -> anyhow::Result<()> {
+    let exe = std::env::current_exe()?;
+    let search_path = std::env::var("PATH").unwrap_or_default();
+    let path = service_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    if cfg!(target_os = "macos") {
+        fs::write(&path, launchd_plist(&exe, config_dir, &search_path))?;
+        let path_str = path.to_string_lossy();
+        if let Err(e) = cmd.run("launchctl", &args(&["load", "-w", &path_str])) {
+            println!("Wrote {} but could not load it: {}", path.display(), e);
+            return Ok(());
+        }
+    } else {
+        fs::write(&path, systemd_unit(&exe, config_dir, &search_path))?;
+        let enabled = cmd
+            .run("systemctl", &args(&["--user", "daemon-reload"]))
+            .and_then(|_| {
+                cmd.run("systemctl", &args(&["--user", "enable", "--now", SYSTEMD_UNIT]))
+            });
+        if let Err(e) = enabled {
+            println!("Wrote {} but could not enable it: {}", path.display(), e);
+            return Ok(());
+        }
+    }
+
+    println!("Daemon service installed: {}", path.display());
+    Ok(())
+}
+
+/// Stop, disable and remove the daemon login service.
+pub fn uninstall(cmd: &dyn CmdExec) -> anyhow::Result<()> {
+    let path = service_path()?;
+    if !path.exists() {
+        println!("No daemon service installed");
+        return Ok(());
+    }
+
+    if cfg!(target_os = "macos") {
+        let path_str = path.to_string_lossy();
+        let _ = cmd.run("launchctl", &args(&["unload", "-w", &path_str]));
+    } else {
+        let _ = cmd.run("systemctl", &args(&["--user", "disable", "--now", SYSTEMD_UNIT]));
+    }
+    fs::remove_file(&path)?;
+    if !cfg!(target_os = "macos") {
+        let _ = cmd.run("systemctl", &args(&["--user", "daemon-reload"]));
+    }
+
+    println!("Daemon service removed: {}", path.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_systemd_unit_points_at_binary_and_config_dir() {
+        let unit = systemd_unit(
+            Path::new("/usr/local/bin/gana"),
+            Path::new("/home/me/.gana"),
+            "/home/me/.cargo/bin:/usr/bin",
+        );
+        assert!(unit.contains("ExecStart=\"/usr/local/bin/gana\" daemon --config-dir \"/home/me/.gana\""));
+        assert!(unit.contains("Environment=\"PATH=/home/me/.cargo/bin:/usr/bin\""));
+        assert!(unit.contains("WantedBy=default.target"));
+    }
+
+    #[test]
+    fn test_launchd_plist_arguments() {
+        let plist = launchd_plist(
+            Path::new("/opt/gana"),
+            Path::new("/Users/me/.gana"),
+            "/opt/homebrew/bin:/usr/bin",
+        );
+        assert!(plist.contains("<string>com.gana.daemon</string>"));
+        assert!(plist.contains("<string>/opt/gana</string>"));
+        assert!(plist.contains("<string>--config-dir</string>"));
+        assert!(plist.contains("<string>/Users/me/.gana</string>"));
+        assert!(plist.contains("<key>PATH</key>\n        <string>/opt/homebrew/bin:/usr/bin</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>"));
+    }
+}
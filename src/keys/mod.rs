@@ -7,8 +7,17 @@ pub enum KeyAction {
     Down,
     Left,
     Right,
+    /// First session (`gg`), or the top of the preview while scrolling.
+    Top,
+    /// Last session (`G`), or back to the live preview while scrolling.
+    Bottom,
+    PageUp,
+    PageDown,
     Enter,
     New,
+    NewFromBranch,
+    /// A new session set up like the selected one, on a fresh branch.
+    NewLike,
     Attach,
     Delete,
     Kill,
@@ -16,9 +25,27 @@ pub enum KeyAction {
     Push,
     Prompt,
     Restart,
+    ToggleAutoYes,
+    ToggleAutoPush,
+    Undo,
+    Details,
+    CleanupWorktrees,
+    OpenInBrowser,
+    OpenInEditor,
+    GitTui,
+    CopyPath,
+    CopyBranch,
+    ToggleBoard,
+    SwitchProject,
+    RequestChanges,
     Quit,
     Help,
+    Schedules,
+    SlowCommands,
+    Team,
     Tab,
+    PreviousTab,
+    RefreshDiff,
     ScrollUp,
     ScrollDown,
     ResetScroll,
@@ -34,8 +61,14 @@ impl KeyAction {
             KeyAction::Down => "Move down",
             KeyAction::Left => "Move left",
             KeyAction::Right => "Move right",
+            KeyAction::Top => "Go to top",
+            KeyAction::Bottom => "Go to bottom",
+            KeyAction::PageUp => "Page up",
+            KeyAction::PageDown => "Page down",
             KeyAction::Enter => "Select / Attach",
             KeyAction::New => "New session",
+            KeyAction::NewFromBranch => "New session from existing branch",
+            KeyAction::NewLike => "New session like the selected one",
             KeyAction::Attach => "Attach to session",
             KeyAction::Delete => "Delete session",
             KeyAction::Kill => "Kill session",
@@ -43,9 +76,27 @@ impl KeyAction {
             KeyAction::Push => "Push & create PR",
             KeyAction::Prompt => "New with prompt",
             KeyAction::Restart => "Restart session",
+            KeyAction::ToggleAutoYes => "Toggle auto-yes",
+            KeyAction::ToggleAutoPush => "Toggle push when done",
+            KeyAction::Undo => "Undo delete/kill",
+            KeyAction::Details => "Session details",
+            KeyAction::CleanupWorktrees => "Remove worktrees of paused sessions",
+            KeyAction::OpenInBrowser => "Open PR/branch in browser",
+            KeyAction::OpenInEditor => "Open worktree in editor",
+            KeyAction::GitTui => "Open git TUI in worktree",
+            KeyAction::CopyPath => "Copy worktree path",
+            KeyAction::CopyBranch => "Copy branch name",
+            KeyAction::ToggleBoard => "Toggle board view",
+            KeyAction::SwitchProject => "Switch project (all / one repo)",
+            KeyAction::RequestChanges => "Request changes on the selected hunk",
             KeyAction::Quit => "Quit",
             KeyAction::Help => "Toggle help",
+            KeyAction::Schedules => "Show scheduled prompts",
+            KeyAction::SlowCommands => "Show slow external commands",
+            KeyAction::Team => "Show teammates' sessions",
             KeyAction::Tab => "Switch tab",
+            KeyAction::PreviousTab => "Previous tab",
+            KeyAction::RefreshDiff => "Refresh diff",
             KeyAction::ScrollUp => "Scroll up",
             KeyAction::ScrollDown => "Scroll down",
             KeyAction::ResetScroll => "Reset scroll",
@@ -61,6 +112,10 @@ impl KeyAction {
             KeyAction::Down => "j/\u{2193}",
             KeyAction::Left => "h/\u{2190}",
             KeyAction::Right => "l/\u{2192}",
+            KeyAction::Top => "gg",
+            KeyAction::Bottom => "G",
+            KeyAction::PageUp => "PgUp",
+            KeyAction::PageDown => "PgDn",
             KeyAction::Enter => "Enter",
             KeyAction::New => "n",
             KeyAction::Attach => "a",
@@ -70,9 +125,29 @@ impl KeyAction {
             KeyAction::Push => "P",
             KeyAction::Prompt => "N",
             KeyAction::Restart => "r",
+            KeyAction::ToggleAutoYes => "A",
+            KeyAction::ToggleAutoPush => "F",
+            KeyAction::Undo => "u",
+            KeyAction::Details => "i",
+            KeyAction::CleanupWorktrees => "C",
+            KeyAction::OpenInBrowser => "o",
+            KeyAction::OpenInEditor => "e",
+            KeyAction::GitTui => "L",
+            KeyAction::CopyPath => "y",
+            KeyAction::CopyBranch => "Y",
+            KeyAction::ToggleBoard => "v",
+            KeyAction::SwitchProject => "w",
+            KeyAction::RequestChanges => "c",
+            KeyAction::NewFromBranch => "b",
+            KeyAction::NewLike => "S",
             KeyAction::Quit => "q",
             KeyAction::Help => "?",
+            KeyAction::Schedules => "s",
+            KeyAction::SlowCommands => "T",
+            KeyAction::Team => "t",
             KeyAction::Tab => "Tab",
+            KeyAction::PreviousTab => "S-Tab",
+            KeyAction::RefreshDiff => "R",
             KeyAction::ScrollUp => "K",
             KeyAction::ScrollDown => "J",
             KeyAction::ResetScroll => "Esc",
@@ -100,10 +175,16 @@ pub fn map_key(event: KeyEvent) -> Option<KeyAction> {
         // Scroll (uppercase vim keys)
         KeyCode::Char('K') => Some(KeyAction::ScrollUp),
         KeyCode::Char('J') => Some(KeyAction::ScrollDown),
+        KeyCode::PageUp => Some(KeyAction::PageUp),
+        KeyCode::PageDown => Some(KeyAction::PageDown),
+        KeyCode::Home => Some(KeyAction::Top),
+        KeyCode::End | KeyCode::Char('G') => Some(KeyAction::Bottom),
 
         // Actions
         KeyCode::Enter => Some(KeyAction::Enter),
         KeyCode::Char('n') => Some(KeyAction::New),
+        KeyCode::Char('b') => Some(KeyAction::NewFromBranch),
+        KeyCode::Char('S') => Some(KeyAction::NewLike),
         KeyCode::Char('a') => Some(KeyAction::Attach),
         KeyCode::Char('d') => Some(KeyAction::Delete),
         KeyCode::Char('D') => Some(KeyAction::Kill),
@@ -111,9 +192,29 @@ pub fn map_key(event: KeyEvent) -> Option<KeyAction> {
         KeyCode::Char('P') => Some(KeyAction::Push),
         KeyCode::Char('N') => Some(KeyAction::Prompt),
         KeyCode::Char('r') => Some(KeyAction::Restart),
+        KeyCode::Char('A') => Some(KeyAction::ToggleAutoYes),
+        KeyCode::Char('F') => Some(KeyAction::ToggleAutoPush),
+        KeyCode::Char('u') => Some(KeyAction::Undo),
+        KeyCode::Char('i') => Some(KeyAction::Details),
+        KeyCode::Char('C') => Some(KeyAction::CleanupWorktrees),
+        KeyCode::Char('o') => Some(KeyAction::OpenInBrowser),
+        KeyCode::Char('e') => Some(KeyAction::OpenInEditor),
+        KeyCode::Char('L') => Some(KeyAction::GitTui),
+        KeyCode::Char('y') => Some(KeyAction::CopyPath),
+        KeyCode::Char('Y') => Some(KeyAction::CopyBranch),
+        KeyCode::Char('v') => Some(KeyAction::ToggleBoard),
+        KeyCode::Char('w') => Some(KeyAction::SwitchProject),
+        KeyCode::Char('c') if !event.modifiers.contains(KeyModifiers::CONTROL) => {
+            Some(KeyAction::RequestChanges)
+        }
         KeyCode::Char('q') => Some(KeyAction::Quit),
         KeyCode::Char('?') => Some(KeyAction::Help),
+        KeyCode::Char('s') => Some(KeyAction::Schedules),
+        KeyCode::Char('T') => Some(KeyAction::SlowCommands),
+        KeyCode::Char('t') => Some(KeyAction::Team),
         KeyCode::Tab => Some(KeyAction::Tab),
+        KeyCode::BackTab => Some(KeyAction::PreviousTab),
+        KeyCode::Char('R') => Some(KeyAction::RefreshDiff),
         KeyCode::Esc => Some(KeyAction::Cancel),
 
         // Ctrl+C as quit
@@ -125,6 +226,51 @@ pub fn map_key(event: KeyEvent) -> Option<KeyAction> {
     }
 }
 
+/// Largest count accepted in front of a key.
+const MAX_COUNT: usize = 9999;
+
+/// Turns key presses into actions with vim-style counts (`5j`) and the
+/// two-key `gg`.
+#[derive(Debug, Default)]
+pub struct KeyParser {
+    count: Option<usize>,
+    pending_g: bool,
+}
+
+impl KeyParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a key press. Returns the action with the count typed before it,
+    /// or `None` while a count or `g` is pending or the key isn't mapped.
+    pub fn feed(&mut self, event: KeyEvent) -> Option<(KeyAction, Option<usize>)> {
+        let plain = !event.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+        match event.code {
+            KeyCode::Char(c @ '0'..='9') if plain && (c != '0' || self.count.is_some()) => {
+                let digit = c.to_digit(10).unwrap_or(0) as usize;
+                let count = self.count.unwrap_or(0).saturating_mul(10).saturating_add(digit);
+                self.count = Some(count.min(MAX_COUNT));
+                self.pending_g = false;
+                None
+            }
+            KeyCode::Char('g') if plain => {
+                if std::mem::take(&mut self.pending_g) {
+                    Some((KeyAction::Top, self.count.take()))
+                } else {
+                    self.pending_g = true;
+                    None
+                }
+            }
+            _ => {
+                self.pending_g = false;
+                let count = self.count.take();
+                map_key(event).map(|action| (action, count))
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,9 +281,116 @@ mod tests {
         assert_eq!(map_key(event), Some(KeyAction::Pause));
     }
 
+    #[test]
+    fn test_restart_key_mapping() {
+        let event = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::Restart));
+        assert_eq!(KeyAction::Restart.key_label(), "r");
+    }
+
+    #[test]
+    fn test_schedules_key_mapping() {
+        let event = KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::Schedules));
+    }
+
+    #[test]
+    fn test_toggle_auto_yes_key_mapping() {
+        let event = KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::ToggleAutoYes));
+        let event = KeyEvent::new(KeyCode::Char('F'), KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::ToggleAutoPush));
+    }
+
+    #[test]
+    fn test_new_from_branch_key_mapping() {
+        let event = KeyEvent::new(KeyCode::Char('b'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::NewFromBranch));
+        let event = KeyEvent::new(KeyCode::Char('S'), KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::NewLike));
+    }
+
+    #[test]
+    fn test_details_and_cleanup_key_mapping() {
+        let event = KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::Details));
+        let event = KeyEvent::new(KeyCode::Char('C'), KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::CleanupWorktrees));
+    }
+
+    #[test]
+    fn test_open_key_mappings() {
+        let event = KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::OpenInBrowser));
+        let event = KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::OpenInEditor));
+        let event = KeyEvent::new(KeyCode::Char('L'), KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::GitTui));
+    }
+
+    #[test]
+    fn test_copy_key_mappings() {
+        let event = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::CopyPath));
+        let event = KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::CopyBranch));
+    }
+
+    #[test]
+    fn test_request_changes_key_mapping() {
+        let event = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::RequestChanges));
+        let event = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert_eq!(map_key(event), Some(KeyAction::Quit));
+    }
+
+    #[test]
+    fn test_slow_commands_key_mapping() {
+        let event = KeyEvent::new(KeyCode::Char('T'), KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::SlowCommands));
+    }
+
+    #[test]
+    fn test_tab_key_mappings() {
+        let event = KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::PreviousTab));
+        let event = KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT);
+        assert_eq!(map_key(event), Some(KeyAction::RefreshDiff));
+    }
+
+    #[test]
+    fn test_board_key_mapping() {
+        let event = KeyEvent::new(KeyCode::Char('v'), KeyModifiers::NONE);
+        assert_eq!(map_key(event), Some(KeyAction::ToggleBoard));
+    }
+
     #[test]
     fn test_push_key_mapping() {
         let event = KeyEvent::new(KeyCode::Char('P'), KeyModifiers::SHIFT);
         assert_eq!(map_key(event), Some(KeyAction::Push));
     }
+
+    #[test]
+    fn test_key_parser_counts_and_gg() {
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        let mut parser = KeyParser::new();
+        assert_eq!(parser.feed(key('j')), Some((KeyAction::Down, None)));
+
+        assert_eq!(parser.feed(key('1')), None);
+        assert_eq!(parser.feed(key('0')), None);
+        assert_eq!(parser.feed(key('k')), Some((KeyAction::Up, Some(10))));
+
+        assert_eq!(parser.feed(key('g')), None);
+        assert_eq!(parser.feed(key('g')), Some((KeyAction::Top, None)));
+        assert_eq!(parser.feed(key('3')), None);
+        assert_eq!(parser.feed(key('G')), Some((KeyAction::Bottom, Some(3))));
+
+        // A lone g is dropped by the next key, and 0 alone isn't a count
+        assert_eq!(parser.feed(key('g')), None);
+        assert_eq!(parser.feed(key('j')), Some((KeyAction::Down, None)));
+        assert_eq!(parser.feed(key('0')), None);
+        assert_eq!(parser.feed(key('j')), Some((KeyAction::Down, None)));
+        let page_down = KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE);
+        assert_eq!(parser.feed(page_down), Some((KeyAction::PageDown, None)));
+    }
 }
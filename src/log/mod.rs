@@ -1,36 +1,96 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
 use tracing_subscriber::EnvFilter;
 
-/// Initialize the tracing/logging subsystem.
+mod rotate;
+mod session;
+
+use rotate::RotatingFile;
+use session::SessionLogLayer;
+
+/// Level used when nothing else is configured.
+const DEFAULT_LEVEL: &str = "info";
+
+/// The log filter in effect: `--log-level` (or `--verbose`), then
+/// `RUST_LOG`, then the config's `log_level`, then "info".
+pub fn log_level(cli: Option<&str>, env: Option<&str>, config: Option<&str>) -> String {
+    [cli, env, config]
+        .into_iter()
+        .flatten()
+        .map(str::trim)
+        .find(|level| !level.is_empty())
+        .unwrap_or(DEFAULT_LEVEL)
+        .to_string()
+}
+
+/// Check a `--log-level` value: a level like "debug" or a filter like
+/// "gana=trace,info".
+pub fn parse_level(level: &str) -> Result<String, String> {
+    EnvFilter::try_new(level)
+        .map(|_| level.to_string())
+        .map_err(|e| e.to_string())
+}
+
+/// Initialize the tracing/logging subsystem at `level` (see [`log_level`]).
 ///
-/// When `to_file` is true, logs are written to a file in the OS temp directory.
-/// Otherwise, logs go nowhere (useful for tests).
-pub fn initialize(to_file: bool) {
-    let builder = tracing_subscriber::fmt().with_env_filter(
-        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info")),
-    );
-
-    if to_file
-        && let Some(path) = log_file_path()
-        && let Ok(file) = std::fs::OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(&path)
-    {
-        let _ = builder.with_writer(file).with_ansi(false).try_init();
+/// With a `log_dir`, logs are written to `gana.log` there, and events
+/// carrying a `session` field are also copied to `{session}.log`. Both
+/// rotate by size and day. Without one, logs go nowhere (useful for tests).
+pub fn initialize(log_dir: Option<&Path>, level: &str) {
+    let filter = EnvFilter::try_new(level).unwrap_or_else(|_| EnvFilter::new(DEFAULT_LEVEL));
+
+    if let Some(dir) = log_dir {
+        let main_log = RotatingFile::new(log_file(dir));
+        let _ = tracing_subscriber::registry()
+            .with(filter)
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .with_writer(Mutex::new(main_log))
+                    .with_ansi(false),
+            )
+            .with(SessionLogLayer::new(dir.to_path_buf()))
+            .try_init();
         return;
     }
 
-    // Fallback: discard output (test mode or file creation failed)
-    let _ = builder
+    let _ = tracing_subscriber::fmt()
+        .with_env_filter(filter)
         .with_writer(std::io::sink)
         .with_ansi(false)
         .try_init();
 }
 
-/// Return the log file path: {temp_dir}/gana.log
-fn log_file_path() -> Option<PathBuf> {
-    let mut path = std::env::temp_dir();
-    path.push("gana.log");
-    Some(path)
+/// Directory for gana's logs: `{config_dir}/logs`.
+pub fn log_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join("logs")
+}
+
+/// The main log file in `log_dir`.
+pub fn log_file(log_dir: &Path) -> PathBuf {
+    log_dir.join("gana.log")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_level_precedence() {
+        assert_eq!(log_level(None, None, None), "info");
+        assert_eq!(log_level(None, None, Some("warn")), "warn");
+        assert_eq!(log_level(None, Some("trace"), Some("warn")), "trace");
+        assert_eq!(log_level(Some("debug"), Some("trace"), Some("warn")), "debug");
+        // An empty RUST_LOG doesn't count
+        assert_eq!(log_level(None, Some(""), Some("warn")), "warn");
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert!(parse_level("debug").is_ok());
+        assert!(parse_level("gana=trace,info").is_ok());
+        assert!(parse_level("gana=loud").is_err());
+    }
 }
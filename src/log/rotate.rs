@@ -0,0 +1,125 @@
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Local, NaiveDate};
+
+/// A log file is rotated once it would grow past this size.
+pub const MAX_LOG_BYTES: u64 = 1024 * 1024;
+
+/// Rotated files kept next to the live one, as `.1` (newest) to `.3`.
+const KEEP_ROTATED: usize = 3;
+
+/// An append-only log file that rotates when it grows past
+/// [`MAX_LOG_BYTES`] or when the day changes.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: Option<File>,
+    size: u64,
+    day: NaiveDate,
+}
+
+impl RotatingFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            file: None,
+            size: 0,
+            day: Local::now().date_naive(),
+        }
+    }
+
+    /// Path of the `n`th rotated file.
+    pub fn rotated_path(path: &Path, n: usize) -> PathBuf {
+        let mut name = path.as_os_str().to_os_string();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    }
+
+    fn open(&mut self) -> io::Result<&mut File> {
+        if self.file.is_none() {
+            if let Some(parent) = self.path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            // Pick up where an earlier process left the file
+            if let Ok(meta) = std::fs::metadata(&self.path) {
+                self.size = meta.len();
+                if let Ok(modified) = meta.modified() {
+                    self.day = DateTime::<Local>::from(modified).date_naive();
+                }
+            }
+            let file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+            self.file = Some(file);
+        }
+        Ok(self.file.as_mut().expect("log file was just opened"))
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        for n in (1..KEEP_ROTATED).rev() {
+            let from = Self::rotated_path(&self.path, n);
+            if from.exists() {
+                std::fs::rename(&from, Self::rotated_path(&self.path, n + 1))?;
+            }
+        }
+        if self.path.exists() {
+            std::fs::rename(&self.path, Self::rotated_path(&self.path, 1))?;
+        }
+        self.size = 0;
+        self.day = Local::now().date_naive();
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.open()?;
+        if self.size > 0
+            && (self.size + buf.len() as u64 > MAX_LOG_BYTES || self.day != Local::now().date_naive())
+        {
+            self.rotate()?;
+        }
+        let written = self.open()?.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file {
+            Some(ref mut file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotates_past_size_limit() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("logs").join("s.log");
+        let mut log = RotatingFile::new(path.clone());
+        let line = vec![b'x'; MAX_LOG_BYTES as usize / 2];
+        for _ in 0..(2 * KEEP_ROTATED + 4) {
+            log.write_all(&line).unwrap();
+        }
+
+        assert!(path.exists());
+        assert!(RotatingFile::rotated_path(&path, KEEP_ROTATED).exists());
+        assert!(!RotatingFile::rotated_path(&path, KEEP_ROTATED + 1).exists());
+        assert!(std::fs::metadata(&path).unwrap().len() <= MAX_LOG_BYTES);
+    }
+
+    #[test]
+    fn test_appends_to_existing_file() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("s.log");
+        std::fs::write(&path, "earlier\n").unwrap();
+
+        let mut log = RotatingFile::new(path.clone());
+        log.write_all(b"later\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "earlier\nlater\n");
+    }
+}
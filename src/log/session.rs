@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+use super::rotate::RotatingFile;
+
+/// Copies every event with a `session` field to that session's own log,
+/// `{dir}/{session}.log`.
+///
+/// Log with e.g. `tracing::warn!(session = %title, "Push failed: {}", e)`.
+pub struct SessionLogLayer {
+    dir: PathBuf,
+    files: Mutex<HashMap<String, RotatingFile>>,
+}
+
+impl SessionLogLayer {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            files: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Path of a session's log file.
+    pub fn log_path(&self, session: &str) -> PathBuf {
+        self.dir
+            .join(format!("{}.log", crate::session::tmux::sanitize_name(session)))
+    }
+}
+
+/// Collects an event's session, message and remaining fields.
+#[derive(Default)]
+struct EventFields {
+    session: Option<String>,
+    message: String,
+    rest: String,
+}
+
+impl Visit for EventFields {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "session" => self.session = Some(value.to_string()),
+            "message" => self.message = value.to_string(),
+            name => {
+                let _ = write!(self.rest, " {}={:?}", name, value);
+            }
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "session" => self.session = Some(format!("{:?}", value)),
+            "message" => self.message = format!("{:?}", value),
+            name => {
+                let _ = write!(self.rest, " {}={:?}", name, value);
+            }
+        }
+    }
+}
+
+impl<S: Subscriber> Layer<S> for SessionLogLayer {
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut fields = EventFields::default();
+        event.record(&mut fields);
+        let Some(session) = fields.session else {
+            return;
+        };
+        let line = format!(
+            "{} {:>5} {}{}\n",
+            chrono::Local::now().format("%Y-%m-%dT%H:%M:%S%.3f%:z"),
+            event.metadata().level(),
+            fields.message,
+            fields.rest
+        );
+
+        let Ok(mut files) = self.files.lock() else {
+            return;
+        };
+        let path = self.log_path(&session);
+        let file = files
+            .entry(session)
+            .or_insert_with(|| RotatingFile::new(path));
+        // Nowhere left to report a failure to
+        let _ = file.write_all(line.as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[test]
+    fn test_events_go_to_their_session_log() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let layer = SessionLogLayer::new(dir.path().to_path_buf());
+        let alpha = layer.log_path("alpha");
+        let beta = layer.log_path("feature/beta");
+        let subscriber = tracing_subscriber::registry().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::info!(session = %"alpha", "Paused");
+            tracing::warn!(session = %"feature/beta", exit = 1, "Agent exited");
+            tracing::info!("Not about a session");
+        });
+
+        let alpha = std::fs::read_to_string(alpha).unwrap();
+        assert_eq!(alpha.lines().count(), 1);
+        assert!(alpha.trim_end().ends_with(" INFO Paused"), "{}", alpha);
+        let beta = std::fs::read_to_string(beta).unwrap();
+        assert!(beta.trim_end().ends_with(" WARN Agent exited exit=1"), "{}", beta);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
@@ -23,12 +23,27 @@ use session::storage::InstanceStorage;
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+    /// Log level or filter, e.g. "debug" or "gana=trace" (overrides RUST_LOG
+    /// and the `log_level` config key)
+    #[arg(long, global = true, value_name = "LEVEL", value_parser = log::parse_level)]
+    log_level: Option<String>,
+    /// Log at debug level; same as --log-level debug
+    #[arg(short, long, global = true, conflicts_with = "log_level")]
+    verbose: bool,
+    /// Only show the sessions of the repo containing PATH (default: the
+    /// current one)
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = ".")]
+    project: Option<std::path::PathBuf>,
 }
 
 #[derive(Subcommand)]
 enum Commands {
     /// Reset all sessions and clean up resources
-    Reset,
+    Reset {
+        /// Only list what would be deleted
+        #[arg(long)]
+        dry_run: bool,
+    },
     /// Show debug information
     Debug,
     /// Start the background daemon
@@ -36,32 +51,193 @@ enum Commands {
         /// Config directory override
         #[arg(long)]
         config_dir: Option<String>,
+        #[command(subcommand)]
+        action: Option<DaemonAction>,
     },
     /// Stop the background daemon
     StopDaemon,
+    /// Print a compact session summary for tmux/starship status bars
+    Statusline,
+    /// Print a table of sessions with their status, branch, diff and
+    /// whether the agent waits for input
+    Status {
+        /// Keep redrawing the table until interrupted
+        #[arg(long)]
+        watch: bool,
+        /// Seconds between redraws with --watch
+        #[arg(long, default_value_t = 2)]
+        interval: u64,
+    },
+    /// Create a new session without launching the TUI
+    New {
+        /// Session title (defaults to the issue title with --from-issue, or
+        /// is derived from the prompt)
+        #[arg(long, required_unless_present_any = ["from_issue", "prompt", "prompt_file"])]
+        title: Option<String>,
+        /// Initial prompt to send once the session is running ("-" reads stdin)
+        #[arg(long, conflicts_with = "prompt_file")]
+        prompt: Option<String>,
+        /// Read the initial prompt from a file
+        #[arg(long, value_name = "PATH")]
+        prompt_file: Option<std::path::PathBuf>,
+        /// Start the session from a GitHub issue number
+        #[arg(long, value_name = "NUMBER")]
+        from_issue: Option<u64>,
+        /// Copy the repo's uncommitted changes into the new worktree
+        #[arg(long)]
+        carry_changes: bool,
+        /// Check out an existing local or remote branch (e.g. origin/pr-branch)
+        #[arg(long, value_name = "BRANCH", conflicts_with = "from_issue")]
+        branch: Option<String>,
+        /// Hold the session until the named session's agent finishes or its
+        /// PR is pushed, then create it (the TUI starts it)
+        #[arg(long, value_name = "TITLE")]
+        after: Option<String>,
+        /// Run the agent in a container with only the worktree mounted
+        /// (see `container_images`)
+        #[arg(long)]
+        container: bool,
+        /// Set the session up like an existing one: its repo, program,
+        /// auto-yes/push, author and container image, on a fresh branch.
+        /// The title defaults to that session's with a number appended
+        #[arg(long, value_name = "SESSION", conflicts_with = "branch")]
+        like: Option<String>,
+    },
+    /// Update gana to the latest release
+    Update {
+        /// Only check whether a newer release exists
+        #[arg(long)]
+        check: bool,
+        /// Release channel to update from, instead of the config's
+        /// `update_channel`
+        #[arg(long, value_enum)]
+        channel: Option<config::UpdateChannel>,
+    },
+    /// Write session metadata to a file for `gana import` on another machine
+    Export {
+        /// File to write, e.g. sessions.json
+        file: std::path::PathBuf,
+        /// Also bundle each session's commits (uncommitted changes are
+        /// committed first) into FILE's `.bundles` directory
+        #[arg(long)]
+        bundle: bool,
+        /// Only export these sessions (repeatable)
+        #[arg(long, value_name = "TITLE")]
+        title: Vec<String>,
+    },
+    /// Add sessions from a `gana export` file to the current repo, paused
+    Import {
+        /// File written by `gana export`
+        file: std::path::PathBuf,
+    },
+    /// Publish your sessions to `team_repo` and list your teammates'
+    Team,
+    /// List sessions archived after being paused for `archive_paused_days`
+    Archived {
+        /// Move an archived session back into the list as paused
+        #[arg(long, value_name = "TITLE")]
+        restore: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum DaemonAction {
+    /// Install a login service (systemd user unit or launchd agent)
+    Install,
+    /// Remove the login service
+    Uninstall,
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
-    log::initialize(true);
     let config_dir = config::get_config_dir()?;
     let config = config::Config::load(&config_dir).unwrap_or_default();
+    let cli_level = if cli.verbose { Some("debug") } else { cli.log_level.as_deref() };
+    let log_level = log::log_level(
+        cli_level,
+        std::env::var("RUST_LOG").ok().as_deref(),
+        config.log_level.as_deref(),
+    );
+    log::initialize(Some(&log::log_dir(&config_dir)), &log_level);
 
-    // Auto-update check (background, never blocks)
-    if let Some(version) = update::auto_update(&config_dir) {
+    // Update check (background, never blocks); `gana update` does its own
+    let checks_updates = !matches!(cli.command, Some(Commands::Update { .. }));
+    let updated = checks_updates
+        .then(|| update::check_on_startup(&config_dir, config.auto_update, config.update_channel))
+        .flatten();
+    // The TUI shows the release notes instead
+    if cli.command.is_some()
+        && let Some(version) = &updated
+    {
         eprintln!("☸ gana updated to v{} — restart to use the new version", version);
     }
 
     match cli.command {
-        Some(Commands::Reset) => {
+        Some(Commands::Reset { dry_run: true }) => {
+            let cmd = cmd::SystemCmdExec;
+            println!("Would delete:");
+            for name in session::tmux::TmuxSession::list_gana_sessions(&cmd) {
+                println!("  tmux session {}", name);
+            }
+            if !config.container_images.is_empty() {
+                let ids = session::container::Container::list_all(&config.container_runtime, &cmd)
+                    .unwrap_or_default();
+                for id in ids {
+                    println!("  container {}", id);
+                }
+            }
+            let config_dir_str = config_dir.to_string_lossy();
+            let storage = session::storage::FileStorage::new(&config_dir);
+            let instances = storage.load_instances().unwrap_or_default();
+            let report = session::git::cleanup_worktrees(
+                &config_dir_str,
+                &config.branch_prefix,
+                &owned_branches(&instances),
+                true,
+                &cmd,
+            )?;
+            for dir in &report.dirs {
+                println!("  worktree {}", dir.display());
+            }
+            for (repo, branch) in &report.branches {
+                println!("  branch {} in {}", branch, repo);
+            }
+            for instance in &instances {
+                println!("  session '{}'", instance.title);
+            }
+            if !report.skipped.is_empty() {
+                println!("Would keep:");
+                for reason in &report.skipped {
+                    println!("  {}", reason);
+                }
+            }
+            Ok(())
+        }
+        Some(Commands::Reset { dry_run: false }) => {
             println!("Resetting all sessions...");
             let cmd = cmd::SystemCmdExec;
             let _ = session::tmux::TmuxSession::cleanup_sessions(&cmd);
+            if !config.container_images.is_empty()
+                && let Err(e) =
+                    session::container::Container::cleanup_all(&config.container_runtime, &cmd)
+            {
+                eprintln!("Warning: failed to remove session containers: {}", e);
+            }
             let config_dir_str = config_dir.to_string_lossy();
-            session::git::cleanup_worktrees(&config_dir_str, &cmd)?;
-            // Delete stored instances
             let storage = session::storage::FileStorage::new(&config_dir);
+            let instances = storage.load_instances().unwrap_or_default();
+            let report = session::git::cleanup_worktrees(
+                &config_dir_str,
+                &config.branch_prefix,
+                &owned_branches(&instances),
+                false,
+                &cmd,
+            )?;
+            for reason in &report.skipped {
+                eprintln!("Kept {}", reason);
+            }
+            // Delete stored instances
             storage.save_instances(&[])?;
             println!("All sessions reset.");
             Ok(())
@@ -69,6 +245,9 @@ async fn main() -> anyhow::Result<()> {
         Some(Commands::Debug) => {
             println!("Debug information:");
             println!("  Config directory: {}", config_dir.display());
+            println!("  Log file: {}", log::log_file(&log::log_dir(&config_dir)).display());
+            println!("  Session logs: {}", log::log_dir(&config_dir).display());
+            println!("  Log level: {}", log_level);
             println!("  Default program: {}", config.default_program);
             println!("  Auto-yes: {}", config.auto_yes);
             println!("  Poll interval: {}ms", config.daemon_poll_interval);
@@ -79,16 +258,484 @@ async fn main() -> anyhow::Result<()> {
             );
             Ok(())
         }
-        Some(Commands::Daemon { config_dir: dir_override }) => {
+        Some(Commands::Daemon { config_dir: dir_override, action }) => {
             let dir = dir_override
                 .map(std::path::PathBuf::from)
                 .unwrap_or(config_dir);
-            daemon::run_daemon(&dir, &config)
+            match action {
+                Some(DaemonAction::Install) => daemon::service::install(&dir, &cmd::SystemCmdExec),
+                Some(DaemonAction::Uninstall) => daemon::service::uninstall(&cmd::SystemCmdExec),
+                None => daemon::run_daemon(&dir, &config),
+            }
         }
         Some(Commands::StopDaemon) => daemon::stop_daemon(&config_dir),
+        Some(Commands::Statusline) => {
+            let storage = session::storage::FileStorage::new(&config_dir);
+            let instances = storage.load_instances().unwrap_or_default();
+            let summary = session::summary::StatusSummary::from_instances(&instances, |inst| {
+                session::tmux::TmuxSession::new(
+                    &inst.title,
+                    &inst.program,
+                    Box::new(cmd::SystemCmdExec),
+                    Box::new(session::tmux::pty::SystemPtyFactory),
+                )
+                .with_prompts(config.prompt_patterns(&inst.program))
+                .needs_attention()
+                .unwrap_or(false)
+            });
+            println!("{}", summary);
+            Ok(())
+        }
+        Some(Commands::Status { watch, interval }) => {
+            status_sessions(&config, &config_dir, watch, interval)
+        }
+        Some(Commands::New {
+            title, prompt, prompt_file, from_issue, carry_changes, branch, after, container, like,
+        }) => {
+            let prompt = read_prompt(prompt, prompt_file)?;
+            let request = NewSession {
+                title, prompt, from_issue, carry_changes, branch, after, container, like,
+            };
+            new_session(&config, &config_dir, request)
+        }
+        Some(Commands::Export { file, bundle, title }) => {
+            export_sessions(&config, &config_dir, &file, bundle, &title)
+        }
+        Some(Commands::Import { file }) => import_sessions(&config_dir, &file),
+        Some(Commands::Team) => team_sessions(&config, &config_dir),
+        Some(Commands::Archived { restore }) => archived_sessions(&config_dir, restore),
+        Some(Commands::Update { check, channel }) => {
+            update::update_now(&config_dir, check, channel.unwrap_or(config.update_channel))
+        }
         None => {
             // Launch TUI
-            app::run(config, config_dir)
+            let project = cli.project.as_deref().map(project_root).transpose()?;
+            app::run(config, config_dir, project, updated)
+        }
+    }
+}
+
+/// `(repo, branch)` of every session branch gana created, the only ones
+/// `reset` may delete.
+fn owned_branches(instances: &[session::Instance]) -> Vec<(String, String)> {
+    instances
+        .iter()
+        .filter(|i| i.owns_branch)
+        .filter_map(|i| i.git_worktree.as_ref())
+        .map(|wt| (wt.repo_path().to_string(), wt.branch().to_string()))
+        .collect()
+}
+
+/// Print the session table once, or redraw it every `interval` seconds
+/// with `watch`, e.g. in a spare tmux pane.
+fn status_sessions(
+    config: &config::Config,
+    config_dir: &std::path::Path,
+    watch: bool,
+    interval: u64,
+) -> anyhow::Result<()> {
+    use std::io::Write;
+
+    let cmd = cmd::SystemCmdExec;
+    let storage = session::storage::FileStorage::new(config_dir);
+    if !watch {
+        let rows = status_rows(config, &storage.load_instances()?, &cmd)?;
+        if rows.is_empty() {
+            println!("No sessions.");
+        } else {
+            print!("{}", session::summary::status_table(&rows));
         }
+        return Ok(());
     }
+
+    let interval = std::time::Duration::from_secs(interval.max(1));
+    let mut stdout = std::io::stdout();
+    loop {
+        // The TUI saves as sessions change, so reload every round
+        let instances = storage.load_instances().unwrap_or_default();
+        let rows = status_rows(config, &instances, &cmd);
+        crossterm::execute!(
+            stdout,
+            crossterm::terminal::Clear(crossterm::terminal::ClearType::All),
+            crossterm::cursor::MoveTo(0, 0)
+        )?;
+        println!(
+            "gana status · {} · every {}s, Ctrl-C to stop\n",
+            chrono::Local::now().format("%H:%M:%S"),
+            interval.as_secs()
+        );
+        match rows {
+            Ok(rows) if rows.is_empty() => println!("No sessions."),
+            Ok(rows) => print!("{}", session::summary::status_table(&rows)),
+            Err(e) => println!("tmux didn't answer, retrying: {}", e),
+        }
+        stdout.flush()?;
+        std::thread::sleep(interval);
+    }
+}
+
+/// Table rows for `instances`: liveness from one tmux call, a fresh diff
+/// for sessions whose worktree exists, and attention for live agents.
+fn status_rows(
+    config: &config::Config,
+    instances: &[session::Instance],
+    cmd: &dyn cmd::CmdExec,
+) -> Result<Vec<session::summary::StatusRow>, cmd::CmdError> {
+    let activity = session::tmux::TmuxSession::pane_activity(cmd)?;
+    Ok(instances
+        .iter()
+        .map(|inst| {
+            let alive = inst.status.is_running()
+                && activity.contains_key(&session::tmux::sanitize_name(&inst.title));
+            let diff = inst
+                .git_worktree
+                .as_ref()
+                .filter(|wt| std::path::Path::new(wt.worktree_path()).is_dir())
+                .map(|wt| wt.diff(cmd));
+            let attention = alive
+                && session::tmux::TmuxSession::new(
+                    &inst.title,
+                    &inst.program,
+                    Box::new(cmd::SystemCmdExec),
+                    Box::new(session::tmux::pty::SystemPtyFactory),
+                )
+                .with_prompts(config.prompt_patterns(&inst.program))
+                .needs_attention()
+                .unwrap_or(false);
+            session::summary::StatusRow::new(inst, diff.as_ref(), attention)
+        })
+        .collect())
+}
+
+/// Publish your sessions to the team repo and list your teammates'.
+fn team_sessions(config: &config::Config, config_dir: &std::path::Path) -> anyhow::Result<()> {
+    let cmd = cmd::SystemCmdExec;
+    let store = session::team::TeamStore::from_config(config, config_dir, &cmd)?;
+    let instances = session::storage::FileStorage::new(config_dir).load_instances()?;
+    let members = store.sync_now(&instances, &cmd)?;
+    println!("Published your sessions as {}", store.member());
+    if members.is_empty() {
+        println!("No teammates have published sessions yet.");
+    }
+    for member in &members {
+        println!();
+        println!("{}:", member.member);
+        for s in &member.sessions {
+            println!("  {}  {}  {}", s.title, s.branch, s.status);
+        }
+    }
+    Ok(())
+}
+
+/// List archived sessions, or restore one into the session list.
+fn archived_sessions(config_dir: &std::path::Path, restore: Option<String>) -> anyhow::Result<()> {
+    let storage = session::storage::FileStorage::new(config_dir);
+    let mut archived = storage.load_archive()?;
+
+    let Some(title) = restore else {
+        if archived.is_empty() {
+            println!("No archived sessions.");
+        }
+        for instance in &archived {
+            println!(
+                "{}  {}  paused since {}",
+                instance.title,
+                instance.branch,
+                instance.updated_at.format("%Y-%m-%d")
+            );
+        }
+        return Ok(());
+    };
+
+    let Some(pos) = archived.iter().position(|i| i.title == title) else {
+        anyhow::bail!("no archived session named '{}'", title);
+    };
+    let instances = storage.load_instances().unwrap_or_default();
+    if let Some(existing) = session::title::clashing(&title, &instances) {
+        anyhow::bail!("'{}' would clash with the session '{}'; delete that first", title, existing.title);
+    }
+    let mut instance = archived.remove(pos);
+    instance.status = session::InstanceStatus::Paused;
+    // Restart the archive clock so it isn't archived again straight away
+    instance.updated_at = chrono::Utc::now();
+    println!("Restored '{}' as paused (branch {}); resume it with 'p'", instance.title, instance.branch);
+    storage.update_instances(|stored| stored.push(instance))?;
+    storage.save_archive(&archived)?;
+    Ok(())
+}
+
+fn export_sessions(
+    config: &config::Config,
+    config_dir: &std::path::Path,
+    file: &std::path::Path,
+    bundle: bool,
+    titles: &[String],
+) -> anyhow::Result<()> {
+    let storage = session::storage::FileStorage::new(config_dir);
+    let mut instances = storage.load_instances()?;
+    if !titles.is_empty() {
+        if let Some(missing) = titles.iter().find(|t| !instances.iter().any(|i| &i.title == *t)) {
+            anyhow::bail!("no session named '{}'", missing);
+        }
+        instances.retain(|i| titles.contains(&i.title));
+    }
+    let opts = session::git::worktree_git::CommitOptions::from_config(config);
+    let export = session::transfer::export(&instances, file, bundle, &opts, &cmd::SystemCmdExec)?;
+    for entry in &export.sessions {
+        match entry.bundle {
+            Some(ref bundle) => println!("Exported '{}' (commits in {})", entry.session.title, bundle),
+            None => println!("Exported '{}'", entry.session.title),
+        }
+    }
+    println!("Wrote {} session(s) to {}", export.sessions.len(), file.display());
+    Ok(())
+}
+
+fn import_sessions(config_dir: &std::path::Path, file: &std::path::Path) -> anyhow::Result<()> {
+    let export = session::transfer::read_export(file)?;
+    let cmd = cmd::SystemCmdExec;
+    let cwd = std::env::current_dir()?.to_string_lossy().to_string();
+    let repo_root = session::git::util::find_git_repo_root(&cmd, &cwd)
+        .map_err(|_| anyhow::anyhow!("run gana import inside the repo the sessions belong to"))?;
+
+    let storage = session::storage::FileStorage::new(config_dir);
+    let mut instances = storage.load_instances()?;
+    let loaded = instances.len();
+    let report = session::transfer::import(export, file, &repo_root, config_dir, &mut instances, &cmd);
+    for (title, reason) in &report.skipped {
+        eprintln!("Skipped '{}': {}", title, reason);
+    }
+    for title in &report.imported {
+        println!("Imported '{}' as paused; resume it with 'p'", title);
+    }
+    for (title, branch) in &report.renamed {
+        println!(
+            "'{}' is on the new branch {}: the branch it had here points at other work",
+            title, branch
+        );
+    }
+    // Only add the imported sessions, in case the list changed meanwhile
+    let imported = instances.split_off(loaded);
+    storage.update_instances(|stored| stored.extend(imported))?;
+    Ok(())
+}
+
+/// Resolve the `--prompt` / `--prompt-file` arguments into prompt text.
+fn read_prompt(
+    prompt: Option<String>,
+    prompt_file: Option<std::path::PathBuf>,
+) -> anyhow::Result<Option<String>> {
+    let text = match (prompt, prompt_file) {
+        (_, Some(path)) => std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {}", path.display(), e))?,
+        (Some(p), None) if p == "-" => std::io::read_to_string(std::io::stdin())?,
+        (Some(p), None) => p,
+        (None, None) => return Ok(None),
+    };
+    Ok(Some(text.trim_end().to_string()))
+}
+
+/// Title for a session created without one: from `title_command` when
+/// configured, otherwise the first words of the prompt.
+fn untitled_session_title(config: &config::Config, prompt: &str) -> Option<String> {
+    let generated = config.title_command.as_deref().and_then(|command| {
+        session::title::agent_title(command, prompt, &cmd::SystemCmdExec)
+            .map_err(|e| eprintln!("Warning: title_command failed: {}", e))
+            .ok()
+    });
+    generated.or_else(|| session::title::title_from_prompt(prompt))
+}
+
+/// Arguments of `gana new`, with the prompt already read.
+struct NewSession {
+    title: Option<String>,
+    prompt: Option<String>,
+    from_issue: Option<u64>,
+    carry_changes: bool,
+    branch: Option<String>,
+    after: Option<String>,
+    container: bool,
+    like: Option<String>,
+}
+
+/// Create a session headlessly and persist it so the TUI picks it up.
+fn new_session(
+    config: &config::Config,
+    config_dir: &std::path::Path,
+    request: NewSession,
+) -> anyhow::Result<()> {
+    use session::git::issue::Issue;
+
+    let NewSession { title, prompt, from_issue, carry_changes, branch, after, container, like } =
+        request;
+
+    let cmd = cmd::SystemCmdExec;
+    let storage = session::storage::FileStorage::new(config_dir);
+    let instances = storage.load_instances().unwrap_or_default();
+    let source = like
+        .map(|like| {
+            instances
+                .iter()
+                .find(|i| i.title == like)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("no session named '{}' to copy", like))
+        })
+        .transpose()?;
+
+    let issue = match from_issue {
+        Some(number) => {
+            if let Some(hint) = session::git::util::gh_status(&cmd).hint() {
+                anyhow::bail!("--from-issue needs gh: {}", hint);
+            }
+            Some(Issue::fetch(number, &cmd)?)
+        }
+        None => None,
+    };
+
+    let title = match (title, &issue, &source) {
+        (Some(title), _, _) => title,
+        (None, Some(issue), _) => issue.session_title(),
+        (None, None, Some(source)) => session::title::unique_title(&source.title, &instances),
+        (None, None, None) => prompt
+            .as_deref()
+            .and_then(|prompt| untitled_session_title(config, prompt))
+            .ok_or_else(|| anyhow::anyhow!("a session title is required"))?,
+    };
+    if title.trim().is_empty() {
+        anyhow::bail!("a session title is required");
+    }
+    let title = if config.branch_unique_suffix {
+        session::title::with_time_suffix(&title, chrono::Local::now())
+    } else {
+        title
+    };
+
+    // Issue body comes first; an explicit --prompt is appended as extra guidance
+    let prompt = match (&issue, prompt) {
+        (Some(issue), Some(extra)) => {
+            format!("{}\n\n{}", issue.render_prompt(&config.issue_prompt_template), extra)
+        }
+        (Some(issue), None) => issue.render_prompt(&config.issue_prompt_template),
+        (None, prompt) => prompt.unwrap_or_default(),
+    };
+
+    if let Some(existing) = session::title::clashing(&title, &instances) {
+        anyhow::bail!(
+            "'{}' would share a tmux session or branch with the session '{}'; pick another title",
+            title,
+            existing.title
+        );
+    }
+
+    let cwd = match source {
+        Some(ref source) => source.project().to_string(),
+        None => std::env::current_dir()?.to_string_lossy().to_string(),
+    };
+    let carry_changes = carry_changes || source.as_ref().is_some_and(|s| s.carry_changes);
+    // Fail now rather than after fetching and creating the worktree
+    if branch.is_none() {
+        let check = session::title::TitleCheck {
+            branch_prefix: config.branch_prefix.clone(),
+            taken: Vec::new(),
+            branches: session::git::util::local_branches(&cwd, &cmd).unwrap_or_default(),
+            checked_out: session::git::util::checked_out_branches(&cwd, &cmd).unwrap_or_default(),
+            ..Default::default()
+        };
+        if let Err(e) = check.check(&title) {
+            anyhow::bail!("{}", e);
+        }
+    }
+    if !carry_changes && session::git::util::is_repo_dirty(&cwd, &cmd).unwrap_or(false) {
+        eprintln!(
+            "Note: the repo has uncommitted changes that won't be in the new worktree \
+             (pass --carry-changes to include them)"
+        );
+    }
+
+    let mut instance = match source {
+        Some(ref source) => source.sibling(title.clone()),
+        None => session::Instance::new(session::InstanceOptions {
+            title: title.clone(),
+            path: cwd,
+            program: config.default_program.clone(),
+            auto_yes: config.auto_yes,
+            git_identity: config.git_identity.clone(),
+            shared_dirs: config.shared_dirs.clone(),
+            shared_dirs_mode: config.shared_dirs_mode,
+        }),
+    };
+    instance.carry_changes = carry_changes;
+    instance.existing_branch = branch;
+    if instance.container.is_none() && (container || config.container_sessions) {
+        instance.container =
+            Some(session::container::Container::for_session(&title, &instance.program, config)?);
+    }
+
+    if let Some(upstream) = after {
+        if !instances.iter().any(|i| i.title == upstream) {
+            anyhow::bail!("no session named '{}' to run after", upstream);
+        }
+        if issue.is_some() {
+            eprintln!("Note: the branch is created later, so it won't be linked to the issue");
+        }
+        println!(
+            "Session '{}' will be created once '{}' finishes (while the gana TUI is open)",
+            instance.title, upstream
+        );
+        instance.status = session::InstanceStatus::Pending;
+        instance.after = Some(upstream);
+        instance.pending_prompt = (!prompt.is_empty()).then_some(prompt);
+        storage.update_instances(|stored| stored.push(instance))?;
+        return Ok(());
+    }
+
+    let factory = session::factory::SessionFactory::new(&instance, config.clone());
+    let exec: std::sync::Arc<dyn cmd::CmdExec> = std::sync::Arc::new(cmd::SystemCmdExec);
+    let created = factory.create(&exec, &mut |step| println!("  {}…", step.label()))?;
+    if let Some(ref e) = created.carry_error {
+        eprintln!(
+            "Warning: your uncommitted changes couldn't be carried into the session, \
+             which starts without them: {}",
+            e
+        );
+    }
+    instance.attach_created(created);
+    // As in the TUI, the prompt waits until the agent got past its trust
+    // prompt, or it would land in the dialog
+    if let Some(trust) = instance.trust_prompt() {
+        println!("  waiting for the agent…");
+        if let Err(e) = trust.answer(&cmd) {
+            eprintln!("Warning: failed to answer the trust prompt: {}", e);
+        }
+    }
+
+    if let Some(ref issue) = issue
+        && let Err(e) = issue.link_branch(&instance.branch, &cmd)
+    {
+        eprintln!("Warning: failed to link branch to issue #{}: {}", issue.number, e);
+    }
+
+    let journal = session::journal::Journal::new(config_dir);
+    journal.record(&instance.title, session::journal::EventKind::Created, None);
+    instance.pending_prompt = (!prompt.is_empty()).then_some(prompt);
+    if let Some(prompt) = instance.deliver_pending_prompt() {
+        journal.record(&instance.title, session::journal::EventKind::PromptSent, Some(&prompt));
+    } else if instance.pending_prompt.is_some() {
+        // Kept queued; the TUI sends it once it restores the session
+        eprintln!("Warning: the prompt couldn't be sent to the agent yet; gana will retry");
+    }
+
+    println!("Created session '{}' on branch {}", instance.title, instance.branch);
+    // The list may have changed while the session was set up, e.g. by the TUI
+    storage.update_instances(|stored| stored.push(instance))?;
+    Ok(())
+}
+
+/// Root of the git repo containing `path`, or `path` itself outside one.
+fn project_root(path: &std::path::Path) -> anyhow::Result<std::path::PathBuf> {
+    let path = path
+        .canonicalize()
+        .map_err(|e| anyhow::anyhow!("{}: {}", path.display(), e))?;
+    let root = session::git::util::find_git_repo_root(&cmd::SystemCmdExec, &path.to_string_lossy());
+    Ok(root.map(std::path::PathBuf::from).unwrap_or(path))
 }
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use crate::cmd::{CmdExec, SystemCmdExec, args};
+use crate::config::AttentionAlert;
+use crate::session::tmux::{TmuxSession, sanitize_name};
+
+/// How often the other sessions are checked while attached.
+const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long the tmux message stays up, in milliseconds.
+const MESSAGE_MS: &str = "4000";
+
+/// Watches the other running sessions while one is attached, and alerts
+/// when one of them starts waiting for input. Sessions already waiting
+/// when the watch starts don't alert.
+pub struct AttentionWatcher {
+    stop: Arc<AtomicBool>,
+}
+
+impl AttentionWatcher {
+    /// Watch the `others` sessions while `attached` is in the foreground.
+    pub fn start(attached: &str, others: Vec<TmuxSession>, alert: AttentionAlert) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        if alert != AttentionAlert::Off && !others.is_empty() {
+            let stop = Arc::clone(&stop);
+            let attached = attached.to_string();
+            std::thread::spawn(move || {
+                let mut waiting: Option<HashSet<String>> = None;
+                while !stop.load(Ordering::Relaxed) {
+                    let now = waiting_sessions(&others);
+                    if let Some(ref before) = waiting {
+                        for title in newly_waiting(before, &now) {
+                            if stop.load(Ordering::Relaxed) {
+                                return;
+                            }
+                            alert_attached(&attached, &title, alert, &SystemCmdExec, &mut std::io::stdout());
+                        }
+                    }
+                    waiting = Some(now);
+                    std::thread::sleep(CHECK_INTERVAL);
+                }
+            });
+        }
+        Self { stop }
+    }
+
+    /// Stop watching; call once detached.
+    pub fn stop(self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+fn waiting_sessions(sessions: &[TmuxSession]) -> HashSet<String> {
+    sessions
+        .iter()
+        .filter(|tmux| tmux.needs_attention().unwrap_or(false))
+        .map(|tmux| tmux.session_name().to_string())
+        .collect()
+}
+
+/// Sessions in `now` that weren't waiting `before`, sorted.
+fn newly_waiting(before: &HashSet<String>, now: &HashSet<String>) -> Vec<String> {
+    let mut titles: Vec<String> = now.difference(before).cloned().collect();
+    titles.sort();
+    titles
+}
+
+/// Tell the user attached to `attached` that `waiting` needs them: a bell
+/// on `out` (the terminal) and/or a message on the session's tmux clients.
+fn alert_attached(
+    attached: &str,
+    waiting: &str,
+    alert: AttentionAlert,
+    cmd: &dyn CmdExec,
+    out: &mut dyn Write,
+) {
+    if matches!(alert, AttentionAlert::Bell | AttentionAlert::Both) {
+        let _ = out.write_all(b"\x07");
+        let _ = out.flush();
+    }
+    if matches!(alert, AttentionAlert::Message | AttentionAlert::Both) {
+        let message = format!("gana: '{}' is waiting for input (Ctrl+Q to switch)", waiting);
+        let target = sanitize_name(attached);
+        let clients = cmd
+            .output("tmux", &args(&["list-clients", "-t", &target, "-F", "#{client_name}"]))
+            .unwrap_or_default();
+        for client in clients.lines().filter(|c| !c.is_empty()) {
+            let _ = cmd.run(
+                "tmux",
+                &args(&["display-message", "-c", client, "-d", MESSAGE_MS, &message]),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::MockCmdExec;
+
+    #[test]
+    fn test_only_newly_waiting_sessions_alert() {
+        let set = |titles: &[&str]| titles.iter().map(|t| t.to_string()).collect::<HashSet<_>>();
+        assert_eq!(newly_waiting(&set(&["a"]), &set(&["a", "c", "b"])), ["b", "c"]);
+        assert!(newly_waiting(&set(&["a", "b"]), &set(&["a"])).is_empty());
+    }
+
+    #[test]
+    fn test_alert_rings_and_messages_attached_clients() {
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|name, a| name == "tmux" && a[0] == "list-clients" && a[2] == "gana_api")
+            .returning(|_, _| Ok("/dev/pts/3\n".to_string()));
+        mock.expect_run()
+            .withf(|_, a| {
+                a[0] == "display-message"
+                    && a[2] == "/dev/pts/3"
+                    && a[5] == "gana: 'docs' is waiting for input (Ctrl+Q to switch)"
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let mut out = Vec::new();
+        alert_attached("api", "docs", AttentionAlert::Both, &mock, &mut out);
+        assert_eq!(out, b"\x07");
+
+        let mut out = Vec::new();
+        alert_attached("api", "docs", AttentionAlert::Bell, &MockCmdExec::new(), &mut out);
+        assert_eq!(out, b"\x07");
+    }
+}
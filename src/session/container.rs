@@ -0,0 +1,284 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cmd::{CmdExec, args, quote};
+use crate::config::{Config, per_program, program_name};
+use crate::session::git::GitWorktree;
+
+/// Label put on every container gana starts, holding its name.
+const SESSION_LABEL: &str = "gana.session";
+
+#[derive(Debug, Error)]
+pub enum ContainerError {
+    #[error("no container image for '{0}': add one to container_images")]
+    NoImage(String),
+    #[error(transparent)]
+    Cmd(#[from] crate::cmd::CmdError),
+}
+
+/// A container a session's agent runs in. Only the worktree and the repo's
+/// git directory are mounted, so the agent can't reach the rest of the
+/// filesystem. The git directory is read-only but for the worktree's own
+/// part of it (index, HEAD, logs), and the files telling git where that
+/// is and what config to read stay read-only too: host-side git would
+/// otherwise pick up hooks or config the agent planted.
+///
+/// The container idles once started; the agent runs in it through
+/// `{runtime} exec -it` in the session's tmux pane, so preview, attach and
+/// prompts work as for any other session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Container {
+    /// "docker" or "podman".
+    pub runtime: String,
+    pub image: String,
+    /// Container name; the session's tmux name.
+    pub name: String,
+}
+
+impl Container {
+    /// The container for a session running `program`, with the image
+    /// configured for it in `container_images`: by full command line, else
+    /// by program name.
+    pub fn for_session(title: &str, program: &str, config: &Config) -> Result<Self, ContainerError> {
+        let image = per_program(&config.container_images, program)
+            .ok_or_else(|| ContainerError::NoImage(program_name(program).to_string()))?;
+        Ok(Self {
+            runtime: config.container_runtime.clone(),
+            image: image.to_string(),
+            name: crate::session::tmux::sanitize_name(title),
+        })
+    }
+
+    /// Start the container with `worktree`, `git_dir` and the worktree's
+    /// `admin_dir` in it mounted at the same paths; see [`mounts`]. A
+    /// leftover container of the same name is replaced. `extra_args` go to
+    /// `run` before the image, e.g. `-e API_KEY`.
+    pub fn start(
+        &self,
+        worktree: &str,
+        git_dir: &str,
+        admin_dir: &str,
+        extra_args: &[String],
+        cmd: &dyn CmdExec,
+    ) -> Result<(), ContainerError> {
+        let _ = self.remove(cmd);
+        let mut run_args = args(&["run", "-d", "--rm", "--init", "--name", &self.name]);
+        run_args.push("--label".to_string());
+        run_args.push(format!("{}={}", SESSION_LABEL, self.name));
+        // Rootless podman already maps root to the user; docker writes files
+        // as root unless told to use the worktree owner's ids
+        if self.runtime == "docker"
+            && let Ok(meta) = std::fs::metadata(worktree)
+        {
+            use std::os::unix::fs::MetadataExt;
+            run_args.push("--user".to_string());
+            run_args.push(format!("{}:{}", meta.uid(), meta.gid()));
+        }
+        for mount in mounts(worktree, git_dir, admin_dir) {
+            run_args.push("-v".to_string());
+            run_args.push(mount);
+        }
+        run_args.extend(args(&["-w", worktree]));
+        run_args.extend(extra_args.iter().cloned());
+        // Keep the container alive whatever the image's entrypoint does
+        run_args.extend(args(&["--entrypoint", "sleep", &self.image, "infinity"]));
+        cmd.run(&self.runtime, &run_args)?;
+        Ok(())
+    }
+
+    /// Start the container for a session's worktree and return the pane
+    /// command that runs `program` in it.
+    pub fn launch(
+        &self,
+        worktree: &GitWorktree,
+        program: &str,
+        extra_args: &[String],
+        cmd: &dyn CmdExec,
+    ) -> Result<String, ContainerError> {
+        let git_dir = git_dir(worktree);
+        let admin_dir = worktree.admin_dir();
+        self.start(worktree.worktree_path(), &git_dir, &admin_dir, extra_args, cmd)?;
+        Ok(self.exec_command(worktree.worktree_path(), program))
+    }
+
+    /// Command line that runs `program` in the container, in `workdir`, on
+    /// a fresh PTY. Used as the tmux pane's command.
+    pub fn exec_command(&self, workdir: &str, program: &str) -> String {
+        format!(
+            "{} exec -it -w {} {} {}",
+            self.runtime,
+            quote(workdir),
+            self.name,
+            program
+        )
+    }
+
+    /// Stop and remove the container.
+    pub fn remove(&self, cmd: &dyn CmdExec) -> Result<(), ContainerError> {
+        cmd.run(&self.runtime, &args(&["rm", "-f", &self.name]))?;
+        Ok(())
+    }
+
+    /// IDs of every container gana started with `runtime`.
+    pub fn list_all(runtime: &str, cmd: &dyn CmdExec) -> Result<Vec<String>, ContainerError> {
+        let ids = cmd.output(
+            runtime,
+            &args(&["ps", "-aq", "--filter", &format!("label={}", SESSION_LABEL)]),
+        )?;
+        Ok(ids.split_whitespace().map(str::to_string).collect())
+    }
+
+    /// Remove every container gana started with `runtime`. Returns how
+    /// many were removed.
+    pub fn cleanup_all(runtime: &str, cmd: &dyn CmdExec) -> Result<usize, ContainerError> {
+        let ids = Self::list_all(runtime, cmd)?;
+        if !ids.is_empty() {
+            let mut rm_args = args(&["rm", "-f"]);
+            rm_args.extend(ids.iter().cloned());
+            cmd.run(runtime, &rm_args)?;
+        }
+        Ok(ids.len())
+    }
+}
+
+/// Bind mounts (`-v` values) for a session: the worktree and the worktree's
+/// `admin_dir` read-write, the rest of `git_dir` read-only, and the files
+/// locating the worktree's git data and config pinned read-only on top.
+///
+/// An empty `config.worktree` is created first, or the agent could create
+/// one for git to read.
+pub fn mounts(worktree: &str, git_dir: &str, admin_dir: &str) -> Vec<String> {
+    let config_worktree = Path::new(admin_dir).join("config.worktree");
+    if Path::new(admin_dir).is_dir() && !config_worktree.exists() {
+        let _ = std::fs::write(&config_worktree, "");
+    }
+    let mut mounts = vec![
+        format!("{}:{}", worktree, worktree),
+        format!("{}:{}:ro", git_dir, git_dir),
+        format!("{}:{}", admin_dir, admin_dir),
+    ];
+    let pinned = [
+        Path::new(worktree).join(".git"),
+        Path::new(admin_dir).join("gitdir"),
+        Path::new(admin_dir).join("commondir"),
+        config_worktree,
+    ];
+    // A missing source would be created as a directory
+    for file in pinned.iter().filter(|f| f.is_file()) {
+        let file = file.to_string_lossy();
+        mounts.push(format!("{}:{}:ro", file, file));
+    }
+    mounts
+}
+
+/// The repo's git directory, which a worktree's `.git` file points into.
+pub fn git_dir(worktree: &GitWorktree) -> String {
+    Path::new(worktree.repo_path()).join(".git").to_string_lossy().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::MockCmdExec;
+
+    fn config() -> Config {
+        let mut config = Config::default();
+        config
+            .container_images
+            .insert("claude".to_string(), "ghcr.io/me/claude:latest".to_string());
+        config
+    }
+
+    #[test]
+    fn test_image_by_program_name() {
+        let container =
+            Container::for_session("fix bug", "claude --dangerously-skip-permissions", &config())
+                .unwrap();
+        assert_eq!(container.image, "ghcr.io/me/claude:latest");
+        assert_eq!(container.runtime, "docker");
+        assert_eq!(container.name, "gana_fix_bug");
+
+        assert!(matches!(
+            Container::for_session("x", "aider", &config()),
+            Err(ContainerError::NoImage(ref name)) if name == "aider"
+        ));
+    }
+
+    #[test]
+    fn test_start_mounts_only_the_worktree_and_git_dir() {
+        let container = Container::for_session("s", "claude", &config()).unwrap();
+        let mut mock = MockCmdExec::new();
+        mock.expect_run()
+            .withf(|_, a| a.first().is_some_and(|a| a == "rm"))
+            .returning(|_, _| Ok(()));
+        mock.expect_run()
+            .withf(|name, a| {
+                let joined = a.join(" ");
+                name == "docker"
+                    && a.first().is_some_and(|a| a == "run")
+                    && joined.contains(
+                        "-v /wt/s:/wt/s -v /repo/.git:/repo/.git:ro \
+                         -v /repo/.git/worktrees/s:/repo/.git/worktrees/s -w /wt/s",
+                    )
+                    && joined.contains("-e API_KEY --entrypoint sleep ghcr.io/me/claude:latest infinity")
+                    && joined.matches("-v ").count() == 3
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        container
+            .start(
+                "/wt/s",
+                "/repo/.git",
+                "/repo/.git/worktrees/s",
+                &args(&["-e", "API_KEY"]),
+                &mock,
+            )
+            .unwrap();
+        assert_eq!(
+            container.exec_command("/wt/s", "claude --resume"),
+            "docker exec -it -w '/wt/s' gana_s claude --resume"
+        );
+    }
+
+    #[test]
+    fn test_mounts_pin_git_files_read_only() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let repo_path = repo.path().to_string_lossy().to_string();
+        let wt_path = repo.path().join("wt").to_string_lossy().to_string();
+        let git = |a: &[&str]| {
+            let out = std::process::Command::new("git").args(a).output().unwrap();
+            assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+        };
+        git(&["-C", &repo_path, "init", "-q"]);
+        git(&[
+            "-C", &repo_path, "-c", "user.name=T", "-c", "user.email=t@t", "commit", "-q",
+            "--allow-empty", "-m", "init",
+        ]);
+        git(&["-C", &repo_path, "worktree", "add", "-q", "-b", "s", &wt_path]);
+        let worktree = GitWorktree::from_storage(
+            repo_path.clone(),
+            wt_path.clone(),
+            "s".to_string(),
+            "s".to_string(),
+            String::new(),
+        );
+        let admin = worktree.admin_dir();
+
+        let mounts = mounts(&wt_path, &git_dir(&worktree), &admin);
+        assert_eq!(mounts[0], format!("{}:{}", wt_path, wt_path));
+        assert!(mounts[1].ends_with("/.git:ro"));
+        assert_eq!(mounts[2], format!("{}:{}", admin, admin));
+        let pinned = [
+            format!("{}/.git", wt_path),
+            format!("{}/commondir", admin),
+            format!("{}/gitdir", admin),
+            format!("{}/config.worktree", admin),
+        ];
+        for file in pinned {
+            assert!(mounts.contains(&format!("{}:{}:ro", file, file)), "{} in {:?}", file, mounts);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::collections::{HashMap, HashSet};
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+
+/// Disk usage of session worktrees, as measured by a background scan.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiskUsage {
+    /// Bytes used per session title. Sessions without a worktree on disk are absent.
+    pub per_session: HashMap<String, u64>,
+    /// Bytes used by the whole worktrees directory, including orphaned worktrees.
+    pub total: u64,
+}
+
+impl DiskUsage {
+    /// Measure each session's worktree and the worktrees directory under `config_dir`.
+    pub fn scan<'a>(
+        worktrees: impl IntoIterator<Item = (&'a str, &'a str)>,
+        config_dir: &Path,
+    ) -> Self {
+        let per_session = worktrees
+            .into_iter()
+            .filter(|(_, dir)| Path::new(dir).exists())
+            .map(|(title, dir)| (title.to_string(), dir_size(Path::new(dir))))
+            .collect();
+        Self {
+            per_session,
+            total: dir_size(&config_dir.join("worktrees")),
+        }
+    }
+}
+
+/// Bytes allocated on disk for `path` and everything below it.
+///
+/// Symlinks are not followed, so directories shared via `shared_dirs` only
+/// count once, in the main checkout. Hardlinked files count once however
+/// many links to them are below `path`.
+pub fn dir_size(path: &Path) -> u64 {
+    size_of(path, &mut HashSet::new())
+}
+
+/// `dir_size`, skipping files whose (device, inode) is in `seen`.
+fn size_of(path: &Path, seen: &mut HashSet<(u64, u64)>) -> u64 {
+    let Ok(meta) = path.symlink_metadata() else {
+        return 0;
+    };
+    if !meta.is_dir() && meta.nlink() > 1 && !seen.insert((meta.dev(), meta.ino())) {
+        return 0;
+    }
+    let own = meta.blocks() * 512;
+    if !meta.is_dir() {
+        return own;
+    }
+    let children = std::fs::read_dir(path)
+        .map(|entries| entries.flatten().map(|e| size_of(&e.path(), seen)).sum())
+        .unwrap_or(0);
+    own + children
+}
+
+/// Human-readable size, e.g. `512 B`, `1.5 MB`, `2.0 GB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn test_scan_skips_symlinks_and_missing_worktrees() {
+        let config_dir = TempDir::new().unwrap();
+        let shared = TempDir::new().unwrap();
+        std::fs::write(shared.path().join("big"), vec![1u8; 256 * 1024]).unwrap();
+
+        let wt = config_dir.path().join("worktrees/a_1");
+        std::fs::create_dir_all(&wt).unwrap();
+        std::fs::write(wt.join("file"), vec![1u8; 64 * 1024]).unwrap();
+        std::os::unix::fs::symlink(shared.path(), wt.join("node_modules")).unwrap();
+
+        let wt_str = wt.to_string_lossy().to_string();
+        let usage = DiskUsage::scan([("a", wt_str.as_str()), ("gone", "/nonexistent/wt")], config_dir.path());
+
+        let a = usage.per_session["a"];
+        assert!(a >= 64 * 1024, "worktree size {} too small", a);
+        assert!(a < 256 * 1024, "symlinked dir was counted: {}", a);
+        assert!(!usage.per_session.contains_key("gone"));
+        assert!(usage.total >= a);
+    }
+
+    #[test]
+    fn test_dir_size_counts_hardlinks_once() {
+        let dir = TempDir::new().unwrap();
+        std::fs::write(dir.path().join("big"), vec![1u8; 256 * 1024]).unwrap();
+        let single = dir_size(dir.path());
+        std::fs::hard_link(dir.path().join("big"), dir.path().join("link")).unwrap();
+        assert_eq!(dir_size(dir.path()), single);
+    }
+}
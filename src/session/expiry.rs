@@ -0,0 +1,96 @@
+use chrono::{DateTime, Duration, Utc};
+
+use crate::config::Config;
+
+use super::instance::{Instance, InstanceStatus};
+
+/// When idle sessions are paused and old paused sessions archived.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpiryPolicy {
+    /// Pause running sessions with no terminal activity for this long.
+    pub pause_idle_after: Option<Duration>,
+    /// Archive sessions that have been paused for this long.
+    pub archive_paused_after: Option<Duration>,
+}
+
+impl ExpiryPolicy {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            pause_idle_after: config.idle_pause_hours.map(|h| Duration::hours(h as i64)),
+            archive_paused_after: config.archive_paused_days.map(|d| Duration::days(d as i64)),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.pause_idle_after.is_some() || self.archive_paused_after.is_some()
+    }
+
+    /// Whether a running session last active at `last_activity` is idle
+    /// long enough to pause. Lifecycle events (start, resume) count as
+    /// activity too.
+    pub fn should_pause(&self, instance: &Instance, last_activity: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        let Some(limit) = self.pause_idle_after else {
+            return false;
+        };
+        instance.status.is_running()
+            && now - last_activity.max(instance.updated_at) >= limit
+    }
+
+    /// Whether a paused session has been paused long enough to archive.
+    pub fn should_archive(&self, instance: &Instance, now: DateTime<Utc>) -> bool {
+        let Some(limit) = self.archive_paused_after else {
+            return false;
+        };
+        instance.status == InstanceStatus::Paused && now - instance.updated_at >= limit
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::instance::InstanceOptions;
+
+    fn instance(status: InstanceStatus, updated_ago: Duration) -> Instance {
+        let mut inst = Instance::new(InstanceOptions {
+            title: "s".to_string(),
+            path: "/tmp".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        inst.status = status;
+        inst.updated_at = Utc::now() - updated_ago;
+        inst
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let policy = ExpiryPolicy::from_config(&Config::default());
+        assert!(!policy.is_enabled());
+        let old = instance(InstanceStatus::Paused, Duration::days(365));
+        assert!(!policy.should_archive(&old, Utc::now()));
+    }
+
+    #[test]
+    fn test_pause_uses_latest_activity() {
+        let policy = ExpiryPolicy { pause_idle_after: Some(Duration::hours(8)), ..Default::default() };
+        let now = Utc::now();
+        let running = instance(InstanceStatus::Running, Duration::hours(10));
+
+        assert!(policy.should_pause(&running, now - Duration::hours(9), now));
+        assert!(!policy.should_pause(&running, now - Duration::hours(1), now));
+        // Resumed recently, even though the pane has been quiet for long
+        let resumed = instance(InstanceStatus::Running, Duration::minutes(5));
+        assert!(!policy.should_pause(&resumed, now - Duration::days(2), now));
+        let paused = instance(InstanceStatus::Paused, Duration::hours(10));
+        assert!(!policy.should_pause(&paused, now - Duration::hours(9), now));
+    }
+
+    #[test]
+    fn test_archive_only_old_paused_sessions() {
+        let policy = ExpiryPolicy { archive_paused_after: Some(Duration::days(14)), ..Default::default() };
+        let now = Utc::now();
+        assert!(policy.should_archive(&instance(InstanceStatus::Paused, Duration::days(15)), now));
+        assert!(!policy.should_archive(&instance(InstanceStatus::Paused, Duration::days(3)), now));
+        assert!(!policy.should_archive(&instance(InstanceStatus::Ready, Duration::days(30)), now));
+    }
+}
@@ -0,0 +1,206 @@
+use std::sync::Arc;
+
+use crate::cmd::CmdExec;
+use crate::config::{Config, GitIdentity, LinkMode};
+use crate::session::container::Container;
+use crate::session::git::GitWorktree;
+use crate::session::launch;
+use crate::session::tmux::pty::SystemPtyFactory;
+use crate::session::tmux::TmuxSession;
+use crate::session::Instance;
+
+/// A step of creating a session, reported as it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CreationStep {
+    /// `git worktree add`, and carrying over uncommitted changes.
+    Worktree,
+    /// Shared dirs and commit signing.
+    Workspace,
+    /// The tmux session running the agent.
+    Launch,
+}
+
+impl CreationStep {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Worktree => "creating the worktree",
+            Self::Workspace => "preparing the workspace",
+            Self::Launch => "starting the agent",
+        }
+    }
+}
+
+/// A session's worktree and the tmux session running its agent in it.
+pub struct CreatedSession {
+    pub worktree: GitWorktree,
+    pub tmux: TmuxSession,
+    /// Whether the branch was created for the session, rather than one the
+    /// user already had.
+    pub owns_branch: bool,
+    /// Why the main checkout's uncommitted changes couldn't be carried
+    /// over, when they were asked for. The session is created without them.
+    pub carry_error: Option<String>,
+}
+
+/// Sets up a session's worktree and tmux session, for the TUI's background
+/// creation as well as `gana new` and resume.
+///
+/// Owns what it needs, so it can be handed to a worker thread.
+#[derive(Clone)]
+pub struct SessionFactory {
+    pub title: String,
+    pub path: String,
+    pub program: String,
+    pub carry_changes: bool,
+    pub existing_branch: Option<String>,
+    pub container: Option<Container>,
+    pub git_identity: Option<GitIdentity>,
+    pub shared_dirs: Vec<String>,
+    pub shared_dirs_mode: LinkMode,
+    pub config: Config,
+}
+
+impl SessionFactory {
+    /// Factory for `instance`'s session, set up as `config` says.
+    pub fn new(instance: &Instance, config: Config) -> Self {
+        Self {
+            title: instance.title.clone(),
+            path: instance.path.clone(),
+            program: instance.program.clone(),
+            carry_changes: instance.carry_changes,
+            existing_branch: instance.existing_branch.clone(),
+            container: instance.container.clone(),
+            git_identity: instance.git_identity.clone(),
+            shared_dirs: instance.shared_dirs.clone(),
+            shared_dirs_mode: instance.shared_dirs_mode,
+            config,
+        }
+    }
+
+    /// Create the worktree, then start the agent in it, calling `progress`
+    /// before each step.
+    ///
+    /// If the agent can't be started the worktree is removed again; its
+    /// branch is kept, so a retry reuses it.
+    pub fn create(
+        &self,
+        exec: &Arc<dyn CmdExec>,
+        progress: &mut dyn FnMut(CreationStep),
+    ) -> Result<CreatedSession, anyhow::Error> {
+        let cmd: &dyn CmdExec = &**exec;
+        progress(CreationStep::Worktree);
+        // Refuses paths inside gana's own worktrees, see `GitWorktree::nesting_error`
+        let mut worktree =
+            GitWorktree::new(&self.title, &self.path, &self.program, &self.title, cmd)?;
+        worktree.isolated = self.container.is_some();
+        worktree.identity = self.git_identity.clone();
+        let owns_branch = match self.existing_branch {
+            Some(ref branch) => worktree.setup_on_branch(branch, cmd)?,
+            None => worktree.setup(cmd)?,
+        };
+        // Bring the main checkout's uncommitted work along if asked to; the
+        // caller tells the user when that fails
+        let carry_error = if self.carry_changes {
+            worktree.carry_local_changes(cmd).err().map(|e| {
+                tracing::warn!(session = %self.title, "Failed to carry local changes: {}", e);
+                e.to_string()
+            })
+        } else {
+            None
+        };
+
+        progress(CreationStep::Workspace);
+        self.share_dirs(&worktree, cmd);
+        self.configure(&worktree, cmd);
+
+        progress(CreationStep::Launch);
+        match self.launch(&worktree, exec) {
+            Ok(tmux) => Ok(CreatedSession {
+                worktree,
+                tmux,
+                owns_branch,
+                carry_error,
+            }),
+            Err(e) => {
+                if let Some(ref container) = self.container {
+                    let _ = container.remove(cmd);
+                }
+                let _ = worktree.remove(cmd);
+                Err(e)
+            }
+        }
+    }
+
+    /// Link the session's shared dirs into `worktree` so the agent doesn't
+    /// start with a reinstall. A failure is logged; the agent reinstalls.
+    pub fn share_dirs(&self, worktree: &GitWorktree, cmd: &dyn CmdExec) {
+        if let Err(e) = worktree.link_shared_dirs(&self.shared_dirs, self.shared_dirs_mode, cmd) {
+            tracing::warn!(session = %self.title, "Failed to link shared dirs: {}", e);
+        }
+    }
+
+    /// Apply the per-worktree git config for commit signing. A failure is
+    /// logged; the session works without it.
+    pub fn configure(&self, worktree: &GitWorktree, cmd: &dyn CmdExec) {
+        if let Err(e) = worktree.configure_signing(&self.config, cmd) {
+            tracing::warn!(session = %self.title, "Failed to configure commit signing: {}", e);
+        }
+    }
+
+    /// Start the agent in a new tmux session in `worktree`, replacing any
+    /// left over under the same name. Container sessions run the agent
+    /// through `exec` in the pane.
+    ///
+    /// The tmux session runs its commands through `cmd`. The trust prompt
+    /// isn't answered; see `TmuxSession::trust_prompt`.
+    pub fn launch(
+        &self,
+        worktree: &GitWorktree,
+        cmd: &Arc<dyn CmdExec>,
+    ) -> Result<TmuxSession, anyhow::Error> {
+        let mut tmux = TmuxSession::new(
+            &self.title,
+            &self.program,
+            Box::new(Arc::clone(cmd)),
+            Box::new(SystemPtyFactory),
+        )
+        .with_prompts(self.config.prompt_patterns(&self.program));
+        tmux.set_command(launch::pane_command(
+            self.container.as_ref(),
+            worktree,
+            &self.program,
+            self.git_identity.as_ref(),
+            &self.config,
+            &**cmd,
+        )?);
+        tmux.start(worktree.worktree_path())?;
+        Ok(tmux)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::SystemCmdExec;
+    use crate::session::InstanceOptions;
+
+    #[test]
+    fn test_create_reports_progress_until_failure() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let mut instance = Instance::new(InstanceOptions {
+            title: "outside".to_string(),
+            path: dir.path().to_string_lossy().to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        instance.existing_branch = Some("origin/pr-7".to_string());
+        let factory = SessionFactory::new(&instance, Config::default());
+        assert_eq!(factory.existing_branch.as_deref(), Some("origin/pr-7"));
+
+        // Not a git repo: creation stops at the worktree
+        let mut steps = Vec::new();
+        let result = factory.create(&(Arc::new(SystemCmdExec) as Arc<dyn CmdExec>), &mut |step| steps.push(step));
+        assert!(result.is_err());
+        assert_eq!(steps, [CreationStep::Worktree]);
+    }
+}
@@ -14,16 +14,16 @@ pub struct DiffStats {
 impl DiffStats {
     /// Count added and removed lines from a unified diff string.
     ///
-    /// Lines starting with "+" (but not "+++") count as added.
-    /// Lines starting with "-" (but not "---") count as removed.
+    /// Lines starting with "+" (but not "+++" outside a hunk) count as added.
+    /// Lines starting with "-" (but not "---" outside a hunk) count as removed.
     pub fn from_diff(content: String) -> Self {
         let mut added = 0;
         let mut removed = 0;
 
-        for line in content.lines() {
-            if line.starts_with('+') && !line.starts_with("+++") {
+        for (line, in_hunk) in diff_lines(&content) {
+            if line.starts_with('+') && (in_hunk || !line.starts_with("+++")) {
                 added += 1;
-            } else if line.starts_with('-') && !line.starts_with("---") {
+            } else if line.starts_with('-') && (in_hunk || !line.starts_with("---")) {
                 removed += 1;
             }
         }
@@ -37,6 +37,23 @@ impl DiffStats {
     }
 }
 
+/// The lines of a unified diff, each with whether it is inside a hunk.
+///
+/// Only lines outside hunks are file headers (`---`, `+++`, `index`, …):
+/// inside one, a removed `-- comment` line reads `--- comment`.
+pub fn diff_lines(content: &str) -> impl Iterator<Item = (&str, bool)> {
+    let mut in_hunk = false;
+    content.lines().map(move |line| {
+        // Hunk lines start with ' ', '+', '-' or '\', so these can't be one
+        if line.starts_with("diff ") {
+            in_hunk = false;
+        } else if line.starts_with("@@") {
+            in_hunk = true;
+        }
+        (line, in_hunk)
+    })
+}
+
 impl GitWorktree {
     /// Compute a diff between the worktree and the base commit.
     ///
@@ -118,6 +135,15 @@ index abc123..def456 100644
         assert_eq!(stats.removed_lines, 1);
     }
 
+    #[test]
+    fn test_diff_stats_counts_removed_comment_lines() {
+        // Removing "-- note" (SQL, Lua) gives a "--- note" hunk line
+        let diff = "diff --git a/q.sql b/q.sql\n--- a/q.sql\n+++ b/q.sql\n@@ -1,2 +1 @@\n--- note\n select 1;\n";
+        let stats = DiffStats::from_diff(diff.to_string());
+        assert_eq!(stats.added_lines, 0);
+        assert_eq!(stats.removed_lines, 1);
+    }
+
     #[test]
     fn test_diff_stats_multiple_files() {
         let diff = r#"diff --git a/a.rs b/a.rs
@@ -0,0 +1,116 @@
+use serde::Deserialize;
+
+use crate::cmd::{args, CmdError, CmdExec};
+
+/// Maximum length of a session title derived from an issue title.
+const MAX_ISSUE_TITLE_LEN: usize = 32;
+
+/// A GitHub issue as returned by `gh issue view --json`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    #[serde(default)]
+    pub body: String,
+    #[serde(default)]
+    pub url: String,
+}
+
+impl Issue {
+    /// Fetch an issue from the current repository using `gh issue view`.
+    pub fn fetch(number: u64, cmd: &dyn CmdExec) -> Result<Self, CmdError> {
+        let output = cmd.output("gh", &args(&[
+            "issue", "view", &number.to_string(),
+            "--json", "number,title,body,url",
+        ]))?;
+        serde_json::from_str(&output)
+            .map_err(|e| CmdError::Failed(format!("failed to parse issue #{}: {}", number, e)))
+    }
+
+    /// Session title derived from the issue title, truncated to fit the
+    /// session name limit.
+    pub fn session_title(&self) -> String {
+        self.title.trim().chars().take(MAX_ISSUE_TITLE_LEN).collect::<String>().trim_end().to_string()
+    }
+
+    /// Render the initial prompt for this issue.
+    ///
+    /// Supported placeholders: `{number}`, `{title}`, `{body}`, `{url}`.
+    pub fn render_prompt(&self, template: &str) -> String {
+        template
+            .replace("{number}", &self.number.to_string())
+            .replace("{title}", &self.title)
+            .replace("{url}", &self.url)
+            .replace("{body}", self.body.trim())
+    }
+
+    /// Link `branch` to this issue on GitHub using `gh issue develop`.
+    pub fn link_branch(&self, branch: &str, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+        cmd.run("gh", &args(&[
+            "issue", "develop", &self.number.to_string(),
+            "--name", branch,
+        ]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::MockCmdExec;
+
+    fn make_issue() -> Issue {
+        Issue {
+            number: 42,
+            title: "Fix login redirect".to_string(),
+            body: "Users end up on /404 after login.\n".to_string(),
+            url: "https://github.com/owner/repo/issues/42".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_fetch_parses_gh_json() {
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|name, cmd_args| {
+                name == "gh"
+                    && cmd_args.iter().any(|a| a == "view")
+                    && cmd_args.iter().any(|a| a == "42")
+            })
+            .returning(|_, _| {
+                Ok(r#"{"number":42,"title":"Fix login redirect","body":"details","url":"https://example.com/42"}"#.to_string())
+            });
+
+        let issue = Issue::fetch(42, &mock).unwrap();
+        assert_eq!(issue.number, 42);
+        assert_eq!(issue.title, "Fix login redirect");
+        assert_eq!(issue.body, "details");
+    }
+
+    #[test]
+    fn test_fetch_invalid_json() {
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .returning(|_, _| Ok("not json".to_string()));
+
+        assert!(Issue::fetch(1, &mock).is_err());
+    }
+
+    #[test]
+    fn test_session_title_truncates() {
+        let mut issue = make_issue();
+        issue.title = "A very long issue title that exceeds the limit".to_string();
+        let title = issue.session_title();
+        assert!(title.chars().count() <= MAX_ISSUE_TITLE_LEN);
+        assert!(!title.ends_with(' '));
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let issue = make_issue();
+        let prompt = issue.render_prompt("#{number} {title}\n\n{body}\n\n{url}");
+        assert_eq!(
+            prompt,
+            "#42 Fix login redirect\n\nUsers end up on /404 after login.\n\nhttps://github.com/owner/repo/issues/42"
+        );
+    }
+}
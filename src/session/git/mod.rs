@@ -1,9 +1,11 @@
 pub mod diff;
+pub mod issue;
 pub mod util;
 pub mod worktree;
 pub mod worktree_branch;
 pub mod worktree_git;
 pub mod worktree_ops;
+pub mod worktree_shared;
 
 pub use diff::DiffStats;
 pub use worktree::GitWorktree;
@@ -3,7 +3,7 @@ use std::path::Path;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 use crate::cmd::{args, CmdError, CmdExec};
-use crate::config::{get_config_dir, Config};
+use crate::config::{get_config_dir, Config, GitIdentity};
 use crate::session::git::util::sanitize_branch_name;
 
 /// Represents a git worktree associated with a session instance.
@@ -14,6 +14,14 @@ pub struct GitWorktree {
     pub session_id: String,
     pub branch: String,
     pub base_commit: String,
+    /// The session's agent runs in a container that can write the worktree,
+    /// so host-side git mustn't run hooks from it; see [`Self::host_git`].
+    #[serde(default)]
+    pub isolated: bool,
+    /// Author of the commits gana makes here, as the agent's are through
+    /// its environment; the repo's own identity applies when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<GitIdentity>,
 }
 
 impl GitWorktree {
@@ -37,6 +45,17 @@ impl GitWorktree {
         Self::new_with_config(title, path, session_id, cmd, &config, &config_dir)
     }
 
+    /// Branch a session titled `title` gets: the prefix plus the sanitized
+    /// title, or the title as typed without a prefix (the user types the
+    /// branch name directly).
+    pub fn branch_for(branch_prefix: &str, title: &str) -> String {
+        if branch_prefix.is_empty() {
+            title.to_string()
+        } else {
+            format!("{}{}", branch_prefix, sanitize_branch_name(title))
+        }
+    }
+
     /// Like `new`, but accepts an explicit config and config directory.
     /// This avoids depending on the home directory, making it suitable for tests.
     pub fn new_with_config(
@@ -51,6 +70,9 @@ impl GitWorktree {
         let abs_path = std::fs::canonicalize(path)
             .map_err(|e| CmdError::Failed(format!("failed to resolve path {}: {}", path, e)))?;
         let abs_path_str = abs_path.to_string_lossy().to_string();
+        if let Some(msg) = Self::nesting_error(&abs_path, config_dir, cmd) {
+            return Err(CmdError::Failed(msg));
+        }
 
         // Find git repo root
         let repo_path = cmd
@@ -58,27 +80,22 @@ impl GitWorktree {
             .trim()
             .to_string();
 
-        // Generate branch name: prefix + title (user types the branch name directly)
-        let branch = if config.branch_prefix.is_empty() {
-            title.to_string()
-        } else {
-            format!("{}{}", config.branch_prefix, sanitize_branch_name(title))
-        };
+        let branch = Self::branch_for(&config.branch_prefix, title);
 
-        // Generate unique worktree directory
-        let nanos = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap_or_default()
-            .as_nanos();
-        let worktree_dir = config_dir
-            .join("worktrees")
-            .join(format!("{}_{}", session_id, nanos))
-            .to_string_lossy()
-            .to_string();
+        let worktree_dir = Self::unique_dir(config_dir, session_id);
 
-        // Get base commit
+        // Base commit: origin's freshly fetched default branch when configured,
+        // otherwise whatever is checked out
+        let base_ref = if config.branch_from_origin {
+            Self::fetch_origin_default(&repo_path, cmd).unwrap_or_else(|e| {
+                tracing::warn!("Falling back to HEAD, could not resolve origin's default branch: {}", e);
+                "HEAD".to_string()
+            })
+        } else {
+            "HEAD".to_string()
+        };
         let base_commit = cmd
-            .output("git", &args(&["-C", &repo_path, "rev-parse", "HEAD"]))?
+            .output("git", &args(&["-C", &repo_path, "rev-parse", &base_ref]))?
             .trim()
             .to_string();
 
@@ -88,9 +105,75 @@ impl GitWorktree {
             session_id: session_id.to_string(),
             branch,
             base_commit,
+            isolated: false,
+            identity: None,
         })
     }
 
+    /// A fresh worktree directory for a session under `{config_dir}/worktrees/`.
+    pub fn unique_dir(config_dir: &Path, session_id: &str) -> String {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        config_dir
+            .join("worktrees")
+            .join(format!("{}_{}", session_id, nanos))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// Why no session can be created from `path` when it is inside one of
+    /// the session worktrees under `{config_dir}/worktrees/`: worktrees would
+    /// nest, and cleaning them up could remove the wrong ones.
+    pub fn nesting_error(path: &Path, config_dir: &Path, cmd: &dyn CmdExec) -> Option<String> {
+        let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+        let path = canonical(path);
+        if !path.starts_with(canonical(&config_dir.join("worktrees"))) {
+            return None;
+        }
+        let path_str = path.to_string_lossy();
+        // A worktree's common git dir is the original repo's .git
+        let original = cmd
+            .output(
+                "git",
+                &args(&["-C", &path_str, "rev-parse", "--path-format=absolute", "--git-common-dir"]),
+            )
+            .ok()
+            .and_then(|dir| Path::new(dir.trim()).parent().map(|p| p.to_string_lossy().to_string()));
+        let hint = match original {
+            Some(repo) => format!("run gana from the original repo at {}", repo),
+            None => "run gana from the original repo".to_string(),
+        };
+        Some(format!(
+            "{} is a gana session worktree, and sessions created in it would nest worktrees; {}",
+            path_str, hint
+        ))
+    }
+
+    /// Fetch `origin` and return its default branch as a ref like `origin/main`.
+    fn fetch_origin_default(repo_path: &str, cmd: &dyn CmdExec) -> Result<String, CmdError> {
+        cmd.run("git", &args(&["-C", repo_path, "fetch", "--quiet", "origin"]))?;
+
+        if let Ok(head) = cmd.output(
+            "git",
+            &args(&["-C", repo_path, "symbolic-ref", "--short", "refs/remotes/origin/HEAD"]),
+        ) && !head.trim().is_empty()
+        {
+            return Ok(head.trim().to_string());
+        }
+
+        // origin/HEAD isn't always set (e.g. repos created with `git remote add`)
+        ["origin/main", "origin/master"]
+            .into_iter()
+            .find(|candidate| {
+                cmd.run("git", &args(&["-C", repo_path, "rev-parse", "--verify", "--quiet", candidate]))
+                    .is_ok()
+            })
+            .map(str::to_string)
+            .ok_or_else(|| CmdError::Failed("origin has no main or master branch".to_string()))
+    }
+
     /// Reconstruct a GitWorktree from previously stored data (e.g. loaded from disk).
     pub fn from_storage(
         repo_path: String,
@@ -105,6 +188,8 @@ impl GitWorktree {
             session_id,
             branch,
             base_commit,
+            isolated: false,
+            identity: None,
         }
     }
 
@@ -135,6 +220,71 @@ impl GitWorktree {
     pub fn base_commit_sha(&self) -> &str {
         &self.base_commit
     }
+
+    /// The repo's git directory for this worktree, `.git/worktrees/<name>`,
+    /// which git names after the worktree directory.
+    pub fn admin_dir(&self) -> String {
+        let name = Path::new(&self.worktree_dir).file_name().unwrap_or_default();
+        Path::new(&self.repo_path)
+            .join(".git")
+            .join("worktrees")
+            .join(name)
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// `cmd` for git that can run hooks (commit, push, checkout) on the
+    /// host. In an isolated worktree hooks and fsmonitor are turned off, as
+    /// a `core.hooksPath` inside the worktree would run what the agent
+    /// wrote there. Commits are made as `identity` when it's set.
+    pub fn host_git<'a>(&'a self, cmd: &'a dyn CmdExec) -> HostGit<'a> {
+        HostGit {
+            inner: cmd,
+            isolated: self.isolated,
+            identity: self.identity.as_ref(),
+        }
+    }
+}
+
+/// Runs commands through another [`CmdExec`], pinning the config that
+/// would let git run code from an isolated worktree; see
+/// [`GitWorktree::host_git`].
+pub struct HostGit<'a> {
+    inner: &'a dyn CmdExec,
+    isolated: bool,
+    identity: Option<&'a GitIdentity>,
+}
+
+impl HostGit<'_> {
+    fn args(&self, name: &str, cmd_args: &[String]) -> Vec<String> {
+        if name != "git" {
+            return cmd_args.to_vec();
+        }
+        let mut pinned = Vec::new();
+        if self.isolated {
+            pinned.extend(args(&["-c", "core.hooksPath=/dev/null", "-c", "core.fsmonitor=false"]));
+        }
+        if let Some(identity) = self.identity {
+            pinned.extend(args(&[
+                "-c",
+                &format!("user.name={}", identity.name),
+                "-c",
+                &format!("user.email={}", identity.email),
+            ]));
+        }
+        pinned.extend_from_slice(cmd_args);
+        pinned
+    }
+}
+
+impl CmdExec for HostGit<'_> {
+    fn run(&self, name: &str, cmd_args: &[String]) -> Result<(), CmdError> {
+        self.inner.run(name, &self.args(name, cmd_args))
+    }
+
+    fn output(&self, name: &str, cmd_args: &[String]) -> Result<String, CmdError> {
+        self.inner.output(name, &self.args(name, cmd_args))
+    }
 }
 
 #[cfg(test)]
@@ -156,6 +306,62 @@ mod tests {
         assert_eq!(wt.repo_path(), "/home/user/repos/myproject");
         assert_eq!(wt.repo_name(), "myproject");
         assert_eq!(wt.base_commit_sha(), "abc123def456");
+        assert_eq!(wt.admin_dir(), "/home/user/repos/myproject/.git/worktrees/sess_123");
+    }
+
+    #[test]
+    fn test_host_git_pins_hooks_when_isolated() {
+        let mut wt = GitWorktree::from_storage(
+            "/repo".to_string(),
+            "/wt".to_string(),
+            "sid".to_string(),
+            "branch".to_string(),
+            "commit".to_string(),
+        );
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run()
+            .withf(|name, a| name == "git" && a[0] == "-C")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock.expect_run()
+            .withf(|name, a| name == "git" && a[..2] == ["-c", "core.hooksPath=/dev/null"])
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock.expect_run()
+            .withf(|name, a| name == "gh" && a[0] == "-C")
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let push = args(&["-C", "/wt", "push"]);
+        wt.host_git(&mock).run("git", &push).unwrap();
+        wt.isolated = true;
+        wt.host_git(&mock).run("git", &push).unwrap();
+        wt.host_git(&mock).run("gh", &args(&["-C", "/wt", "repo", "sync"])).unwrap();
+    }
+
+    #[test]
+    fn test_host_git_commits_as_identity() {
+        let mut wt = GitWorktree::from_storage(
+            "/repo".to_string(),
+            "/wt".to_string(),
+            "sid".to_string(),
+            "branch".to_string(),
+            "commit".to_string(),
+        );
+        wt.identity = Some(GitIdentity {
+            name: "gana-bot".to_string(),
+            email: "bot@example.com".to_string(),
+        });
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_run()
+            .withf(|name, a| {
+                name == "git"
+                    && a[..5] == ["-c", "user.name=gana-bot", "-c", "user.email=bot@example.com", "-C"]
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        wt.host_git(&mock).run("git", &args(&["-C", "/wt", "commit"])).unwrap();
     }
 
     #[test]
@@ -220,6 +426,105 @@ mod tests {
         assert!(wt.base_commit.len() >= 7);
     }
 
+    #[test]
+    fn test_new_refuses_inside_session_worktree() {
+        use crate::cmd::SystemCmdExec;
+        use crate::config::Config;
+
+        let tmp = setup_test_repo();
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let cmd = SystemCmdExec;
+        let path = tmp.path().to_string_lossy().to_string();
+        let nested = config_dir.path().join("worktrees").join("first_1");
+        cmd.run(
+            "git",
+            &args(&["-C", &path, "worktree", "add", "-q", "-b", "first", &nested.to_string_lossy()]),
+        )
+        .unwrap();
+
+        let err = GitWorktree::new_with_config(
+            "second",
+            &nested.to_string_lossy(),
+            "second",
+            &cmd,
+            &Config::default(),
+            config_dir.path(),
+        )
+        .unwrap_err();
+        let repo = std::fs::canonicalize(tmp.path()).unwrap();
+        assert!(err.to_string().contains("is a gana session worktree"), "{}", err);
+        assert!(err.to_string().contains(&*repo.to_string_lossy()), "{}", err);
+        assert!(GitWorktree::nesting_error(tmp.path(), config_dir.path(), &cmd).is_none());
+    }
+
+    #[test]
+    fn test_new_branches_from_fetched_origin() {
+        use crate::cmd::SystemCmdExec;
+        use crate::config::Config;
+
+        let upstream = setup_test_repo();
+        let clone_parent = tempfile::TempDir::new().unwrap();
+        let clone = clone_parent.path().join("clone");
+        let git = |dir: &std::path::Path, a: &[&str]| {
+            let out = std::process::Command::new("git").arg("-C").arg(dir).args(a).output().unwrap();
+            assert!(out.status.success(), "git {:?}: {}", a, String::from_utf8_lossy(&out.stderr));
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        git(
+            clone_parent.path(),
+            &["clone", "-q", &upstream.path().to_string_lossy(), "clone"],
+        );
+        let stale_head = git(&clone, &["rev-parse", "HEAD"]);
+
+        // Upstream moves on after the clone
+        std::fs::write(upstream.path().join("new.txt"), "new").unwrap();
+        git(upstream.path(), &["add", "."]);
+        git(upstream.path(), &["commit", "-q", "-m", "second"]);
+        let upstream_head = git(upstream.path(), &["rev-parse", "HEAD"]);
+
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            branch_from_origin: true,
+            ..Config::default()
+        };
+        let wt = GitWorktree::new_with_config(
+            "fresh",
+            &clone.to_string_lossy(),
+            "fresh",
+            &SystemCmdExec,
+            &config,
+            config_dir.path(),
+        )
+        .unwrap();
+
+        assert_ne!(stale_head, upstream_head);
+        assert_eq!(wt.base_commit, upstream_head);
+    }
+
+    #[test]
+    fn test_branch_from_origin_falls_back_to_head() {
+        use crate::cmd::SystemCmdExec;
+        use crate::config::Config;
+
+        // No remote configured
+        let tmp = setup_test_repo();
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let config = Config {
+            branch_from_origin: true,
+            ..Config::default()
+        };
+        let wt = GitWorktree::new_with_config(
+            "local",
+            &tmp.path().to_string_lossy(),
+            "local",
+            &SystemCmdExec,
+            &config,
+            config_dir.path(),
+        )
+        .unwrap();
+        assert!(wt.base_commit.len() >= 7);
+    }
+
     fn setup_test_repo() -> tempfile::TempDir {
         let tmp = tempfile::TempDir::new().unwrap();
         std::process::Command::new("git")
@@ -1,7 +1,85 @@
 use crate::cmd::{args, CmdError, CmdExec};
+use crate::config::Config;
 
 use super::worktree::GitWorktree;
 
+/// How gana makes commits on a session's behalf.
+#[derive(Debug, Clone)]
+pub struct CommitOptions {
+    /// Run the repo's commit hooks instead of passing `--no-verify`.
+    pub run_hooks: bool,
+    /// Message template for auto-save commits (pause, cleanup).
+    pub autosave_template: String,
+    /// Message template for the commit made when pushing.
+    pub push_template: String,
+}
+
+/// Where and how a session branch is pushed.
+#[derive(Debug, Clone, Default)]
+pub struct PushOptions {
+    /// Remote from the gana config; see [`GitWorktree::push_remote`].
+    pub remote: Option<String>,
+    /// Push with `--force-with-lease`, e.g. after a squash or rebase.
+    pub force_with_lease: bool,
+    /// Push with git alone because `gh` is missing or not logged in.
+    pub plain_git: bool,
+    /// Open the PR as a draft, without showing it in the browser.
+    pub draft: bool,
+}
+
+/// Session details available to commit message templates.
+pub struct MessageContext<'a> {
+    pub session: &'a str,
+    pub program: &'a str,
+    pub branch: &'a str,
+}
+
+impl CommitOptions {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            run_hooks: config.run_git_hooks,
+            autosave_template: config.autosave_message_template.clone(),
+            push_template: config.push_message_template.clone(),
+        }
+    }
+
+    /// Message for an auto-save commit; `{title}` is the session title.
+    pub fn autosave_message(&self, ctx: &MessageContext) -> String {
+        render_message(&self.autosave_template, ctx.session, ctx)
+    }
+
+    /// Message for the push commit; `{title}` is `title`, e.g. the message
+    /// typed when squashing.
+    pub fn push_message(&self, title: &str, ctx: &MessageContext) -> String {
+        render_message(&self.push_template, title, ctx)
+    }
+}
+
+impl Default for CommitOptions {
+    fn default() -> Self {
+        Self::from_config(&Config::default())
+    }
+}
+
+/// Fill in `{title}`, `{session}`, `{program}`, `{branch}` and `{timestamp}`.
+fn render_message(template: &str, title: &str, ctx: &MessageContext) -> String {
+    template
+        .replace("{timestamp}", &chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string())
+        .replace("{session}", ctx.session)
+        .replace("{program}", ctx.program)
+        .replace("{branch}", ctx.branch)
+        .replace("{title}", title)
+}
+
+/// Point-in-time record of a worktree, enough to bring it back after cleanup.
+#[derive(Debug, Clone)]
+pub struct WorktreeSnapshot {
+    /// Commit the branch pointed at.
+    pub tip: String,
+    /// Commit holding uncommitted changes, if there were any.
+    pub uncommitted: Option<String>,
+}
+
 impl GitWorktree {
     /// Execute a git command in the given directory and return the trimmed output.
     fn run_git_command(
@@ -17,72 +95,238 @@ impl GitWorktree {
 
     /// Push changes: stage all, commit, and push to remote.
     ///
-    /// First tries `gh repo sync`, falling back to `git push -u origin {branch}`.
-    pub fn push_changes(&self, title: &str, cmd: &dyn CmdExec) -> Result<(), CmdError> {
-        // Stage all changes
-        cmd.run("git", &args(&["-C", &self.worktree_dir, "add", "."]))?;
+    /// Pushes to [`Self::push_remote`]. For `origin` without force, first
+    /// tries `gh repo sync` (unless `push.plain_git`), falling back to
+    /// `git push -u origin {branch}`.
+    pub fn push_changes(
+        &self,
+        title: &str,
+        opts: &CommitOptions,
+        push: &PushOptions,
+        cmd: &dyn CmdExec,
+    ) -> Result<(), CmdError> {
+        // Stage and commit whatever is left
+        self.commit_changes(title, opts, cmd)?;
+        let cmd = &self.host_git(cmd);
 
-        // Commit
-        cmd.run(
-            "git",
-            &args(&[
-                "-C",
-                &self.worktree_dir,
-                "commit",
-                "--no-verify",
-                "-m",
-                title,
-            ]),
-        )?;
+        let remote = self.push_remote(push.remote.as_deref(), cmd);
 
         // Try gh repo sync first, fallback to git push
-        if cmd
-            .run(
-                "gh",
-                &args(&["-C", &self.worktree_dir, "repo", "sync"]),
-            )
-            .is_err()
+        if remote == "origin"
+            && !push.force_with_lease
+            && !push.plain_git
+            && cmd
+                .run(
+                    "gh",
+                    &args(&["-C", &self.worktree_dir, "repo", "sync"]),
+                )
+                .is_ok()
         {
-            cmd.run(
-                "git",
-                &args(&[
-                    "-C",
-                    &self.worktree_dir,
-                    "push",
-                    "-u",
-                    "origin",
-                    &self.branch,
-                ]),
-            )?;
+            return Ok(());
         }
 
-        Ok(())
+        let mut push_args = vec!["-C", &self.worktree_dir, "push"];
+        if push.force_with_lease {
+            push_args.push("--force-with-lease");
+        }
+        push_args.extend(["-u", &remote, &self.branch]);
+        cmd.run("git", &args(&push_args)).map_err(|e| {
+            let rejected = e.to_string().contains("[rejected]");
+            if rejected && !push.force_with_lease {
+                CmdError::Failed(format!(
+                    "{}\nThe branch history was rewritten; push again with \"Force with lease\"",
+                    e
+                ))
+            } else {
+                e
+            }
+        })
+    }
+
+    /// Remote to push the session branch to: the repo's `gana.pushRemote`
+    /// git config, then `configured` (the gana config), then git's
+    /// `remote.pushDefault`, then `origin`.
+    pub fn push_remote(&self, configured: Option<&str>, cmd: &dyn CmdExec) -> String {
+        let git_config = |key: &str| {
+            Self::run_git_command(cmd, &self.worktree_dir, &["config", "--get", key])
+                .ok()
+                .filter(|v| !v.is_empty())
+        };
+        git_config("gana.pushRemote")
+            .or_else(|| configured.map(str::to_string))
+            .or_else(|| git_config("remote.pushDefault"))
+            .unwrap_or_else(|| "origin".to_string())
+    }
+
+    /// Squash every commit since `base_commit`, plus uncommitted changes,
+    /// into a single commit with `message`.
+    ///
+    /// Does nothing when the branch has no changes. Rewrites history, so a
+    /// previously pushed branch needs a forced push afterwards.
+    pub fn squash_commits(
+        &self,
+        message: &str,
+        opts: &CommitOptions,
+        cmd: &dyn CmdExec,
+    ) -> Result<(), CmdError> {
+        if self.base_commit.is_empty() {
+            return Err(CmdError::Failed(
+                "no base commit recorded for this session, cannot squash".to_string(),
+            ));
+        }
+        cmd.run("git", &args(&["-C", &self.worktree_dir, "reset", "--soft", &self.base_commit]))?;
+        cmd.run("git", &args(&["-C", &self.worktree_dir, "add", "."]))?;
+
+        let nothing_staged = cmd
+            .run("git", &args(&["-C", &self.worktree_dir, "diff", "--cached", "--quiet"]))
+            .is_ok();
+        if nothing_staged {
+            return Ok(());
+        }
+        self.commit(message, opts, cmd)
     }
 
     /// Commit changes if the worktree is dirty.
     ///
     /// Stages all files and commits with the given title.
     /// Returns Ok(()) if no changes to commit.
-    pub fn commit_changes(&self, title: &str, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+    pub fn commit_changes(
+        &self,
+        title: &str,
+        opts: &CommitOptions,
+        cmd: &dyn CmdExec,
+    ) -> Result<(), CmdError> {
         if !self.is_dirty(cmd)? {
             return Ok(());
         }
 
         cmd.run("git", &args(&["-C", &self.worktree_dir, "add", "."]))?;
 
+        self.commit(title, opts, cmd)
+    }
+
+    /// Commit what is staged. Hooks are skipped unless `opts.run_hooks` is
+    /// set; a failing hook's output is kept in the returned error.
+    fn commit(&self, message: &str, opts: &CommitOptions, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+        let cmd = &self.host_git(cmd);
+        let mut commit_args = vec!["-C", &self.worktree_dir, "commit"];
+        if !opts.run_hooks {
+            commit_args.push("--no-verify");
+        }
+        commit_args.extend(["-m", message]);
+
+        cmd.run("git", &args(&commit_args)).map_err(|e| {
+            if opts.run_hooks {
+                CmdError::Failed(format!("commit failed (git hooks enabled):\n{}", e))
+            } else {
+                e
+            }
+        })
+    }
+
+    /// Record the branch tip and uncommitted changes without touching the worktree.
+    ///
+    /// Uses `git stash create`, which writes a stash commit but leaves the
+    /// working tree and stash list unchanged.
+    pub fn snapshot(&self, cmd: &dyn CmdExec) -> Result<WorktreeSnapshot, CmdError> {
+        let tip = Self::run_git_command(cmd, &self.worktree_dir, &["rev-parse", "HEAD"])?;
+        let stash = Self::run_git_command(cmd, &self.worktree_dir, &["stash", "create"])?;
+        Ok(WorktreeSnapshot {
+            tip,
+            uncommitted: (!stash.is_empty()).then_some(stash),
+        })
+    }
+
+    /// Recreate the branch at the snapshot's tip (e.g. after `cleanup`).
+    pub fn restore_branch(&self, snapshot: &WorktreeSnapshot, cmd: &dyn CmdExec) -> Result<(), CmdError> {
         cmd.run(
             "git",
-            &args(&[
-                "-C",
-                &self.worktree_dir,
-                "commit",
-                "--no-verify",
-                "-m",
-                title,
-            ]),
+            &args(&["-C", &self.repo_path, "branch", "-f", &self.branch, &snapshot.tip]),
         )
     }
 
+    /// Re-apply the snapshot's uncommitted changes to the worktree.
+    pub fn apply_snapshot(&self, snapshot: &WorktreeSnapshot, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+        match snapshot.uncommitted {
+            Some(ref stash) => cmd.run(
+                "git",
+                &args(&["-C", &self.worktree_dir, "stash", "apply", stash]),
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Copy the main checkout's uncommitted changes into this worktree.
+    ///
+    /// Tracked changes go through `git stash create` + `stash apply`, which
+    /// leaves the main checkout untouched; untracked (non-ignored) files are
+    /// copied over as-is. Expects the worktree to be based on the repo's HEAD.
+    pub fn carry_local_changes(&self, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+        let stash = Self::run_git_command(cmd, &self.repo_path, &["stash", "create"])?;
+        if !stash.is_empty() {
+            cmd.run(
+                "git",
+                &args(&["-C", &self.worktree_dir, "stash", "apply", &stash]),
+            )?;
+        }
+
+        let untracked = Self::run_git_command(
+            cmd,
+            &self.repo_path,
+            &["ls-files", "--others", "--exclude-standard"],
+        )?;
+        for file in untracked.lines().filter(|l| !l.is_empty()) {
+            let dest = std::path::Path::new(&self.worktree_dir).join(file);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|e| CmdError::Failed(format!("create {}: {}", parent.display(), e)))?;
+            }
+            std::fs::copy(std::path::Path::new(&self.repo_path).join(file), &dest)
+                .map_err(|e| CmdError::Failed(format!("copy {}: {}", file, e)))?;
+        }
+        Ok(())
+    }
+
+    /// Enable commit signing in this worktree only, per `config.sign_commits`.
+    ///
+    /// Uses worktree-scoped config so the main checkout and other worktrees
+    /// are left alone. That needs `extensions.worktreeConfig` in the repo,
+    /// which is turned on if it isn't and then stays on: other worktrees
+    /// may rely on it. Must run after each setup, since the settings go
+    /// away with the worktree.
+    pub fn configure_signing(&self, config: &Config, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+        if !config.sign_commits {
+            return Ok(());
+        }
+        let enabled = Self::run_git_command(
+            cmd,
+            &self.repo_path,
+            &["config", "--bool", "--get", "extensions.worktreeConfig"],
+        )
+        .is_ok_and(|value| value == "true");
+        if !enabled {
+            cmd.run(
+                "git",
+                &args(&["-C", &self.repo_path, "config", "extensions.worktreeConfig", "true"]),
+            )?;
+        }
+
+        let mut settings = vec![("commit.gpgsign", "true")];
+        if let Some(ref key) = config.signing_key {
+            settings.push(("user.signingkey", key));
+        }
+        if let Some(ref format) = config.signing_format {
+            settings.push(("gpg.format", format));
+        }
+        for (key, value) in settings {
+            cmd.run(
+                "git",
+                &args(&["-C", &self.worktree_dir, "config", "--worktree", key, value]),
+            )?;
+        }
+        Ok(())
+    }
+
     /// Check if the worktree has any uncommitted changes.
     pub fn is_dirty(&self, cmd: &dyn CmdExec) -> Result<bool, CmdError> {
         let output = Self::run_git_command(cmd, &self.worktree_dir, &["status", "--porcelain"])?;
@@ -97,19 +341,61 @@ impl GitWorktree {
     }
 
     /// Create a pull request for this branch using `gh pr create`.
-    pub fn create_pr(&self, title: &str, cmd: &dyn CmdExec) -> Result<(), CmdError> {
-        cmd.run("gh", &args(&[
+    ///
+    /// `body` defaults to a one-line note naming the session.
+    pub fn create_pr(
+        &self,
+        title: &str,
+        body: Option<&str>,
+        draft: bool,
+        cmd: &dyn CmdExec,
+    ) -> Result<(), CmdError> {
+        let default_body = format!("Changes from gana session: {}", title);
+        let mut pr_args = args(&[
             "pr", "create",
             "--title", title,
-            "--body", &format!("Changes from gana session: {}", title),
+            "--body", body.unwrap_or(&default_body),
             "--head", &self.branch,
-        ]))
+        ]);
+        if draft {
+            pr_args.push("--draft".to_string());
+        }
+        cmd.run("gh", &pr_args)
+    }
+
+    /// URL for opening a pull request from this branch in the browser,
+    /// for when `gh` can't create it.
+    pub fn compare_url(&self, remote: &str, cmd: &dyn CmdExec) -> Option<String> {
+        let remote_url =
+            Self::run_git_command(cmd, &self.worktree_dir, &["remote", "get-url", remote]).ok()?;
+        super::util::compare_url(&remote_url, &self.branch)
     }
 
     /// Open the branch in the browser using `gh browse`.
     pub fn open_branch_url(&self, cmd: &dyn CmdExec) -> Result<(), CmdError> {
         cmd.run("gh", &args(&["browse", "-b", &self.branch]))
     }
+
+    /// URL of this branch's pull request, if one has been created.
+    pub fn pr_url(&self, cmd: &dyn CmdExec) -> Option<String> {
+        cmd.output("gh", &args(&["pr", "view", &self.branch, "--json", "url", "--jq", ".url"]))
+            .ok()
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+    }
+
+    /// Open this branch's pull request in the browser using `gh pr view --web`.
+    pub fn open_pr(&self, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+        cmd.run("gh", &args(&["pr", "view", &self.branch, "--web"]))
+    }
+
+    /// URL that `gh browse -b` opens for this branch.
+    pub fn branch_url(&self, cmd: &dyn CmdExec) -> Option<String> {
+        cmd.output("gh", &args(&["browse", "-n", "-b", &self.branch]))
+            .ok()
+            .map(|url| url.trim().to_string())
+            .filter(|url| !url.is_empty())
+    }
 }
 
 #[cfg(test)]
@@ -153,6 +439,304 @@ mod tests {
         assert!(!wt.is_dirty(&mock).unwrap());
     }
 
+    #[test]
+    fn test_snapshot_records_tip_and_stash() {
+        let wt = make_worktree();
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|_, cmd_args| cmd_args.iter().any(|a| a == "rev-parse"))
+            .returning(|_, _| Ok("abc123\n".to_string()));
+        mock.expect_output()
+            .withf(|_, cmd_args| cmd_args.iter().any(|a| a == "stash"))
+            .returning(|_, _| Ok("def456\n".to_string()));
+
+        let snapshot = wt.snapshot(&mock).unwrap();
+        assert_eq!(snapshot.tip, "abc123");
+        assert_eq!(snapshot.uncommitted.as_deref(), Some("def456"));
+    }
+
+    #[test]
+    fn test_snapshot_clean_worktree() {
+        let wt = make_worktree();
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|_, cmd_args| cmd_args.iter().any(|a| a == "rev-parse"))
+            .returning(|_, _| Ok("abc123\n".to_string()));
+        mock.expect_output()
+            .withf(|_, cmd_args| cmd_args.iter().any(|a| a == "stash"))
+            .returning(|_, _| Ok(String::new()));
+
+        let snapshot = wt.snapshot(&mock).unwrap();
+        assert!(snapshot.uncommitted.is_none());
+        // Nothing to apply for a clean snapshot
+        wt.apply_snapshot(&snapshot, &MockCmdExec::new()).unwrap();
+    }
+
+    #[test]
+    fn test_restore_branch() {
+        let wt = make_worktree();
+        let mut mock = MockCmdExec::new();
+        mock.expect_run()
+            .withf(|name, cmd_args| {
+                name == "git"
+                    && cmd_args.iter().any(|a| a == "-f")
+                    && cmd_args.iter().any(|a| a == "gana/test")
+                    && cmd_args.iter().any(|a| a == "abc123")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let snapshot = WorktreeSnapshot {
+            tip: "abc123".to_string(),
+            uncommitted: None,
+        };
+        wt.restore_branch(&snapshot, &mock).unwrap();
+    }
+
+    #[test]
+    fn test_carry_local_changes_real_repo() {
+        use crate::cmd::SystemCmdExec;
+        use std::process::Command;
+
+        let repo = tempfile::TempDir::new().unwrap();
+        let git = |dir: &std::path::Path, a: &[&str]| {
+            let ok = Command::new("git").arg("-C").arg(dir).args(a).output().unwrap();
+            assert!(ok.status.success(), "git {:?}: {}", a, String::from_utf8_lossy(&ok.stderr));
+        };
+        git(repo.path(), &["init", "-q"]);
+        git(repo.path(), &["config", "user.email", "t@t"]);
+        git(repo.path(), &["config", "user.name", "t"]);
+        std::fs::write(repo.path().join("a.txt"), "one\n").unwrap();
+        git(repo.path(), &["add", "."]);
+        git(repo.path(), &["commit", "-q", "-m", "init"]);
+
+        // Local edits: a modified tracked file and a new untracked one
+        std::fs::write(repo.path().join("a.txt"), "two\n").unwrap();
+        std::fs::create_dir(repo.path().join("new")).unwrap();
+        std::fs::write(repo.path().join("new/b.txt"), "b\n").unwrap();
+
+        let wt_parent = tempfile::TempDir::new().unwrap();
+        let wt_dir = wt_parent.path().join("wt").to_string_lossy().to_string();
+        git(repo.path(), &["worktree", "add", "-q", "-b", "carry", &wt_dir, "HEAD"]);
+        let wt = GitWorktree::from_storage(
+            repo.path().to_string_lossy().to_string(),
+            wt_dir.clone(),
+            "sess".to_string(),
+            "carry".to_string(),
+            String::new(),
+        );
+
+        wt.carry_local_changes(&SystemCmdExec).unwrap();
+        let wt_path = std::path::Path::new(&wt_dir);
+        assert_eq!(std::fs::read_to_string(wt_path.join("a.txt")).unwrap(), "two\n");
+        assert_eq!(std::fs::read_to_string(wt_path.join("new/b.txt")).unwrap(), "b\n");
+        // The main checkout keeps its changes
+        assert_eq!(std::fs::read_to_string(repo.path().join("a.txt")).unwrap(), "two\n");
+    }
+
+    #[test]
+    fn test_commit_hooks_run_only_when_enabled() {
+        use crate::cmd::SystemCmdExec;
+        use std::os::unix::fs::PermissionsExt;
+        use std::process::Command;
+
+        let repo = tempfile::TempDir::new().unwrap();
+        let git = |a: &[&str]| {
+            let ok = Command::new("git").arg("-C").arg(repo.path()).args(a).output().unwrap();
+            assert!(ok.status.success(), "git {:?}: {}", a, String::from_utf8_lossy(&ok.stderr));
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "t@t"]);
+        git(&["config", "user.name", "t"]);
+        let hook = repo.path().join(".git/hooks/pre-commit");
+        std::fs::write(&hook, "#!/bin/sh\necho 'lint: trailing whitespace in a.txt'\nexit 1\n").unwrap();
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = repo.path().to_string_lossy().to_string();
+        let wt = GitWorktree::from_storage(path.clone(), path, "s".into(), "main".into(), String::new());
+
+        std::fs::write(repo.path().join("a.txt"), "one \n").unwrap();
+        let hooks = CommitOptions { run_hooks: true, ..CommitOptions::default() };
+        let err = wt.commit_changes("save", &hooks, &SystemCmdExec).unwrap_err();
+        assert!(err.to_string().contains("lint: trailing whitespace"), "{}", err);
+
+        wt.commit_changes("save", &CommitOptions::default(), &SystemCmdExec).unwrap();
+        assert!(!wt.is_dirty(&SystemCmdExec).unwrap());
+    }
+
+    #[test]
+    fn test_configure_signing_is_worktree_scoped() {
+        use crate::cmd::SystemCmdExec;
+        use std::process::Command;
+
+        let repo = tempfile::TempDir::new().unwrap();
+        let wt_parent = tempfile::TempDir::new().unwrap();
+        let wt_dir = wt_parent.path().join("wt").to_string_lossy().to_string();
+        let git = |dir: &str, a: &[&str]| {
+            let out = Command::new("git").arg("-C").arg(dir).args(a).output().unwrap();
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        let repo_path = repo.path().to_string_lossy().to_string();
+        git(&repo_path, &["init", "-q"]);
+        git(&repo_path, &["-c", "user.email=t@t", "-c", "user.name=t", "commit", "-q", "--allow-empty", "-m", "init"]);
+        git(&repo_path, &["worktree", "add", "-q", "-b", "s", &wt_dir]);
+        let wt = GitWorktree::from_storage(repo_path.clone(), wt_dir.clone(), "s".into(), "s".into(), String::new());
+
+        // Disabled: nothing is written
+        wt.configure_signing(&Config::default(), &SystemCmdExec).unwrap();
+        assert_eq!(git(&wt_dir, &["config", "commit.gpgsign"]), "");
+
+        let config = Config {
+            sign_commits: true,
+            signing_key: Some("~/.ssh/id_ed25519.pub".to_string()),
+            signing_format: Some("ssh".to_string()),
+            ..Config::default()
+        };
+        wt.configure_signing(&config, &SystemCmdExec).unwrap();
+        assert_eq!(git(&wt_dir, &["config", "commit.gpgsign"]), "true");
+        assert_eq!(git(&wt_dir, &["config", "gpg.format"]), "ssh");
+        assert_eq!(git(&wt_dir, &["config", "user.signingkey"]), "~/.ssh/id_ed25519.pub");
+        // The main checkout is unaffected
+        assert_eq!(git(&repo_path, &["config", "commit.gpgsign"]), "");
+    }
+
+    #[test]
+    fn test_squash_commits_since_base() {
+        use crate::cmd::SystemCmdExec;
+        use std::process::Command;
+
+        let repo = tempfile::TempDir::new().unwrap();
+        let git = |a: &[&str]| {
+            let out = Command::new("git").arg("-C").arg(repo.path()).args(a).output().unwrap();
+            assert!(out.status.success(), "git {:?}: {}", a, String::from_utf8_lossy(&out.stderr));
+            String::from_utf8_lossy(&out.stdout).trim().to_string()
+        };
+        git(&["init", "-q"]);
+        git(&["config", "user.email", "t@t"]);
+        git(&["config", "user.name", "t"]);
+        git(&["commit", "-q", "--allow-empty", "-m", "base"]);
+        let base = git(&["rev-parse", "HEAD"]);
+        for n in 1..=3 {
+            std::fs::write(repo.path().join(format!("f{}", n)), "x").unwrap();
+            git(&["add", "."]);
+            git(&["commit", "-q", "-m", &format!("gana: auto-save {}", n)]);
+        }
+        std::fs::write(repo.path().join("uncommitted"), "y").unwrap();
+
+        let path = repo.path().to_string_lossy().to_string();
+        let wt = GitWorktree::from_storage(path.clone(), path, "s".into(), "main".into(), base.clone());
+        wt.squash_commits("Add the feature", &CommitOptions::default(), &SystemCmdExec).unwrap();
+
+        assert_eq!(git(&["rev-list", "--count", &format!("{}..HEAD", base)]), "1");
+        assert_eq!(git(&["log", "-1", "--format=%s"]), "Add the feature");
+        assert_eq!(git(&["show", "--name-only", "--format=", "HEAD"]), "f1\nf2\nf3\nuncommitted");
+        assert!(!wt.is_dirty(&SystemCmdExec).unwrap());
+    }
+
+    #[test]
+    fn test_commit_message_templates() {
+        let opts = CommitOptions {
+            autosave_template: "chore(gana): autosave {session} {timestamp}".to_string(),
+            push_template: "{title}\n\nSession: {session}\nAgent: {program}".to_string(),
+            ..CommitOptions::default()
+        };
+        let ctx = MessageContext { session: "docs", program: "claude", branch: "gana/docs" };
+
+        let autosave = opts.autosave_message(&ctx);
+        assert!(autosave.starts_with("chore(gana): autosave docs 20"), "{}", autosave);
+        assert!(!autosave.contains('{'));
+        assert_eq!(
+            opts.push_message("Rewrite the README", &ctx),
+            "Rewrite the README\n\nSession: docs\nAgent: claude"
+        );
+
+        // Defaults keep the previous messages
+        let defaults = CommitOptions::default();
+        assert!(defaults.autosave_message(&ctx).starts_with("gana: auto-save 20"));
+        assert_eq!(defaults.push_message("docs", &ctx), "docs");
+    }
+
+    #[test]
+    fn test_push_remote_precedence() {
+        let wt = make_worktree();
+        let git_config = |values: &'static [(&'static str, &'static str)]| {
+            let mut mock = MockCmdExec::new();
+            mock.expect_output().returning(move |_, cmd_args| {
+                let key = cmd_args.last().unwrap().clone();
+                values
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, v)| Ok(format!("{}\n", v)))
+                    .unwrap_or_else(|| Err(CmdError::Failed("unset".into())))
+            });
+            mock
+        };
+
+        let mock = git_config(&[("gana.pushRemote", "fork"), ("remote.pushDefault", "upstream")]);
+        assert_eq!(wt.push_remote(Some("mine"), &mock), "fork");
+
+        let mock = git_config(&[("remote.pushDefault", "upstream")]);
+        assert_eq!(wt.push_remote(Some("mine"), &mock), "mine");
+        assert_eq!(wt.push_remote(None, &mock), "upstream");
+
+        assert_eq!(wt.push_remote(None, &git_config(&[])), "origin");
+    }
+
+    #[test]
+    fn test_force_push_goes_to_selected_remote() {
+        let wt = make_worktree();
+        let mut mock = MockCmdExec::new();
+        // Clean worktree, no remote overrides in git config
+        mock.expect_output()
+            .withf(|_, a| a.iter().any(|x| x == "--porcelain"))
+            .returning(|_, _| Ok(String::new()));
+        mock.expect_output()
+            .withf(|_, a| a.iter().any(|x| x == "config"))
+            .returning(|_, _| Err(CmdError::Failed("unset".into())));
+        mock.expect_run()
+            .withf(|name, a| {
+                name == "git"
+                    && a.iter().any(|x| x == "--force-with-lease")
+                    && a.ends_with(&["-u".to_string(), "fork".to_string(), "gana/test".to_string()])
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let push = PushOptions {
+            remote: Some("fork".into()),
+            force_with_lease: true,
+            ..PushOptions::default()
+        };
+        wt.push_changes("msg", &CommitOptions::default(), &push, &mock).unwrap();
+    }
+
+    #[test]
+    fn test_plain_git_push_skips_gh() {
+        let wt = make_worktree();
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|_, a| a.iter().any(|x| x == "--porcelain"))
+            .returning(|_, _| Ok(String::new()));
+        mock.expect_output()
+            .withf(|_, a| a.iter().any(|x| x == "config"))
+            .returning(|_, _| Err(CmdError::Failed("unset".into())));
+        mock.expect_output()
+            .withf(|_, a| a.iter().any(|x| x == "get-url"))
+            .returning(|_, _| Ok("git@github.com:acme/app.git\n".to_string()));
+        mock.expect_run().withf(|name, _| name == "gh").never();
+        mock.expect_run()
+            .withf(|name, a| name == "git" && a.ends_with(&["-u".to_string(), "origin".to_string(), "gana/test".to_string()]))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let push = PushOptions { plain_git: true, ..PushOptions::default() };
+        wt.push_changes("msg", &CommitOptions::default(), &push, &mock).unwrap();
+        assert_eq!(
+            wt.compare_url("origin", &mock).as_deref(),
+            Some("https://github.com/acme/app/compare/gana/test?expand=1")
+        );
+    }
+
     #[test]
     fn test_is_branch_checked_out_yes() {
         let wt = make_worktree();
@@ -191,7 +775,7 @@ mod tests {
             .returning(|_, _| Ok(String::new()));
 
         // Should succeed without calling add or commit
-        wt.commit_changes("test commit", &mock).unwrap();
+        wt.commit_changes("test commit", &CommitOptions::default(), &mock).unwrap();
     }
 
     #[test]
@@ -220,7 +804,7 @@ mod tests {
             })
             .returning(|_, _| Ok(()));
 
-        wt.commit_changes("test commit", &mock).unwrap();
+        wt.commit_changes("test commit", &CommitOptions::default(), &mock).unwrap();
     }
 
     #[test]
@@ -236,6 +820,32 @@ mod tests {
             })
             .returning(|_, _| Ok(()));
 
-        wt.create_pr("my feature", &mock).unwrap();
+        wt.create_pr("my feature", None, false, &mock).unwrap();
+    }
+
+    #[test]
+    fn test_create_draft_pr() {
+        let wt = make_worktree();
+        let mut mock = MockCmdExec::new();
+        mock.expect_run()
+            .withf(|name, cmd_args| name == "gh" && cmd_args.iter().any(|a| a == "--draft"))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        wt.create_pr("my feature", None, true, &mock).unwrap();
+    }
+
+    #[test]
+    fn test_create_pr_with_drafted_body() {
+        let wt = make_worktree();
+        let mut mock = MockCmdExec::new();
+        mock.expect_run()
+            .withf(|name, cmd_args| {
+                name == "gh" && cmd_args.windows(2).any(|w| w[0] == "--body" && w[1] == "## Summary")
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        wt.create_pr("my feature", Some("## Summary"), false, &mock).unwrap();
     }
 }
@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use crate::cmd::{args, CmdError, CmdExec};
+use crate::session::git::util::{checked_out_branches, remotes, strip_remote};
 
 use super::worktree::GitWorktree;
 
@@ -8,8 +9,10 @@ impl GitWorktree {
     /// Set up the worktree on disk.
     ///
     /// If the branch already exists, reuses it. Otherwise creates a new branch
-    /// from HEAD.
-    pub fn setup(&self, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+    /// from the base commit. Returns whether the branch was created.
+    pub fn setup(&self, cmd: &dyn CmdExec) -> Result<bool, CmdError> {
+        // The checkout runs post-checkout hooks from the branch's contents
+        let cmd = &self.host_git(cmd);
         let branch_exists = cmd
             .output(
                 "git",
@@ -23,48 +26,96 @@ impl GitWorktree {
             .is_ok();
 
         if branch_exists {
-            self.setup_from_existing_branch(cmd)
+            self.setup_from_existing_branch(cmd)?;
         } else {
-            self.setup_new_worktree(cmd)
+            self.setup_new_worktree(cmd)?;
         }
+        Ok(!branch_exists)
+    }
+
+    /// Set up the worktree on an existing branch picked by the user instead
+    /// of a new one.
+    ///
+    /// `branch_ref` is either a local branch (`feature`) or a remote-tracking
+    /// one (`origin/feature`, for any of the repo's remotes). A remote branch
+    /// gets a local tracking branch of the same name unless one already
+    /// exists. Returns whether that tracking branch was created.
+    pub fn setup_on_branch(
+        &mut self,
+        branch_ref: &str,
+        cmd: &dyn CmdExec,
+    ) -> Result<bool, CmdError> {
+        let local_exists = |branch: &str| {
+            cmd.output(
+                "git",
+                &args(&["-C", &self.repo_path, "show-ref", &format!("refs/heads/{}", branch)]),
+            )
+            .is_ok()
+        };
+
+        if local_exists(branch_ref) {
+            self.branch = branch_ref.to_string();
+        } else {
+            let remote_names = remotes(&self.repo_path, cmd).unwrap_or_default();
+            let local = strip_remote(branch_ref, &remote_names).unwrap_or(branch_ref).to_string();
+            if !local_exists(&local) {
+                self.branch = local;
+                self.base_commit = cmd
+                    .output("git", &args(&["-C", &self.repo_path, "rev-parse", branch_ref]))?
+                    .trim()
+                    .to_string();
+                cmd.run(
+                    "git",
+                    &args(&[
+                        "-C",
+                        &self.repo_path,
+                        "worktree",
+                        "add",
+                        "--track",
+                        "-b",
+                        &self.branch,
+                        &self.worktree_dir,
+                        branch_ref,
+                    ]),
+                )?;
+                return Ok(true);
+            }
+            self.branch = local;
+        }
+
+        self.base_commit = cmd
+            .output("git", &args(&["-C", &self.repo_path, "rev-parse", &self.branch]))?
+            .trim()
+            .to_string();
+        self.setup_from_existing_branch(cmd)?;
+        Ok(false)
     }
 
     /// Set up a worktree using an existing branch.
     ///
-    /// Removes any stale worktree that already uses this branch before creating
-    /// the new one.
+    /// Git only lets a branch be checked out once, so a branch still checked
+    /// out in the main checkout or another worktree is refused rather than
+    /// taken over. Worktrees whose directories are gone are pruned first.
     fn setup_from_existing_branch(&self, cmd: &dyn CmdExec) -> Result<(), CmdError> {
         // Remove existing worktree directory at our target path if present
         if Path::new(&self.worktree_dir).exists() {
             let _ = std::fs::remove_dir_all(&self.worktree_dir);
         }
 
-        // Find and remove any existing worktree that uses this branch.
-        // This handles the case where a previous session with the same name
-        // left a stale worktree at a different path (different timestamp).
-        if let Ok(output) = cmd.output(
-            "git",
-            &args(&["-C", &self.repo_path, "worktree", "list", "--porcelain"]),
-        ) {
-            let mut current_path: Option<String> = None;
-            for line in output.lines() {
-                if let Some(path) = line.strip_prefix("worktree ") {
-                    current_path = Some(path.to_string());
-                } else if let Some(branch_ref) = line.strip_prefix("branch refs/heads/") {
-                    if branch_ref == self.branch {
-                        if let Some(ref stale_path) = current_path {
-                            let _ = std::fs::remove_dir_all(stale_path);
-                        }
-                    }
-                } else if line.is_empty() {
-                    current_path = None;
-                }
-            }
-        }
-
-        // Prune any now-stale worktree entries
+        // Drop entries of worktrees whose directories are gone, such as a
+        // paused session's
         let _ = cmd.run("git", &args(&["-C", &self.repo_path, "worktree", "prune"]));
 
+        if let Some((_, path)) = checked_out_branches(&self.repo_path, cmd)?
+            .into_iter()
+            .find(|(branch, _)| *branch == self.branch)
+        {
+            return Err(CmdError::Failed(format!(
+                "branch {} is already checked out at {}",
+                self.branch, path
+            )));
+        }
+
         cmd.run(
             "git",
             &args(&[
@@ -78,11 +129,18 @@ impl GitWorktree {
         )
     }
 
-    /// Set up a new worktree with a new branch from HEAD.
+    /// Set up a new worktree with a new branch from the base commit (HEAD
+    /// when none was recorded).
     fn setup_new_worktree(&self, cmd: &dyn CmdExec) -> Result<(), CmdError> {
         // Clean up any stale branch refs that might conflict
         let _ = self.cleanup_existing_branch(cmd);
 
+        let start_point = if self.base_commit.is_empty() {
+            "HEAD"
+        } else {
+            &self.base_commit
+        };
+
         cmd.run(
             "git",
             &args(&[
@@ -93,7 +151,7 @@ impl GitWorktree {
                 "-b",
                 &self.branch,
                 &self.worktree_dir,
-                "HEAD",
+                start_point,
             ]),
         )
     }
@@ -135,16 +193,42 @@ impl GitWorktree {
     }
 }
 
+/// What [`cleanup_worktrees`] removed, or would remove on a dry run.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct WorktreeCleanup {
+    /// Worktree directories under the worktree root.
+    pub dirs: Vec<std::path::PathBuf>,
+    /// `(repo, branch)` pairs whose branch is deleted.
+    pub branches: Vec<(String, String)>,
+    /// Things left alone, each with the reason.
+    pub skipped: Vec<String>,
+}
+
 /// Clean up all worktrees in the config directory's worktrees folder.
 ///
 /// For each worktree directory: finds the parent repo, identifies the branch,
-/// removes the directory, deletes the branch, and prunes.
+/// removes the directory, deletes the branch, and prunes. Only directories
+/// really under the worktree root are removed. A branch is only deleted
+/// when it's one of the `(repo, branch)` pairs in `owned`, the branches
+/// sessions created, starts with a non-empty `branch_prefix` and isn't the
+/// one checked out in its main repo. With `dry_run`, nothing is touched and
+/// the report says what would be.
 #[allow(dead_code)]
-pub fn cleanup_worktrees(config_dir: &str, cmd: &dyn CmdExec) -> Result<(), CmdError> {
+pub fn cleanup_worktrees(
+    config_dir: &str,
+    branch_prefix: &str,
+    owned: &[(String, String)],
+    dry_run: bool,
+    cmd: &dyn CmdExec,
+) -> Result<WorktreeCleanup, CmdError> {
+    let mut report = WorktreeCleanup::default();
     let worktrees_dir = Path::new(config_dir).join("worktrees");
     if !worktrees_dir.exists() {
-        return Ok(());
+        return Ok(report);
     }
+    let root = worktrees_dir
+        .canonicalize()
+        .map_err(|e| CmdError::Failed(format!("resolve worktrees dir: {}", e)))?;
 
     let entries = std::fs::read_dir(&worktrees_dir)
         .map_err(|e| CmdError::Failed(format!("read worktrees dir: {}", e)))?;
@@ -155,49 +239,103 @@ pub fn cleanup_worktrees(config_dir: &str, cmd: &dyn CmdExec) -> Result<(), CmdE
     for entry in entries {
         let entry = entry.map_err(|e| CmdError::Failed(format!("read dir entry: {}", e)))?;
         let path = entry.path();
-        if path.is_dir() {
-            let git_dir = path.join(".git");
-            if git_dir.exists() {
-                // Read the .git file to find the main repo
-                if let Ok(content) = std::fs::read_to_string(&git_dir)
-                    && let Some(gitdir) = content.strip_prefix("gitdir: ")
+        // A symlink could point anywhere; never follow one out of the root
+        if entry.file_type().is_ok_and(|t| t.is_symlink()) {
+            report
+                .skipped
+                .push(format!("{}: symlink, not a session worktree", path.display()));
+            continue;
+        }
+        if !path.is_dir() {
+            continue;
+        }
+        if !path.canonicalize().is_ok_and(|p| p.starts_with(&root)) {
+            report
+                .skipped
+                .push(format!("{}: outside {}", path.display(), root.display()));
+            continue;
+        }
+
+        let git_dir = path.join(".git");
+        // Read the .git file to find the main repo
+        if let Ok(content) = std::fs::read_to_string(&git_dir)
+            && let Some(gitdir) = content.strip_prefix("gitdir: ")
+        {
+            let gitdir = gitdir.trim();
+            // The gitdir points to .git/worktrees/<name> in the main repo
+            if let Some(main_git) = Path::new(gitdir).parent().and_then(|p| p.parent()) {
+                let main_repo = main_git.parent().unwrap_or(main_git);
+                let repo_str = main_repo.to_string_lossy().to_string();
+
+                // Find the branch associated with this worktree, deleted after
+                // the prune below
+                if let Ok(head) = std::fs::read_to_string(Path::new(gitdir).join("HEAD"))
+                    && let Some(branch) = head.trim().strip_prefix("ref: refs/heads/")
                 {
-                    let gitdir = gitdir.trim();
-                    // The gitdir points to .git/worktrees/<name> in the main repo
-                    if let Some(main_git) =
-                        Path::new(gitdir).parent().and_then(|p| p.parent())
+                    if let Some(reason) =
+                        branch_kept(&repo_str, branch, branch_prefix, owned, cmd)
                     {
-                        let main_repo = main_git.parent().unwrap_or(main_git);
-                        let repo_str = main_repo.to_string_lossy().to_string();
-
-                        // Find and delete the branch associated with this worktree
-                        if let Ok(head) = std::fs::read_to_string(
-                            Path::new(gitdir).join("HEAD"),
-                        ) {
-                            if let Some(branch_ref) = head.trim().strip_prefix("ref: refs/heads/") {
-                                let _ = cmd.run(
-                                    "git",
-                                    &args(&["-C", &repo_str, "branch", "-D", branch_ref]),
-                                );
-                            }
-                        }
-
-                        repos.insert(repo_str);
+                        report
+                            .skipped
+                            .push(format!("branch {} in {}: {}", branch, repo_str, reason));
+                    } else {
+                        report.branches.push((repo_str.clone(), branch.to_string()));
                     }
                 }
+
+                repos.insert(repo_str);
             }
+        }
 
-            // Remove the directory
+        // Remove the directory
+        if !dry_run {
             let _ = std::fs::remove_dir_all(&path);
         }
+        report.dirs.push(path);
     }
 
-    // Prune all affected repos
-    for repo in &repos {
-        let _ = cmd.run("git", &args(&["-C", repo, "worktree", "prune"]));
+    // Prune all affected repos, then delete the branches, which git refuses
+    // while a worktree still has them checked out
+    if !dry_run {
+        for repo in &repos {
+            let _ = cmd.run("git", &args(&["-C", repo, "worktree", "prune"]));
+        }
+        for (repo, branch) in &report.branches {
+            let _ = cmd.run("git", &args(&["-C", repo, "branch", "-D", branch]));
+        }
     }
 
-    Ok(())
+    Ok(report)
+}
+
+/// Why `branch` must survive a cleanup, if it must: no session in `owned`
+/// created it or it lacks gana's branch prefix, so it may be the user's
+/// own, or it's checked out in `repo`. Without a prefix every branch could
+/// be the user's, so none is deleted.
+fn branch_kept(
+    repo: &str,
+    branch: &str,
+    branch_prefix: &str,
+    owned: &[(String, String)],
+    cmd: &dyn CmdExec,
+) -> Option<String> {
+    if branch_prefix.is_empty() {
+        return Some("no branch_prefix is set to tell gana's branches apart".to_string());
+    }
+    if !branch.starts_with(branch_prefix) {
+        return Some(format!("doesn't start with the branch prefix {:?}", branch_prefix));
+    }
+    let canonical = |p: &str| std::fs::canonicalize(p).unwrap_or_else(|_| p.into());
+    if !owned.iter().any(|(r, b)| b == branch && canonical(r) == canonical(repo)) {
+        return Some("not created by a gana session".to_string());
+    }
+    let checked_out = cmd
+        .output("git", &args(&["-C", repo, "symbolic-ref", "--quiet", "--short", "HEAD"]))
+        .ok();
+    if checked_out.as_deref().map(str::trim) == Some(branch) {
+        return Some("checked out in the main repo".to_string());
+    }
+    None
 }
 
 #[cfg(test)]
@@ -285,6 +423,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_setup_on_remote_branch_tracks_it() {
+        let upstream = setup_test_repo();
+        let cmd = SystemCmdExec;
+        let upstream_path = upstream.path().to_string_lossy().to_string();
+        cmd.run("git", &args(&["-C", &upstream_path, "branch", "colleague-pr"])).unwrap();
+
+        let clone_parent = tempfile::TempDir::new().unwrap();
+        let clone_path = clone_parent.path().join("clone").to_string_lossy().to_string();
+        cmd.run("git", &args(&["clone", "-q", &upstream_path, &clone_path])).unwrap();
+
+        let wt_dir = tempfile::TempDir::new().unwrap();
+        let wt_path_str = wt_dir.path().join("wt").to_string_lossy().to_string();
+        let mut wt = GitWorktree::from_storage(
+            clone_path.clone(),
+            wt_path_str.clone(),
+            "sess".to_string(),
+            "placeholder".to_string(),
+            String::new(),
+        );
+
+        wt.setup_on_branch("origin/colleague-pr", &cmd).expect("setup should succeed");
+        assert_eq!(wt.branch(), "colleague-pr");
+        assert!(!wt.base_commit_sha().is_empty());
+        let upstream_ref = cmd
+            .output(
+                "git",
+                &args(&["-C", &wt_path_str, "rev-parse", "--abbrev-ref", "@{upstream}"]),
+            )
+            .unwrap();
+        assert_eq!(upstream_ref.trim(), "origin/colleague-pr");
+    }
+
+    #[test]
+    fn test_setup_on_branch_of_other_remote_strips_its_name() {
+        let upstream = setup_test_repo();
+        let cmd = SystemCmdExec;
+        let upstream_path = upstream.path().to_string_lossy().to_string();
+        cmd.run("git", &args(&["-C", &upstream_path, "branch", "colleague-pr"])).unwrap();
+
+        let clone_parent = tempfile::TempDir::new().unwrap();
+        let clone_path = clone_parent.path().join("clone").to_string_lossy().to_string();
+        cmd.run("git", &args(&["clone", "-q", "-o", "team/fork", &upstream_path, &clone_path]))
+            .unwrap();
+
+        let wt_dir = tempfile::TempDir::new().unwrap();
+        let wt_path_str = wt_dir.path().join("wt").to_string_lossy().to_string();
+        let mut wt = GitWorktree::from_storage(
+            clone_path,
+            wt_path_str,
+            "sess".to_string(),
+            "placeholder".to_string(),
+            String::new(),
+        );
+
+        wt.setup_on_branch("team/fork/colleague-pr", &cmd).expect("setup should succeed");
+        assert_eq!(wt.branch(), "colleague-pr");
+    }
+
     #[test]
     fn test_remove_keeps_branch() {
         let repo = setup_test_repo();
@@ -363,4 +560,104 @@ mod tests {
 
         wt.cleanup(&cmd).unwrap();
     }
+
+    #[test]
+    fn test_setup_refuses_branch_checked_out_elsewhere() {
+        let repo = setup_test_repo();
+        let cmd = SystemCmdExec;
+        let repo_path = repo.path().to_string_lossy().to_string();
+        let main = cmd
+            .output("git", &args(&["-C", &repo_path, "symbolic-ref", "--short", "HEAD"]))
+            .unwrap()
+            .trim()
+            .to_string();
+
+        let wt_dir = tempfile::TempDir::new().unwrap();
+        let wt_path_str = wt_dir.path().join("wt").to_string_lossy().to_string();
+        let wt = GitWorktree::from_storage(
+            repo_path.clone(),
+            wt_path_str.clone(),
+            "sess".to_string(),
+            main.clone(),
+            String::new(),
+        );
+
+        let err = wt.setup(&cmd).unwrap_err();
+        assert!(err.to_string().contains("already checked out"), "{}", err);
+        assert!(repo.path().join("test.txt").exists(), "the main checkout is untouched");
+        assert!(!Path::new(&wt_path_str).exists());
+    }
+
+    #[test]
+    fn test_cleanup_worktrees_keeps_foreign_branches() {
+        let repo = setup_test_repo();
+        let cmd = SystemCmdExec;
+        let repo_path = repo.path().to_string_lossy().to_string();
+        let base = cmd
+            .output("git", &args(&["-C", &repo_path, "rev-parse", "HEAD"]))
+            .unwrap()
+            .trim()
+            .to_string();
+
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let config_dir_str = config_dir.path().to_string_lossy().to_string();
+        for (name, branch) in [("ours", "gana/ours"), ("mine", "feature/mine")] {
+            let path = config_dir.path().join("worktrees").join(name);
+            GitWorktree::from_storage(
+                repo_path.clone(),
+                path.to_string_lossy().to_string(),
+                name.to_string(),
+                branch.to_string(),
+                base.clone(),
+            )
+            .setup(&cmd)
+            .unwrap();
+        }
+
+        // A prefixed branch no session recorded
+        let stray = config_dir.path().join("worktrees/stray").to_string_lossy().to_string();
+        cmd.run("git", &args(&["-C", &repo_path, "worktree", "add", "-q", "-b", "gana/stray", &stray]))
+            .unwrap();
+        let owned = [(repo_path.clone(), "gana/ours".to_string())];
+
+        let plan = cleanup_worktrees(&config_dir_str, "gana/", &owned, true, &cmd).unwrap();
+        assert_eq!(plan.dirs.len(), 3);
+        assert_eq!(plan.branches.len(), 1);
+        assert_eq!(plan.branches[0].1, "gana/ours");
+        assert_eq!(plan.skipped.len(), 2);
+        assert!(plan.skipped.iter().any(|s| s.contains("feature/mine")));
+        assert!(plan.skipped.iter().any(|s| s.contains("gana/stray") && s.contains("not created")));
+        assert!(config_dir.path().join("worktrees/ours").exists(), "dry run removes nothing");
+
+        let unprefixed = cleanup_worktrees(&config_dir_str, "", &owned, true, &cmd).unwrap();
+        assert!(unprefixed.branches.is_empty());
+
+        let done = cleanup_worktrees(&config_dir_str, "gana/", &owned, false, &cmd).unwrap();
+        assert_eq!(done, plan);
+        assert!(!config_dir.path().join("worktrees/ours").exists());
+        let branches = cmd
+            .output("git", &args(&["-C", &repo_path, "branch"]))
+            .unwrap();
+        assert!(!branches.contains("gana/ours"));
+        assert!(branches.contains("feature/mine"));
+        assert!(branches.contains("gana/stray"));
+    }
+
+    #[test]
+    fn test_branch_kept_when_checked_out_in_main_repo() {
+        let mut cmd = crate::cmd::MockCmdExec::new();
+        cmd.expect_output()
+            .returning(|_, _| Ok("gana/fix\n".to_string()));
+
+        let owned = [
+            ("/repo".to_string(), "gana/fix".to_string()),
+            ("/repo".to_string(), "gana/other".to_string()),
+        ];
+        assert!(branch_kept("/repo", "gana/fix", "gana/", &owned, &cmd)
+            .is_some_and(|r| r.contains("checked out")));
+        assert_eq!(branch_kept("/repo", "gana/other", "gana/", &owned, &cmd), None);
+        assert!(branch_kept("/repo", "main", "gana/", &owned, &cmd).is_some());
+        assert!(branch_kept("/repo", "gana/third", "gana/", &owned, &cmd).is_some());
+        assert!(branch_kept("/repo", "gana/other", "", &owned, &cmd).is_some());
+    }
 }
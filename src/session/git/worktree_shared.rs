@@ -0,0 +1,199 @@
+use std::io::Write;
+use std::path::Path;
+
+use crate::cmd::{args, CmdError, CmdExec};
+use crate::config::LinkMode;
+
+use super::worktree::GitWorktree;
+
+impl GitWorktree {
+    /// Share dependency/build directories from the main checkout with this
+    /// worktree so sessions skip reinstalling them.
+    ///
+    /// Directories missing from the main checkout, or already present in the
+    /// worktree (e.g. tracked files), are skipped. Returns the directories
+    /// that were linked.
+    pub fn link_shared_dirs(
+        &self,
+        dirs: &[String],
+        mode: LinkMode,
+        cmd: &dyn CmdExec,
+    ) -> Result<Vec<String>, CmdError> {
+        let mut linked = Vec::new();
+        for dir in dirs {
+            let rel = dir.trim_matches('/');
+            if rel.is_empty() || Path::new(rel).is_absolute() || rel.split('/').any(|c| c == "..") {
+                tracing::warn!("Ignoring shared dir '{}': must be relative to the repo root", dir);
+                continue;
+            }
+            let source = Path::new(&self.repo_path).join(rel);
+            let target = Path::new(&self.worktree_dir).join(rel);
+            if !source.is_dir() || target.symlink_metadata().is_ok() {
+                continue;
+            }
+            if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            match mode {
+                LinkMode::Symlink => std::os::unix::fs::symlink(&source, &target)?,
+                LinkMode::Hardlink => hardlink_tree(&source, &target)?,
+            }
+            linked.push(rel.to_string());
+        }
+
+        // Keep shared dirs out of `git add -A` explicitly: a symlink doesn't
+        // match directory-only ignore patterns like `node_modules/`, and the
+        // repo may not ignore a shared dir at all.
+        if !linked.is_empty() {
+            self.exclude_paths(&linked, cmd)?;
+        }
+        Ok(linked)
+    }
+
+    /// Append `/path` entries to the repo's `info/exclude` unless present.
+    fn exclude_paths(&self, paths: &[String], cmd: &dyn CmdExec) -> Result<(), CmdError> {
+        let common_dir = cmd
+            .output(
+                "git",
+                &args(&["-C", &self.worktree_dir, "rev-parse", "--path-format=absolute", "--git-common-dir"]),
+            )?
+            .trim()
+            .to_string();
+        let info_dir = Path::new(&common_dir).join("info");
+        std::fs::create_dir_all(&info_dir)?;
+        let exclude = info_dir.join("exclude");
+        let existing = std::fs::read_to_string(&exclude).unwrap_or_default();
+
+        let mut file = std::fs::OpenOptions::new().create(true).append(true).open(&exclude)?;
+        if !existing.is_empty() && !existing.ends_with('\n') {
+            writeln!(file)?;
+        }
+        for path in paths {
+            let pattern = format!("/{}", path);
+            if !existing.lines().any(|l| l.trim() == pattern) {
+                writeln!(file, "{}", pattern)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Recreate `source` at `target` with every file hardlinked. Symlinks are
+/// copied as symlinks.
+fn hardlink_tree(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(target)?;
+    for entry in std::fs::read_dir(source)? {
+        let entry = entry?;
+        let from = entry.path();
+        let to = target.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            hardlink_tree(&from, &to)?;
+        } else if file_type.is_symlink() {
+            std::os::unix::fs::symlink(std::fs::read_link(&from)?, &to)?;
+        } else {
+            std::fs::hard_link(&from, &to)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::SystemCmdExec;
+    use std::os::unix::fs::MetadataExt;
+    use tempfile::TempDir;
+
+    fn git(dir: &Path, git_args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(git_args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", git_args);
+    }
+
+    /// A repo with a gitignored `node_modules/` and a worktree of it.
+    fn repo_with_worktree() -> (TempDir, TempDir, GitWorktree) {
+        let repo = TempDir::new().unwrap();
+        let wt_parent = TempDir::new().unwrap();
+        git(repo.path(), &["init", "-q", "-b", "main"]);
+        git(repo.path(), &["config", "user.email", "t@example.com"]);
+        git(repo.path(), &["config", "user.name", "t"]);
+        std::fs::write(repo.path().join(".gitignore"), "node_modules/\n").unwrap();
+        git(repo.path(), &["add", "."]);
+        git(repo.path(), &["commit", "-qm", "init"]);
+        std::fs::create_dir_all(repo.path().join("node_modules/pkg")).unwrap();
+        std::fs::write(repo.path().join("node_modules/pkg/index.js"), "x").unwrap();
+
+        let wt_dir = wt_parent.path().join("wt");
+        git(repo.path(), &["worktree", "add", "-q", "-b", "s", wt_dir.to_str().unwrap()]);
+        let worktree = GitWorktree::from_storage(
+            repo.path().to_string_lossy().to_string(),
+            wt_dir.to_string_lossy().to_string(),
+            "s".to_string(),
+            "s".to_string(),
+            String::new(),
+        );
+        (repo, wt_parent, worktree)
+    }
+
+    #[test]
+    fn test_symlinked_dir_is_not_staged() {
+        let (_repo, _wt, worktree) = repo_with_worktree();
+        let dirs = vec!["node_modules".to_string(), "target".to_string()];
+
+        let linked = worktree.link_shared_dirs(&dirs, LinkMode::Symlink, &SystemCmdExec).unwrap();
+        assert_eq!(linked, vec!["node_modules"]);
+
+        let link = Path::new(&worktree.worktree_dir).join("node_modules");
+        assert!(link.symlink_metadata().unwrap().file_type().is_symlink());
+        assert!(link.join("pkg/index.js").exists());
+
+        let status = std::process::Command::new("git")
+            .args(["-C", &worktree.worktree_dir, "status", "--porcelain"])
+            .output()
+            .unwrap();
+        assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+
+        // Linking again is a no-op
+        let linked = worktree.link_shared_dirs(&dirs, LinkMode::Symlink, &SystemCmdExec).unwrap();
+        assert!(linked.is_empty());
+    }
+
+    #[test]
+    fn test_hardlinked_dir_shares_inodes() {
+        let (repo, _wt, worktree) = repo_with_worktree();
+        let dirs = vec!["node_modules".to_string(), "../escape".to_string()];
+
+        let linked = worktree.link_shared_dirs(&dirs, LinkMode::Hardlink, &SystemCmdExec).unwrap();
+        assert_eq!(linked, vec!["node_modules"]);
+
+        let original = repo.path().join("node_modules/pkg/index.js");
+        let copy = Path::new(&worktree.worktree_dir).join("node_modules/pkg/index.js");
+        assert!(copy.symlink_metadata().unwrap().is_file());
+        assert_eq!(
+            std::fs::metadata(&original).unwrap().ino(),
+            std::fs::metadata(&copy).unwrap().ino()
+        );
+    }
+
+    #[test]
+    fn test_hardlinked_dir_is_not_staged() {
+        let (repo, _wt, worktree) = repo_with_worktree();
+        // Not covered by the repo's .gitignore
+        std::fs::create_dir_all(repo.path().join(".venv/bin")).unwrap();
+        std::fs::write(repo.path().join(".venv/bin/python"), "x").unwrap();
+
+        let dirs = vec![".venv".to_string()];
+        worktree.link_shared_dirs(&dirs, LinkMode::Hardlink, &SystemCmdExec).unwrap();
+
+        let status = std::process::Command::new("git")
+            .args(["-C", &worktree.worktree_dir, "status", "--porcelain"])
+            .output()
+            .unwrap();
+        assert!(String::from_utf8_lossy(&status.stdout).trim().is_empty());
+    }
+}
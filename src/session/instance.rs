@@ -1,10 +1,19 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::cmd::{CmdExec, SystemCmdExec};
+use crate::config::{Config, GitIdentity, LinkMode, PromptPatterns};
+use crate::session::git::util::{self, GhStatus};
+use crate::session::git::worktree_git::{CommitOptions, MessageContext, PushOptions};
+use crate::session::container::Container;
+use crate::session::factory::{CreatedSession, SessionFactory};
 use crate::session::git::{DiffStats, GitWorktree};
+use crate::session::test_watch::TestStatus;
 use crate::session::tmux::pty::SystemPtyFactory;
-use crate::session::tmux::TmuxSession;
+use crate::session::tmux::{TmuxError, TmuxSession, TrustPrompt};
 
 /// Status of a session instance.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,8 +21,20 @@ use crate::session::tmux::TmuxSession;
 pub enum InstanceStatus {
     Ready,
     Running,
+    /// Running, but the agent finished its task and went idle.
+    Done,
     Loading,
     Paused,
+    /// Waiting for a free creation slot.
+    Queued,
+    /// Creation or restart failed; see [`Instance::error`].
+    Failed,
+    /// Waiting for the session named in [`Instance::after`] to finish.
+    Pending,
+    /// The worktree directory is gone but the branch is still there.
+    WorktreeMissing,
+    /// The branch (or the whole repo) is gone.
+    Orphaned,
 }
 
 impl std::fmt::Display for InstanceStatus {
@@ -21,18 +42,72 @@ impl std::fmt::Display for InstanceStatus {
         match self {
             InstanceStatus::Ready => write!(f, "ready"),
             InstanceStatus::Running => write!(f, "running"),
+            InstanceStatus::Done => write!(f, "done"),
             InstanceStatus::Loading => write!(f, "loading"),
+            InstanceStatus::Queued => write!(f, "queued"),
             InstanceStatus::Paused => write!(f, "paused"),
+            InstanceStatus::Failed => write!(f, "failed"),
+            InstanceStatus::Pending => write!(f, "pending"),
+            InstanceStatus::WorktreeMissing => write!(f, "worktree missing"),
+            InstanceStatus::Orphaned => write!(f, "orphaned"),
+        }
+    }
+}
+
+impl InstanceStatus {
+    /// Whether the agent's tmux session is up: running or done.
+    pub fn is_running(&self) -> bool {
+        matches!(self, InstanceStatus::Running | InstanceStatus::Done)
+    }
+}
+
+/// A slow status change running in the background; the session keeps its
+/// old status until it completes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition {
+    Pausing,
+    Resuming,
+    Killing,
+    /// Pausing before being removed from the list.
+    Closing,
+    /// Committing and removing a paused session's worktree.
+    CleaningUp,
+    /// Cleaning up before being moved to the archive.
+    Archiving,
+}
+
+impl Transition {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Transition::Pausing => "Pausing",
+            Transition::Resuming => "Resuming",
+            Transition::Killing => "Killing",
+            Transition::Closing => "Closing",
+            Transition::CleaningUp => "Cleaning up",
+            Transition::Archiving => "Archiving",
         }
     }
 }
 
 /// Options for creating a new Instance.
+#[derive(Default)]
 pub struct InstanceOptions {
     pub title: String,
     pub path: String,
     pub program: String,
     pub auto_yes: bool,
+    /// Author of commits in the session's worktree; the repo's own when `None`.
+    pub git_identity: Option<GitIdentity>,
+    /// Dependency dirs shared with the session's worktree (`Config::shared_dirs`).
+    pub shared_dirs: Vec<String>,
+    pub shared_dirs_mode: LinkMode,
+}
+
+/// A prompt sent to a session's agent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentPrompt {
+    pub text: String,
+    pub sent_at: DateTime<Utc>,
 }
 
 /// A session instance that manages a tmux session + git worktree pair.
@@ -44,22 +119,79 @@ pub struct Instance {
     pub status: InstanceStatus,
     pub program: String,
     pub auto_yes: bool,
+    /// Once the agent is done and `test_command` passes, commit, push and
+    /// open a draft PR.
+    #[serde(default)]
+    pub auto_push: bool,
     pub height: u16,
     pub width: u16,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     #[serde(default)]
     pub started: bool,
+    /// Number of times the daemon restarted the agent after it exited.
+    #[serde(default)]
+    pub restart_count: u32,
+    /// Prompt to send once the session finishes starting.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_prompt: Option<String>,
+    /// Why the session is [`InstanceStatus::Failed`], or what is missing
+    /// for [`InstanceStatus::WorktreeMissing`] and [`InstanceStatus::Orphaned`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Last lines the agent printed before it exited on its own.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_output: Option<String>,
+    /// Prompts sent to the agent, oldest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prompts: Vec<SentPrompt>,
 
     // Persisted — git worktree metadata survives restart
     #[serde(default)]
     pub git_worktree: Option<GitWorktree>,
+    /// Whether gana created the session's branch. Kill deletes only such
+    /// branches; one the user already had is kept, as are those of sessions
+    /// saved before this was recorded.
+    #[serde(default)]
+    pub owns_branch: bool,
 
     // Runtime-only fields (not serialized)
     #[serde(skip)]
     pub tmux_session: Option<TmuxSession>,
     #[serde(skip)]
     pub diff_stats: Option<DiffStats>,
+    /// Reconnecting to the tmux session in the background after startup.
+    #[serde(skip)]
+    pub restoring: bool,
+    /// Outcome of the last `test_command` run in the worktree.
+    #[serde(skip)]
+    pub test_status: Option<TestStatus>,
+    /// Pause, resume or kill running on a background thread.
+    #[serde(skip)]
+    pub transition: Option<Transition>,
+
+    // Creation options, persisted so an interrupted creation can rerun
+    /// Copy the main checkout's uncommitted changes into the new worktree.
+    #[serde(default)]
+    pub carry_changes: bool,
+    /// Check out this existing branch (local or `remote/branch`) instead of
+    /// creating a new one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub existing_branch: Option<String>,
+    /// Title of the session this one is created after (a pipeline step).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<String>,
+    /// Author of commits in the worktree, set up again on each resume.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_identity: Option<GitIdentity>,
+    /// Container the agent runs in, for container-isolated sessions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<Container>,
+    /// Dependency dirs linked into the worktree, set up again on each resume.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub shared_dirs: Vec<String>,
+    #[serde(default)]
+    pub shared_dirs_mode: LinkMode,
 }
 
 impl std::fmt::Debug for Instance {
@@ -87,15 +219,32 @@ impl Clone for Instance {
             status: self.status,
             program: self.program.clone(),
             auto_yes: self.auto_yes,
+            auto_push: self.auto_push,
             height: self.height,
             width: self.width,
             created_at: self.created_at,
             updated_at: self.updated_at,
             started: self.started,
+            restart_count: self.restart_count,
+            pending_prompt: self.pending_prompt.clone(),
+            error: self.error.clone(),
+            exit_output: self.exit_output.clone(),
+            prompts: self.prompts.clone(),
             // Runtime fields cannot be cloned (TmuxSession has Box<dyn ...>)
             tmux_session: None,
             git_worktree: self.git_worktree.clone(),
+            owns_branch: self.owns_branch,
             diff_stats: self.diff_stats.clone(),
+            restoring: self.restoring,
+            test_status: self.test_status.clone(),
+            transition: self.transition,
+            carry_changes: self.carry_changes,
+            existing_branch: self.existing_branch.clone(),
+            after: self.after.clone(),
+            git_identity: self.git_identity.clone(),
+            container: self.container.clone(),
+            shared_dirs: self.shared_dirs.clone(),
+            shared_dirs_mode: self.shared_dirs_mode,
         }
     }
 }
@@ -111,14 +260,31 @@ impl Instance {
             status: InstanceStatus::Ready,
             program: opts.program,
             auto_yes: opts.auto_yes,
+            auto_push: false,
             height: 0,
             width: 0,
             created_at: now,
             updated_at: now,
             started: false,
+            restart_count: 0,
+            pending_prompt: None,
+            error: None,
+            exit_output: None,
+            prompts: Vec::new(),
             tmux_session: None,
             git_worktree: None,
+            owns_branch: false,
             diff_stats: None,
+            restoring: false,
+            test_status: None,
+            transition: None,
+            carry_changes: false,
+            existing_branch: None,
+            after: None,
+            git_identity: opts.git_identity,
+            container: None,
+            shared_dirs: opts.shared_dirs,
+            shared_dirs_mode: opts.shared_dirs_mode,
         }
     }
 
@@ -127,68 +293,58 @@ impl Instance {
         self.updated_at = Utc::now();
     }
 
-    /// Start the instance: create git worktree + tmux session.
-    ///
-    /// If `first_time` is true, creates a new worktree and tmux session.
-    /// If false (restore), attaches to an existing tmux session.
-    pub fn start(&mut self, first_time: bool, cmd: &dyn CmdExec) -> Result<(), anyhow::Error> {
-        if first_time {
-            // Create GitWorktree
-            let worktree =
-                GitWorktree::new(&self.title, &self.path, &self.program, &self.title, cmd)?;
-
-            // Set up the worktree on disk
-            worktree.setup(cmd)?;
-
-            let worktree_path = worktree.worktree_path().to_string();
-            self.branch = worktree.branch().to_string();
-
-            // Create and start TmuxSession
-            let mut tmux = TmuxSession::new(
-                &self.title,
-                &self.program,
-                Box::new(SystemCmdExec),
-                Box::new(SystemPtyFactory),
-            );
-            tmux.start(&worktree_path)?;
-
-            self.tmux_session = Some(tmux);
-            self.git_worktree = Some(worktree);
-            self.status = InstanceStatus::Running;
-            self.started = true;
-        } else {
-            // Restore: attach to existing tmux session
-            let mut tmux = TmuxSession::new(
-                &self.title,
-                &self.program,
-                Box::new(SystemCmdExec),
-                Box::new(SystemPtyFactory),
-            );
-            tmux.restore()?;
-
-            self.tmux_session = Some(tmux);
-            self.status = InstanceStatus::Running;
-        }
-
+    /// Take over a session `SessionFactory::create` set up and mark it
+    /// running.
+    pub fn attach_created(&mut self, created: CreatedSession) {
+        self.branch = created.worktree.branch().to_string();
+        self.git_worktree = Some(created.worktree);
+        self.owns_branch = created.owns_branch;
+        self.tmux_session = Some(created.tmux);
+        self.status = InstanceStatus::Running;
+        self.started = true;
+        self.error = None;
+        self.exit_output = None;
         self.touch();
-        Ok(())
     }
 
     /// Restore a previously saved session by reconnecting to its tmux session.
     /// Called on app startup for instances loaded from disk.
     /// Does NOT create a new worktree — assumes it still exists on disk.
-    pub fn restore_session(&mut self) -> Result<(), anyhow::Error> {
+    pub fn restore_session(&mut self, prompts: PromptPatterns) -> Result<(), anyhow::Error> {
+        let tmux = Self::connect_tmux(&self.title, &self.program, prompts)?;
+        self.attach_tmux(tmux);
+        Ok(())
+    }
+
+    /// Open a monitoring PTY on the existing tmux session for `title`.
+    ///
+    /// Split from `restore_session` so the slow part can run off the UI thread.
+    pub fn connect_tmux(
+        title: &str,
+        program: &str,
+        prompts: PromptPatterns,
+    ) -> Result<TmuxSession, anyhow::Error> {
         let mut tmux = TmuxSession::new(
-            &self.title,
-            &self.program,
+            title,
+            program,
             Box::new(SystemCmdExec),
             Box::new(SystemPtyFactory),
-        );
+        )
+        .with_prompts(prompts);
         tmux.restore()?;
+        Ok(tmux)
+    }
+
+    /// Take ownership of a connected tmux session and mark the instance
+    /// running, unless its agent was already done.
+    pub fn attach_tmux(&mut self, tmux: TmuxSession) {
         self.tmux_session = Some(tmux);
         self.started = true;
-        self.status = InstanceStatus::Running;
-        Ok(())
+        self.restoring = false;
+        if self.status != InstanceStatus::Done {
+            self.status = InstanceStatus::Running;
+        }
+        self.exit_output = None;
     }
 
     /// Kill the instance: cleanup both tmux and git.
@@ -198,10 +354,15 @@ impl Instance {
             tmux.close()?;
         }
         self.tmux_session = None;
+        self.remove_container(cmd);
 
-        // Cleanup git worktree (removes directory, branch, and prunes)
+        // Remove the worktree, and the branch too if gana created it
         if let Some(ref worktree) = self.git_worktree {
-            worktree.cleanup(cmd)?;
+            if self.owns_branch {
+                worktree.cleanup(cmd)?;
+            } else {
+                worktree.remove(cmd)?;
+            }
         }
         self.git_worktree = None;
 
@@ -211,12 +372,30 @@ impl Instance {
         Ok(())
     }
 
+    /// Stop the session's container, if it runs in one.
+    fn remove_container(&self, cmd: &dyn CmdExec) {
+        if let Some(ref container) = self.container
+            && let Err(e) = container.remove(cmd)
+        {
+            tracing::warn!(session = %self.title, "Failed to remove container: {}", e);
+        }
+    }
+
+    /// Details of this session for commit message templates.
+    pub fn message_context(&self) -> MessageContext<'_> {
+        MessageContext {
+            session: &self.title,
+            program: &self.program,
+            branch: &self.branch,
+        }
+    }
+
     /// Pause: commit changes, remove worktree (keep branch), close tmux.
-    pub fn pause(&mut self, cmd: &dyn CmdExec) -> Result<(), anyhow::Error> {
+    pub fn pause(&mut self, opts: &CommitOptions, cmd: &dyn CmdExec) -> Result<(), anyhow::Error> {
         // Commit any changes with a timestamp message
         if let Some(ref worktree) = self.git_worktree {
-            let msg = format!("gana: auto-save {}", Utc::now().format("%Y-%m-%d %H:%M:%S"));
-            worktree.commit_changes(&msg, cmd)?;
+            let msg = opts.autosave_message(&self.message_context());
+            worktree.commit_changes(&msg, opts, cmd)?;
 
             // Remove worktree directory but keep the branch
             worktree.remove(cmd)?;
@@ -227,45 +406,110 @@ impl Instance {
             tmux.close()?;
         }
         self.tmux_session = None;
+        self.remove_container(cmd);
 
         self.status = InstanceStatus::Paused;
         self.touch();
         Ok(())
     }
 
-    /// Resume: recreate worktree from branch, restart tmux.
-    pub fn resume(&mut self, cmd: &dyn CmdExec) -> Result<(), anyhow::Error> {
+    /// Resume: recreate worktree from branch, restart tmux as `config` says.
+    pub fn resume(&mut self, cmd: &Arc<dyn CmdExec>, config: &Config) -> Result<(), anyhow::Error> {
+        // Sessions from before the identity was kept with the worktree
+        if let Some(ref mut worktree) = self.git_worktree
+            && worktree.identity.is_none()
+        {
+            worktree.identity = self.git_identity.clone();
+        }
         // Setup worktree (from existing branch)
         if let Some(ref worktree) = self.git_worktree {
-            worktree.setup(cmd)?;
-
-            let worktree_path = worktree.worktree_path().to_string();
-
-            // Start tmux session
-            let mut tmux = TmuxSession::new(
-                &self.title,
-                &self.program,
-                Box::new(SystemCmdExec),
-                Box::new(SystemPtyFactory),
-            );
-            tmux.start(&worktree_path)?;
-
-            self.tmux_session = Some(tmux);
+            worktree.setup(&**cmd)?;
+            let factory = SessionFactory::new(self, config.clone());
+            factory.share_dirs(worktree, &**cmd);
+            factory.configure(worktree, &**cmd);
+            self.tmux_session = Some(factory.launch(worktree, cmd)?);
         }
 
         self.status = InstanceStatus::Running;
+        self.exit_output = None;
         self.touch();
         Ok(())
     }
 
+    /// Record that the agent exited for good: the session is Ready again,
+    /// keeping `tail`, its pane's last output (see `PaneTails`), as why.
+    pub fn mark_exited(&mut self, tail: Option<String>) {
+        self.status = InstanceStatus::Ready;
+        self.tmux_session = None;
+        self.exit_output = tail;
+    }
+
     /// Push changes and create a PR.
-    pub fn push_and_pr(&mut self, cmd: &dyn CmdExec) -> Result<(), anyhow::Error> {
+    ///
+    /// With `squash`, the session's commits are first squashed into a single
+    /// commit with that message and the branch is force-pushed with lease.
+    /// `pr_body` replaces the default PR description. With `push.plain_git`
+    /// no PR is created; the URL to open one in the browser is returned.
+    /// With `push.draft` the PR's URL is returned instead of opened.
+    pub fn push_and_pr(
+        &mut self,
+        opts: &CommitOptions,
+        push: &PushOptions,
+        squash: Option<&str>,
+        pr_body: Option<&str>,
+        cmd: &dyn CmdExec,
+    ) -> Result<Option<String>, anyhow::Error> {
         if let Some(ref worktree) = self.git_worktree {
-            worktree.push_changes(&self.title, cmd)?;
-            let _ = worktree.create_pr(&self.title, cmd);
+            let message = opts.push_message(squash.unwrap_or(&self.title), &self.message_context());
+            let mut push = push.clone();
+            if squash.is_some() {
+                worktree.squash_commits(&message, opts, cmd)?;
+                push.force_with_lease = true;
+            }
+            worktree.push_changes(&message, opts, &push, cmd)?;
+            if push.plain_git {
+                let remote = worktree.push_remote(push.remote.as_deref(), cmd);
+                return Ok(worktree.compare_url(&remote, cmd));
+            }
+            let _ = worktree.create_pr(&self.title, pr_body, push.draft, cmd);
+            if push.draft {
+                return Ok(worktree.pr_url(cmd));
+            }
             let _ = worktree.open_branch_url(cmd);
         }
-        Ok(())
+        Ok(None)
+    }
+
+    /// Open the session's pull request, or its branch when there is no PR
+    /// yet, in the browser. Returns the URL opened.
+    ///
+    /// Without a usable `gh`, opens the branch compare page on `remote`.
+    pub fn open_in_browser(
+        &self,
+        gh: GhStatus,
+        remote: Option<&str>,
+        cmd: &dyn CmdExec,
+    ) -> Result<String, anyhow::Error> {
+        let worktree = self
+            .git_worktree
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("session has no git worktree"))?;
+
+        if gh == GhStatus::Ready {
+            if let Some(url) = worktree.pr_url(cmd) {
+                worktree.open_pr(cmd)?;
+                return Ok(url);
+            }
+            worktree.open_branch_url(cmd)?;
+            return Ok(worktree.branch_url(cmd).unwrap_or_default());
+        }
+
+        let remote = worktree.push_remote(remote, cmd);
+        let url = worktree.compare_url(&remote, cmd).ok_or_else(|| {
+            anyhow::anyhow!("no web URL for remote '{}'; {}", remote, gh.hint().unwrap_or_default())
+        })?;
+        util::open_url(&url, cmd)?;
+        Ok(url)
     }
 
     /// Attach interactively to the tmux session.
@@ -284,19 +528,53 @@ impl Instance {
             .and_then(|t| t.capture_pane_content(false).ok())
     }
 
-    /// Get full history from tmux pane.
-    pub fn preview_full_history(&self) -> Option<String> {
+    /// Get pane lines `start..=end` (negative = scrollback) from tmux.
+    pub fn preview_history(&self, start: i64, end: Option<i64>) -> Option<String> {
         self.tmux_session
             .as_ref()
-            .and_then(|t| t.capture_pane_content(true).ok())
+            .and_then(|t| t.capture_pane_range(start, end).ok())
     }
 
-    /// Send a prompt to the session.
-    pub fn send_prompt(&self, prompt: &str) {
-        if let Some(ref tmux) = self.tmux_session {
-            let _ = tmux.send_keys(prompt);
-            let _ = tmux.send_keys("Enter");
+    /// Number of scrollback lines above the visible pane.
+    pub fn history_size(&self) -> Option<usize> {
+        self.tmux_session.as_ref().and_then(|t| t.history_size().ok())
+    }
+
+    /// Send a prompt to the session, recording it in `prompts` once tmux
+    /// took it.
+    pub fn send_prompt(&mut self, prompt: &str) -> Result<(), TmuxError> {
+        let tmux = self
+            .tmux_session
+            .as_ref()
+            .ok_or_else(|| TmuxError::SessionNotFound(self.title.clone()))?;
+        tmux.send_keys(prompt)?;
+        tmux.send_keys("Enter")?;
+        self.prompts.push(SentPrompt {
+            text: prompt.to_string(),
+            sent_at: Utc::now(),
+        });
+        Ok(())
+    }
+
+    /// Send the queued prompt if the tmux session is attached.
+    ///
+    /// Returns the prompt when one was sent. The prompt stays queued when
+    /// there is no session to send it to, or sending it failed.
+    pub fn deliver_pending_prompt(&mut self) -> Option<String> {
+        self.tmux_session.as_ref()?;
+        let prompt = self.pending_prompt.take().filter(|p| !p.is_empty())?;
+        if let Err(e) = self.send_prompt(&prompt) {
+            tracing::warn!(session = %self.title, "Failed to send the queued prompt: {}", e);
+            self.pending_prompt = Some(prompt);
+            return None;
         }
+        Some(prompt)
+    }
+
+    /// The trust prompt to answer once the agent was started by creation or
+    /// `resume`, neither of which waits for it.
+    pub fn trust_prompt(&self) -> Option<TrustPrompt> {
+        self.tmux_session.as_ref()?.trust_prompt()
     }
 
     /// Send raw keys to the session.
@@ -335,6 +613,49 @@ impl Instance {
     pub fn repo_name(&self) -> Option<String> {
         self.git_worktree.as_ref().map(|w| w.repo_name().to_string())
     }
+
+    /// A new session titled `title` set up like this one, to try another
+    /// take on the same task: same repo, program, auto-yes/push, commit
+    /// author, carried changes and container image, on a fresh branch.
+    pub fn sibling(&self, title: String) -> Instance {
+        let mut sibling = Instance::new(InstanceOptions {
+            title,
+            path: self.project().to_string(),
+            program: self.program.clone(),
+            auto_yes: self.auto_yes,
+            git_identity: self.git_identity.clone(),
+            shared_dirs: self.shared_dirs.clone(),
+            shared_dirs_mode: self.shared_dirs_mode,
+        });
+        sibling.auto_push = self.auto_push;
+        sibling.carry_changes = self.carry_changes;
+        sibling.container = self.container.as_ref().map(|c| Container {
+            name: crate::session::tmux::sanitize_name(&sibling.title),
+            ..c.clone()
+        });
+        sibling
+    }
+
+    /// Root of the repo the session belongs to; the directory it was
+    /// created from until its worktree exists.
+    pub fn project(&self) -> &str {
+        self.git_worktree
+            .as_ref()
+            .map(|w| w.repo_path())
+            .unwrap_or(&self.path)
+    }
+
+    /// Canonical root of the session's repo, as `git rev-parse
+    /// --show-toplevel` gives it, also when the session was created from a
+    /// subdirectory. The directory itself outside a repo.
+    pub fn repo_root(&self) -> PathBuf {
+        if let Some(ref wt) = self.git_worktree {
+            return wt.repo_path().into();
+        }
+        util::find_git_repo_root(&SystemCmdExec, &self.path)
+            .unwrap_or_else(|_| self.path.clone())
+            .into()
+    }
 }
 
 #[cfg(test)]
@@ -346,7 +667,7 @@ mod tests {
             title: "test-session".to_string(),
             path: "/tmp/test".to_string(),
             program: "claude".to_string(),
-            auto_yes: false,
+            ..Default::default()
         })
     }
 
@@ -362,6 +683,65 @@ mod tests {
         assert_eq!(instance.program, "claude");
     }
 
+    #[test]
+    fn test_pending_prompt_kept_without_session() {
+        let mut instance = make_instance();
+        instance.pending_prompt = Some("write tests".to_string());
+        assert!(instance.deliver_pending_prompt().is_none());
+        assert_eq!(instance.pending_prompt.as_deref(), Some("write tests"));
+
+        // Survives a save/load round trip
+        let json = serde_json::to_string(&instance).unwrap();
+        let loaded: Instance = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.pending_prompt.as_deref(), Some("write tests"));
+    }
+
+    #[test]
+    fn test_sibling_copies_setup_not_branch() {
+        let mut instance = make_instance();
+        instance.program = "codex --full-auto".to_string();
+        instance.auto_push = true;
+        instance.branch = "gana/test-session".to_string();
+        instance.existing_branch = Some("origin/pr-1".to_string());
+        instance.container = Some(Container {
+            runtime: "podman".to_string(),
+            image: "agents:latest".to_string(),
+            name: "gana_test-session".to_string(),
+        });
+        instance.shared_dirs = vec!["node_modules".to_string()];
+        instance.status = InstanceStatus::Running;
+
+        let sibling = instance.sibling("take-2".to_string());
+        assert_eq!(sibling.title, "take-2");
+        assert_eq!(sibling.path, "/tmp/test");
+        assert_eq!(sibling.program, "codex --full-auto");
+        assert!(sibling.auto_push);
+        assert_eq!(sibling.status, InstanceStatus::Ready);
+        assert!(sibling.branch.is_empty());
+        assert!(sibling.existing_branch.is_none());
+        assert_eq!(sibling.shared_dirs, vec!["node_modules"]);
+        let container = sibling.container.unwrap();
+        assert_eq!(container.image, "agents:latest");
+        assert_eq!(container.name, crate::session::tmux::sanitize_name("take-2"));
+    }
+
+    #[test]
+    fn test_prompt_history_persists() {
+        let mut instance = make_instance();
+        // Nothing is recorded when there's no session to send it to
+        assert!(instance.send_prompt("lost").is_err());
+        assert!(instance.prompts.is_empty());
+        assert!(!serde_json::to_string(&instance).unwrap().contains("\"prompts\""));
+
+        instance.prompts.push(SentPrompt {
+            text: "write tests".to_string(),
+            sent_at: Utc::now(),
+        });
+        let json = serde_json::to_string(&instance).unwrap();
+        let loaded: Instance = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.prompts, instance.prompts);
+    }
+
     #[test]
     fn test_instance_pause_status() {
         let mut instance = make_instance();
@@ -415,6 +795,28 @@ mod tests {
         assert!(loaded.diff_stats.is_none());
     }
 
+    #[test]
+    fn test_send_prompt_records_only_what_tmux_took() {
+        use crate::cmd::MockCmdExec;
+
+        let mut instance = make_instance();
+        let mut mock = MockCmdExec::new();
+        mock.expect_run()
+            .returning(|_, _| Err(crate::cmd::CmdError::Failed("no session".to_string())));
+        instance.tmux_session = Some(TmuxSession::new(
+            "sess",
+            "claude",
+            Box::new(mock),
+            Box::new(SystemPtyFactory),
+        ));
+        instance.pending_prompt = Some("write tests".to_string());
+
+        assert!(instance.send_prompt("fix it").is_err());
+        assert!(instance.deliver_pending_prompt().is_none());
+        assert!(instance.prompts.is_empty());
+        assert_eq!(instance.pending_prompt.as_deref(), Some("write tests"));
+    }
+
     #[test]
     fn test_instance_diff_stats() {
         use crate::cmd::MockCmdExec;
@@ -448,6 +850,32 @@ mod tests {
         assert!(stats.error.is_none());
     }
 
+    #[test]
+    fn test_open_in_browser_prefers_pr() {
+        use crate::cmd::MockCmdExec;
+
+        let mut instance = make_instance();
+        instance.git_worktree = Some(GitWorktree::from_storage(
+            "/repo".to_string(),
+            "/worktree".to_string(),
+            "sess".to_string(),
+            "gana/test".to_string(),
+            "abc123".to_string(),
+        ));
+
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|name, args| name == "gh" && args[0] == "pr" && args.iter().any(|a| a == "--json"))
+            .returning(|_, _| Ok("https://github.com/acme/app/pull/7\n".to_string()));
+        mock.expect_run()
+            .withf(|name, args| name == "gh" && args.iter().any(|a| a == "--web"))
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let url = instance.open_in_browser(GhStatus::Ready, None, &mock).unwrap();
+        assert_eq!(url, "https://github.com/acme/app/pull/7");
+    }
+
     #[test]
     fn test_instance_repo_name() {
         let mut instance = make_instance();
@@ -491,4 +919,29 @@ mod tests {
             "/repo"
         );
     }
+
+    #[test]
+    fn test_kill_deletes_only_owned_branches() {
+        use crate::cmd::MockCmdExec;
+
+        for owns_branch in [false, true] {
+            let mut instance = make_instance();
+            instance.owns_branch = owns_branch;
+            instance.git_worktree = Some(GitWorktree::from_storage(
+                "/repo".to_string(),
+                "/nonexistent/wt".to_string(),
+                "s".to_string(),
+                "feature".to_string(),
+                "c".to_string(),
+            ));
+
+            let mut mock = MockCmdExec::new();
+            mock.expect_run()
+                .withf(|_, args| args.iter().any(|a| a == "-D"))
+                .times(owns_branch as usize)
+                .returning(|_, _| Ok(()));
+            mock.expect_run().returning(|_, _| Ok(()));
+            instance.kill(&mock).unwrap();
+        }
+    }
 }
@@ -0,0 +1,149 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+const JOURNAL_FILE: &str = "events.jsonl";
+
+/// The journal is moved aside to `events.jsonl.old` past this size.
+const MAX_JOURNAL_BYTES: u64 = 1024 * 1024;
+
+/// Notable things that happened to a session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Created,
+    PromptSent,
+    AttentionNeeded,
+    AutoResponse,
+    Pushed,
+    Died,
+    /// The agent finished its task and went idle.
+    Done,
+}
+
+impl EventKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventKind::Created => "created",
+            EventKind::PromptSent => "prompt sent",
+            EventKind::AttentionNeeded => "needs attention",
+            EventKind::AutoResponse => "auto-approved",
+            EventKind::Pushed => "pushed",
+            EventKind::Died => "died",
+            EventKind::Done => "done",
+        }
+    }
+}
+
+/// One journal entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Event {
+    pub at: DateTime<Utc>,
+    pub session: String,
+    pub kind: EventKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// Append-only log of session events, shared by the TUI, the daemon and the
+/// CLI through `events.jsonl` in the config directory.
+pub struct Journal {
+    path: PathBuf,
+}
+
+impl Journal {
+    pub fn new(config_dir: &Path) -> Self {
+        Self {
+            path: config_dir.join(JOURNAL_FILE),
+        }
+    }
+
+    /// Record an event, and log it to the session's log. Failures are
+    /// logged, never surfaced: the journal is informational and must not
+    /// get in the way of the action itself.
+    pub fn record(&self, session: &str, kind: EventKind, detail: Option<&str>) {
+        match detail {
+            Some(detail) => tracing::info!(session = %session, "{}: {}", kind.label(), detail),
+            None => tracing::info!(session = %session, "{}", kind.label()),
+        }
+        let event = Event {
+            at: Utc::now(),
+            session: session.to_string(),
+            kind,
+            detail: detail.map(str::to_string),
+        };
+        if let Err(e) = self.append(&event) {
+            tracing::warn!(session = %session, "Failed to record {:?} in the journal: {}", kind, e);
+        }
+    }
+
+    fn append(&self, event: &Event) -> std::io::Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        if std::fs::metadata(&self.path).is_ok_and(|m| m.len() > MAX_JOURNAL_BYTES) {
+            std::fs::rename(&self.path, self.path.with_extension("jsonl.old"))?;
+        }
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        let line = serde_json::to_string(event).map_err(std::io::Error::other)?;
+        writeln!(file, "{}", line)
+    }
+
+    /// The last `limit` events, oldest first. Unreadable lines are skipped.
+    pub fn recent(&self, limit: usize) -> Vec<Event> {
+        let Ok(contents) = std::fs::read_to_string(&self.path) else {
+            return Vec::new();
+        };
+        let mut events: Vec<Event> = contents
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect();
+        events.reverse();
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_read_recent() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = Journal::new(dir.path());
+        assert!(journal.recent(10).is_empty());
+
+        journal.record("a", EventKind::Created, None);
+        journal.record("a", EventKind::PromptSent, Some("fix tests"));
+        journal.record("b", EventKind::Died, None);
+
+        let events = journal.recent(2);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].kind, EventKind::PromptSent);
+        assert_eq!(events[0].detail.as_deref(), Some("fix tests"));
+        assert_eq!(events[1].session, "b");
+    }
+
+    #[test]
+    fn test_recent_skips_bad_lines() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let journal = Journal::new(dir.path());
+        journal.record("a", EventKind::Pushed, None);
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(dir.path().join(JOURNAL_FILE))
+            .unwrap();
+        writeln!(file, "{{not json").unwrap();
+        journal.record("a", EventKind::AutoResponse, None);
+
+        let kinds: Vec<EventKind> = journal.recent(10).iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, [EventKind::Pushed, EventKind::AutoResponse]);
+    }
+}
@@ -0,0 +1,99 @@
+use crate::cmd::{CmdExec, quote};
+use crate::config::{Config, GitIdentity, per_program};
+use crate::session::container::{Container, ContainerError};
+use crate::session::git::GitWorktree;
+use crate::session::{resources, sandbox};
+
+/// What a session's tmux pane runs: `program` in its freshly started
+/// container for container sessions, else `program` through its
+/// `sandbox_commands` entry, if any. The program's `resource_limits` are
+/// applied either way, and `identity` authors the agent's commits.
+pub fn pane_command(
+    container: Option<&Container>,
+    worktree: &GitWorktree,
+    program: &str,
+    identity: Option<&GitIdentity>,
+    config: &Config,
+    cmd: &dyn CmdExec,
+) -> Result<String, ContainerError> {
+    let limits = per_program(&config.resource_limits, program);
+    match container {
+        Some(container) => {
+            let mut run_args = config.container_args.clone();
+            if let Some(limits) = limits {
+                run_args.extend(resources::container_args(limits));
+            }
+            // Set inside the container, where the agent runs
+            container.launch(worktree, &with_identity(program, identity), &run_args, cmd)
+        }
+        None => {
+            let command = sandbox::wrap(program, worktree, config);
+            let command = match limits {
+                Some(limits) => resources::limit(&command, limits, cmd),
+                None => command,
+            };
+            Ok(with_identity(&command, identity))
+        }
+    }
+}
+
+/// `command` run with `identity` as the author and committer of the
+/// commits it makes, through git's environment rather than the repo's
+/// config.
+fn with_identity(command: &str, identity: Option<&GitIdentity>) -> String {
+    match identity {
+        Some(identity) => {
+            let (name, email) = (quote(&identity.name), quote(&identity.email));
+            format!(
+                "env GIT_AUTHOR_NAME={name} GIT_AUTHOR_EMAIL={email} \
+                 GIT_COMMITTER_NAME={name} GIT_COMMITTER_EMAIL={email} {command}"
+            )
+        }
+        None => command.to_string(),
+    }
+}
+
+/// `program` with its `resume_flags` entry, which makes the agent continue
+/// its last conversation in the worktree. `None` when it has none.
+pub fn resume_command(program: &str, config: &Config) -> Option<String> {
+    per_program(&config.resume_flags, program).map(|flag| format!("{} {}", program, flag))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resume_command_appends_program_flag() {
+        let mut config = Config::default();
+        config.resume_flags.insert("claude".to_string(), "--continue".to_string());
+        assert_eq!(
+            resume_command("claude --model opus", &config).as_deref(),
+            Some("claude --model opus --continue")
+        );
+        assert_eq!(resume_command("aider", &config), None);
+    }
+
+    #[test]
+    fn test_pane_command_sets_identity() {
+        let worktree = GitWorktree::from_storage(
+            "/repo".to_string(),
+            "/wt".to_string(),
+            "s".to_string(),
+            "s".to_string(),
+            "abc".to_string(),
+        );
+        let bot = GitIdentity {
+            name: "gana bot".to_string(),
+            email: "bot@example.com".to_string(),
+        };
+        let cmd = crate::cmd::MockCmdExec::new();
+        let config = Config::default();
+        assert_eq!(pane_command(None, &worktree, "claude", None, &config, &cmd).unwrap(), "claude");
+        assert_eq!(
+            pane_command(None, &worktree, "claude", Some(&bot), &config, &cmd).unwrap(),
+            "env GIT_AUTHOR_NAME='gana bot' GIT_AUTHOR_EMAIL='bot@example.com' \
+             GIT_COMMITTER_NAME='gana bot' GIT_COMMITTER_EMAIL='bot@example.com' claude"
+        );
+    }
+}
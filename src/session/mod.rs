@@ -1,7 +1,24 @@
+pub mod attention;
+pub mod container;
+pub mod disk_usage;
+pub mod expiry;
+pub mod factory;
 pub mod git;
 pub mod instance;
+pub mod pipeline;
+pub mod journal;
+pub mod launch;
+pub mod pr_draft;
+pub mod reconcile;
+pub mod resources;
+pub mod sandbox;
 pub mod storage;
+pub mod team;
+pub mod summary;
+pub mod test_watch;
+pub mod title;
 pub mod tmux;
+pub mod transfer;
 
 #[allow(unused_imports)]
 pub use instance::{Instance, InstanceOptions, InstanceStatus};
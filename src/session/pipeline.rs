@@ -0,0 +1,84 @@
+use chrono::{DateTime, Duration, Utc};
+
+use super::instance::{Instance, InstanceStatus};
+
+/// How long a running upstream agent must be quiet to count as finished.
+pub const UPSTREAM_IDLE: Duration = Duration::seconds(90);
+
+/// Whether the session a pending session waits on has finished.
+///
+/// `upstream` is `None` when it no longer exists. A running upstream is
+/// finished once its prompt was delivered and its pane has been quiet for
+/// [`UPSTREAM_IDLE`]; `last_activity` is its tmux pane's last output. One
+/// whose agent is done is finished right away.
+/// Sessions still being set up, or whose creation failed, hold their
+/// dependents back.
+pub fn upstream_finished(
+    upstream: Option<&Instance>,
+    last_activity: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    let Some(upstream) = upstream else {
+        return true;
+    };
+    match upstream.status {
+        InstanceStatus::Ready | InstanceStatus::Paused => upstream.started || upstream.git_worktree.is_some(),
+        InstanceStatus::Done => upstream.pending_prompt.is_none(),
+        InstanceStatus::Running => {
+            upstream.pending_prompt.is_none()
+                && last_activity.is_some_and(|at| now - at.max(upstream.updated_at) >= UPSTREAM_IDLE)
+        }
+        InstanceStatus::Loading
+        | InstanceStatus::Queued
+        | InstanceStatus::Pending
+        | InstanceStatus::Failed
+        | InstanceStatus::WorktreeMissing
+        | InstanceStatus::Orphaned => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::instance::InstanceOptions;
+
+    fn upstream(status: InstanceStatus) -> Instance {
+        let mut inst = Instance::new(InstanceOptions {
+            title: "refactor-core".to_string(),
+            path: "/tmp".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        inst.status = status;
+        inst.started = true;
+        inst.updated_at = Utc::now() - Duration::hours(1);
+        inst
+    }
+
+    #[test]
+    fn test_running_upstream_finishes_when_quiet() {
+        let now = Utc::now();
+        let running = upstream(InstanceStatus::Running);
+        assert!(!upstream_finished(Some(&running), Some(now - Duration::seconds(10)), now));
+        assert!(upstream_finished(Some(&running), Some(now - Duration::minutes(5)), now));
+        // Activity unknown: keep waiting
+        assert!(!upstream_finished(Some(&running), None, now));
+
+        let mut owed = upstream(InstanceStatus::Running);
+        owed.pending_prompt = Some("refactor".to_string());
+        assert!(!upstream_finished(Some(&owed), Some(now - Duration::minutes(5)), now));
+
+        let done = upstream(InstanceStatus::Done);
+        assert!(upstream_finished(Some(&done), None, now));
+    }
+
+    #[test]
+    fn test_stopped_or_missing_upstream_releases() {
+        let now = Utc::now();
+        assert!(upstream_finished(None, None, now));
+        assert!(upstream_finished(Some(&upstream(InstanceStatus::Ready)), None, now));
+        assert!(upstream_finished(Some(&upstream(InstanceStatus::Paused)), None, now));
+        assert!(!upstream_finished(Some(&upstream(InstanceStatus::Queued)), None, now));
+        assert!(!upstream_finished(Some(&upstream(InstanceStatus::Failed)), None, now));
+    }
+}
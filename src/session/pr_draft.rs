@@ -0,0 +1,130 @@
+use std::time::{Duration, Instant};
+
+use crate::cmd::{args, CmdError, CmdExec};
+use crate::session::tmux::sanitize_name;
+
+/// Marker lines the agent is asked to wrap the PR description in.
+const BEGIN_MARKER: &str = "BEGIN_PR_BODY";
+const END_MARKER: &str = "END_PR_BODY";
+
+/// Scrollback searched for the drafted description.
+const CAPTURE_LINES: &str = "-3000";
+
+/// Prompt asking the agent to describe the changes on its branch.
+pub fn draft_prompt(base_commit: &str) -> String {
+    let range = if base_commit.is_empty() {
+        "on this branch".to_string()
+    } else {
+        format!("since commit {}", base_commit)
+    };
+    format!(
+        "Write a pull request description for the changes {} (see git log and git diff). \
+         Summarize what changed and why, and how it was tested. Do not change any files. \
+         Print the description in markdown between a line containing only {} and a line \
+         containing only {}.",
+        range, BEGIN_MARKER, END_MARKER
+    )
+}
+
+/// Extract the last description printed between the marker lines.
+///
+/// Markers must stand alone on their line (ignoring indentation and an
+/// agent's bullet glyph), so the echoed prompt itself never matches.
+pub fn extract_body(pane: &str) -> Option<String> {
+    let lines: Vec<&str> = pane.lines().collect();
+    let is_marker = |line: &str, marker: &str| {
+        line.trim().trim_start_matches(['⏺', '●', '•']).trim() == marker
+    };
+    let end = lines.iter().rposition(|l| is_marker(l, END_MARKER))?;
+    let begin = lines[..end].iter().rposition(|l| is_marker(l, BEGIN_MARKER))?;
+    let body = &lines[begin + 1..end];
+
+    // Agents indent their replies; drop the common indentation
+    let indent = body
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| l.len() - l.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let text = body
+        .iter()
+        .map(|l| l.get(indent..).unwrap_or("").trim_end())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let text = text.trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Ask the agent in tmux session `title` for a PR description and wait for it.
+///
+/// Blocks until the description appears in the pane or `timeout` passes.
+pub fn request_draft(
+    title: &str,
+    base_commit: &str,
+    timeout: Duration,
+    cmd: &dyn CmdExec,
+) -> Result<String, CmdError> {
+    let target = sanitize_name(title);
+    let capture = || {
+        cmd.output(
+            "tmux",
+            &args(&["capture-pane", "-p", "-J", "-S", CAPTURE_LINES, "-t", &target]),
+        )
+    };
+    // An earlier draft in the scrollback must not be mistaken for the new one
+    let previous = capture().ok().and_then(|pane| extract_body(&pane));
+
+    cmd.run("tmux", &args(&["send-keys", "-t", &target, "-l", &draft_prompt(base_commit)]))?;
+    cmd.run("tmux", &args(&["send-keys", "-t", &target, "Enter"]))?;
+
+    let start = Instant::now();
+    while start.elapsed() < timeout {
+        std::thread::sleep(Duration::from_secs(2));
+        if let Some(body) = capture().ok().and_then(|pane| extract_body(&pane))
+            && Some(&body) != previous.as_ref()
+        {
+            return Ok(body);
+        }
+    }
+    Err(CmdError::Failed(format!(
+        "the agent did not produce a PR description within {}s",
+        timeout.as_secs()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_ignores_echoed_prompt() {
+        let pane = format!("> {}\n\n  Thinking…\n", draft_prompt("abc123"));
+        assert_eq!(extract_body(&pane), None);
+    }
+
+    #[test]
+    fn test_extract_dedents_agent_reply() {
+        let pane = "\
+> Write a pull request description … only BEGIN_PR_BODY and … only END_PR_BODY.
+
+⏺ BEGIN_PR_BODY
+  ## Summary
+  Adds login.
+
+    - indented item
+  END_PR_BODY
+
+> ";
+        assert_eq!(
+            extract_body(pane).as_deref(),
+            Some("## Summary\nAdds login.\n\n  - indented item")
+        );
+    }
+
+    #[test]
+    fn test_extract_takes_latest_draft() {
+        let pane = "BEGIN_PR_BODY\nold\nEND_PR_BODY\nBEGIN_PR_BODY\nnew\nEND_PR_BODY\n";
+        assert_eq!(extract_body(pane).as_deref(), Some("new"));
+        assert_eq!(extract_body("BEGIN_PR_BODY\nunfinished"), None);
+    }
+}
@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use crate::cmd::{CmdExec, args};
+use crate::session::{Instance, InstanceStatus};
+
+/// What a stored session has lost since it was saved: the status to mark
+/// it with and why. `None` when its repo, branch and worktree are where
+/// they should be, or it has no worktree to check.
+///
+/// Paused sessions have no worktree on disk by design, so only their
+/// branch is checked.
+pub fn check(instance: &Instance, cmd: &dyn CmdExec) -> Option<(InstanceStatus, String)> {
+    if !matches!(
+        instance.status,
+        InstanceStatus::Running
+            | InstanceStatus::Done
+            | InstanceStatus::Ready
+            | InstanceStatus::Paused
+    ) {
+        return None;
+    }
+    let worktree = instance.git_worktree.as_ref()?;
+    if !Path::new(worktree.repo_path()).exists() {
+        return Some((
+            InstanceStatus::Orphaned,
+            format!("The repo {} no longer exists. Press d to remove the session.", worktree.repo_path()),
+        ));
+    }
+    let branch_ref = format!("refs/heads/{}", worktree.branch());
+    if cmd
+        .run("git", &args(&["-C", worktree.repo_path(), "show-ref", "--verify", "--quiet", &branch_ref]))
+        .is_err()
+    {
+        return Some((
+            InstanceStatus::Orphaned,
+            format!(
+                "The branch {} no longer exists. Press r to start over from its base commit, or d to remove it.",
+                worktree.branch()
+            ),
+        ));
+    }
+    if instance.status != InstanceStatus::Paused && !Path::new(worktree.worktree_path()).exists() {
+        return Some((
+            InstanceStatus::WorktreeMissing,
+            format!(
+                "The worktree {} no longer exists. Press r to check out {} again.",
+                worktree.worktree_path(),
+                worktree.branch()
+            ),
+        ));
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::MockCmdExec;
+    use crate::session::InstanceOptions;
+    use crate::session::git::GitWorktree;
+
+    fn session(status: InstanceStatus, repo: &Path, worktree: &Path) -> Instance {
+        let mut instance = Instance::new(InstanceOptions {
+            title: "api".to_string(),
+            path: repo.to_string_lossy().to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        instance.started = true;
+        instance.status = status;
+        instance.git_worktree = Some(GitWorktree::from_storage(
+            repo.to_string_lossy().to_string(),
+            worktree.to_string_lossy().to_string(),
+            "api".to_string(),
+            "gana/api".to_string(),
+            String::new(),
+        ));
+        instance
+    }
+
+    fn branch_exists(exists: bool) -> MockCmdExec {
+        let mut mock = MockCmdExec::new();
+        mock.expect_run()
+            .withf(|_, a| a.contains(&"show-ref".to_string()))
+            .returning(move |_, _| {
+                if exists {
+                    Ok(())
+                } else {
+                    Err(crate::cmd::CmdError::Failed("no ref".to_string()))
+                }
+            });
+        mock
+    }
+
+    #[test]
+    fn test_check_finds_missing_worktree_and_branch() {
+        let repo = tempfile::TempDir::new().unwrap();
+        let gone = repo.path().join("gone");
+
+        let running = session(InstanceStatus::Running, repo.path(), repo.path());
+        assert_eq!(check(&running, &branch_exists(true)), None);
+
+        let missing = session(InstanceStatus::Ready, repo.path(), &gone);
+        let (status, reason) = check(&missing, &branch_exists(true)).unwrap();
+        assert_eq!(status, InstanceStatus::WorktreeMissing);
+        assert!(reason.contains("gana/api"));
+
+        // Paused sessions don't have a worktree on disk
+        let paused = session(InstanceStatus::Paused, repo.path(), &gone);
+        assert_eq!(check(&paused, &branch_exists(true)), None);
+        let (status, _) = check(&paused, &branch_exists(false)).unwrap();
+        assert_eq!(status, InstanceStatus::Orphaned);
+
+        let no_repo = session(InstanceStatus::Running, &gone, &gone);
+        let (status, _) = check(&no_repo, &MockCmdExec::new()).unwrap();
+        assert_eq!(status, InstanceStatus::Orphaned);
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::cmd::{CmdError, CmdExec, args, quote};
+use crate::config::ResourceLimits;
+use crate::session::Instance;
+use crate::session::container::Container;
+use crate::session::tmux::TmuxSession;
+
+/// Present when the unified (v2) cgroup hierarchy is mounted.
+const CGROUP_V2_MARKER: &str = "/sys/fs/cgroup/cgroup.controllers";
+
+/// CPU and memory used by a session's agent.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ResourceUsage {
+    /// Percent of one core, summed over the agent's processes.
+    pub cpu_percent: f64,
+    pub memory_bytes: u64,
+}
+
+/// `command` run under `limits`, in its own cgroup through
+/// `systemd-run --user --scope`. Without cgroups v2 and systemd the agent
+/// runs unlimited: `ulimit -v` would cap address space rather than memory
+/// use, which runtimes like V8 reserve far more of than they use.
+pub fn limit(command: &str, limits: &ResourceLimits, cmd: &dyn CmdExec) -> String {
+    if limits.cpu_percent.is_none() && limits.memory_mb.is_none() {
+        return command.to_string();
+    }
+    if Path::new(CGROUP_V2_MARKER).exists() && cmd.run("systemd-run", &args(&["--version"])).is_ok() {
+        scope_command(command, limits)
+    } else {
+        tracing::warn!("Resource limits need cgroups v2 and systemd; the agent runs unlimited");
+        command.to_string()
+    }
+}
+
+fn scope_command(command: &str, limits: &ResourceLimits) -> String {
+    let mut properties = String::new();
+    if let Some(mb) = limits.memory_mb {
+        properties.push_str(&format!(" -p MemoryMax={}M", mb));
+    }
+    if let Some(percent) = limits.cpu_percent {
+        properties.push_str(&format!(" -p CPUQuota={}%", percent));
+    }
+    format!(
+        "systemd-run --user --scope --quiet{} -- sh -c {}",
+        properties,
+        quote(command)
+    )
+}
+
+/// Arguments for a container runtime's `run` that apply `limits`.
+pub fn container_args(limits: &ResourceLimits) -> Vec<String> {
+    let mut run_args = Vec::new();
+    if let Some(mb) = limits.memory_mb {
+        run_args.push("--memory".to_string());
+        run_args.push(format!("{}m", mb));
+    }
+    if let Some(percent) = limits.cpu_percent {
+        run_args.push("--cpus".to_string());
+        run_args.push(format!("{}", f64::from(percent) / 100.0));
+    }
+    run_args
+}
+
+/// Current usage of a running session's agent: its container's, or that
+/// of the processes under its tmux pane. `None` when it can't be measured.
+pub fn sample(instance: &Instance, cmd: &dyn CmdExec) -> Option<ResourceUsage> {
+    match instance.container {
+        Some(ref container) => container_usage(container, cmd).ok(),
+        None => {
+            let pid = TmuxSession::pane_pid(&instance.title, cmd).ok()?;
+            process_tree_usage(pid, cmd).ok()
+        }
+    }
+}
+
+/// Usage of `root` and all its descendants, as reported by `ps`.
+pub fn process_tree_usage(root: u32, cmd: &dyn CmdExec) -> Result<ResourceUsage, CmdError> {
+    let output = cmd.output("ps", &args(&["-A", "-o", "pid=,ppid=,pcpu=,rss="]))?;
+    Ok(parse_ps(&output, root))
+}
+
+fn parse_ps(output: &str, root: u32) -> ResourceUsage {
+    let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+    let mut usage: HashMap<u32, (f64, u64)> = HashMap::new();
+    for line in output.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [pid, ppid, cpu, rss] = fields[..] else {
+            continue;
+        };
+        let (Ok(pid), Ok(ppid)) = (pid.parse::<u32>(), ppid.parse::<u32>()) else {
+            continue;
+        };
+        children.entry(ppid).or_default().push(pid);
+        usage.insert(pid, (cpu.parse().unwrap_or(0.0), rss.parse().unwrap_or(0)));
+    }
+
+    let mut total = ResourceUsage::default();
+    let mut pending = vec![root];
+    while let Some(pid) = pending.pop() {
+        if let Some(&(cpu, rss_kb)) = usage.get(&pid) {
+            total.cpu_percent += cpu;
+            total.memory_bytes += rss_kb * 1024;
+        }
+        if let Some(kids) = children.get(&pid) {
+            pending.extend(kids);
+        }
+    }
+    total
+}
+
+/// Usage of a container, from `{runtime} stats`.
+fn container_usage(container: &Container, cmd: &dyn CmdExec) -> Result<ResourceUsage, CmdError> {
+    let output = cmd.output(
+        &container.runtime,
+        &args(&[
+            "stats",
+            "--no-stream",
+            "--format",
+            "{{.CPUPerc}}|{{.MemUsage}}",
+            &container.name,
+        ]),
+    )?;
+    parse_stats(output.trim())
+        .ok_or_else(|| CmdError::Failed(format!("unexpected stats output: {}", output.trim())))
+}
+
+/// Parse e.g. "12.50%|812.3MiB / 7.6GiB".
+fn parse_stats(line: &str) -> Option<ResourceUsage> {
+    let (cpu, memory) = line.split_once('|')?;
+    let cpu_percent = cpu.trim().trim_end_matches('%').parse().ok()?;
+    let used = memory.split('/').next()?.trim();
+    let split = used.find(|c: char| c.is_ascii_alphabetic())?;
+    let (number, unit) = used.split_at(split);
+    let number: f64 = number.trim().parse().ok()?;
+    let scale: f64 = match unit {
+        "B" => 1.0,
+        "kB" | "KB" => 1e3,
+        "KiB" => 1024.0,
+        "MB" => 1e6,
+        "MiB" => 1024.0 * 1024.0,
+        "GB" => 1e9,
+        "GiB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    Some(ResourceUsage {
+        cpu_percent,
+        memory_bytes: (number * scale) as u64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_commands() {
+        let limits = ResourceLimits {
+            cpu_percent: Some(150),
+            memory_mb: Some(2048),
+        };
+        assert_eq!(
+            scope_command("bwrap -- claude", &limits),
+            "systemd-run --user --scope --quiet -p MemoryMax=2048M -p CPUQuota=150% -- sh -c 'bwrap -- claude'"
+        );
+        assert_eq!(container_args(&limits), args(&["--memory", "2048m", "--cpus", "1.5"]));
+        assert_eq!(
+            limit("claude", &ResourceLimits::default(), &crate::cmd::MockCmdExec::new()),
+            "claude"
+        );
+    }
+
+    #[test]
+    fn test_parse_ps_sums_process_tree() {
+        let output = "    1     0  0.0  1000\n  100     1  1.0  2000\n  101   100 30.5 40000\n  102   101 10.0 10000\n  200     1 99.0 99999\n";
+        let usage = parse_ps(output, 100);
+        assert!((usage.cpu_percent - 41.5).abs() < 1e-9);
+        assert_eq!(usage.memory_bytes, 52000 * 1024);
+    }
+
+    #[test]
+    fn test_parse_stats() {
+        let usage = parse_stats("12.50%|812MiB / 7.6GiB").unwrap();
+        assert!((usage.cpu_percent - 12.5).abs() < 1e-9);
+        assert_eq!(usage.memory_bytes, 812 * 1024 * 1024);
+        assert_eq!(parse_stats("--|--"), None);
+    }
+}
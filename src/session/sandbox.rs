@@ -0,0 +1,48 @@
+use crate::cmd::quote;
+use crate::config::{Config, per_program};
+use crate::session::container::git_dir;
+use crate::session::git::GitWorktree;
+
+/// `program` prefixed with its `sandbox_commands` entry, e.g. a `bwrap` or
+/// `firejail` invocation, with `{worktree}` and `{git_dir}` filled in
+/// (quoted for `sh`). Unchanged when the program has no entry.
+pub fn wrap(program: &str, worktree: &GitWorktree, config: &Config) -> String {
+    match per_program(&config.sandbox_commands, program) {
+        Some(prefix) if !prefix.trim().is_empty() => {
+            let prefix = prefix
+                .replace("{worktree}", &quote(worktree.worktree_path()))
+                .replace("{git_dir}", &quote(&git_dir(worktree)));
+            format!("{} {}", prefix.trim(), program)
+        }
+        _ => program.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_fills_in_paths() {
+        let worktree = GitWorktree::from_storage(
+            "/repo".to_string(),
+            "/wt/my session".to_string(),
+            "s".to_string(),
+            "s".to_string(),
+            "abc".to_string(),
+        );
+        let mut config = Config::default();
+        assert_eq!(wrap("claude", &worktree, &config), "claude");
+
+        config.sandbox_commands.insert(
+            "claude".to_string(),
+            "bwrap --ro-bind / / --bind {worktree} {worktree} --bind {git_dir} {git_dir} --".to_string(),
+        );
+        assert_eq!(
+            wrap("claude --dangerously-skip-permissions", &worktree, &config),
+            "bwrap --ro-bind / / --bind '/wt/my session' '/wt/my session' \
+             --bind '/repo/.git' '/repo/.git' -- claude --dangerously-skip-permissions"
+        );
+        assert_eq!(wrap("aider", &worktree, &config), "aider");
+    }
+}
@@ -1,8 +1,19 @@
-use super::instance::Instance;
-use std::path::Path;
+use super::instance::{Instance, InstanceStatus};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 
-const INSTANCES_FILE: &str = "instances.json";
+/// One file per repo in here, see `FileStorage::project_file`.
+const PROJECTS_DIR: &str = "projects";
+/// Where all sessions were kept before they were stored per repo; moved
+/// into `PROJECTS_DIR` on first use.
+const LEGACY_INSTANCES_FILE: &str = "instances.json";
+const ARCHIVE_FILE: &str = "archive.json";
+/// Held while the session list is read and rewritten, so the TUI, the
+/// daemon and CLI commands don't overwrite each other's changes.
+const LOCK_FILE: &str = "instances.lock";
 
 #[derive(Debug, Error)]
 pub enum StorageError {
@@ -19,45 +30,282 @@ pub trait InstanceStorage: Send + Sync {
     fn load_instances(&self) -> Result<Vec<Instance>, StorageError>;
 }
 
+/// Persist started instances, plus unstarted ones that still owe work:
+/// sessions being created or that failed, and any with an unsent prompt.
+fn should_persist(instance: &Instance) -> bool {
+    instance.started
+        || instance.pending_prompt.is_some()
+        || matches!(
+            instance.status,
+            InstanceStatus::Queued
+                | InstanceStatus::Loading
+                | InstanceStatus::Failed
+                | InstanceStatus::Pending
+        )
+}
+
+/// The sessions of one repo, as stored.
+#[derive(Serialize, Deserialize)]
+struct ProjectFile<T> {
+    /// Canonical repo root, see `Instance::repo_root`.
+    root: PathBuf,
+    instances: Vec<T>,
+}
+
 /// File-based instance storage.
+///
+/// Each repo's sessions are kept in a file of their own, keyed by the
+/// repo's canonical root. A storage scoped to a project only reads and
+/// writes that repo's file; an unscoped one sees all of them.
 pub struct FileStorage {
-    config_dir: std::path::PathBuf,
+    config_dir: PathBuf,
+    project: Option<PathBuf>,
 }
 
 impl FileStorage {
     pub fn new(config_dir: &Path) -> Self {
         Self {
             config_dir: config_dir.to_path_buf(),
+            project: None,
         }
     }
-}
 
-impl InstanceStorage for FileStorage {
-    fn save_instances(&self, instances: &[Instance]) -> Result<(), StorageError> {
+    /// Storage limited to the sessions of the repo rooted at `project`, or
+    /// all sessions when `None`.
+    pub fn scoped(config_dir: &Path, project: Option<&Path>) -> Self {
+        Self {
+            config_dir: config_dir.to_path_buf(),
+            project: project.map(Path::to_path_buf),
+        }
+    }
+
+    /// File of the repo rooted at `root`: the repo's name, for people
+    /// looking, plus a hash of the whole path, so same-named repos don't
+    /// share one.
+    fn project_file(&self, root: &Path) -> PathBuf {
+        let name: String = root
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let hash = Sha256::digest(root.as_os_str().as_encoded_bytes());
+        let hash: String = hash[..8].iter().map(|b| format!("{:02x}", b)).collect();
+        self.config_dir
+            .join(PROJECTS_DIR)
+            .join(format!("{}-{}.json", name, hash))
+    }
+
+    fn read_project(path: &Path) -> Result<ProjectFile<Instance>, StorageError> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Every repo's stored sessions, by root.
+    fn load_projects(&self) -> Result<Vec<ProjectFile<Instance>>, StorageError> {
+        let dir = self.config_dir.join(PROJECTS_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mut projects = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                projects.push(Self::read_project(&path)?);
+            }
+        }
+        projects.sort_by(|a, b| a.root.cmp(&b.root));
+        Ok(projects)
+    }
+
+    /// The stored sessions in scope, grouped by repo.
+    fn load_all(&self) -> Result<Vec<Instance>, StorageError> {
+        Ok(match self.project {
+            Some(ref root) => {
+                let path = self.project_file(root);
+                if !path.exists() {
+                    return Ok(Vec::new());
+                }
+                Self::read_project(&path)?.instances
+            }
+            None => self
+                .load_projects()?
+                .into_iter()
+                .flat_map(|p| p.instances)
+                .collect(),
+        })
+    }
+
+    /// Run `f` holding the lock on the session list.
+    fn locked<T>(
+        &self,
+        f: impl FnOnce() -> Result<T, StorageError>,
+    ) -> Result<T, StorageError> {
         std::fs::create_dir_all(&self.config_dir)?;
-        let path = self.config_dir.join(INSTANCES_FILE);
-        // Only persist started instances
-        let started: Vec<&Instance> = instances.iter().filter(|i| i.started).collect();
-        let json = serde_json::to_string_pretty(&started)?;
-        std::fs::write(&path, json)?;
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(self.config_dir.join(LOCK_FILE))?;
+        let _lock = crate::daemon::platform::lock(file);
+        f()
+    }
+
+    /// Split the single file sessions used to share into one per repo.
+    fn migrate_legacy(&self) -> Result<(), StorageError> {
+        let legacy = self.config_dir.join(LEGACY_INSTANCES_FILE);
+        if !legacy.exists() {
+            return Ok(());
+        }
+        self.locked(|| {
+            // Someone else may have got here first
+            if !legacy.exists() {
+                return Ok(());
+            }
+            let contents = std::fs::read_to_string(&legacy)?;
+            let instances: Vec<Instance> = serde_json::from_str(&contents)?;
+            let unscoped = Self::new(&self.config_dir);
+            let stored = unscoped.load_all()?;
+            let mut all: Vec<&Instance> = stored.iter().collect();
+            all.extend(instances.iter().filter(|i| !stored.iter().any(|s| s.title == i.title)));
+            unscoped.write_all(&all)?;
+            std::fs::remove_file(&legacy)?;
+            Ok(())
+        })
+    }
+
+    /// Write one repo's sessions through a temporary file, so readers
+    /// never see it half written. A repo without sessions loses its file.
+    fn write_project(&self, root: &Path, instances: &[&Instance]) -> Result<(), StorageError> {
+        let path = self.project_file(root);
+        if instances.is_empty() {
+            if path.exists() {
+                std::fs::remove_file(&path)?;
+            }
+            return Ok(());
+        }
+        std::fs::create_dir_all(self.config_dir.join(PROJECTS_DIR))?;
+        let file = ProjectFile {
+            root: root.to_path_buf(),
+            instances: instances.to_vec(),
+        };
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_string_pretty(&file)?)?;
+        std::fs::rename(&tmp, &path)?;
         Ok(())
     }
 
-    fn load_instances(&self) -> Result<Vec<Instance>, StorageError> {
-        let path = self.config_dir.join(INSTANCES_FILE);
+    /// Store `instances` as the sessions in scope. Unscoped, they are the
+    /// whole list: each stays in its repo's file, new ones go to the file
+    /// of `Instance::repo_root`, and repos left without sessions are dropped.
+    fn write_all(&self, instances: &[&Instance]) -> Result<(), StorageError> {
+        if let Some(ref root) = self.project {
+            return self.write_project(root, instances);
+        }
+        let stored = self.load_projects()?;
+        let mut by_root: BTreeMap<PathBuf, Vec<&Instance>> =
+            stored.iter().map(|p| (p.root.clone(), Vec::new())).collect();
+        for &instance in instances {
+            let root = stored
+                .iter()
+                .find(|p| p.instances.iter().any(|i| i.title == instance.title))
+                .map(|p| p.root.clone())
+                .unwrap_or_else(|| instance.repo_root());
+            by_root.entry(root).or_default().push(instance);
+        }
+        for (root, instances) in &by_root {
+            self.write_project(root, instances)?;
+        }
+        Ok(())
+    }
+
+    /// Change the stored sessions in place: `f` gets all of them as they
+    /// are on disk now, rather than as some earlier load saw them. For
+    /// writers like the daemon that only touch a few fields of a few
+    /// sessions while the TUI saves the rest.
+    pub fn update_instances(&self, f: impl FnOnce(&mut Vec<Instance>)) -> Result<(), StorageError> {
+        self.migrate_legacy()?;
+        self.locked(|| {
+            let mut instances = self.load_all()?;
+            f(&mut instances);
+            self.write_all(&instances.iter().collect::<Vec<_>>())
+        })
+    }
+
+    /// Save `instances` like `save_instances`, but keep the stored sessions
+    /// `known` doesn't recognize: ones another process, e.g. `gana new`,
+    /// added since the caller loaded the list. Returns those.
+    pub fn save_merged(
+        &self,
+        instances: &[Instance],
+        known: impl Fn(&str) -> bool,
+    ) -> Result<Vec<Instance>, StorageError> {
+        self.migrate_legacy()?;
+        self.locked(|| {
+            let added: Vec<Instance> = self
+                .load_all()?
+                .into_iter()
+                .filter(|s| !known(&s.title) && !instances.iter().any(|i| i.title == s.title))
+                .collect();
+            let mut kept: Vec<&Instance> = instances.iter().filter(|i| should_persist(i)).collect();
+            kept.extend(&added);
+            self.write_all(&kept)?;
+            Ok(added)
+        })
+    }
+
+    /// Roots of the repos with stored sessions, sorted.
+    pub fn projects(&self) -> Result<Vec<PathBuf>, StorageError> {
+        self.migrate_legacy()?;
+        Ok(self.load_projects()?.into_iter().map(|p| p.root).collect())
+    }
+
+    /// Sessions moved out of the list by the archive policy.
+    pub fn load_archive(&self) -> Result<Vec<Instance>, StorageError> {
+        let path = self.config_dir.join(ARCHIVE_FILE);
         if !path.exists() {
             return Ok(Vec::new());
         }
         let contents = std::fs::read_to_string(&path)?;
-        let instances: Vec<Instance> = serde_json::from_str(&contents)?;
-        Ok(instances)
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save_archive(&self, archived: &[Instance]) -> Result<(), StorageError> {
+        std::fs::create_dir_all(&self.config_dir)?;
+        let json = serde_json::to_string_pretty(archived)?;
+        std::fs::write(self.config_dir.join(ARCHIVE_FILE), json)?;
+        Ok(())
+    }
+
+    /// Add sessions to the archive, replacing older entries with the same title.
+    pub fn archive(&self, instances: Vec<Instance>) -> Result<(), StorageError> {
+        let mut archived = self.load_archive()?;
+        archived.retain(|a| !instances.iter().any(|i| i.title == a.title));
+        archived.extend(instances);
+        self.save_archive(&archived)
+    }
+}
+
+impl InstanceStorage for FileStorage {
+    fn save_instances(&self, instances: &[Instance]) -> Result<(), StorageError> {
+        self.migrate_legacy()?;
+        self.locked(|| {
+            let kept: Vec<&Instance> = instances.iter().filter(|i| should_persist(i)).collect();
+            self.write_all(&kept)
+        })
+    }
+
+    fn load_instances(&self) -> Result<Vec<Instance>, StorageError> {
+        self.migrate_legacy()?;
+        self.load_all()
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::session::instance::{InstanceOptions, InstanceStatus};
+    use crate::session::instance::InstanceOptions;
     use tempfile::TempDir;
 
     #[test]
@@ -69,7 +317,7 @@ mod tests {
             title: "test-session".to_string(),
             path: "/tmp/test".to_string(),
             program: "claude".to_string(),
-            auto_yes: false,
+            ..Default::default()
         });
         instance.started = true;
         instance.status = InstanceStatus::Running;
@@ -100,7 +348,7 @@ mod tests {
             title: "not-started".to_string(),
             path: "/tmp/test".to_string(),
             program: "claude".to_string(),
-            auto_yes: false,
+            ..Default::default()
         });
         // instance.started is false by default
 
@@ -108,4 +356,152 @@ mod tests {
         let loaded = storage.load_instances().unwrap();
         assert!(loaded.is_empty(), "unstarted instances should not be saved");
     }
+
+    #[test]
+    fn test_save_merged_keeps_sessions_added_meanwhile() {
+        let tmp = TempDir::new().unwrap();
+        let storage = FileStorage::new(tmp.path());
+        storage.save_instances(&[started("loaded", "/a"), started("deleted", "/a")]).unwrap();
+        let loaded = storage.load_instances().unwrap();
+
+        // `gana new` adds a session after the load
+        storage.update_instances(|stored| stored.push(started("added", "/a"))).unwrap();
+
+        let known = |title: &str| loaded.iter().any(|i| i.title == title);
+        let kept = vec![started("loaded", "/a")];
+        let added = storage.save_merged(&kept, known).unwrap();
+        assert_eq!(titles(added), vec!["added"]);
+        assert_eq!(titles(storage.load_instances().unwrap()), vec!["loaded", "added"]);
+    }
+
+    #[test]
+    fn test_archive_replaces_same_title() {
+        let tmp = TempDir::new().unwrap();
+        let storage = FileStorage::new(tmp.path());
+        let make = |title: &str, branch: &str| {
+            let mut inst = Instance::new(InstanceOptions {
+                title: title.to_string(),
+                path: "/tmp/test".to_string(),
+                program: "claude".to_string(),
+                ..Default::default()
+            });
+            inst.branch = branch.to_string();
+            inst
+        };
+
+        storage.archive(vec![make("a", "gana/a"), make("b", "gana/b")]).unwrap();
+        storage.archive(vec![make("a", "gana/a-2")]).unwrap();
+
+        let archived = storage.load_archive().unwrap();
+        let branches: Vec<&str> = archived.iter().map(|i| i.branch.as_str()).collect();
+        assert_eq!(branches, vec!["gana/b", "gana/a-2"]);
+    }
+
+    #[test]
+    fn test_storage_keeps_unsent_prompts() {
+        let tmp = TempDir::new().unwrap();
+        let storage = FileStorage::new(tmp.path());
+
+        let mut queued = Instance::new(InstanceOptions {
+            title: "queued".to_string(),
+            path: "/tmp/test".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        queued.status = InstanceStatus::Queued;
+        queued.pending_prompt = Some("write the docs".to_string());
+        queued.existing_branch = Some("origin/docs".to_string());
+
+        storage.save_instances(&[queued]).unwrap();
+        let loaded = storage.load_instances().unwrap();
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded[0].pending_prompt.as_deref(), Some("write the docs"));
+        assert_eq!(loaded[0].existing_branch.as_deref(), Some("origin/docs"));
+    }
+
+    fn started(title: &str, path: &str) -> Instance {
+        let mut instance = Instance::new(InstanceOptions {
+            title: title.to_string(),
+            path: path.to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        instance.started = true;
+        instance
+    }
+
+    fn titles(instances: Vec<Instance>) -> Vec<String> {
+        instances.into_iter().map(|i| i.title).collect()
+    }
+
+    #[test]
+    fn test_scoped_storage_keeps_other_projects() {
+        let tmp = TempDir::new().unwrap();
+        FileStorage::new(tmp.path())
+            .save_instances(&[started("api", "/src/api"), started("web", "/src/web")])
+            .unwrap();
+        assert_eq!(std::fs::read_dir(tmp.path().join(PROJECTS_DIR)).unwrap().count(), 2);
+
+        let web = FileStorage::scoped(tmp.path(), Some(Path::new("/src/web")));
+        assert_eq!(titles(web.load_instances().unwrap()), ["web"]);
+
+        web.save_instances(&[started("web", "/src/web"), started("docs", "/src/web")])
+            .unwrap();
+        let all = FileStorage::new(tmp.path()).load_instances().unwrap();
+        assert_eq!(titles(all), ["api", "web", "docs"]);
+        assert_eq!(
+            web.projects().unwrap(),
+            vec![PathBuf::from("/src/api"), PathBuf::from("/src/web")]
+        );
+
+        // A project whose sessions are all gone is no longer listed
+        FileStorage::new(tmp.path())
+            .save_instances(&[started("web", "/src/web")])
+            .unwrap();
+        assert_eq!(web.projects().unwrap(), vec![PathBuf::from("/src/web")]);
+    }
+
+    #[test]
+    fn test_sessions_from_subdirectories_share_the_repo() {
+        let tmp = TempDir::new().unwrap();
+        let repo = TempDir::new().unwrap();
+        let status = std::process::Command::new("git")
+            .args(["-C", &repo.path().to_string_lossy(), "init", "-q"])
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let sub = repo.path().join("crates/core");
+        std::fs::create_dir_all(&sub).unwrap();
+        let root = repo.path().canonicalize().unwrap();
+
+        let storage = FileStorage::new(tmp.path());
+        storage
+            .save_instances(&[
+                started("top", &root.to_string_lossy()),
+                started("sub", &sub.to_string_lossy()),
+            ])
+            .unwrap();
+        assert_eq!(storage.projects().unwrap(), vec![root.clone()]);
+        let scoped = FileStorage::scoped(tmp.path(), Some(&root));
+        assert_eq!(titles(scoped.load_instances().unwrap()), ["top", "sub"]);
+    }
+
+    #[test]
+    fn test_shared_file_is_split_per_repo() {
+        let tmp = TempDir::new().unwrap();
+        let legacy = vec![started("api", "/src/api"), started("web", "/src/web")];
+        std::fs::write(
+            tmp.path().join(LEGACY_INSTANCES_FILE),
+            serde_json::to_string(&legacy).unwrap(),
+        )
+        .unwrap();
+
+        let storage = FileStorage::scoped(tmp.path(), Some(Path::new("/src/api")));
+        assert_eq!(titles(storage.load_instances().unwrap()), ["api"]);
+        assert!(!tmp.path().join(LEGACY_INSTANCES_FILE).exists());
+        assert_eq!(
+            FileStorage::new(tmp.path()).projects().unwrap(),
+            vec![PathBuf::from("/src/api"), PathBuf::from("/src/web")]
+        );
+    }
 }
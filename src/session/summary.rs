@@ -0,0 +1,205 @@
+use unicode_width::UnicodeWidthStr;
+
+use super::git::DiffStats;
+use super::instance::{Instance, InstanceStatus};
+
+/// Compact per-status session counts, e.g. for tmux/starship status bars.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatusSummary {
+    pub running: usize,
+    pub waiting: usize,
+    /// Being created, queued, or held until another session finishes.
+    pub starting: usize,
+    pub done: usize,
+    pub paused: usize,
+    pub ready: usize,
+    pub failed: usize,
+}
+
+impl StatusSummary {
+    /// Tally instances by status.
+    ///
+    /// Running instances for which `is_waiting` returns true are counted as
+    /// waiting instead of running.
+    pub fn from_instances(instances: &[Instance], is_waiting: impl Fn(&Instance) -> bool) -> Self {
+        let mut summary = Self::default();
+        for instance in instances {
+            match instance.status {
+                InstanceStatus::Running if is_waiting(instance) => summary.waiting += 1,
+                InstanceStatus::Running => summary.running += 1,
+                InstanceStatus::Loading | InstanceStatus::Queued | InstanceStatus::Pending => {
+                    summary.starting += 1
+                }
+                InstanceStatus::Done => summary.done += 1,
+                InstanceStatus::Paused => summary.paused += 1,
+                InstanceStatus::Ready => summary.ready += 1,
+                InstanceStatus::Failed
+                | InstanceStatus::WorktreeMissing
+                | InstanceStatus::Orphaned => summary.failed += 1,
+            }
+        }
+        summary
+    }
+}
+
+impl std::fmt::Display for StatusSummary {
+    /// Render non-zero counts joined by " · "; empty when there are no sessions.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts: Vec<String> = [
+            (self.running, "running"),
+            (self.waiting, "waiting"),
+            (self.starting, "starting"),
+            (self.done, "done"),
+            (self.paused, "paused"),
+            (self.ready, "ready"),
+            (self.failed, "failed"),
+        ]
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, label)| format!("{} {}", count, label))
+        .collect();
+        write!(f, "{}", parts.join(" · "))
+    }
+}
+
+/// One session's line in `gana status`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatusRow {
+    pub title: String,
+    pub status: String,
+    pub branch: String,
+    /// Added and removed lines, e.g. "+12 -3"; empty without a diff.
+    pub diff: String,
+    pub attention: bool,
+}
+
+impl StatusRow {
+    /// Row for `instance`, with its current diff if it could be computed
+    /// and whether its agent waits for input.
+    pub fn new(instance: &Instance, diff: Option<&DiffStats>, attention: bool) -> Self {
+        let diff = diff
+            .filter(|d| d.error.is_none())
+            .map(|d| format!("+{} -{}", d.added_lines, d.removed_lines))
+            .unwrap_or_default();
+        Self {
+            title: instance.title.clone(),
+            status: instance.status.to_string(),
+            branch: instance.branch.clone(),
+            diff,
+            attention,
+        }
+    }
+}
+
+/// Render rows as a table with aligned columns under a header line.
+pub fn status_table(rows: &[StatusRow]) -> String {
+    let header = ["SESSION", "STATUS", "BRANCH", "+/-", "ATTENTION"];
+    let cells: Vec<[&str; 5]> = rows
+        .iter()
+        .map(|r| {
+            let attention = if r.attention { "waiting for input" } else { "" };
+            [&r.title, &r.status, &r.branch, &r.diff, attention]
+        })
+        .collect();
+    let mut widths = header.map(UnicodeWidthStr::width);
+    for row in &cells {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.width());
+        }
+    }
+
+    let mut out = String::new();
+    for row in std::iter::once(&header).chain(&cells) {
+        let mut line = String::new();
+        for (cell, width) in row.iter().zip(widths) {
+            line.push_str(cell);
+            line.push_str(&" ".repeat(width - cell.width() + 2));
+        }
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::instance::InstanceOptions;
+
+    fn make_instance(title: &str, status: InstanceStatus) -> Instance {
+        let mut inst = Instance::new(InstanceOptions {
+            title: title.to_string(),
+            path: "/tmp".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        inst.status = status;
+        inst
+    }
+
+    #[test]
+    fn test_summary_counts_and_format() {
+        let instances = vec![
+            make_instance("a", InstanceStatus::Running),
+            make_instance("b", InstanceStatus::Running),
+            make_instance("c", InstanceStatus::Running),
+            make_instance("d", InstanceStatus::Running),
+            make_instance("e", InstanceStatus::Paused),
+            make_instance("f", InstanceStatus::Paused),
+            make_instance("g", InstanceStatus::Done),
+        ];
+
+        let summary = StatusSummary::from_instances(&instances, |i| i.title == "d");
+        assert_eq!(summary.running, 3);
+        assert_eq!(summary.waiting, 1);
+        assert_eq!(summary.paused, 2);
+        assert_eq!(summary.done, 1);
+        assert_eq!(summary.to_string(), "3 running · 1 waiting · 1 done · 2 paused");
+    }
+
+    #[test]
+    fn test_summary_counts_starting_sessions_apart() {
+        let instances = vec![
+            make_instance("a", InstanceStatus::Running),
+            make_instance("b", InstanceStatus::Loading),
+            make_instance("c", InstanceStatus::Queued),
+            make_instance("d", InstanceStatus::Pending),
+        ];
+
+        let summary = StatusSummary::from_instances(&instances, |_| false);
+        assert_eq!(summary.running, 1);
+        assert_eq!(summary.starting, 3);
+        assert_eq!(summary.to_string(), "1 running · 3 starting");
+    }
+
+    #[test]
+    fn test_summary_empty() {
+        let summary = StatusSummary::from_instances(&[], |_| false);
+        assert_eq!(summary, StatusSummary::default());
+        assert_eq!(summary.to_string(), "");
+    }
+
+    #[test]
+    fn test_status_table_aligns_columns() {
+        let mut instance = make_instance("fix-ünïcode", InstanceStatus::Running);
+        instance.branch = "gana/fix".to_string();
+        let diff = DiffStats {
+            added_lines: 12,
+            removed_lines: 3,
+            ..DiffStats::default()
+        };
+        let rows = [
+            StatusRow::new(&instance, Some(&diff), true),
+            StatusRow::new(&make_instance("b", InstanceStatus::Paused), None, false),
+        ];
+
+        let table = status_table(&rows);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("SESSION      STATUS"));
+        assert!(lines[1].starts_with("fix-ünïcode  "));
+        assert!(lines[1].ends_with("+12 -3  waiting for input"));
+        assert!(lines[2].starts_with("b            "));
+        assert!(!lines[2].contains("waiting"));
+    }
+}
@@ -0,0 +1,331 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use thiserror::Error;
+
+use crate::cmd::{CmdError, CmdExec, args};
+use crate::config::Config;
+use crate::session::{Instance, InstanceStatus};
+
+/// Checkout of `team_repo` under the config directory.
+const CHECKOUT_DIR: &str = "team";
+
+/// Directory in the team repo holding one file per member.
+const SESSIONS_DIR: &str = "sessions";
+
+/// Status changes alone are published at most this often, batched into one
+/// commit. Added or removed sessions go out with the next sync.
+const STATUS_PUBLISH_INTERVAL: chrono::Duration = chrono::Duration::minutes(10);
+
+#[derive(Debug, Error)]
+pub enum TeamError {
+    #[error("no team repo configured: set team_repo")]
+    NotConfigured,
+    #[error("failed to read team sessions: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to parse team sessions: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error(transparent)]
+    Cmd(#[from] CmdError),
+}
+
+/// What teammates see of a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TeamSession {
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    pub branch: String,
+    pub status: InstanceStatus,
+    pub program: String,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<&Instance> for TeamSession {
+    fn from(instance: &Instance) -> Self {
+        Self {
+            title: instance.title.clone(),
+            repo: instance.repo_name(),
+            branch: instance.branch.clone(),
+            status: instance.status,
+            program: instance.program.clone(),
+            updated_at: instance.updated_at,
+        }
+    }
+}
+
+/// One member's published sessions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MemberSessions {
+    pub member: String,
+    pub published_at: DateTime<Utc>,
+    pub sessions: Vec<TeamSession>,
+}
+
+/// Sessions shared through a git repo. Each member only ever writes their
+/// own file, so pushes from different machines rebase cleanly; everyone
+/// else's sessions are read-only.
+pub struct TeamStore {
+    remote: String,
+    checkout: PathBuf,
+    member: String,
+    /// Names the member's file, so renaming yourself doesn't leave the old
+    /// file behind as a teammate; see `member_id`.
+    id: String,
+}
+
+impl TeamStore {
+    pub fn new(remote: &str, checkout: &Path, member: &str, id: &str) -> Self {
+        Self {
+            remote: remote.to_string(),
+            checkout: checkout.to_path_buf(),
+            member: member.to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    /// The store for `team_repo`, checked out in the config directory.
+    pub fn from_config(config: &Config, config_dir: &Path, cmd: &dyn CmdExec) -> Result<Self, TeamError> {
+        let remote = config.team_repo.as_deref().ok_or(TeamError::NotConfigured)?;
+        let member = member_name(config, cmd);
+        let id = member_id(&member, cmd);
+        Ok(Self::new(remote, &config_dir.join(CHECKOUT_DIR), &member, &id))
+    }
+
+    pub fn member(&self) -> &str {
+        &self.member
+    }
+
+    /// Publish `instances` as this member's sessions and return the other
+    /// members', freshly pulled. Status changes are held back until
+    /// `STATUS_PUBLISH_INTERVAL` passed since the last publish, so periodic
+    /// syncs don't commit every flip between running and ready.
+    pub fn sync(&self, instances: &[Instance], cmd: &dyn CmdExec) -> Result<Vec<MemberSessions>, TeamError> {
+        self.update_checkout(cmd)?;
+        self.publish(instances, false, cmd)?;
+        self.others()
+    }
+
+    /// `sync`, publishing status changes straight away, for an explicit
+    /// `gana team`.
+    pub fn sync_now(&self, instances: &[Instance], cmd: &dyn CmdExec) -> Result<Vec<MemberSessions>, TeamError> {
+        self.update_checkout(cmd)?;
+        self.publish(instances, true, cmd)?;
+        self.others()
+    }
+
+    /// Clone the team repo, or bring the checkout up to date.
+    fn update_checkout(&self, cmd: &dyn CmdExec) -> Result<(), TeamError> {
+        if !self.checkout.join(".git").exists() {
+            let checkout = self.checkout.to_string_lossy();
+            cmd.run("git", &args(&["clone", "--quiet", &self.remote, &checkout]))?;
+        }
+        self.pull(cmd)
+    }
+
+    fn pull(&self, cmd: &dyn CmdExec) -> Result<(), TeamError> {
+        // A new team repo has no branch to pull yet
+        let heads = self.git(cmd, &["ls-remote", "--heads", "origin"])?;
+        if !heads.trim().is_empty() {
+            self.git(cmd, &["pull", "--quiet", "--rebase"])?;
+        }
+        Ok(())
+    }
+
+    fn publish(&self, instances: &[Instance], now: bool, cmd: &dyn CmdExec) -> Result<(), TeamError> {
+        let mine = MemberSessions {
+            member: self.member.clone(),
+            published_at: Utc::now(),
+            sessions: instances.iter().filter(|i| i.started).map(TeamSession::from).collect(),
+        };
+        if let Some(previous) = self.read(&self.member_file())? {
+            let same_sessions = previous.sessions.len() == mine.sessions.len()
+                && previous
+                    .sessions
+                    .iter()
+                    .zip(&mine.sessions)
+                    .all(|(a, b)| a.title == b.title && a.branch == b.branch);
+            let recent = mine.published_at - previous.published_at < STATUS_PUBLISH_INTERVAL;
+            if previous.sessions == mine.sessions || (same_sessions && recent && !now) {
+                return Ok(());
+            }
+        }
+
+        let dir = self.checkout.join(SESSIONS_DIR);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::write(self.member_file(), serde_json::to_string_pretty(&mine)?)?;
+        // Files used to be named after the member
+        let legacy = dir.join(format!("{}.json", file_name(&self.member)));
+        if legacy != self.member_file() && legacy.exists() {
+            std::fs::remove_file(&legacy)?;
+        }
+        self.git(cmd, &["add", "--all", SESSIONS_DIR])?;
+
+        let mut commit = args(&["-c", "commit.gpgsign=false"]);
+        // Bookkeeping commits shouldn't fail on a machine without an identity
+        if self.git(cmd, &["config", "user.email"]).is_err() {
+            commit.extend(args(&["-c", &format!("user.name={}", self.member)]));
+            commit.extend(args(&["-c", "user.email=gana@localhost"]));
+        }
+        let message = format!("Update {}'s sessions", self.member);
+        commit.extend(args(&["commit", "--quiet", "--no-verify", "-m", &message]));
+        let commit: Vec<&str> = commit.iter().map(String::as_str).collect();
+        self.git(cmd, &commit)?;
+
+        if self.git(cmd, &["push", "--quiet", "origin", "HEAD"]).is_err() {
+            // Someone else pushed first; their files don't touch ours
+            self.pull(cmd)?;
+            self.git(cmd, &["push", "--quiet", "origin", "HEAD"])?;
+        }
+        Ok(())
+    }
+
+    /// The other members' sessions, sorted by member.
+    fn others(&self) -> Result<Vec<MemberSessions>, TeamError> {
+        let dir = self.checkout.join(SESSIONS_DIR);
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+        let mine = self.member_file();
+        let mut members = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path == mine || path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            match self.read(&path) {
+                Ok(Some(member)) => members.push(member),
+                Ok(None) => {}
+                Err(e) => tracing::warn!("Skipping {}: {}", path.display(), e),
+            }
+        }
+        members.sort_by(|a, b| a.member.cmp(&b.member));
+        Ok(members)
+    }
+
+    fn member_file(&self) -> PathBuf {
+        self.checkout.join(SESSIONS_DIR).join(format!("{}.json", file_name(&self.id)))
+    }
+
+    fn read(&self, path: &Path) -> Result<Option<MemberSessions>, TeamError> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_str(&std::fs::read_to_string(path)?)?))
+    }
+
+    fn git(&self, cmd: &dyn CmdExec, git_args: &[&str]) -> Result<String, CmdError> {
+        let checkout = self.checkout.to_string_lossy();
+        let mut full = args(&["-C", &checkout]);
+        full.extend(args(git_args));
+        cmd.output("git", &full)
+    }
+}
+
+/// `s` with anything but letters, digits, `-` and `.` replaced, for a file name.
+fn file_name(s: &str) -> String {
+    s.chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '.' { c } else { '_' })
+        .collect()
+}
+
+/// Stable id for `member`'s file: a hash of git's `user.email`, or of the
+/// member name without one. Hashed so addresses don't end up in file names.
+fn member_id(member: &str, cmd: &dyn CmdExec) -> String {
+    let email = cmd
+        .output("git", &args(&["config", "--global", "user.email"]))
+        .ok()
+        .map(|email| email.trim().to_lowercase())
+        .filter(|email| !email.is_empty());
+    let hash = Sha256::digest(email.as_deref().unwrap_or(member).as_bytes());
+    hash[..8].iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// `team_member`, else git's `user.name`, else `$USER`.
+fn member_name(config: &Config, cmd: &dyn CmdExec) -> String {
+    if let Some(ref member) = config.team_member {
+        return member.clone();
+    }
+    cmd.output("git", &args(&["config", "--global", "user.name"]))
+        .ok()
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .or_else(|| std::env::var("USER").ok())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::SystemCmdExec;
+    use crate::session::InstanceOptions;
+
+    fn session(title: &str) -> Instance {
+        let mut instance = Instance::new(InstanceOptions {
+            title: title.to_string(),
+            path: "/tmp".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        instance.started = true;
+        instance.branch = format!("gana/{}", title);
+        instance.status = InstanceStatus::Running;
+        instance
+    }
+
+    #[test]
+    fn test_members_see_each_others_sessions() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let remote = tmp.path().join("team.git");
+        let status = std::process::Command::new("git")
+            .args(["init", "--quiet", "--bare"])
+            .arg(&remote)
+            .status()
+            .unwrap();
+        assert!(status.success());
+        let remote = remote.to_string_lossy();
+        let alice = TeamStore::new(&remote, &tmp.path().join("alice"), "alice", "a1");
+        let bob = TeamStore::new(&remote, &tmp.path().join("bob"), "Bob Smith", "b2");
+
+        assert!(alice.sync(&[session("login")], &SystemCmdExec).unwrap().is_empty());
+        let seen_by_bob = bob.sync(&[session("search")], &SystemCmdExec).unwrap();
+        assert_eq!(seen_by_bob.len(), 1);
+        assert_eq!(seen_by_bob[0].member, "alice");
+        assert_eq!(seen_by_bob[0].sessions[0].branch, "gana/login");
+
+        // A status change alone waits for the next batch
+        let mut paused = session("login");
+        paused.status = InstanceStatus::Paused;
+        let seen_by_alice = alice.sync(std::slice::from_ref(&paused), &SystemCmdExec).unwrap();
+        assert_eq!(seen_by_alice.len(), 1);
+        assert_eq!(seen_by_alice[0].member, "Bob Smith");
+        assert_eq!(seen_by_alice[0].sessions[0].title, "search");
+        let seen_by_bob = bob.sync(&[session("search")], &SystemCmdExec).unwrap();
+        assert_eq!(seen_by_bob[0].sessions[0].status, InstanceStatus::Running);
+
+        alice.sync_now(&[paused], &SystemCmdExec).unwrap();
+        let seen_by_bob = bob.sync(&[session("search")], &SystemCmdExec).unwrap();
+        assert_eq!(seen_by_bob[0].sessions[0].status, InstanceStatus::Paused);
+
+        // A new session goes out straight away
+        alice.sync(&[session("login"), session("signup")], &SystemCmdExec).unwrap();
+        let seen_by_bob = bob.sync(&[session("search")], &SystemCmdExec).unwrap();
+        assert_eq!(seen_by_bob[0].sessions.len(), 2);
+    }
+
+    #[test]
+    fn test_member_file_is_keyed_by_id() {
+        let store = TeamStore::new("unused", Path::new("/team"), "Bob Smith", "b2");
+        assert_eq!(store.member_file(), Path::new("/team/sessions/b2.json"));
+
+        let mut cmd = crate::cmd::MockCmdExec::new();
+        cmd.expect_output().returning(|_, _| Ok("Bob@Example.com\n".to_string()));
+        let id = member_id("Bob Smith", &cmd);
+        assert_eq!(id.len(), 16);
+        let mut renamed = crate::cmd::MockCmdExec::new();
+        renamed.expect_output().returning(|_, _| Ok("bob@example.com".to_string()));
+        assert_eq!(member_id("Robert Smith", &renamed), id);
+    }
+}
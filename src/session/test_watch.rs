@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::cmd::{args, CmdError, CmdExec};
+
+/// Changes under these directories never trigger a run: they are VCS
+/// metadata, dependencies or build output the test command itself writes.
+/// Anything else the worktree's `.gitignore` ignores is skipped too.
+const IGNORED_DIRS: [&str; 8] = [
+    ".git",
+    "target",
+    "node_modules",
+    ".venv",
+    "__pycache__",
+    "dist",
+    "build",
+    ".next",
+];
+
+/// Lines of output kept from a failing run.
+const FAILURE_TAIL_LINES: usize = 20;
+
+/// Result of the last `test_command` run in a session's worktree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TestStatus {
+    Running,
+    Passed,
+    /// Holds the tail of the command's output.
+    Failed(String),
+}
+
+/// Whether a change at `path` inside the worktree `root` should rerun tests.
+pub fn is_relevant(path: &Path, root: &Path) -> bool {
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    !relative
+        .components()
+        .any(|c| IGNORED_DIRS.iter().any(|dir| c.as_os_str() == *dir))
+}
+
+/// Whether any of `paths`, changed inside the worktree `root`, should rerun
+/// tests: it is outside `IGNORED_DIRS` and git doesn't ignore it, e.g. as
+/// coverage output listed in `.gitignore`.
+pub fn has_relevant_change(paths: &[PathBuf], root: &Path, cmd: &dyn CmdExec) -> bool {
+    let candidates: Vec<String> = paths
+        .iter()
+        .filter(|p| is_relevant(p, root))
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    if candidates.is_empty() {
+        return false;
+    }
+    let root = root.to_string_lossy();
+    let mut check = args(&["-C", &root, "check-ignore", "--"]);
+    check.extend(candidates.iter().cloned());
+    match cmd.output("git", &check) {
+        // Lists the ignored ones
+        Ok(ignored) => ignored.lines().count() < candidates.len(),
+        // None is ignored, or git can't tell
+        Err(_) => true,
+    }
+}
+
+/// Run `command` through `sh` in `dir`.
+pub fn run_tests(command: &str, dir: &str, cmd: &dyn CmdExec) -> TestStatus {
+    // Everything goes to stderr so a failure's message carries all output
+    let script = format!("cd \"$1\" && {{ {}\n}} 1>&2", command);
+    match cmd.output("sh", &args(&["-c", &script, "sh", dir])) {
+        Ok(_) => TestStatus::Passed,
+        Err(CmdError::Failed(output)) => {
+            let lines: Vec<&str> = output.lines().collect();
+            let tail = lines[lines.len().saturating_sub(FAILURE_TAIL_LINES)..].join("\n");
+            TestStatus::Failed(tail)
+        }
+        Err(e) => TestStatus::Failed(e.to_string()),
+    }
+}
+
+/// Watches session worktrees and sends a session's title on `sender`
+/// whenever a relevant file in its worktree changes.
+pub struct TestWatcher {
+    watchers: HashMap<String, RecommendedWatcher>,
+    sender: mpsc::Sender<String>,
+    /// Asks git which changed files are ignored.
+    cmd: Arc<dyn CmdExec>,
+}
+
+impl TestWatcher {
+    pub fn new(sender: mpsc::Sender<String>, cmd: Arc<dyn CmdExec>) -> Self {
+        Self {
+            watchers: HashMap::new(),
+            sender,
+            cmd,
+        }
+    }
+
+    pub fn is_watching(&self, title: &str) -> bool {
+        self.watchers.contains_key(title)
+    }
+
+    pub fn watch(&mut self, title: &str, dir: &Path) -> notify::Result<()> {
+        let sender = self.sender.clone();
+        let session = title.to_string();
+        let root = dir.to_path_buf();
+        let cmd = Arc::clone(&self.cmd);
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res
+                && !event.kind.is_access()
+                && has_relevant_change(&event.paths, &root, &*cmd)
+            {
+                let _ = sender.send(session.clone());
+            }
+        })?;
+        watcher.watch(dir, RecursiveMode::Recursive)?;
+        self.watchers.insert(title.to_string(), watcher);
+        Ok(())
+    }
+
+    /// Stop watching every session for which `keep` returns false.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.watchers.retain(|title, _| keep(title));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::MockCmdExec;
+
+    #[test]
+    fn test_is_relevant_ignores_build_output() {
+        let root = Path::new("/wt/feature");
+        assert!(is_relevant(Path::new("/wt/feature/src/main.rs"), root));
+        assert!(!is_relevant(Path::new("/wt/feature/target/debug/gana"), root));
+        assert!(!is_relevant(Path::new("/wt/feature/.git/index"), root));
+        assert!(!is_relevant(Path::new("/wt/feature/web/node_modules/x.js"), root));
+        // Only components below the worktree count
+        assert!(is_relevant(Path::new("/build/wt/src/lib.rs"), Path::new("/build/wt")));
+    }
+
+    #[test]
+    fn test_gitignored_changes_are_not_relevant() {
+        let root = Path::new("/wt/feature");
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|name, a| name == "git" && a.contains(&"check-ignore".to_string()))
+            .returning(|_, a| {
+                // coverage/ is in .gitignore
+                Ok(a.iter()
+                    .filter(|p| p.contains("/coverage/"))
+                    .map(|p| format!("{}\n", p))
+                    .collect())
+            });
+
+        let paths = |ps: &[&str]| ps.iter().map(PathBuf::from).collect::<Vec<_>>();
+        assert!(!has_relevant_change(&paths(&["/wt/feature/coverage/lcov.info"]), root, &mock));
+        assert!(has_relevant_change(
+            &paths(&["/wt/feature/coverage/lcov.info", "/wt/feature/src/lib.rs"]),
+            root,
+            &mock
+        ));
+        // Never asks git about always-ignored dirs
+        assert!(!has_relevant_change(&paths(&["/wt/feature/target/debug/gana"]), root, &mock));
+    }
+
+    #[test]
+    fn test_run_tests_keeps_output_tail() {
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|name, a| name == "sh" && a[1].contains("cargo test") && a[3] == "/wt")
+            .returning(|_, _| {
+                let output: Vec<String> = (1..=30).map(|i| format!("line {}", i)).collect();
+                Err(CmdError::Failed(output.join("\n")))
+            });
+
+        let TestStatus::Failed(tail) = run_tests("cargo test", "/wt", &mock) else {
+            panic!("expected a failure");
+        };
+        assert_eq!(tail.lines().count(), FAILURE_TAIL_LINES);
+        assert!(tail.ends_with("line 30"));
+        assert!(!tail.contains("line 10\n"));
+    }
+
+    #[test]
+    fn test_watch_reports_changes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = TestWatcher::new(sender, Arc::new(crate::cmd::SystemCmdExec));
+        watcher.watch("s", dir.path()).unwrap();
+        assert!(watcher.is_watching("s"));
+
+        std::fs::write(dir.path().join("lib.rs"), "fn main() {}").unwrap();
+        let title = receiver.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+        assert_eq!(title, "s");
+
+        watcher.retain(|_| false);
+        assert!(!watcher.is_watching("s"));
+    }
+}
@@ -0,0 +1,314 @@
+use crate::cmd::{args, CmdError, CmdExec};
+use crate::session::Instance;
+use crate::session::git::GitWorktree;
+use crate::session::git::util::{branch_name_error, sanitize_branch_name};
+use crate::session::tmux::sanitize_name;
+
+/// Longest title derived from a prompt, in characters.
+const MAX_TITLE_LEN: usize = 32;
+
+/// Most words kept from the prompt.
+const MAX_TITLE_WORDS: usize = 5;
+
+/// Session title from the first words of a prompt, e.g. "Fix the login
+/// redirect bug" becomes `fix-the-login-redirect-bug`.
+///
+/// Only lowercase letters and digits are kept so the title is also a clean
+/// branch name. Returns `None` when the prompt has no usable words.
+pub fn title_from_prompt(prompt: &str) -> Option<String> {
+    let mut title = String::new();
+    let words = prompt
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .take(MAX_TITLE_WORDS);
+    for word in words {
+        let sep = usize::from(!title.is_empty());
+        if title.chars().count() + sep + word.chars().count() > MAX_TITLE_LEN {
+            if title.is_empty() {
+                title = word.chars().take(MAX_TITLE_LEN).collect();
+            }
+            break;
+        }
+        if sep == 1 {
+            title.push('-');
+        }
+        title.push_str(&word);
+    }
+    (!title.is_empty()).then_some(title)
+}
+
+/// Ask an agent for a short session title by running `command` with the
+/// request appended as its last argument (e.g. `claude -p`).
+pub fn agent_title(command: &str, prompt: &str, cmd: &dyn CmdExec) -> Result<String, CmdError> {
+    let request = format!(
+        "Reply with only a short title (at most {} words) for this coding task, nothing else:\n\n{}",
+        MAX_TITLE_WORDS, prompt
+    );
+    let output = cmd.output(
+        "sh",
+        &args(&["-c", &format!("{} \"$1\"", command), "sh", &request]),
+    )?;
+    output
+        .lines()
+        .find(|line| !line.trim().is_empty())
+        .and_then(title_from_prompt)
+        .ok_or_else(|| CmdError::Failed(format!("`{}` returned no title", command)))
+}
+
+/// Whether a session titled `a` would collide with one titled `b`: both
+/// map to the same tmux session or the same branch.
+pub fn titles_clash(a: &str, b: &str) -> bool {
+    sanitize_name(a) == sanitize_name(b) || sanitize_branch_name(a) == sanitize_branch_name(b)
+}
+
+/// The session in `instances` that a new session titled `title` would
+/// collide with.
+pub fn clashing<'a>(title: &str, instances: &'a [Instance]) -> Option<&'a Instance> {
+    instances.iter().find(|i| titles_clash(title, &i.title))
+}
+
+/// `title`, or `title-2`, `title-3`… when it clashes with one of `instances`.
+pub fn unique_title(title: &str, instances: &[Instance]) -> String {
+    let taken: Vec<&str> = instances.iter().map(|i| i.title.as_str()).collect();
+    unique_among(title, &taken)
+}
+
+/// `title`, or `title-2`, `title-3`… when it clashes with one of `taken`.
+fn unique_among(title: &str, taken: &[&str]) -> String {
+    let free = |candidate: &str| !taken.iter().any(|t| titles_clash(candidate, t));
+    if free(title) {
+        return title.to_string();
+    }
+    (2..)
+        .map(|n| format!("{}-{}", title, n))
+        .find(|candidate| free(candidate))
+        .unwrap_or_else(|| title.to_string())
+}
+
+/// `title` with the creation time appended, as `branch_unique_suffix`
+/// asks: `fix` becomes `fix-20261017-093012`. A time `title` already ends
+/// in, e.g. when copying a session, is replaced rather than added to.
+pub fn with_time_suffix(title: &str, now: chrono::DateTime<chrono::Local>) -> String {
+    format!("{}-{}", without_time_suffix(title), now.format("%Y%m%d-%H%M%S"))
+}
+
+/// `title` without the time `with_time_suffix` appended, including the
+/// `-MMDD-HHMM` form of earlier versions.
+fn without_time_suffix(title: &str) -> &str {
+    let digits = |s: &str, n: usize| s.len() == n && s.bytes().all(|b| b.is_ascii_digit());
+    let mut parts = title.rsplitn(3, '-');
+    match (parts.next(), parts.next(), parts.next()) {
+        (Some(time), Some(date), Some(rest))
+            if !rest.is_empty()
+                && ((digits(date, 8) && digits(time, 6)) || (digits(date, 4) && digits(time, 4))) =>
+        {
+            rest
+        }
+        _ => title,
+    }
+}
+
+/// Checks a new session's title as it's typed: which branch it gets, and
+/// whether git would create it.
+#[derive(Debug, Clone, Default)]
+pub struct TitleCheck {
+    pub branch_prefix: String,
+    /// Titles of the existing sessions.
+    pub taken: Vec<String>,
+    /// The repo's local branches.
+    pub branches: Vec<String>,
+    /// Branches checked out in the repo's worktrees, main checkout
+    /// included, with where.
+    pub checked_out: Vec<(String, String)>,
+    /// Where worktrees are created, as shown to the user; empty to leave
+    /// the worktree out of the note.
+    pub worktrees_dir: String,
+    /// Titles get the creation time appended (`branch_unique_suffix`).
+    pub time_suffix: bool,
+}
+
+impl TitleCheck {
+    /// The branch and worktree `title` leads to, one per line, noting when
+    /// an existing session changes what happens; or why it can't be
+    /// created. An empty title passes with nothing to say.
+    ///
+    /// A branch that already exists is refused: it isn't gana's, since
+    /// every session's title is in `taken`, and one checked out elsewhere
+    /// can't get a worktree at all.
+    pub fn check(&self, title: &str) -> Result<String, String> {
+        if title.trim().is_empty() {
+            return Ok(String::new());
+        }
+        let taken: Vec<&str> = self.taken.iter().map(String::as_str).collect();
+        let title = if self.time_suffix {
+            with_time_suffix(title, chrono::Local::now())
+        } else {
+            title.to_string()
+        };
+        let title = title.as_str();
+        let unique = unique_among(title, &taken);
+        if !self.branch_prefix.is_empty() && sanitize_branch_name(&unique).is_empty() {
+            return Err(format!("'{}' has no characters usable in a branch name", title));
+        }
+        let branch = GitWorktree::branch_for(&self.branch_prefix, &unique);
+        if let Some(e) = branch_name_error(&branch) {
+            return Err(format!("Branch '{}': {}", branch, e));
+        }
+        // refs/heads/a and refs/heads/a/b can't both exist
+        if let Some(other) = self.branches.iter().find(|b| {
+            b.strip_prefix(branch.as_str()).is_some_and(|rest| rest.starts_with('/'))
+                || branch.strip_prefix(b.as_str()).is_some_and(|rest| rest.starts_with('/'))
+        }) {
+            return Err(format!("Branch '{}' conflicts with the branch '{}'", branch, other));
+        }
+        if let Some((_, path)) = self.checked_out.iter().find(|(b, _)| *b == branch) {
+            return Err(format!("Branch '{}' is checked out at {}", branch, path));
+        }
+        if self.branches.contains(&branch) {
+            return Err(format!(
+                "Branch '{}' already exists; pick another title, or start a session on it \
+                 from the branch list",
+                branch
+            ));
+        }
+        let mut note = if unique != title {
+            format!("Branch: {} (as '{}', '{}' is taken)", branch, unique, title)
+        } else {
+            format!("Branch: {}", branch)
+        };
+        if !self.worktrees_dir.is_empty() {
+            // The directory gets a timestamp suffix when it's created
+            note.push_str(&format!("\nWorktree: {}/{}_…", self.worktrees_dir, unique));
+        }
+        Ok(note)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use crate::cmd::MockCmdExec;
+
+    #[test]
+    fn test_title_from_prompt() {
+        assert_eq!(
+            title_from_prompt("Fix the login redirect bug, it loops forever").as_deref(),
+            Some("fix-the-login-redirect-bug")
+        );
+        assert_eq!(
+            title_from_prompt("# Plan\n\nAdd `--json` output to status").as_deref(),
+            Some("plan-add-json-output-to")
+        );
+        assert_eq!(title_from_prompt("  ?! -- "), None);
+    }
+
+    #[test]
+    fn test_title_from_prompt_respects_length() {
+        let title = title_from_prompt("internationalization localization accessibility").unwrap();
+        assert_eq!(title, "internationalization");
+        let title = title_from_prompt(&"x".repeat(50)).unwrap();
+        assert_eq!(title.len(), MAX_TITLE_LEN);
+    }
+
+    #[test]
+    fn test_agent_title_uses_first_line() {
+        let mut mock = MockCmdExec::new();
+        mock.expect_output()
+            .withf(|name, a| name == "sh" && a[1] == "claude -p \"$1\"" && a[3].contains("flaky test"))
+            .returning(|_, _| Ok("\n\"Stabilize flaky CI test\"\nExtra chatter".to_string()));
+
+        let title = agent_title("claude -p", "fix the flaky test in ci", &mock).unwrap();
+        assert_eq!(title, "stabilize-flaky-ci-test");
+    }
+
+    #[test]
+    fn test_unique_title_suffixes_clashes() {
+        let session = |title: &str| {
+            Instance::new(crate::session::InstanceOptions {
+                title: title.to_string(),
+                path: "/tmp".to_string(),
+                program: "claude".to_string(),
+                ..Default::default()
+            })
+        };
+        let instances = [session("fix-bug"), session("fix-bug-2")];
+        assert_eq!(unique_title("docs", &instances), "docs");
+        assert_eq!(unique_title("fix-bug", &instances), "fix-bug-3");
+        // Same tmux name and branch as "fix-bug"
+        assert!(titles_clash("fix bug", "fix-bug"));
+        assert!(titles_clash("Fix-Bug", "fix-bug"));
+        assert_eq!(clashing("fix_bug-2", &instances).map(|i| i.title.as_str()), None);
+    }
+
+    #[test]
+    fn test_title_check() {
+        let check = TitleCheck {
+            branch_prefix: "gana/".to_string(),
+            taken: vec!["fix-bug".to_string()],
+            branches: vec!["main".to_string(), "gana/docs".to_string(), "gana/api/v2".to_string()],
+            ..TitleCheck::default()
+        };
+        assert_eq!(check.check(""), Ok(String::new()));
+        assert_eq!(check.check("Add tests").as_deref(), Ok("Branch: gana/add-tests"));
+        assert!(check.check("fix bug").unwrap().contains("gana/fix-bug-2"));
+        assert!(check.check("docs").unwrap_err().contains("already exists"));
+        assert!(check.check("🚀🔥").unwrap_err().contains("no characters usable"));
+        assert!(check.check("a..b").is_err());
+        assert!(check.check("api").unwrap_err().contains("gana/api/v2"));
+        assert!(check.check("docs/more").unwrap_err().contains("'gana/docs'"));
+
+        // Without a prefix the title is the branch, as typed
+        let check = TitleCheck {
+            branches: vec!["main".to_string()],
+            checked_out: vec![("main".to_string(), "/repo".to_string())],
+            ..TitleCheck::default()
+        };
+        assert!(check.check("fix login").unwrap_err().contains("' '"));
+        assert_eq!(check.check("fix-login").as_deref(), Ok("Branch: fix-login"));
+        assert_eq!(
+            check.check("main"),
+            Err("Branch 'main' is checked out at /repo".to_string())
+        );
+    }
+
+    #[test]
+    fn test_time_suffix() {
+        let now = chrono::Local.with_ymd_and_hms(2026, 10, 17, 9, 30, 12).unwrap();
+        assert_eq!(with_time_suffix("fix", now), "fix-20261017-093012");
+        // A copy of a suffixed session gets a new time instead of a second one
+        assert_eq!(with_time_suffix("fix-20261016-170000", now), "fix-20261017-093012");
+        assert_eq!(with_time_suffix("fix-1016-1700", now), "fix-20261017-093012");
+        assert_eq!(with_time_suffix("v-1-2", now), "v-1-2-20261017-093012");
+
+        let check = TitleCheck {
+            branch_prefix: "gana/".to_string(),
+            time_suffix: true,
+            ..TitleCheck::default()
+        };
+        let note = check.check("fix").unwrap();
+        assert!(note.starts_with("Branch: gana/fix-"), "{}", note);
+        assert_eq!(note.len(), "Branch: gana/fix-20261017-093012".len());
+    }
+
+    #[test]
+    fn test_title_check_shows_worktree() {
+        let check = TitleCheck {
+            branch_prefix: "gana/".to_string(),
+            taken: vec!["fix-login".to_string()],
+            worktrees_dir: "~/.gana/worktrees".to_string(),
+            ..TitleCheck::default()
+        };
+        assert_eq!(
+            check.check("fix-login").as_deref(),
+            Ok("Branch: gana/fix-login-2 (as 'fix-login-2', 'fix-login' is taken)\n\
+                Worktree: ~/.gana/worktrees/fix-login-2_…")
+        );
+    }
+}
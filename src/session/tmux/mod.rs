@@ -1,11 +1,14 @@
+pub mod normalize;
 pub mod pty;
 
+use std::collections::HashMap;
 use std::fs::File;
 
 use sha2::{Digest, Sha256};
 use thiserror::Error;
 
-use crate::cmd::{CmdExec, args};
+use crate::cmd::{CmdError, CmdExec, args};
+use crate::config::{PromptPatterns, program_name};
 use pty::PtyFactory;
 
 /// Prefix for all gana tmux session names.
@@ -23,6 +26,100 @@ pub enum TmuxError {
     Cmd(#[from] crate::cmd::CmdError),
 }
 
+/// Number of trailing pane lines checked against the deny-list.
+const PROMPT_CONTEXT_LINES: usize = 20;
+
+/// How long to watch for a trust prompt whose patterns don't say.
+pub const DEFAULT_TRUST_TIMEOUT_SECS: u64 = 30;
+
+/// Return the first deny pattern found in the prompt region of `content`.
+pub fn denied_pattern<'a>(content: &str, deny_patterns: &'a [String]) -> Option<&'a str> {
+    prompt_match(content, deny_patterns)
+}
+
+/// Whether auto-yes may answer the prompt in `content`: any prompt when
+/// `allow_patterns` is empty, else only one matching an allowed pattern.
+pub fn allowed_prompt(content: &str, allow_patterns: &[String]) -> bool {
+    allow_patterns.is_empty() || prompt_match(content, allow_patterns).is_some()
+}
+
+/// First of `patterns` found (case-insensitive) in the prompt region of `content`.
+fn prompt_match<'a>(content: &str, patterns: &'a [String]) -> Option<&'a str> {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(PROMPT_CONTEXT_LINES);
+    let context = lines[start..].join("\n").to_lowercase();
+    patterns
+        .iter()
+        .map(|p| p.as_str())
+        .find(|p| !p.is_empty() && context.contains(&p.to_lowercase()))
+}
+
+/// What an agent is doing, as far as its pane shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentState {
+    Working,
+    /// A prompt waits for the user.
+    Waiting,
+    /// Neither working nor asking: done, or not started on anything yet.
+    Idle,
+}
+
+/// What the agent in pane `content` is doing, going by its `busy` and
+/// `attention` patterns; `None` when the program has no `busy` patterns.
+pub fn agent_state(content: &str, prompts: &PromptPatterns) -> Option<AgentState> {
+    let busy = prompts.busy.as_ref().filter(|p| !p.is_empty())?;
+    let state = if TmuxSession::has_ai_prompt(content, prompts) {
+        AgentState::Waiting
+    } else if prompt_match(content, busy).is_some() {
+        AgentState::Working
+    } else {
+        AgentState::Idle
+    };
+    Some(state)
+}
+
+/// The last `count` lines of pane `content`, ignoring the blank lines
+/// below the cursor.
+pub fn last_lines(content: &str, count: usize) -> String {
+    let lines: Vec<&str> = content.trim_end().lines().collect();
+    let start = lines.len().saturating_sub(count);
+    lines[start..].join("\n")
+}
+
+/// Pane lines kept per session, to show why an agent exited.
+pub const EXIT_OUTPUT_LINES: usize = 20;
+
+/// The last lines of each running session's pane, kept while they can
+/// still be captured: once the agent exits its tmux session is gone, and
+/// they usually say why. The TUI and the daemon each keep one, for
+/// `Instance::mark_exited`.
+#[derive(Debug, Default)]
+pub struct PaneTails(HashMap<String, String>);
+
+impl PaneTails {
+    /// Remember the end of `title`'s pane `content`.
+    pub fn record(&mut self, title: &str, content: &str) {
+        self.0.insert(title.to_string(), last_lines(content, EXIT_OUTPUT_LINES));
+    }
+
+    /// The last output of `title`, whose agent just exited, if it had any.
+    pub fn take(&mut self, title: &str) -> Option<String> {
+        self.0.remove(title).filter(|tail| !tail.is_empty())
+    }
+
+    /// Forget sessions for which `keep` returns false.
+    pub fn retain(&mut self, keep: impl Fn(&str) -> bool) {
+        self.0.retain(|title, _| keep(title));
+    }
+}
+
+/// Whether `e` says no tmux server is running, as opposed to one that
+/// failed to answer.
+fn no_server(e: &CmdError) -> bool {
+    let msg = e.to_string();
+    msg.contains("no server running") || msg.contains("No such file or directory")
+}
+
 /// Sanitize a session name for use as a tmux session name.
 /// Replaces non-alphanumeric characters with underscores and adds prefix.
 pub fn sanitize_name(name: &str) -> String {
@@ -49,6 +146,67 @@ pub fn sanitize_name(name: &str) -> String {
     format!("{}{}", TMUX_PREFIX, trimmed)
 }
 
+/// A program's first-launch trust prompt to watch for in one tmux session.
+///
+/// Programs show a trust prompt on first launch in a folder, e.g. Claude's
+/// "Do you trust the files in this folder?"; the text, the keys answering
+/// it and how long to wait come from the prompt patterns. Detached from
+/// `TmuxSession` so the wait can run on a background thread.
+#[derive(Debug, Clone)]
+pub struct TrustPrompt {
+    sanitized_name: String,
+    search: String,
+    keys: Vec<String>,
+    timeout: std::time::Duration,
+}
+
+impl TrustPrompt {
+    /// The trust prompt to answer in tmux session `sanitized_name`, or
+    /// `None` when `prompts` has none (e.g. unknown programs).
+    pub fn new(sanitized_name: &str, prompts: &PromptPatterns) -> Option<Self> {
+        let search = prompts.trust.clone()?;
+        let timeout_secs = prompts.trust_timeout_secs.unwrap_or(DEFAULT_TRUST_TIMEOUT_SECS);
+        Some(Self {
+            sanitized_name: sanitized_name.to_string(),
+            search,
+            keys: prompts.trust_keys.clone().unwrap_or_default(),
+            timeout: std::time::Duration::from_secs(timeout_secs),
+        })
+    }
+
+    /// Poll the pane until the prompt shows, then answer it. Returns whether
+    /// it was answered; a timeout is not an error, the prompt may have been
+    /// handled already.
+    ///
+    /// Blocks for up to the timeout. Uses exponential backoff polling,
+    /// matching the Go implementation.
+    pub fn answer(&self, cmd: &dyn CmdExec) -> Result<bool, TmuxError> {
+        let start = std::time::Instant::now();
+        let mut poll_interval = std::time::Duration::from_millis(100);
+
+        while start.elapsed() < self.timeout {
+            std::thread::sleep(poll_interval);
+
+            if let Ok(content) =
+                cmd.output("tmux", &args(&["capture-pane", "-p", "-t", &self.sanitized_name]))
+                && content.contains(self.search.as_str())
+            {
+                for key in &self.keys {
+                    cmd.run("tmux", &args(&["send-keys", "-t", &self.sanitized_name, key]))?;
+                }
+                return Ok(true);
+            }
+
+            // Exponential backoff with cap at 1 second (matching Go: *= 1.2, cap 1s)
+            poll_interval = std::time::Duration::from_millis(
+                ((poll_interval.as_millis() as f64 * 1.2) as u64).min(1000),
+            );
+        }
+
+        Ok(false)
+    }
+}
+
 /// A tmux session manager that handles the lifecycle of a tmux session.
 pub struct TmuxSession {
     /// Raw session name from the user.
@@ -61,6 +219,11 @@ pub struct TmuxSession {
     status_hash: String,
     /// Program to run in the session (e.g. "claude", "aider").
     program: String,
+    /// How the program's prompts are recognized and answered.
+    prompts: PromptPatterns,
+    /// Command line run in the pane instead of `program`, e.g. to run it in
+    /// a container. Prompt detection still goes by `program`.
+    command: Option<String>,
     /// Command executor for running tmux commands.
     cmd_exec: Box<dyn CmdExec>,
     /// Factory for creating PTY handles.
@@ -88,6 +251,8 @@ impl TmuxSession {
             ptmx: None,
             status_hash: String::new(),
             program: program.to_string(),
+            prompts: PromptPatterns::builtin(program_name(program)),
+            command: None,
             cmd_exec,
             pty_factory,
             attached: false,
@@ -111,6 +276,23 @@ impl TmuxSession {
         self.attached
     }
 
+    /// Recognize prompts by `prompts` (e.g. `Config::prompt_patterns`)
+    /// instead of the built-in patterns for the program.
+    pub fn with_prompts(mut self, prompts: PromptPatterns) -> Self {
+        self.prompts = prompts;
+        self
+    }
+
+    /// Run `command` in the pane instead of the program itself.
+    pub fn set_command(&mut self, command: String) {
+        self.command = Some(command);
+    }
+
+    /// What the pane runs.
+    fn launch_command(&self) -> &str {
+        self.command.as_deref().unwrap_or(&self.program)
+    }
+
     /// Start a new tmux session in the given working directory.
     ///
     /// 1. If a session with this name already exists, kill it.
@@ -139,7 +321,7 @@ impl TmuxSession {
             &self.sanitized_name,
             "-c",
             workdir,
-            &self.program,
+            self.launch_command(),
         ]);
         let _first_pty = self.pty_factory.start(&mut new_cmd)?;
         // Close the first PTY - we only needed it to create the session.
@@ -152,50 +334,13 @@ impl TmuxSession {
         self.ptmx = Some(ptmx);
         self.attached = true;
 
-        // Auto-respond to trust prompts (e.g. "Do you trust the files in this folder?")
-        self.handle_trust_prompt()?;
-
         Ok(())
     }
 
-    /// Poll for and auto-respond to trust prompts from AI programs.
-    ///
-    /// Different programs show different trust prompts on first launch:
-    /// - Claude: "Do you trust the files in this folder?" → Enter
-    /// - Aider/Gemini: "Open documentation url" → "d" then Enter
-    ///
-    /// Uses exponential backoff polling, matching the Go implementation.
-    fn handle_trust_prompt(&self) -> Result<(), TmuxError> {
-        let (search_string, response_keys, timeout_secs) = match self.program.as_str() {
-            "claude" => ("Do you trust the files in this folder?", vec!["Enter"], 30u64),
-            "aider" | "gemini" => ("Open documentation url", vec!["d", "Enter"], 45u64),
-            _ => return Ok(()), // No trust prompt handling for unknown programs
-        };
-
-        let start = std::time::Instant::now();
-        let timeout = std::time::Duration::from_secs(timeout_secs);
-        let mut poll_interval = std::time::Duration::from_millis(100);
-
-        while start.elapsed() < timeout {
-            std::thread::sleep(poll_interval);
-
-            if let Ok(content) = self.capture_pane_content(false) {
-                if content.contains(search_string) {
-                    for key in &response_keys {
-                        self.send_keys(key)?;
-                    }
-                    return Ok(());
-                }
-            }
-
-            // Exponential backoff with cap at 1 second (matching Go: *= 1.2, cap 1s)
-            poll_interval = std::time::Duration::from_millis(
-                ((poll_interval.as_millis() as f64 * 1.2) as u64).min(1000),
-            );
-        }
-
-        // Timeout is not an error - the prompt may have been handled already
-        Ok(())
+    /// The program's trust prompt to watch for after `start`, answered with
+    /// `TrustPrompt::answer` off the calling thread.
+    pub fn trust_prompt(&self) -> Option<TrustPrompt> {
+        TrustPrompt::new(&self.sanitized_name, &self.prompts)
     }
 
     /// Restore an existing tmux session by attaching to it.
@@ -230,14 +375,67 @@ impl TmuxSession {
         Ok(output)
     }
 
+    /// Capture pane lines `start` through `end` (the pane's last line when
+    /// `None`). Negative line numbers address the scrollback history, as in
+    /// `capture-pane -S/-E`.
+    pub fn capture_pane_range(&self, start: i64, end: Option<i64>) -> Result<String, TmuxError> {
+        let start = start.to_string();
+        let mut cmd_args = vec![
+            "capture-pane", "-p", "-e", "-J", "-t", &self.sanitized_name, "-S", &start,
+        ];
+        let end = end.map(|e| e.to_string());
+        if let Some(ref end) = end {
+            cmd_args.extend(["-E", end.as_str()]);
+        }
+        Ok(self.cmd_exec.output("tmux", &args(&cmd_args))?)
+    }
+
+    /// Number of lines in the pane's scrollback history.
+    pub fn history_size(&self) -> Result<usize, TmuxError> {
+        let output = self.cmd_exec.output(
+            "tmux",
+            &args(&["display-message", "-p", "-t", &self.sanitized_name, "#{history_size}"]),
+        )?;
+        output
+            .trim()
+            .parse()
+            .map_err(|_| TmuxError::CommandFailed(format!("unexpected history size: {}", output.trim())))
+    }
+
+    /// When the session last had terminal activity (output or input), as
+    /// tracked by tmux.
+    pub fn last_activity(&self) -> Result<chrono::DateTime<chrono::Utc>, TmuxError> {
+        Self::activity_of(&self.sanitized_name, &*self.cmd_exec)
+    }
+
+    /// `last_activity` of `title`'s session, for workers without one.
+    pub fn activity(title: &str, cmd_exec: &dyn CmdExec) -> Result<chrono::DateTime<chrono::Utc>, TmuxError> {
+        Self::activity_of(&sanitize_name(title), cmd_exec)
+    }
+
+    fn activity_of(name: &str, cmd_exec: &dyn CmdExec) -> Result<chrono::DateTime<chrono::Utc>, TmuxError> {
+        let output = cmd_exec.output(
+            "tmux",
+            &args(&["display-message", "-p", "-t", name, "#{window_activity}"]),
+        )?;
+        output
+            .trim()
+            .parse::<i64>()
+            .ok()
+            .and_then(|secs| chrono::DateTime::from_timestamp(secs, 0))
+            .ok_or_else(|| TmuxError::CommandFailed(format!("unexpected activity time: {}", output.trim())))
+    }
+
     /// Check if the pane content has changed since the last check.
     ///
-    /// Captures the current pane content, computes its SHA256 hash, and
-    /// compares it with the stored hash. Returns true if content has changed.
-    /// Also returns true if AI-specific prompts are detected.
+    /// Captures the current pane content, normalizes away spinner and clock
+    /// noise, and compares its SHA256 hash with the stored hash. Returns true
+    /// if content has changed. Also returns true if AI-specific prompts are
+    /// detected.
     pub fn has_updated(&mut self) -> Result<bool, TmuxError> {
         let content = self.capture_pane_content(false)?;
-        let hash = format!("{:x}", Sha256::digest(content.as_bytes()));
+        let normalized = normalize::normalize_content(&content);
+        let hash = format!("{:x}", Sha256::digest(normalized.as_bytes()));
 
         let changed = hash != self.status_hash;
         if changed {
@@ -245,23 +443,113 @@ impl TmuxSession {
         }
 
         // Also check for AI-specific prompts that indicate the session needs attention
-        let has_prompt = Self::has_ai_prompt(&content, &self.program);
+        let has_prompt = Self::has_ai_prompt(&content, &self.prompts);
 
         Ok(changed || has_prompt)
     }
 
-    /// Check if the content contains AI-specific prompts that need user attention.
-    fn has_ai_prompt(content: &str, program: &str) -> bool {
-        match program {
-            "claude" => content.contains("No, and tell Claude what to do differently"),
-            "aider" => content.contains("(Y)es/(N)o/(D)on't ask again"),
-            "gemini" => content.contains("Yes, allow once"),
-            "amp" => {
-                // Amp has specific prompt patterns
-                content.contains("Allow") && content.contains("Deny")
-            }
-            _ => false,
+    /// Check whether the session exists and its agent process is still running.
+    pub fn is_alive(&self) -> bool {
+        if self
+            .cmd_exec
+            .run("tmux", &args(&["has-session", "-t", &self.sanitized_name]))
+            .is_err()
+        {
+            return false;
         }
+        match self.cmd_exec.output(
+            "tmux",
+            &args(&["display-message", "-p", "-t", &self.sanitized_name, "#{pane_dead}"]),
+        ) {
+            Ok(output) => output.trim() != "1",
+            Err(_) => false,
+        }
+    }
+
+    /// PID of the process the pane of `title`'s session runs (the agent, or
+    /// its launcher). Needs no attached session, so workers can look it up.
+    pub fn pane_pid(title: &str, cmd_exec: &dyn CmdExec) -> Result<u32, TmuxError> {
+        let name = sanitize_name(title);
+        let output = cmd_exec.output(
+            "tmux",
+            &args(&["display-message", "-p", "-t", &name, "#{pane_pid}"]),
+        )?;
+        output
+            .trim()
+            .parse()
+            .map_err(|_| TmuxError::CommandFailed(format!("no pane pid for {}", name)))
+    }
+
+    /// Restart the program in a fresh detached session rooted at `work_dir`.
+    pub fn respawn(&self, work_dir: &str) -> Result<(), TmuxError> {
+        let _ = self
+            .cmd_exec
+            .run("tmux", &args(&["kill-session", "-t", &self.sanitized_name]));
+        self.cmd_exec.run(
+            "tmux",
+            &args(&[
+                "new-session", "-d", "-s", &self.sanitized_name,
+                "-c", work_dir, self.launch_command(),
+            ]),
+        )?;
+        Ok(())
+    }
+
+    /// Check if the visible pane currently shows a prompt waiting for input.
+    pub fn needs_attention(&self) -> Result<bool, TmuxError> {
+        let content = self.capture_pane_content(false)?;
+        Ok(Self::has_ai_prompt(&content, &self.prompts))
+    }
+
+    /// Answer a pending permission prompt with the program's approval keys.
+    ///
+    /// Does nothing unless a known prompt is visible and the program has known
+    /// approval keys. When `allow_patterns` is set, only prompts whose
+    /// surrounding text matches one of them are approved; prompts matching
+    /// any of `deny_patterns` never are (both case-insensitive). Returns true
+    /// if an approval was sent.
+    pub fn auto_approve(&self, allow_patterns: &[String], deny_patterns: &[String]) -> Result<bool, TmuxError> {
+        let content = self.capture_pane_content(false)?;
+        if !Self::has_ai_prompt(&content, &self.prompts) {
+            return Ok(false);
+        }
+        let Some(ref keys) = self.prompts.approve_keys else {
+            return Ok(false);
+        };
+        if let Some(pattern) = denied_pattern(&content, deny_patterns) {
+            tracing::info!(
+                session = %self.session_name,
+                "Not auto-approving prompt: matches deny pattern '{}'",
+                pattern
+            );
+            return Ok(false);
+        }
+        if !allowed_prompt(&content, allow_patterns) {
+            tracing::info!(
+                session = %self.session_name,
+                "Not auto-approving prompt: matches no allow pattern"
+            );
+            return Ok(false);
+        }
+        for key in keys {
+            self.send_keys(key)?;
+        }
+        Ok(true)
+    }
+
+    /// Check if the content contains AI-specific prompts that need user attention:
+    /// all of the `attention` patterns.
+    ///
+    /// Only the last `PROMPT_CONTEXT_LINES` lines are considered, so prompt
+    /// text that has scrolled up into the output doesn't count.
+    fn has_ai_prompt(content: &str, prompts: &PromptPatterns) -> bool {
+        let Some(ref patterns) = prompts.attention else {
+            return false;
+        };
+        let lines: Vec<&str> = content.lines().collect();
+        let start = lines.len().saturating_sub(PROMPT_CONTEXT_LINES);
+        let content = lines[start..].join("\n");
+        !patterns.is_empty() && patterns.iter().all(|p| content.contains(p.as_str()))
     }
 
     /// Attach interactively to the tmux session.
@@ -375,11 +663,11 @@ impl TmuxSession {
 
             while !resize_stop.load(Ordering::Relaxed) {
                 std::thread::sleep(std::time::Duration::from_millis(200));
-                if let Ok(current_size) = crossterm::terminal::size() {
-                    if current_size != last_size {
-                        last_size = current_size;
-                        do_resize(current_size.0, current_size.1, &session_name_for_resize, pty_fd);
-                    }
+                if let Ok(current_size) = crossterm::terminal::size()
+                    && current_size != last_size
+                {
+                    last_size = current_size;
+                    do_resize(current_size.0, current_size.1, &session_name_for_resize, pty_fd);
                 }
             }
         });
@@ -480,27 +768,60 @@ impl TmuxSession {
         }
     }
 
-    /// Clean up all gana tmux sessions.
+    /// Names of all gana tmux sessions. Empty when no tmux server is
+    /// running.
+    pub fn list_gana_sessions(cmd_exec: &dyn CmdExec) -> Vec<String> {
+        let Ok(output) = cmd_exec.output(
+            "tmux",
+            &args(&["list-sessions", "-F", "#{session_name}"]),
+        ) else {
+            return Vec::new();
+        };
+        output
+            .lines()
+            .map(str::trim)
+            .filter(|name| name.starts_with(TMUX_PREFIX))
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// When each gana session's panes last printed something, in Unix
+    /// seconds, from a single `list-panes` call for all of them.
     ///
-    /// Lists all tmux sessions and kills any that start with the gana prefix.
-    pub fn cleanup_sessions(cmd_exec: &dyn CmdExec) -> Result<(), TmuxError> {
+    /// Sessions that aren't running are missing; if no tmux server is
+    /// running the map is empty. Any other failure is an error, since
+    /// reading it as "no sessions" would report them all dead.
+    pub fn pane_activity(cmd_exec: &dyn CmdExec) -> Result<HashMap<String, u64>, CmdError> {
         let output = match cmd_exec.output(
             "tmux",
-            &args(&["list-sessions", "-F", "#{session_name}"]),
+            &args(&["list-panes", "-a", "-F", "#{session_name} #{window_activity}"]),
         ) {
             Ok(output) => output,
-            Err(_) => {
-                // No tmux server running or no sessions - nothing to clean up
-                return Ok(());
-            }
+            Err(e) if no_server(&e) => return Ok(HashMap::new()),
+            Err(e) => return Err(e),
         };
-
+        let mut activity = HashMap::new();
         for line in output.lines() {
-            let session_name = line.trim();
-            if session_name.starts_with(TMUX_PREFIX) {
-                // Best-effort cleanup - ignore errors for individual sessions
-                let _ = cmd_exec.run("tmux", &args(&["kill-session", "-t", session_name]));
+            let Some((name, at)) = line.trim().rsplit_once(' ') else {
+                continue;
+            };
+            if !name.starts_with(TMUX_PREFIX) {
+                continue;
             }
+            let at = at.parse().unwrap_or(0);
+            let latest = activity.entry(name.to_string()).or_insert(at);
+            *latest = (*latest).max(at);
+        }
+        Ok(activity)
+    }
+
+    /// Clean up all gana tmux sessions.
+    ///
+    /// Lists all tmux sessions and kills any that start with the gana prefix.
+    pub fn cleanup_sessions(cmd_exec: &dyn CmdExec) -> Result<(), TmuxError> {
+        for session_name in Self::list_gana_sessions(cmd_exec) {
+            // Best-effort cleanup - ignore errors for individual sessions
+            let _ = cmd_exec.run("tmux", &args(&["kill-session", "-t", &session_name]));
         }
 
         Ok(())
@@ -514,9 +835,11 @@ mod tests {
 
     // --- Mock CmdExec that records commands ---
 
+    type CommandLog = Arc<Mutex<Vec<(String, Vec<String>)>>>;
+
     #[derive(Default, Clone)]
     struct RecordingCmdExec {
-        commands: Arc<Mutex<Vec<(String, Vec<String>)>>>,
+        commands: CommandLog,
         output_responses: Arc<Mutex<Vec<String>>>,
         run_fail_on: Arc<Mutex<Vec<String>>>,
     }
@@ -651,7 +974,6 @@ mod tests {
             }
         }
 
-        // Use "vim" to skip trust prompt polling (which would block on timeout)
         let mut session = TmuxSession::new(
             "test-session",
             "vim",
@@ -682,10 +1004,9 @@ mod tests {
         let cmd_exec = RecordingCmdExec::new();
         // has-session succeeds (session exists), so it should be killed
 
-        // Use "vim" to skip trust prompt polling (which would block on timeout)
         let mut session = TmuxSession::new(
             "existing",
-            "vim",
+            "claude",
             Box::new(cmd_exec.clone()),
             Box::new(MockPtyFactory::new()),
         );
@@ -693,9 +1014,11 @@ mod tests {
         session.start("/tmp/workdir").unwrap();
 
         let commands = cmd_exec.commands();
-        // Should have: has-session, kill-session
+        // Should have: has-session, kill-session, and no waiting for the
+        // trust prompt, which is the caller's to answer
         assert_eq!(commands[0].1[0], "has-session");
         assert_eq!(commands[1].1[0], "kill-session");
+        assert_eq!(commands.len(), 2);
     }
 
     #[test]
@@ -746,6 +1069,21 @@ mod tests {
         assert!(commands[0].1.contains(&"-J".to_string()));
     }
 
+    #[test]
+    fn test_last_activity() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec!["1700000000\n".to_string()]);
+        let session = TmuxSession::new(
+            "test-activity",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+
+        let at = session.last_activity().unwrap();
+        assert_eq!(at.timestamp(), 1_700_000_000);
+        assert!(cmd_exec.commands()[0].1.contains(&"#{window_activity}".to_string()));
+    }
+
     #[test]
     fn test_has_updated() {
         let cmd_exec = RecordingCmdExec::with_output_responses(vec![
@@ -771,6 +1109,36 @@ mod tests {
         assert!(!session.has_updated().unwrap());
     }
 
+    #[test]
+    fn test_has_updated_ignores_spinner_noise() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "output\n✻ Working… (2s · esc to interrupt)".to_string(),
+            "output\n✶ Working… (3s · esc to interrupt)".to_string(),
+            "output\nDone".to_string(),
+        ]);
+
+        let mut session = TmuxSession::new(
+            "test-spinner",
+            "claude",
+            Box::new(cmd_exec),
+            Box::new(MockPtyFactory::new()),
+        );
+
+        assert!(session.has_updated().unwrap());
+        // Only the spinner frame and timer changed
+        assert!(!session.has_updated().unwrap());
+        assert!(session.has_updated().unwrap());
+    }
+
+    #[test]
+    fn test_scrolled_away_prompt_not_attention() {
+        let mut content = "No, and tell Claude what to do differently\n".to_string();
+        for _ in 0..PROMPT_CONTEXT_LINES {
+            content.push_str("more output\n");
+        }
+        assert!(!TmuxSession::has_ai_prompt(&content, &PromptPatterns::builtin("claude")));
+    }
+
     #[test]
     fn test_has_updated_detects_ai_prompt() {
         let prompt_content = "Some output\nNo, and tell Claude what to do differently\n";
@@ -793,6 +1161,210 @@ mod tests {
         assert!(session.has_updated().unwrap());
     }
 
+    #[test]
+    fn test_needs_attention() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "No, and tell Claude what to do differently\n".to_string(),
+            "Working...\n".to_string(),
+        ]);
+
+        let session = TmuxSession::new(
+            "test-attention",
+            "claude",
+            Box::new(cmd_exec),
+            Box::new(MockPtyFactory::new()),
+        );
+
+        assert!(session.needs_attention().unwrap());
+        assert!(!session.needs_attention().unwrap());
+    }
+
+    #[test]
+    fn test_needs_attention_with_configured_prompts() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "Do you want to proceed?\n❯ 1. Yes\n".to_string(),
+        ]);
+        let prompts = PromptPatterns {
+            attention: Some(vec!["Do you want to proceed?".to_string()]),
+            ..PromptPatterns::default()
+        };
+        let session = TmuxSession::new(
+            "test-configured",
+            "claude",
+            Box::new(cmd_exec),
+            Box::new(MockPtyFactory::new()),
+        )
+        .with_prompts(prompts.or(PromptPatterns::builtin("claude")));
+
+        assert!(session.needs_attention().unwrap());
+    }
+
+    #[test]
+    fn test_is_alive_detects_dead_pane() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "0\n".to_string(),
+            "1\n".to_string(),
+        ]);
+        let session = TmuxSession::new(
+            "test-alive",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+
+        assert!(session.is_alive());
+        assert!(!session.is_alive());
+
+        cmd_exec.fail_run_when_contains("has-session");
+        assert!(!session.is_alive());
+    }
+
+    #[test]
+    fn test_respawn_starts_detached_session() {
+        let cmd_exec = RecordingCmdExec::default();
+        let session = TmuxSession::new(
+            "test-respawn",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+
+        session.respawn("/tmp/worktree").unwrap();
+
+        let commands = cmd_exec.commands();
+        assert_eq!(commands[0].1[0], "kill-session");
+        assert_eq!(commands[1].1[0], "new-session");
+        assert!(commands[1].1.contains(&"/tmp/worktree".to_string()));
+        assert!(commands[1].1.contains(&"claude".to_string()));
+    }
+
+    #[test]
+    fn test_respawn_runs_command_override() {
+        let cmd_exec = RecordingCmdExec::default();
+        let mut session = TmuxSession::new(
+            "test-respawn",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+        session.set_command("docker exec -it gana_test-respawn claude".to_string());
+
+        session.respawn("/tmp/worktree").unwrap();
+
+        let new_session = &cmd_exec.commands()[1].1;
+        assert_eq!(
+            new_session.last().map(String::as_str),
+            Some("docker exec -it gana_test-respawn claude")
+        );
+    }
+
+    #[test]
+    fn test_auto_approve_sends_program_keys() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "Edit src/main.rs?\n> Yes\nNo, and tell Claude what to do differently\n".to_string(),
+        ]);
+        let session = TmuxSession::new(
+            "test-approve",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+
+        assert!(session.auto_approve(&[], &[]).unwrap());
+        let commands = cmd_exec.commands();
+        let sent = commands.last().unwrap();
+        assert_eq!(sent.1[0], "send-keys");
+        assert_eq!(sent.1.last().unwrap(), "Enter");
+    }
+
+    #[test]
+    fn test_auto_approve_respects_deny_list() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "Bash(git push --force origin main)\nNo, and tell Claude what to do differently\n".to_string(),
+        ]);
+        let session = TmuxSession::new(
+            "test-deny",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+
+        assert!(!session.auto_approve(&[], &["Push --Force".to_string()]).unwrap());
+        assert!(cmd_exec.commands().iter().all(|(_, a)| a[0] != "send-keys"));
+    }
+
+    #[test]
+    fn test_auto_approve_without_prompt_or_unknown_program() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "just output\n".to_string(),
+            "No, and tell Claude what to do differently\n".to_string(),
+        ]);
+        let session = TmuxSession::new(
+            "test-noprompt",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+        assert!(!session.auto_approve(&[], &[]).unwrap());
+
+        assert!(PromptPatterns::builtin("bash").approve_keys.is_none());
+        assert_eq!(PromptPatterns::builtin("aider").approve_keys.unwrap(), ["y", "Enter"]);
+    }
+
+    #[test]
+    fn test_auto_approve_only_allowed_prompts() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "Delete branch gana/old?\nNo, and tell Claude what to do differently\n".to_string(),
+            "Read file src/main.rs?\nNo, and tell Claude what to do differently\n".to_string(),
+        ]);
+        let session = TmuxSession::new(
+            "test-allow",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+        let allow = ["read file".to_string()];
+
+        assert!(!session.auto_approve(&allow, &[]).unwrap());
+        assert!(cmd_exec.commands().iter().all(|(_, a)| a[0] != "send-keys"));
+        assert!(session.auto_approve(&allow, &[]).unwrap());
+        assert!(allowed_prompt("anything", &[]));
+    }
+
+    #[test]
+    fn test_agent_state_from_busy_and_attention_patterns() {
+        let claude = PromptPatterns::builtin("claude");
+        assert_eq!(
+            agent_state("Refactoring…\n✻ Thinking… (esc to interrupt)\n", &claude),
+            Some(AgentState::Working)
+        );
+        assert_eq!(
+            agent_state("Edit file?\nNo, and tell Claude what to do differently\n", &claude),
+            Some(AgentState::Waiting)
+        );
+        assert_eq!(agent_state("All tests pass.\n> \n", &claude), Some(AgentState::Idle));
+        assert_eq!(agent_state("anything", &PromptPatterns::builtin("aider")), None);
+    }
+
+    #[test]
+    fn test_last_lines_skips_blank_pane_bottom() {
+        assert_eq!(last_lines("a\nb\nError: rate limited\n\n\n", 2), "b\nError: rate limited");
+        assert_eq!(last_lines("only\n", 5), "only");
+    }
+
+    #[test]
+    fn test_denied_pattern_only_checks_recent_lines() {
+        let mut content = String::from("delete old logs\n");
+        for _ in 0..PROMPT_CONTEXT_LINES {
+            content.push_str("line\n");
+        }
+        let patterns = vec!["delete".to_string()];
+        assert!(denied_pattern(&content, &patterns).is_none());
+
+        content.push_str("Delete 3 files?\n");
+        assert_eq!(denied_pattern(&content, &patterns), Some("delete"));
+    }
+
     #[test]
     fn test_send_keys() {
         let cmd_exec = RecordingCmdExec::new();
@@ -882,6 +1454,34 @@ mod tests {
         assert_eq!(commands[2].1[0], "kill-session");
     }
 
+    #[test]
+    fn test_pane_activity_takes_latest_pane_per_session() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![format!(
+            "{p}a 100\n{p}a 140\n{p}b 90\nother 200\n",
+            p = TMUX_PREFIX
+        )]);
+
+        let activity = TmuxSession::pane_activity(&cmd_exec).unwrap();
+        assert_eq!(activity.len(), 2);
+        assert_eq!(activity[&format!("{}a", TMUX_PREFIX)], 140);
+        assert_eq!(activity[&format!("{}b", TMUX_PREFIX)], 90);
+        assert_eq!(cmd_exec.commands().len(), 1);
+    }
+
+    #[test]
+    fn test_pane_activity_errors_unless_no_server() {
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_output().times(1).returning(|_, _| {
+            Err(CmdError::Failed("no server running on /tmp/tmux-1000/default".into()))
+        });
+        assert!(TmuxSession::pane_activity(&mock).unwrap().is_empty());
+
+        let mut mock = crate::cmd::MockCmdExec::new();
+        mock.expect_output()
+            .returning(|_, _| Err(CmdError::Failed("timed out".into())));
+        assert!(TmuxSession::pane_activity(&mock).is_err());
+    }
+
     #[test]
     fn test_cleanup_sessions_no_server() {
         // When tmux server isn't running, cleanup should succeed silently
@@ -913,19 +1513,15 @@ mod tests {
     #[test]
     fn test_has_ai_prompt_aider() {
         assert!(TmuxSession::has_ai_prompt(
-            "output\n(Y)es/(N)o/(D)on't ask again\n> ",
-            "aider"
-        ));
-        assert!(!TmuxSession::has_ai_prompt("normal output", "aider"));
+            "output\n(Y)es/(N)o/(D)on't ask again\n> ", &PromptPatterns::builtin("aider")));
+        assert!(!TmuxSession::has_ai_prompt("normal output", &PromptPatterns::builtin("aider")));
     }
 
     #[test]
     fn test_has_ai_prompt_gemini() {
         assert!(TmuxSession::has_ai_prompt(
-            "Do you want to proceed? Yes, allow once",
-            "gemini"
-        ));
-        assert!(!TmuxSession::has_ai_prompt("normal output", "gemini"));
+            "Do you want to proceed? Yes, allow once", &PromptPatterns::builtin("gemini")));
+        assert!(!TmuxSession::has_ai_prompt("normal output", &PromptPatterns::builtin("gemini")));
     }
 
     #[test]
@@ -991,6 +1587,43 @@ mod tests {
         assert!(!session.attached);
     }
 
+    #[test]
+    fn test_capture_pane_range() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec![
+            "older".to_string(),
+            "recent".to_string(),
+        ]);
+        let session = TmuxSession::new(
+            "test-range",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+
+        assert_eq!(session.capture_pane_range(-600, Some(-101)).unwrap(), "older");
+        assert_eq!(session.capture_pane_range(-100, None).unwrap(), "recent");
+
+        let commands = cmd_exec.commands();
+        let joined = commands[0].1.join(" ");
+        assert!(joined.ends_with("-S -600 -E -101"), "{}", joined);
+        let joined = commands[1].1.join(" ");
+        assert!(joined.ends_with("-S -100"), "{}", joined);
+        assert!(commands[1].1.contains(&"-e".to_string()));
+    }
+
+    #[test]
+    fn test_history_size() {
+        let cmd_exec = RecordingCmdExec::with_output_responses(vec!["1234\n".to_string()]);
+        let session = TmuxSession::new(
+            "test-history-size",
+            "claude",
+            Box::new(cmd_exec.clone()),
+            Box::new(MockPtyFactory::new()),
+        );
+        assert_eq!(session.history_size().unwrap(), 1234);
+        assert!(cmd_exec.commands()[0].1.contains(&"#{history_size}".to_string()));
+    }
+
     #[test]
     fn test_capture_pane_includes_ansi_and_join_flags() {
         // Verify that both normal and full_history capture include -e and -J
@@ -1019,7 +1652,7 @@ mod tests {
     }
 
     #[test]
-    fn test_handle_trust_prompt_claude_detects_and_sends_enter() {
+    fn test_trust_prompt_claude_detects_and_sends_enter() {
         // Mock returns the Claude trust prompt on the first capture
         let cmd_exec = RecordingCmdExec::with_output_responses(vec![
             "Welcome to Claude\nDo you trust the files in this folder?\n> ".to_string(),
@@ -1032,7 +1665,8 @@ mod tests {
             Box::new(MockPtyFactory::new()),
         );
 
-        session.handle_trust_prompt().unwrap();
+        let answered = session.trust_prompt().unwrap().answer(&cmd_exec).unwrap();
+        assert!(answered);
 
         let commands = cmd_exec.commands();
         // Should have: capture-pane (to detect prompt), then send-keys Enter
@@ -1046,7 +1680,7 @@ mod tests {
     }
 
     #[test]
-    fn test_handle_trust_prompt_aider_sends_d_and_enter() {
+    fn test_trust_prompt_aider_sends_d_and_enter() {
         let cmd_exec = RecordingCmdExec::with_output_responses(vec![
             "Open documentation url for more info\n".to_string(),
         ]);
@@ -1058,7 +1692,7 @@ mod tests {
             Box::new(MockPtyFactory::new()),
         );
 
-        session.handle_trust_prompt().unwrap();
+        session.trust_prompt().unwrap().answer(&cmd_exec).unwrap();
 
         let commands = cmd_exec.commands();
         let send_cmds: Vec<_> = commands
@@ -1072,7 +1706,7 @@ mod tests {
     }
 
     #[test]
-    fn test_handle_trust_prompt_unknown_program_skips() {
+    fn test_trust_prompt_unknown_program_skips() {
         let cmd_exec = RecordingCmdExec::new();
 
         let session = TmuxSession::new(
@@ -1082,10 +1716,7 @@ mod tests {
             Box::new(MockPtyFactory::new()),
         );
 
-        session.handle_trust_prompt().unwrap();
-
-        // No commands should have been issued
-        let commands = cmd_exec.commands();
-        assert!(commands.is_empty(), "unknown program should skip trust prompt handling");
+        assert!(session.trust_prompt().is_none(), "unknown program has no trust prompt");
+        assert!(cmd_exec.commands().is_empty());
     }
 }
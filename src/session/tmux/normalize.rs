@@ -0,0 +1,72 @@
+use std::sync::LazyLock;
+
+use regex_lite::Regex;
+
+/// ANSI escape sequences (CSI and OSC) emitted by `capture-pane -e`.
+static ANSI: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\x1b\[[0-9;?]*[ -/]*[@-~]|\x1b\][^\x07\x1b]*(?:\x07|\x1b\\)").unwrap()
+});
+
+/// Wall-clock times such as `14:03`, `14:03:59` or `2:03 PM`.
+static CLOCK: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\b\d{1,2}:\d{2}(?::\d{2})?(?:\s?[AaPp][Mm])?\b").unwrap()
+});
+
+/// Spinner glyphs used by agent status lines (braille dots, Claude's stars).
+static SPINNER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[\x{2800}-\x{28FF}✻✽✶✳✢·◐◓◑◒]").unwrap());
+
+static DIGITS: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"\d+").unwrap());
+
+/// Reduce pane content to what matters for change detection.
+///
+/// Strips colours, spinner glyphs, clock times and trailing whitespace. On
+/// lines that carry a spinner (agent status lines) numbers are masked too,
+/// since they hold elapsed-time and token counters that tick every frame.
+pub fn normalize_content(content: &str) -> String {
+    let plain = ANSI.replace_all(content, "");
+    let mut lines: Vec<String> = plain
+        .lines()
+        .map(|line| {
+            let line = if SPINNER.is_match(line) {
+                let stripped = SPINNER.replace_all(line, "");
+                DIGITS.replace_all(&stripped, "0").into_owned()
+            } else {
+                CLOCK.replace_all(line, "00:00").into_owned()
+            };
+            line.trim_end().to_string()
+        })
+        .collect();
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spinner_frames_normalize_equal() {
+        let a = "output\n⠋ Thinking… (3s · 120 tokens)\n";
+        let b = "output\n⠙ Thinking… (4s · 131 tokens)   \n\n";
+        assert_eq!(normalize_content(a), normalize_content(b));
+    }
+
+    #[test]
+    fn test_clock_and_colours_ignored() {
+        let a = "\x1b[32mready\x1b[0m  last run 09:14:02";
+        let b = "\x1b[33mready\x1b[0m  last run 09:14:03";
+        assert_eq!(normalize_content(a), normalize_content(b));
+    }
+
+    #[test]
+    fn test_real_changes_kept() {
+        assert_ne!(
+            normalize_content("test result: 3 passed"),
+            normalize_content("test result: 4 passed")
+        );
+        assert_ne!(normalize_content("foo"), normalize_content("bar"));
+    }
+}
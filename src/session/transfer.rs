@@ -0,0 +1,368 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::cmd::{CmdError, CmdExec, args};
+use crate::session::git::GitWorktree;
+use crate::session::git::worktree_git::CommitOptions;
+use crate::session::{Instance, InstanceStatus};
+
+/// Format version written to export files.
+const EXPORT_VERSION: u32 = 1;
+
+#[derive(Debug, Error)]
+pub enum TransferError {
+    #[error("{0}: {1}")]
+    Io(String, std::io::Error),
+    #[error("not a gana export: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("export format {0} is newer than this gana supports; update gana")]
+    UnsupportedVersion(u32),
+    #[error(transparent)]
+    Cmd(#[from] CmdError),
+}
+
+/// Sessions written by `gana export`, for `gana import` on another machine.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Export {
+    pub version: u32,
+    pub exported_at: DateTime<Utc>,
+    pub sessions: Vec<ExportedSession>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ExportedSession {
+    pub session: Instance,
+    /// Commit the branch pointed at when exported.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+    /// `git bundle` of the branch's commits since its base, relative to the
+    /// export file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<String>,
+}
+
+/// What `import` did with each session.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: Vec<String>,
+    /// Title and reason.
+    pub skipped: Vec<(String, String)>,
+    /// Title and branch of sessions imported onto a new branch, since the
+    /// repo's branch of that name points elsewhere.
+    pub renamed: Vec<(String, String)>,
+}
+
+/// Directory bundles are written to: `sessions.json` gets `sessions.bundles/`.
+fn bundle_dir(file: &Path) -> PathBuf {
+    file.with_extension("bundles")
+}
+
+/// Write `sessions` to `file`. With `bundle`, running sessions' uncommitted
+/// changes are committed (as on pause) and each branch's commits are
+/// bundled next to the file, so the importer doesn't need them pushed.
+pub fn export(
+    sessions: &[Instance],
+    file: &Path,
+    bundle: bool,
+    opts: &CommitOptions,
+    cmd: &dyn CmdExec,
+) -> Result<Export, TransferError> {
+    let mut exported = Vec::new();
+    for instance in sessions {
+        let mut entry = ExportedSession {
+            session: instance.clone(),
+            head: None,
+            bundle: None,
+        };
+        if let Some(ref worktree) = instance.git_worktree {
+            if bundle && Path::new(worktree.worktree_path()).exists() {
+                let msg = opts.autosave_message(&instance.message_context());
+                worktree.commit_changes(&msg, opts, cmd)?;
+            }
+            entry.head = cmd
+                .output("git", &args(&["-C", worktree.repo_path(), "rev-parse", worktree.branch()]))
+                .ok()
+                .map(|h| h.trim().to_string());
+            if bundle {
+                entry.bundle = bundle_branch(instance, worktree, entry.head.as_deref(), file, cmd)?;
+            }
+        }
+        exported.push(entry);
+    }
+
+    let export = Export {
+        version: EXPORT_VERSION,
+        exported_at: Utc::now(),
+        sessions: exported,
+    };
+    let json = serde_json::to_string_pretty(&export)?;
+    std::fs::write(file, json).map_err(|e| TransferError::Io(file.display().to_string(), e))?;
+    Ok(export)
+}
+
+/// Bundle the branch's commits since its base commit. `None` when it has
+/// none, so there is nothing to carry over.
+fn bundle_branch(
+    instance: &Instance,
+    worktree: &GitWorktree,
+    head: Option<&str>,
+    file: &Path,
+    cmd: &dyn CmdExec,
+) -> Result<Option<String>, TransferError> {
+    let base = worktree.base_commit_sha();
+    if head.is_none() || head == Some(base) {
+        return Ok(None);
+    }
+    let dir = bundle_dir(file);
+    std::fs::create_dir_all(&dir).map_err(|e| TransferError::Io(dir.display().to_string(), e))?;
+    let name = format!("{}.bundle", crate::session::tmux::sanitize_name(&instance.title));
+    let path = dir.join(&name);
+    let mut bundle_args = args(&["-C", worktree.repo_path(), "bundle", "create", "--quiet"]);
+    bundle_args.push(path.to_string_lossy().to_string());
+    bundle_args.push(worktree.branch().to_string());
+    if !base.is_empty() {
+        bundle_args.push(format!("^{}", base));
+    }
+    cmd.run("git", &bundle_args)?;
+
+    let dir_name = dir.file_name().unwrap_or_default().to_string_lossy();
+    Ok(Some(format!("{}/{}", dir_name, name)))
+}
+
+/// Read an export file.
+pub fn read_export(file: &Path) -> Result<Export, TransferError> {
+    let contents =
+        std::fs::read_to_string(file).map_err(|e| TransferError::Io(file.display().to_string(), e))?;
+    let export: Export = serde_json::from_str(&contents)?;
+    if export.version > EXPORT_VERSION {
+        return Err(TransferError::UnsupportedVersion(export.version));
+    }
+    Ok(export)
+}
+
+/// Add the sessions in `export` (read from `file`) to `instances`, attached
+/// to the repo at `repo_root`. Sessions with worktrees come in paused, with
+/// their branch created from the bundle or an existing commit; resuming
+/// sets the worktree up. Titles already in use are skipped.
+pub fn import(
+    export: Export,
+    file: &Path,
+    repo_root: &str,
+    config_dir: &Path,
+    instances: &mut Vec<Instance>,
+    cmd: &dyn CmdExec,
+) -> ImportReport {
+    let base_dir = file.parent().unwrap_or(Path::new("."));
+    let mut report = ImportReport::default();
+    for entry in export.sessions {
+        let title = entry.session.title.clone();
+        if crate::session::title::clashing(&title, instances).is_some() {
+            report.skipped.push((title, "a session with that title exists".to_string()));
+            continue;
+        }
+        let branch = entry.session.git_worktree.as_ref().map(|wt| wt.branch().to_string());
+        match import_session(entry, base_dir, repo_root, config_dir, cmd) {
+            Ok(instance) => {
+                if let Some(ref wt) = instance.git_worktree
+                    && branch.as_deref() != Some(wt.branch())
+                {
+                    report.renamed.push((title.clone(), wt.branch().to_string()));
+                }
+                report.imported.push(title);
+                instances.push(instance);
+            }
+            Err(e) => report.skipped.push((title, e.to_string())),
+        }
+    }
+    report
+}
+
+fn import_session(
+    entry: ExportedSession,
+    base_dir: &Path,
+    repo_root: &str,
+    config_dir: &Path,
+    cmd: &dyn CmdExec,
+) -> Result<Instance, TransferError> {
+    let mut instance = entry.session;
+    instance.path = repo_root.to_string();
+    instance.updated_at = Utc::now();
+    instance.restart_count = 0;
+    // Images and runtimes are per machine; the importer's config decides
+    instance.container = None;
+
+    let Some(worktree) = instance.git_worktree.take() else {
+        return Ok(instance);
+    };
+    if let Some(ref bundle) = entry.bundle {
+        let path = base_dir.join(bundle);
+        cmd.run(
+            "git",
+            &args(&["-C", repo_root, "fetch", "--quiet", &path.to_string_lossy(), worktree.branch()]),
+        )?;
+    }
+    // A branch of that name is only the session's if it's at the exported
+    // commit; otherwise it's someone else's work, imported beside
+    let head = entry.head.as_deref().unwrap_or(worktree.base_commit_sha());
+    let mut branch = worktree.branch().to_string();
+    let existing = branch_head(repo_root, &branch, cmd);
+    let owns_branch = existing.as_deref() != Some(head);
+    if owns_branch {
+        if existing.is_some() {
+            let renamed = format!("{}-imported", branch);
+            branch = (1..)
+                .map(|n| if n == 1 { renamed.clone() } else { format!("{}-{}", renamed, n) })
+                .find(|candidate| branch_head(repo_root, candidate, cmd).is_none())
+                .unwrap_or(renamed);
+        }
+        cmd.run("git", &args(&["-C", repo_root, "branch", &branch, head]))
+            .map_err(|_| {
+                CmdError::Failed(format!(
+                    "branch {} isn't in this repo; fetch it or export with --bundle",
+                    worktree.branch()
+                ))
+            })?;
+    }
+
+    instance.branch = branch.clone();
+    instance.git_worktree = Some(GitWorktree::from_storage(
+        repo_root.to_string(),
+        GitWorktree::unique_dir(config_dir, &worktree.session_id),
+        worktree.session_id.clone(),
+        branch,
+        worktree.base_commit_sha().to_string(),
+    ));
+    // Kill only deletes the branch if the import created it
+    instance.owns_branch = owns_branch;
+    instance.started = true;
+    instance.status = InstanceStatus::Paused;
+    Ok(instance)
+}
+
+/// Commit the local `branch` points at, if it exists.
+fn branch_head(repo_root: &str, branch: &str, cmd: &dyn CmdExec) -> Option<String> {
+    let branch_ref = format!("refs/heads/{}", branch);
+    cmd.output("git", &args(&["-C", repo_root, "rev-parse", "--verify", "--quiet", &branch_ref]))
+        .ok()
+        .map(|sha| sha.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cmd::SystemCmdExec;
+    use crate::session::InstanceOptions;
+    use std::process::Command;
+
+    fn git(dir: &Path, a: &[&str]) -> String {
+        let out = Command::new("git").arg("-C").arg(dir).args(a).output().unwrap();
+        assert!(out.status.success(), "git {:?}: {}", a, String::from_utf8_lossy(&out.stderr));
+        String::from_utf8_lossy(&out.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn test_export_bundle_and_import_real_repos() {
+        let ours = tempfile::TempDir::new().unwrap();
+        git(ours.path(), &["init", "-q"]);
+        git(ours.path(), &["config", "user.email", "t@t"]);
+        git(ours.path(), &["config", "user.name", "t"]);
+        std::fs::write(ours.path().join("a.txt"), "one\n").unwrap();
+        git(ours.path(), &["add", "."]);
+        git(ours.path(), &["commit", "-q", "-m", "init"]);
+        let base = git(ours.path(), &["rev-parse", "HEAD"]);
+
+        // The colleague's clone has the base commit but not the session's work
+        let theirs = tempfile::TempDir::new().unwrap();
+        let theirs_repo = theirs.path().join("repo");
+        git(theirs.path(), &["clone", "-q", &ours.path().to_string_lossy(), "repo"]);
+
+        // A running session with one commit and uncommitted changes
+        let wt = ours.path().join("wt");
+        git(ours.path(), &["worktree", "add", "-q", "-b", "gana/fix", &wt.to_string_lossy(), "HEAD"]);
+        git(&wt, &["config", "user.email", "t@t"]);
+        git(&wt, &["config", "user.name", "t"]);
+        std::fs::write(wt.join("a.txt"), "two\n").unwrap();
+        git(&wt, &["commit", "-q", "-am", "fix"]);
+        std::fs::write(wt.join("b.txt"), "wip\n").unwrap();
+
+        let mut instance = Instance::new(InstanceOptions {
+            title: "fix".to_string(),
+            path: ours.path().to_string_lossy().to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        instance.started = true;
+        instance.status = InstanceStatus::Running;
+        instance.git_worktree = Some(GitWorktree::from_storage(
+            ours.path().to_string_lossy().to_string(),
+            wt.to_string_lossy().to_string(),
+            "fix".to_string(),
+            "gana/fix".to_string(),
+            base,
+        ));
+
+        let out = tempfile::TempDir::new().unwrap();
+        let file = out.path().join("sessions.json");
+        let opts = CommitOptions::from_config(&crate::config::Config::default());
+        let export = export(&[instance], &file, true, &opts, &SystemCmdExec).unwrap();
+        assert_eq!(export.sessions[0].bundle.as_deref(), Some("sessions.bundles/gana_fix.bundle"));
+
+        let config_dir = tempfile::TempDir::new().unwrap();
+        let mut instances = Vec::new();
+        let report = import(
+            read_export(&file).unwrap(),
+            &file,
+            &theirs_repo.to_string_lossy(),
+            config_dir.path(),
+            &mut instances,
+            &SystemCmdExec,
+        );
+        assert_eq!(report.imported, ["fix"]);
+        assert_eq!(instances[0].status, InstanceStatus::Paused);
+        assert_eq!(instances[0].project(), theirs_repo.to_string_lossy());
+        // The uncommitted file was committed and travelled in the bundle
+        assert_eq!(git(&theirs_repo, &["show", "gana/fix:b.txt"]), "wip");
+
+        // Importing again clashes with the session now in the list
+        let again = import(
+            read_export(&file).unwrap(),
+            &file,
+            &theirs_repo.to_string_lossy(),
+            config_dir.path(),
+            &mut instances,
+            &SystemCmdExec,
+        );
+        assert!(again.imported.is_empty());
+        assert_eq!(again.skipped.len(), 1);
+
+        // The branch it created is the session's: a fresh import attaches to it
+        let import_fresh = || {
+            let mut instances = Vec::new();
+            let report = import(
+                read_export(&file).unwrap(),
+                &file,
+                &theirs_repo.to_string_lossy(),
+                config_dir.path(),
+                &mut instances,
+                &SystemCmdExec,
+            );
+            (report, instances.remove(0))
+        };
+        let (report, attached) = import_fresh();
+        assert!(report.renamed.is_empty());
+        assert!(!attached.owns_branch);
+
+        // A branch of that name at another commit is left alone
+        let moved = git(&theirs_repo, &["rev-parse", "gana/fix~1"]);
+        git(&theirs_repo, &["branch", "-f", "gana/fix", &moved]);
+        let (report, beside) = import_fresh();
+        assert_eq!(report.renamed, [("fix".to_string(), "gana/fix-imported".to_string())]);
+        assert!(beside.owns_branch);
+        assert_eq!(beside.git_worktree.unwrap().branch(), "gana/fix-imported");
+        assert_eq!(git(&theirs_repo, &["rev-parse", "gana/fix"]), moved);
+        assert_eq!(git(&theirs_repo, &["show", "gana/fix-imported:b.txt"]), "wip");
+    }
+}
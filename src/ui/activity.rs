@@ -0,0 +1,108 @@
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::session::journal::{Event, EventKind};
+
+/// Renders the event journal across all sessions, newest first.
+pub struct ActivityView {
+    events: Vec<Event>,
+}
+
+impl ActivityView {
+    pub fn new() -> Self {
+        Self { events: Vec::new() }
+    }
+
+    pub fn set_events(&mut self, events: Vec<Event>) {
+        self.events = events;
+    }
+}
+
+/// Colour for an event, matching the session list's status colours.
+fn kind_style(kind: EventKind) -> Style {
+    match kind {
+        EventKind::Created | EventKind::Pushed | EventKind::Done => {
+            Style::default().fg(Color::Green)
+        }
+        EventKind::PromptSent => Style::default().fg(Color::Cyan),
+        EventKind::AttentionNeeded => Style::default().fg(Color::Yellow),
+        EventKind::AutoResponse => Style::default().fg(Color::DarkGray),
+        EventKind::Died => Style::default().fg(Color::Red),
+    }
+}
+
+fn event_line(event: &Event) -> Line<'static> {
+    let at = event.at.with_timezone(&chrono::Local);
+    let mut spans = vec![
+        Span::styled(at.format("%a %H:%M  ").to_string(), Style::default().fg(Color::DarkGray)),
+        Span::styled(event.session.clone(), Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw("  "),
+        Span::styled(event.kind.label(), kind_style(event.kind)),
+    ];
+    if let Some(ref detail) = event.detail {
+        let detail = detail.lines().next().unwrap_or_default();
+        spans.push(Span::styled(format!("  {}", detail), Style::default().fg(Color::DarkGray)));
+    }
+    Line::from(spans)
+}
+
+impl Widget for &ActivityView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default().borders(Borders::ALL).title("Activity");
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        if inner.height == 0 || inner.width == 0 {
+            return;
+        }
+
+        let lines: Vec<Line> = if self.events.is_empty() {
+            vec![Line::styled(
+                "No activity recorded yet",
+                Style::default().fg(Color::DarkGray),
+            )]
+        } else {
+            self.events
+                .iter()
+                .rev()
+                .take(inner.height as usize)
+                .map(event_line)
+                .collect()
+        };
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(session: &str, kind: EventKind, detail: Option<&str>) -> Event {
+        Event {
+            at: chrono::Utc::now(),
+            session: session.to_string(),
+            kind,
+            detail: detail.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_activity_render_newest_first() {
+        let mut view = ActivityView::new();
+        view.set_events(vec![
+            event("older", EventKind::Created, None),
+            event("newer", EventKind::PromptSent, Some("add tests\nmore")),
+        ]);
+
+        let area = Rect::new(0, 0, 60, 5);
+        let mut buf = Buffer::empty(area);
+        Widget::render(&view, area, &mut buf);
+
+        let row = |y: u16| -> String {
+            (0..60).map(|x| buf.cell((x, y)).unwrap().symbol().to_string()).collect()
+        };
+        assert!(row(1).contains("newer  prompt sent  add tests"));
+        assert!(!row(1).contains("more"));
+        assert!(row(2).contains("older  created"));
+    }
+}
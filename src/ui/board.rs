@@ -0,0 +1,271 @@
+use std::collections::HashSet;
+
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::session::instance::{Instance, InstanceStatus};
+
+/// A column of the status board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Loading,
+    Working,
+    Waiting,
+    Paused,
+    Done,
+}
+
+const COLUMNS: [Column; 5] = [
+    Column::Loading,
+    Column::Working,
+    Column::Waiting,
+    Column::Paused,
+    Column::Done,
+];
+
+impl Column {
+    /// The column a session belongs in; `waiting` means its agent shows a
+    /// prompt for the user.
+    pub fn of(instance: &Instance, waiting: bool) -> Column {
+        match instance.status {
+            InstanceStatus::Running if waiting => Column::Waiting,
+            InstanceStatus::Running => Column::Working,
+            InstanceStatus::Loading
+            | InstanceStatus::Queued
+            | InstanceStatus::Pending
+            | InstanceStatus::Failed
+            | InstanceStatus::WorktreeMissing
+            | InstanceStatus::Orphaned => Column::Loading,
+            InstanceStatus::Paused => Column::Paused,
+            InstanceStatus::Ready | InstanceStatus::Done => Column::Done,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Column::Loading => "Loading",
+            Column::Working => "Working",
+            Column::Waiting => "Waiting",
+            Column::Paused => "Paused",
+            Column::Done => "Done",
+        }
+    }
+
+    fn color(&self) -> Color {
+        match self {
+            Column::Loading => Color::DarkGray,
+            Column::Working => Color::Green,
+            Column::Waiting => Color::Yellow,
+            Column::Paused => Color::Blue,
+            Column::Done => Color::White,
+        }
+    }
+}
+
+/// One session on the board.
+struct Card {
+    index: usize,
+    title: String,
+    detail: String,
+    failed: bool,
+}
+
+/// Sessions as cards in status columns, an alternative to the list.
+///
+/// Selection is an index into the session list, so actions keep working
+/// on whatever card is highlighted.
+pub struct BoardView {
+    columns: [Vec<Card>; 5],
+    selected: usize,
+}
+
+impl BoardView {
+    pub fn new() -> Self {
+        Self {
+            columns: Default::default(),
+            selected: 0,
+        }
+    }
+
+    /// Rebuild the cards; `waiting` holds titles of sessions whose agent
+    /// waits for input.
+    pub fn set_items(&mut self, instances: &[Instance], waiting: &HashSet<String>) {
+        self.columns = Default::default();
+        for (index, instance) in instances.iter().enumerate() {
+            let column = Column::of(instance, waiting.contains(&instance.title));
+            let mut detail = instance.branch.clone();
+            if let Some(ref stats) = instance.diff_stats
+                && (stats.added_lines > 0 || stats.removed_lines > 0)
+            {
+                detail = format!("{} +{} -{}", detail, stats.added_lines, stats.removed_lines);
+            }
+            if instance.status == InstanceStatus::Pending
+                && let Some(ref upstream) = instance.after
+            {
+                detail = format!("after {}", upstream);
+            }
+            self.columns[column as usize].push(Card {
+                index,
+                title: instance.title.clone(),
+                detail: detail.trim().to_string(),
+                failed: instance.status == InstanceStatus::Failed,
+            });
+        }
+    }
+
+    pub fn set_selected(&mut self, index: usize) {
+        self.selected = index;
+    }
+
+    /// Column and row of the selected card.
+    fn position(&self) -> Option<(usize, usize)> {
+        self.columns.iter().enumerate().find_map(|(col, cards)| {
+            cards
+                .iter()
+                .position(|c| c.index == self.selected)
+                .map(|row| (col, row))
+        })
+    }
+
+    /// Session index of the card `delta` rows away in the same column,
+    /// wrapping around.
+    pub fn move_vertical(&self, delta: isize) -> usize {
+        let Some((col, row)) = self.position() else {
+            return self.selected;
+        };
+        let cards = &self.columns[col];
+        let row = (row as isize + delta).rem_euclid(cards.len() as isize) as usize;
+        cards[row].index
+    }
+
+    /// Session index of the nearest card in the next non-empty column in
+    /// direction `delta` (-1 left, 1 right). Stays put at the edge.
+    pub fn move_horizontal(&self, delta: isize) -> usize {
+        let Some((col, row)) = self.position() else {
+            return self.selected;
+        };
+        let mut next = col as isize + delta;
+        while (0..COLUMNS.len() as isize).contains(&next) {
+            let cards = &self.columns[next as usize];
+            if !cards.is_empty() {
+                return cards[row.min(cards.len() - 1)].index;
+            }
+            next += delta;
+        }
+        self.selected
+    }
+}
+
+impl Widget for &BoardView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let areas = Layout::horizontal([Constraint::Ratio(1, COLUMNS.len() as u32); 5]).split(area);
+        for (column, (cards, area)) in COLUMNS.iter().zip(self.columns.iter().zip(areas.iter())) {
+            let block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(column.color()))
+                .title(format!(" {} ({}) ", column.title(), cards.len()));
+            let inner = block.inner(*area);
+            block.render(*area, buf);
+
+            // Two lines per card; scroll so the selected card stays visible
+            let fits = (inner.height as usize / 2).max(1);
+            let selected_row = cards.iter().position(|c| c.index == self.selected);
+            let start = selected_row.map_or(0, |row| (row + 1).saturating_sub(fits));
+            let mut lines = Vec::new();
+            for card in cards.iter().skip(start).take(fits) {
+                let selected = card.index == self.selected;
+                let mut title_style = Style::default().add_modifier(Modifier::BOLD);
+                if card.failed {
+                    title_style = title_style.fg(Color::Red);
+                }
+                if selected {
+                    title_style = title_style.bg(Color::DarkGray);
+                }
+                let marker = if selected { "▸ " } else { "  " };
+                let icon = if card.failed { "✗ " } else { "" };
+                lines.push(Line::styled(format!("{}{}{}", marker, icon, card.title), title_style));
+                lines.push(Line::styled(
+                    format!("  {}", card.detail),
+                    Style::default().fg(Color::DarkGray),
+                ));
+            }
+            Paragraph::new(lines).render(inner, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::instance::InstanceOptions;
+
+    fn instance(title: &str, status: InstanceStatus) -> Instance {
+        let mut inst = Instance::new(InstanceOptions {
+            title: title.to_string(),
+            path: "/tmp".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        inst.status = status;
+        inst
+    }
+
+    fn board() -> BoardView {
+        let instances = vec![
+            instance("a", InstanceStatus::Running),
+            instance("b", InstanceStatus::Running),
+            instance("c", InstanceStatus::Paused),
+            instance("d", InstanceStatus::Running),
+            instance("e", InstanceStatus::Queued),
+        ];
+        let waiting: HashSet<String> = ["b".to_string()].into();
+        let mut board = BoardView::new();
+        board.set_items(&instances, &waiting);
+        board
+    }
+
+    #[test]
+    fn test_columns_follow_status() {
+        let board = board();
+        let titles = |col: Column| -> Vec<&str> {
+            board.columns[col as usize].iter().map(|c| c.title.as_str()).collect()
+        };
+        assert_eq!(titles(Column::Loading), ["e"]);
+        assert_eq!(titles(Column::Working), ["a", "d"]);
+        assert_eq!(titles(Column::Waiting), ["b"]);
+        assert_eq!(titles(Column::Paused), ["c"]);
+        assert!(titles(Column::Done).is_empty());
+    }
+
+    #[test]
+    fn test_navigation() {
+        let mut board = board();
+        board.set_selected(0);
+        assert_eq!(board.move_vertical(1), 3);
+        assert_eq!(board.move_vertical(-1), 3);
+        assert_eq!(board.move_horizontal(1), 1);
+        assert_eq!(board.move_horizontal(-1), 4);
+
+        board.set_selected(3);
+        // Row 1 doesn't exist in Waiting: nearest card
+        assert_eq!(board.move_horizontal(1), 1);
+
+        board.set_selected(2);
+        // Done is empty and the last column
+        assert_eq!(board.move_horizontal(1), 2);
+    }
+
+    #[test]
+    fn test_render_board() {
+        let mut board = board();
+        board.set_selected(1);
+        let area = Rect::new(0, 0, 100, 8);
+        let mut buf = Buffer::empty(area);
+        Widget::render(&board, area, &mut buf);
+        let top: String = (0..100).map(|x| buf.cell((x, 0)).unwrap().symbol().to_string()).collect();
+        assert!(top.contains("Working (2)"), "top: {}", top);
+        assert!(top.contains("Waiting (1)"), "top: {}", top);
+        let row: String = (0..100).map(|x| buf.cell((x, 1)).unwrap().symbol().to_string()).collect();
+        assert!(row.contains("▸ b"), "row: {}", row);
+    }
+}
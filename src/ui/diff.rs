@@ -1,31 +1,100 @@
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
 
-use crate::session::git::diff::DiffStats;
+use crate::session::git::diff::{diff_lines, DiffStats};
+
+/// A hunk of the diff, by the lines it covers in the new file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hunk {
+    pub file: String,
+    pub start: usize,
+    pub len: usize,
+    /// Index of the `@@` header in the diff's lines.
+    line: usize,
+}
+
+impl Hunk {
+    /// Where the hunk is, for a prompt, e.g. `src/foo.rs lines 10-30`.
+    pub fn reference(&self) -> String {
+        match self.len {
+            0 if self.start == 0 => format!("{} (all lines removed)", self.file),
+            0 => format!("{} near line {}", self.file, self.start),
+            1 => format!("{} line {}", self.file, self.start),
+            len => format!("{} lines {}-{}", self.file, self.start, self.start + len - 1),
+        }
+    }
+}
 
 /// Renders colored git diff output.
 pub struct DiffView {
     content: String,
+    // Styled lines, rebuilt only when `content` changes
+    lines: Vec<Line<'static>>,
     added: usize,
     removed: usize,
+    hunks: Vec<Hunk>,
+    /// Hunk picked with J/K for a change request; the view scrolls to it.
+    selected_hunk: Option<usize>,
 }
 
 impl DiffView {
     pub fn new() -> Self {
         Self {
             content: String::new(),
+            lines: Vec::new(),
             added: 0,
             removed: 0,
+            hunks: Vec::new(),
+            selected_hunk: None,
         }
     }
 
     /// Update the diff from a `DiffStats` value.
     pub fn set_diff(&mut self, stats: &DiffStats) {
-        self.content = stats.content.clone();
+        if stats.content != self.content {
+            self.content = stats.content.clone();
+            self.lines = diff_lines(&self.content)
+                .map(|(line, in_hunk)| {
+                    Line::from(Span::styled(line.to_string(), classify_diff_line(line, in_hunk)))
+                })
+                .collect();
+            self.hunks = parse_hunks(&self.content);
+            self.selected_hunk = match self.selected_hunk {
+                Some(_) if self.hunks.is_empty() => None,
+                selected => selected.map(|i| i.min(self.hunks.len() - 1)),
+            };
+        }
         self.added = stats.added_lines;
         self.removed = stats.removed_lines;
     }
 
+    /// Select the next hunk, or the first when none is selected.
+    pub fn select_next_hunk(&mut self) {
+        if self.hunks.is_empty() {
+            return;
+        }
+        self.selected_hunk = Some(match self.selected_hunk {
+            Some(i) => (i + 1).min(self.hunks.len() - 1),
+            None => 0,
+        });
+    }
+
+    pub fn select_previous_hunk(&mut self) {
+        if self.hunks.is_empty() {
+            return;
+        }
+        self.selected_hunk = Some(self.selected_hunk.map_or(0, |i| i.saturating_sub(1)));
+    }
+
+    pub fn selected_hunk(&self) -> Option<&Hunk> {
+        self.selected_hunk.and_then(|i| self.hunks.get(i))
+    }
+
+    /// Forget the selection, e.g. when another session is selected.
+    pub fn clear_selection(&mut self) {
+        self.selected_hunk = None;
+    }
+
     /// Summary string like "+15 -3".
     pub fn summary(&self) -> String {
         format!("+{} -{}", self.added, self.removed)
@@ -42,23 +111,53 @@ impl Widget for &DiffView {
             return;
         }
 
-        let lines: Vec<Line<'_>> = self
-            .content
-            .lines()
-            .map(|line| {
-                let style = classify_diff_line(line);
-                Line::from(Span::styled(line, style))
-            })
-            .collect();
+        // Only the lines that fit are handed to the paragraph, starting at
+        // the selected hunk
+        let selected = self.selected_hunk();
+        let start = selected.map_or(0, |h| h.line).min(self.lines.len());
+        let end = self.lines.len().min(start + inner.height as usize);
+        let mut lines = self.lines[start..end].to_vec();
+        if selected.is_some()
+            && let Some(header) = lines.first_mut()
+        {
+            *header = header.clone().patch_style(Modifier::REVERSED);
+        }
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
 
-        let paragraph = Paragraph::new(lines);
-        paragraph.render(inner, buf);
+/// Hunks of a unified diff, with the file each belongs to.
+fn parse_hunks(content: &str) -> Vec<Hunk> {
+    let mut hunks = Vec::new();
+    let mut file = String::new();
+    for (line_idx, (line, in_hunk)) in diff_lines(content).enumerate() {
+        if !in_hunk && let Some(path) = line.strip_prefix("--- ") {
+            file = path.strip_prefix("a/").unwrap_or(path).to_string();
+        } else if !in_hunk && let Some(path) = line.strip_prefix("+++ ") {
+            // Deleted files keep their old path
+            if path != "/dev/null" {
+                file = path.strip_prefix("b/").unwrap_or(path).to_string();
+            }
+        } else if let Some(header) = line.strip_prefix("@@ ") {
+            // @@ -a,b +c,d @@ — the new-file range is "+c,d" (d defaults to 1)
+            let Some(range) = header.split_whitespace().find_map(|r| r.strip_prefix('+')) else {
+                continue;
+            };
+            let mut parts = range.split(',');
+            let start = parts.next().and_then(|n| n.parse().ok()).unwrap_or(0);
+            let len = parts.next().and_then(|n| n.parse().ok()).unwrap_or(1);
+            hunks.push(Hunk { file: file.clone(), start, len, line: line_idx });
+        }
     }
+    hunks
 }
 
-/// Determine the style for a diff line based on its prefix.
-fn classify_diff_line(line: &str) -> Style {
-    if line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff") || line.starts_with("index") {
+/// Determine the style for a diff line based on its prefix, and whether it
+/// is inside a hunk, where e.g. `---` is a removed line.
+fn classify_diff_line(line: &str, in_hunk: bool) -> Style {
+    if !in_hunk
+        && (line.starts_with("+++") || line.starts_with("---") || line.starts_with("diff") || line.starts_with("index"))
+    {
         Style::default().fg(Color::DarkGray)
     } else if line.starts_with('+') {
         Style::default().fg(Color::Green)
@@ -92,33 +191,110 @@ mod tests {
     #[test]
     fn test_diff_coloring() {
         // Added lines
-        let style = classify_diff_line("+added");
+        let style = classify_diff_line("+added", false);
         assert_eq!(style.fg, Some(Color::Green));
 
         // Removed lines
-        let style = classify_diff_line("-removed");
+        let style = classify_diff_line("-removed", false);
         assert_eq!(style.fg, Some(Color::Red));
 
         // Hunk header
-        let style = classify_diff_line("@@ -1,3 +1,4 @@");
+        let style = classify_diff_line("@@ -1,3 +1,4 @@", false);
         assert_eq!(style.fg, Some(Color::Cyan));
 
         // Header lines
-        let style = classify_diff_line("diff --git a/file b/file");
+        let style = classify_diff_line("diff --git a/file b/file", false);
         assert_eq!(style.fg, Some(Color::DarkGray));
 
-        let style = classify_diff_line("index abc123..def456");
+        let style = classify_diff_line("index abc123..def456", false);
         assert_eq!(style.fg, Some(Color::DarkGray));
 
-        let style = classify_diff_line("--- a/file");
+        let style = classify_diff_line("--- a/file", false);
         assert_eq!(style.fg, Some(Color::DarkGray));
 
-        let style = classify_diff_line("+++ b/file");
+        let style = classify_diff_line("+++ b/file", false);
         assert_eq!(style.fg, Some(Color::DarkGray));
 
         // Context line (no prefix)
-        let style = classify_diff_line(" unchanged line");
+        let style = classify_diff_line(" unchanged line", false);
         assert_eq!(style.fg, None);
+
+        // A removed "-- comment" line inside a hunk
+        let style = classify_diff_line("--- comment", true);
+        assert_eq!(style.fg, Some(Color::Red));
+    }
+
+    #[test]
+    fn test_set_diff_caches_styled_lines() {
+        let mut view = DiffView::new();
+        view.set_diff(&DiffStats::from_diff("+a\n-b\n".to_string()));
+        assert_eq!(view.lines.len(), 2);
+        assert_eq!(view.lines[0].spans[0].style.fg, Some(Color::Green));
+
+        view.set_diff(&DiffStats::from_diff("+a\n-b\n context\n".to_string()));
+        assert_eq!(view.lines.len(), 3);
+    }
+
+    const TWO_FILE_DIFF: &str = "diff --git a/src/foo.rs b/src/foo.rs
+index 1..2 100644
+--- a/src/foo.rs
++++ b/src/foo.rs
+@@ -10,3 +10,21 @@ fn main() {
++added
+@@ -40 +58 @@
+-x
++y
+diff --git a/old.txt b/old.txt
+--- a/old.txt
++++ /dev/null
+@@ -1,2 +0,0 @@
+-gone
+";
+
+    #[test]
+    fn test_parse_hunks() {
+        let hunks = parse_hunks(TWO_FILE_DIFF);
+        let refs: Vec<String> = hunks.iter().map(Hunk::reference).collect();
+        assert_eq!(refs, ["src/foo.rs lines 10-30", "src/foo.rs line 58", "old.txt (all lines removed)"]);
+        assert_eq!(hunks[1].line, 6);
+    }
+
+    #[test]
+    fn test_parse_hunks_keeps_file_past_removed_comment() {
+        let diff = "diff --git a/q.sql b/q.sql
+--- a/q.sql
++++ b/q.sql
+@@ -1,3 +1,2 @@
+--- old note
+ select 1;
+@@ -9 +8 @@
+-x
++y
+";
+        let hunks = parse_hunks(diff);
+        let refs: Vec<String> = hunks.iter().map(Hunk::reference).collect();
+        assert_eq!(refs, ["q.sql lines 1-2", "q.sql line 8"]);
+    }
+
+    #[test]
+    fn test_hunk_selection() {
+        let mut view = DiffView::new();
+        view.select_next_hunk();
+        assert!(view.selected_hunk().is_none());
+
+        view.set_diff(&DiffStats::from_diff(TWO_FILE_DIFF.to_string()));
+        view.select_previous_hunk();
+        assert_eq!(view.selected_hunk().unwrap().start, 10);
+        view.select_next_hunk();
+        view.select_next_hunk();
+        view.select_next_hunk();
+        assert_eq!(view.selected_hunk().unwrap().start, 0);
+
+        // A shorter diff keeps the selection in range
+        view.set_diff(&DiffStats::from_diff("+++ b/a.rs\n@@ -1 +1 @@\n+a\n".to_string()));
+        assert_eq!(view.selected_hunk().unwrap().reference(), "a.rs line 1");
+        view.clear_selection();
+        assert!(view.selected_hunk().is_none());
     }
 
     #[test]
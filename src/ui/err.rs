@@ -1,29 +1,65 @@
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
 
-/// Displays an error message in a bordered, red-styled block.
+/// Displays an error message in a bordered, red-styled block, or a notice
+/// (e.g. "Copied …") in a plain one.
 pub struct ErrorDisplay {
     message: Option<String>,
+    info: bool,
 }
 
 impl ErrorDisplay {
     pub fn new() -> Self {
-        Self { message: None }
+        Self { message: None, info: false }
     }
 
     pub fn set_error(&mut self, msg: impl Into<String>) {
         self.message = Some(msg.into());
+        self.info = false;
+    }
+
+    /// Show something that isn't a failure: a confirmation or progress note.
+    pub fn set_info(&mut self, msg: impl Into<String>) {
+        self.message = Some(msg.into());
+        self.info = true;
     }
 
     pub fn clear(&mut self) {
         self.message = None;
     }
 
+    /// Whether an error, not a notice, is shown.
     pub fn has_error(&self) -> bool {
+        self.message.is_some() && !self.info
+    }
+
+    /// Whether a notice, not an error, is shown.
+    pub fn has_info(&self) -> bool {
+        self.message.is_some() && self.info
+    }
+
+    /// Whether anything is shown.
+    pub fn has_message(&self) -> bool {
         self.message.is_some()
     }
+
+    /// The message shown, if any.
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_deref()
+    }
+
+    /// Rows needed to show the message, e.g. multi-line hook output,
+    /// including the border.
+    pub fn height(&self) -> u16 {
+        let lines = self.message.as_deref().map_or(1, |m| m.lines().count());
+        lines.clamp(1, MAX_LINES) as u16 + 2
+    }
 }
 
+/// Longest message shown; earlier lines are dropped so the end of e.g. hook
+/// output, where the failure usually is, stays visible.
+const MAX_LINES: usize = 8;
+
 impl Widget for &ErrorDisplay {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let msg = match &self.message {
@@ -31,15 +67,28 @@ impl Widget for &ErrorDisplay {
             None => return,
         };
 
+        let (title, color, prefix) = if self.info {
+            ("Info", Color::Cyan, "")
+        } else {
+            ("Error", Color::Red, "Error: ")
+        };
         let block = Block::default()
             .borders(Borders::ALL)
-            .title("Error")
-            .border_style(Style::default().fg(Color::Red));
-
-        let text = Line::from(Span::styled(
-            format!("Error: {}", msg),
-            Style::default().fg(Color::Red),
-        ));
+            .title(title)
+            .border_style(Style::default().fg(color));
+
+        let style = Style::default().fg(color);
+        let lines: Vec<&str> = msg.lines().collect();
+        let skip = lines.len().saturating_sub(MAX_LINES);
+        let text: Vec<Line> = lines
+            .iter()
+            .enumerate()
+            .skip(skip)
+            .map(|(i, line)| {
+                let line = if i == 0 { format!("{}{}", prefix, line) } else { line.to_string() };
+                Line::from(Span::styled(line, style))
+            })
+            .collect();
 
         let paragraph = Paragraph::new(text).block(block);
         paragraph.render(area, buf);
@@ -67,6 +116,17 @@ mod tests {
         assert!(!err.has_error());
     }
 
+    #[test]
+    fn test_error_display_height_follows_lines() {
+        let mut err = ErrorDisplay::new();
+        err.set_error("one line");
+        assert_eq!(err.height(), 3);
+        err.set_error("hook failed\nline 2\nline 3");
+        assert_eq!(err.height(), 5);
+        err.set_error("x\n".repeat(50));
+        assert_eq!(err.height(), MAX_LINES as u16 + 2);
+    }
+
     #[test]
     fn test_error_display_render_with_error() {
         let mut err = ErrorDisplay::new();
@@ -86,6 +146,31 @@ mod tests {
         assert!(content.contains("Error: test error"));
     }
 
+    #[test]
+    fn test_info_renders_without_error_prefix() {
+        let mut err = ErrorDisplay::new();
+        err.set_info("Copied gana/fix");
+        assert!(err.has_info());
+        assert!(!err.has_error());
+        assert!(err.has_message());
+
+        let area = Rect::new(0, 0, 40, 3);
+        let mut buf = Buffer::empty(area);
+        Widget::render(&err, area, &mut buf);
+        let mut content = String::new();
+        for y in 0..3 {
+            for x in 0..40 {
+                content.push_str(buf.cell((x, y)).unwrap().symbol());
+            }
+        }
+        assert!(content.contains("Copied gana/fix"));
+        assert!(!content.contains("Error"));
+
+        err.set_error("push failed");
+        assert!(err.has_error());
+        assert!(!err.has_info());
+    }
+
     #[test]
     fn test_error_display_render_without_error() {
         let err = ErrorDisplay::new();
@@ -2,6 +2,7 @@ use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, List, ListItem, ListState, StatefulWidget};
 
 use crate::session::instance::{Instance, InstanceStatus};
+use crate::session::test_watch::TestStatus;
 
 const SPINNER_FRAMES: &[char] = &['\u{280B}', '\u{2819}', '\u{2839}', '\u{2838}', '\u{283C}', '\u{2834}', '\u{2826}', '\u{2827}', '\u{2807}', '\u{280F}'];
 
@@ -10,6 +11,8 @@ pub struct ListPane {
     selected: usize,
     items: Vec<ListItem<'static>>,
     spinner_tick: usize,
+    /// Project the list is limited to, shown in the title.
+    scope: Option<String>,
 }
 
 impl ListPane {
@@ -18,9 +21,14 @@ impl ListPane {
             selected: 0,
             items: Vec::new(),
             spinner_tick: 0,
+            scope: None,
         }
     }
 
+    pub fn set_scope(&mut self, scope: Option<String>) {
+        self.scope = scope;
+    }
+
     pub fn advance_spinner(&mut self) {
         self.spinner_tick = self.spinner_tick.wrapping_add(1);
     }
@@ -94,8 +102,24 @@ impl StatefulWidget for &ListPane {
     type State = ListState;
 
     fn render(self, area: Rect, buf: &mut Buffer, state: &mut Self::State) {
-        let list = List::new(self.items.clone())
-            .block(Block::default().borders(Borders::ALL).title("Sessions"))
+        // Hand only the rows that fit to the List, keeping the selection at
+        // the bottom edge once it scrolls past the first page (as List does).
+        let height = area.height.saturating_sub(2) as usize;
+        let selected = state.selected().unwrap_or(0);
+        let start = (selected + 1).saturating_sub(height);
+        let end = self.items.len().min(start + height);
+        let visible = self.items.get(start..end).unwrap_or_default();
+        if state.selected().is_some() {
+            state.select(Some(selected - start));
+        }
+        *state.offset_mut() = 0;
+
+        let title = match self.scope {
+            Some(ref project) => format!("Sessions · {}", project),
+            None => "Sessions".to_string(),
+        };
+        let list = List::new(visible.to_vec())
+            .block(Block::default().borders(Borders::ALL).title(title))
             .highlight_style(
                 Style::default()
                     .bg(Color::DarkGray)
@@ -120,13 +144,36 @@ impl Widget for &ListPane {
 /// is appended after the branch in parentheses (e.g. `[branch] (repo)`).
 fn render_instance(inst: &Instance, show_repo: bool, spinner_tick: usize) -> ListItem<'static> {
     let (icon, icon_style) = match inst.status {
+        _ if inst.restoring => {
+            let frame = SPINNER_FRAMES[spinner_tick % SPINNER_FRAMES.len()];
+            (format!("{} restoring…", frame), Style::default().fg(Color::DarkGray))
+        }
+        _ if let Some(transition) = inst.transition => {
+            let frame = SPINNER_FRAMES[spinner_tick % SPINNER_FRAMES.len()];
+            let label = transition.label().to_lowercase();
+            (format!("{} {}…", frame, label), Style::default().fg(Color::DarkGray))
+        }
         InstanceStatus::Running => ("●".to_string(), Style::default().fg(Color::Green)),
+        InstanceStatus::Done => (
+            "✔ done".to_string(),
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD),
+        ),
         InstanceStatus::Ready => ("○".to_string(), Style::default()),
         InstanceStatus::Loading => {
             let frame = SPINNER_FRAMES[spinner_tick % SPINNER_FRAMES.len()];
             (format!("☸ {}", frame), Style::default().fg(Color::Yellow))
         }
         InstanceStatus::Paused => ("⏸".to_string(), Style::default().add_modifier(Modifier::DIM)),
+        InstanceStatus::Queued => ("◌ queued".to_string(), Style::default().fg(Color::DarkGray)),
+        InstanceStatus::Failed => ("✗ failed".to_string(), Style::default().fg(Color::Red)),
+        InstanceStatus::WorktreeMissing => {
+            ("⚠ no worktree".to_string(), Style::default().fg(Color::Yellow))
+        }
+        InstanceStatus::Orphaned => ("✗ orphaned".to_string(), Style::default().fg(Color::Red)),
+        InstanceStatus::Pending => (
+            format!("⧖ after {}", inst.after.as_deref().unwrap_or("?")),
+            Style::default().fg(Color::DarkGray),
+        ),
     };
 
     let mut spans = vec![
@@ -135,6 +182,33 @@ fn render_instance(inst: &Instance, show_repo: bool, spinner_tick: usize) -> Lis
         Span::raw(inst.title.clone()),
     ];
 
+    if inst.auto_yes {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            "A",
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    if inst.auto_push {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            "F",
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        ));
+    }
+
+    // A prompt that will be sent once the session is up
+    if inst.pending_prompt.is_some() {
+        let marker = if matches!(inst.status, InstanceStatus::Queued | InstanceStatus::Pending) {
+            "✉"
+        } else {
+            "✉ queued"
+        };
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(marker, Style::default().fg(Color::Yellow)));
+    }
+
     if !inst.branch.is_empty() {
         spans.push(Span::raw(" "));
         spans.push(Span::styled(
@@ -143,28 +217,38 @@ fn render_instance(inst: &Instance, show_repo: bool, spinner_tick: usize) -> Lis
         ));
     }
 
-    if show_repo {
-        if let Some(ref wt) = inst.git_worktree {
-            spans.push(Span::styled(
-                format!(" ({})", wt.repo_name()),
-                Style::default().fg(Color::DarkGray),
-            ));
-        }
+    if show_repo
+        && let Some(ref wt) = inst.git_worktree
+    {
+        spans.push(Span::styled(
+            format!(" ({})", wt.repo_name()),
+            Style::default().fg(Color::DarkGray),
+        ));
     }
 
-    if let Some(ref stats) = inst.diff_stats {
-        if stats.added_lines > 0 || stats.removed_lines > 0 {
-            spans.push(Span::raw(" "));
-            spans.push(Span::styled(
-                format!("+{}", stats.added_lines),
-                Style::default().fg(Color::Green),
-            ));
-            spans.push(Span::raw(" "));
-            spans.push(Span::styled(
-                format!("-{}", stats.removed_lines),
-                Style::default().fg(Color::Red),
-            ));
-        }
+    if let Some(ref status) = inst.test_status {
+        let (marker, color) = match status {
+            TestStatus::Running => ("⧗ tests", Color::DarkGray),
+            TestStatus::Passed => ("✓ tests", Color::Green),
+            TestStatus::Failed(_) => ("✗ tests", Color::Red),
+        };
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(marker, Style::default().fg(color)));
+    }
+
+    if let Some(ref stats) = inst.diff_stats
+        && (stats.added_lines > 0 || stats.removed_lines > 0)
+    {
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!("+{}", stats.added_lines),
+            Style::default().fg(Color::Green),
+        ));
+        spans.push(Span::raw(" "));
+        spans.push(Span::styled(
+            format!("-{}", stats.removed_lines),
+            Style::default().fg(Color::Red),
+        ));
     }
 
     ListItem::new(Line::from(spans))
@@ -173,14 +257,14 @@ fn render_instance(inst: &Instance, show_repo: bool, spinner_tick: usize) -> Lis
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::session::instance::InstanceOptions;
+    use crate::session::instance::{InstanceOptions, Transition};
 
     fn make_instance(title: &str, status: InstanceStatus, branch: &str) -> Instance {
         let mut inst = Instance::new(InstanceOptions {
             title: title.to_string(),
             path: "/tmp".to_string(),
             program: "bash".to_string(),
-            auto_yes: false,
+            ..Default::default()
         });
         inst.status = status;
         inst.branch = branch.to_string();
@@ -255,6 +339,84 @@ mod tests {
             .collect()
     }
 
+    #[test]
+    fn test_render_keeps_selection_visible() {
+        let instances: Vec<Instance> = (0..20)
+            .map(|i| make_instance(&format!("sess-{:02}", i), InstanceStatus::Ready, ""))
+            .collect();
+        let mut pane = ListPane::new();
+        pane.set_items(&instances);
+        pane.set_selected(15);
+
+        let area = Rect::new(0, 0, 40, 7);
+        let mut buf = Buffer::empty(area);
+        Widget::render(&pane, area, &mut buf);
+        let row = |y: u16| -> String {
+            (0..40).map(|x| buf.cell((x, y)).unwrap().symbol().to_string()).collect()
+        };
+        // Five rows fit; the selected one is the last of them
+        assert!(row(1).contains("sess-11"), "row: {}", row(1));
+        assert!(row(5).contains("sess-15"), "row: {}", row(5));
+    }
+
+    #[test]
+    fn test_render_restoring_instance() {
+        let mut inst = make_instance("back", InstanceStatus::Running, "");
+        inst.restoring = true;
+        let content = render_list_row(&[inst], 0);
+        assert!(content.contains("restoring"), "row: {}", content);
+    }
+
+    #[test]
+    fn test_render_transitioning_instance() {
+        let mut inst = make_instance("halting", InstanceStatus::Running, "");
+        inst.transition = Some(Transition::Pausing);
+        let content = render_list_row(&[inst], 0);
+        assert!(content.contains("pausing…"), "row: {}", content);
+    }
+
+    #[test]
+    fn test_render_queued_instance() {
+        let instances = vec![make_instance("later", InstanceStatus::Queued, "")];
+        let content = render_list_row(&instances, 0);
+        assert!(content.contains("queued"), "row: {}", content);
+        assert!(content.contains("later"), "row: {}", content);
+    }
+
+    #[test]
+    fn test_render_done_instance() {
+        let instances = vec![make_instance("tidy", InstanceStatus::Done, "")];
+        let content = render_list_row(&instances, 0);
+        assert!(content.contains("✔ done tidy"), "row: {}", content);
+    }
+
+    #[test]
+    fn test_render_test_status() {
+        let mut inst = make_instance("checked", InstanceStatus::Running, "");
+        assert!(!render_list_row(&[inst.clone()], 0).contains("tests"));
+        inst.test_status = Some(TestStatus::Failed("1 failed".to_string()));
+        let content = render_list_row(&[inst], 0);
+        assert!(content.contains("checked ✗ tests"), "row: {}", content);
+    }
+
+    #[test]
+    fn test_render_pipeline_step() {
+        let mut inst = make_instance("docs-update", InstanceStatus::Pending, "");
+        inst.after = Some("refactor-core".to_string());
+        let content = render_list_row(&[inst], 0);
+        assert!(content.contains("⧖ after refactor-core docs-update"), "row: {}", content);
+    }
+
+    #[test]
+    fn test_render_pending_prompt_marker() {
+        let mut inst = make_instance("owed", InstanceStatus::Ready, "gana/owed");
+        assert!(!render_list_row(&[inst.clone()], 0).contains('✉'));
+
+        inst.pending_prompt = Some("fix it".to_string());
+        let content = render_list_row(&[inst], 0);
+        assert!(content.contains("owed ✉ queued [gana/owed]"), "row: {}", content);
+    }
+
     #[test]
     fn test_render_instance_with_diff_stats() {
         use crate::session::git::DiffStats;
@@ -283,6 +445,20 @@ mod tests {
         assert!(!content.contains("-0"));
     }
 
+    #[test]
+    fn test_render_instance_auto_yes_badge() {
+        let mut inst = make_instance("feature", InstanceStatus::Running, "dev");
+        assert!(!render_list_row(&[inst.clone()], 0).contains("feature A"));
+
+        inst.auto_yes = true;
+        let content = render_list_row(&[inst.clone()], 0);
+        assert!(content.contains("feature A [dev]"), "Expected badge in: {}", content);
+
+        inst.auto_push = true;
+        let content = render_list_row(&[inst], 0);
+        assert!(content.contains("feature A F [dev]"), "Expected badge in: {}", content);
+    }
+
     #[test]
     fn test_render_instance_zero_diff_stats() {
         use crate::session::git::DiffStats;
@@ -1,23 +1,109 @@
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use ratatui::prelude::*;
 
-/// Shows available key bindings at the bottom of the screen.
+/// Width of the progress bar, in cells.
+const PROGRESS_WIDTH: usize = 10;
+
+/// Width of the block sliding back and forth inside the progress bar.
+const PROGRESS_BLOCK: usize = 3;
+
+/// Background state shown at the right of the menu bar while no operation
+/// runs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatusSegment {
+    pub daemon_running: bool,
+    /// Sessions answering prompts on their own.
+    pub auto_yes: usize,
+    /// A newer release, when one was found.
+    pub update: Option<String>,
+}
+
+impl StatusSegment {
+    fn spans(&self) -> Vec<Span<'static>> {
+        let dim = Style::default().add_modifier(Modifier::DIM);
+        let mut spans = vec![if self.daemon_running {
+            Span::styled("daemon ●", Style::default().fg(Color::Green))
+        } else {
+            Span::styled("daemon ○", dim)
+        }];
+        if self.auto_yes > 0 {
+            spans.push(Span::styled(" · ", dim));
+            spans.push(Span::styled(
+                format!("auto-yes {}", self.auto_yes),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+        if let Some(ref version) = self.update {
+            spans.push(Span::styled(" · ", dim));
+            spans.push(Span::styled(
+                format!("⬆ v{}", version),
+                Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD),
+            ));
+        }
+        spans
+    }
+}
+
+/// Shows available key bindings at the bottom of the screen, and the
+/// operations running in the background.
 pub struct MenuBar {
     highlighted_key: Option<(String, Instant)>,
+    operations: Vec<(String, Instant)>,
+    status: StatusSegment,
 }
 
 impl MenuBar {
     pub fn new() -> Self {
         Self {
             highlighted_key: None,
+            operations: Vec::new(),
+            status: StatusSegment::default(),
         }
     }
 
+    pub fn set_status(&mut self, status: StatusSegment) {
+        self.status = status;
+    }
+
     /// Highlight a key for a brief flash (500ms).
     pub fn highlight_key(&mut self, key: &str) {
         self.highlighted_key = Some((key.to_string(), Instant::now()));
     }
+
+    /// Show a progress indicator for `label` until [`finish_operation`].
+    ///
+    /// [`finish_operation`]: MenuBar::finish_operation
+    pub fn start_operation(&mut self, label: &str) {
+        self.operations.push((label.to_string(), Instant::now()));
+    }
+
+    pub fn finish_operation(&mut self, label: &str) {
+        if let Some(pos) = self.operations.iter().position(|(l, _)| l == label) {
+            self.operations.remove(pos);
+        }
+    }
+
+    pub fn is_running(&self, label: &str) -> bool {
+        self.operations.iter().any(|(l, _)| l == label)
+    }
+
+    /// Whether any operation is running, i.e. the progress bar is animating.
+    pub fn is_busy(&self) -> bool {
+        !self.operations.is_empty()
+    }
+}
+
+/// An indeterminate progress bar: a block bouncing between the ends,
+/// one cell per 100ms.
+fn progress_bar(elapsed: Duration) -> String {
+    let span = PROGRESS_WIDTH - PROGRESS_BLOCK;
+    let step = (elapsed.as_millis() / 100) as usize % (2 * span);
+    let offset = if step <= span { step } else { 2 * span - step };
+    let mut bar = " ".repeat(offset);
+    bar.push_str(&"■".repeat(PROGRESS_BLOCK));
+    bar.push_str(&" ".repeat(span - offset));
+    format!("[{}]", bar)
 }
 
 /// Key binding entries displayed in the menu bar.
@@ -72,6 +158,24 @@ impl Widget for &MenuBar {
 
         let line = Line::from(spans);
         buf.set_line(area.x, area.y, &line, area.width);
+
+        // The oldest running operation, right-aligned over the key list
+        if let Some((label, started)) = self.operations.first() {
+            let mut text = format!(" {} {}…", progress_bar(started.elapsed()), label);
+            if self.operations.len() > 1 {
+                text.push_str(&format!(" (+{} more)", self.operations.len() - 1));
+            }
+            let width = (text.chars().count() as u16).min(area.width);
+            let x = area.x + area.width - width;
+            buf.set_stringn(x, area.y, &text, width as usize, Style::default().fg(Color::Cyan));
+        } else {
+            let mut spans = vec![Span::raw(" ")];
+            spans.extend(self.status.spans());
+            let status = Line::from(spans);
+            let width = (status.width() as u16).min(area.width);
+            let x = area.x + area.width - width;
+            buf.set_line(x, area.y, &status, width);
+        }
     }
 }
 
@@ -142,6 +246,56 @@ mod tests {
         assert_ne!(cell_normal.fg, Color::Yellow);
     }
 
+    #[test]
+    fn test_status_segment_yields_to_operations() {
+        let mut menu = MenuBar::new();
+        menu.set_status(StatusSegment {
+            daemon_running: true,
+            auto_yes: 2,
+            update: Some("0.3.0".to_string()),
+        });
+        let area = Rect::new(0, 0, 200, 1);
+        let row = |menu: &MenuBar| {
+            let mut buf = Buffer::empty(area);
+            Widget::render(menu, area, &mut buf);
+            (0..200)
+                .map(|x| buf.cell((x, 0)).unwrap().symbol().to_string())
+                .collect::<String>()
+        };
+        assert!(row(&menu).trim_end().ends_with("daemon ● · auto-yes 2 · ⬆ v0.3.0"));
+
+        menu.start_operation("Pausing 'x'");
+        assert!(!row(&menu).contains("daemon"));
+    }
+
+    #[test]
+    fn test_progress_bar_bounces() {
+        assert_eq!(progress_bar(Duration::ZERO), "[■■■       ]");
+        assert_eq!(progress_bar(Duration::from_millis(300)), "[   ■■■    ]");
+        assert_eq!(progress_bar(Duration::from_millis(700)), "[       ■■■]");
+        assert_eq!(progress_bar(Duration::from_millis(900)), "[     ■■■  ]");
+        assert_eq!(progress_bar(Duration::from_millis(1400)), "[■■■       ]");
+    }
+
+    #[test]
+    fn test_menu_bar_shows_operations() {
+        let mut menu = MenuBar::new();
+        menu.start_operation("Pushing 'a'");
+        menu.start_operation("Pushing 'b'");
+        let area = Rect::new(0, 0, 120, 1);
+        let mut buf = Buffer::empty(area);
+        Widget::render(&menu, area, &mut buf);
+        let content: String = (0..120)
+            .map(|x| buf.cell((x, 0)).unwrap().symbol().to_string())
+            .collect();
+        assert!(content.contains("n:New"));
+        assert!(content.trim_end().ends_with("Pushing 'a'… (+1 more)"), "{}", content);
+
+        menu.finish_operation("Pushing 'a'");
+        assert!(!menu.is_running("Pushing 'a'"));
+        assert!(menu.is_running("Pushing 'b'"));
+    }
+
     #[test]
     fn test_menu_bar_highlight_expires() {
         use std::time::{Duration, Instant};
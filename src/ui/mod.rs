@@ -1,3 +1,5 @@
+pub mod activity;
+pub mod board;
 #[allow(unused_imports)]
 pub mod consts;
 #[allow(unused_imports)]
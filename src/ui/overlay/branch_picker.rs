@@ -0,0 +1,189 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+/// Branch picker overlay — shown when the user presses 'b' to start a session
+/// on an existing local or remote branch. Typing filters the list.
+pub struct BranchPickerOverlay {
+    branches: Vec<String>,
+    filter: String,
+    selected: usize,
+    submitted: bool,
+    cancelled: bool,
+}
+
+impl BranchPickerOverlay {
+    pub fn new(branches: Vec<String>) -> Self {
+        Self {
+            branches,
+            filter: String::new(),
+            selected: 0,
+            submitted: false,
+            cancelled: false,
+        }
+    }
+
+    /// Branches matching the current filter (case-insensitive substring).
+    pub fn matches(&self) -> Vec<&str> {
+        let needle = self.filter.to_lowercase();
+        self.branches
+            .iter()
+            .filter(|b| b.to_lowercase().contains(&needle))
+            .map(String::as_str)
+            .collect()
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                true
+            }
+            KeyCode::Down => {
+                if self.selected + 1 < self.matches().len() {
+                    self.selected += 1;
+                }
+                true
+            }
+            KeyCode::Enter => {
+                if self.selected_branch().is_some() {
+                    self.submitted = true;
+                }
+                true
+            }
+            KeyCode::Esc => {
+                self.cancelled = true;
+                true
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.selected = 0;
+                true
+            }
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.selected = 0;
+                true
+            }
+            _ => true,
+        }
+    }
+
+    /// Append pasted text to the filter.
+    pub fn paste(&mut self, text: &str) {
+        self.filter.push_str(super::text_input::single_line(text).trim());
+        self.selected = 0;
+    }
+
+    pub fn is_submitted(&self) -> bool {
+        self.submitted
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    pub fn selected_branch(&self) -> Option<&str> {
+        self.matches().get(self.selected).copied()
+    }
+
+    pub fn render_content(&self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default()
+            .title(" ☸ New session from branch ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Cyan));
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::styled("Filter: ", Style::default().fg(Color::DarkGray)),
+                Span::raw(self.filter.clone()),
+                Span::styled(" ", Style::default().bg(Color::White)),
+            ]),
+            Line::raw(""),
+        ];
+
+        // Keep the selection visible: rows above are title/filter, rows below are the hint.
+        let list_height = (inner.height as usize).saturating_sub(4).max(1);
+        let matches = self.matches();
+        let offset = self.selected.saturating_sub(list_height - 1);
+        if matches.is_empty() {
+            lines.push(Line::styled(
+                "   no matching branches",
+                Style::default().fg(Color::DarkGray),
+            ));
+        }
+        for (i, branch) in matches.iter().enumerate().skip(offset).take(list_height) {
+            if i == self.selected {
+                lines.push(Line::styled(
+                    format!(" > {}", branch),
+                    Style::default().fg(Color::Cyan).bold(),
+                ));
+            } else {
+                lines.push(Line::raw(format!("   {}", branch)));
+            }
+        }
+        while lines.len() < list_height + 3 {
+            lines.push(Line::raw(""));
+        }
+        lines.push(Line::styled(
+            "type to filter · ↑/↓ navigate · Enter create · Esc cancel",
+            Style::default().fg(Color::DarkGray),
+        ));
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    fn picker() -> BranchPickerOverlay {
+        BranchPickerOverlay::new(vec![
+            "main".into(),
+            "feature/login".into(),
+            "origin/main".into(),
+            "origin/fix-login-redirect".into(),
+        ])
+    }
+
+    #[test]
+    fn test_filter_narrows_and_resets_selection() {
+        let mut p = picker();
+        p.handle_key(key(KeyCode::Down));
+        for c in "LOGIN".chars() {
+            p.handle_key(key(KeyCode::Char(c)));
+        }
+        assert_eq!(p.matches(), vec!["feature/login", "origin/fix-login-redirect"]);
+        assert_eq!(p.selected_branch(), Some("feature/login"));
+        p.handle_key(key(KeyCode::Down));
+        p.handle_key(key(KeyCode::Down));
+        assert_eq!(p.selected_branch(), Some("origin/fix-login-redirect"));
+    }
+
+    #[test]
+    fn test_enter_requires_a_match() {
+        let mut p = picker();
+        p.handle_key(key(KeyCode::Char('z')));
+        p.handle_key(key(KeyCode::Enter));
+        assert!(!p.is_submitted());
+        p.handle_key(key(KeyCode::Backspace));
+        p.handle_key(key(KeyCode::Enter));
+        assert!(p.is_submitted());
+        assert_eq!(p.selected_branch(), Some("main"));
+    }
+
+    #[test]
+    fn test_esc_cancels() {
+        let mut p = picker();
+        p.handle_key(key(KeyCode::Esc));
+        assert!(p.is_cancelled());
+    }
+}
@@ -2,45 +2,106 @@ use crossterm::event::KeyCode;
 use ratatui::prelude::*;
 use ratatui::widgets::*;
 
+/// The answer picked in a [`ConfirmationOverlay`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice {
+    Yes,
+    No,
+    /// Yes, and don't ask again.
+    Always,
+    /// Yes, and also close what's running behind it (e.g. a session's
+    /// tmux and worktree).
+    YesAndClose,
+}
+
+/// One answer offered by a [`ConfirmationOverlay`]: the key that picks it
+/// and its label.
+#[derive(Debug, Clone)]
+pub struct ConfirmButton {
+    pub key: char,
+    pub label: String,
+    pub choice: Choice,
+}
+
+impl ConfirmButton {
+    pub fn new(key: char, label: impl Into<String>, choice: Choice) -> Self {
+        Self {
+            key,
+            label: label.into(),
+            choice,
+        }
+    }
+}
+
 pub struct ConfirmationOverlay {
     message: String,
+    /// Custom answers; a plain y/n question when empty.
+    buttons: Vec<ConfirmButton>,
+    choice: Option<Choice>,
     dismissed: bool,
-    confirmed: bool,
+    cancelled: bool,
 }
 
 #[allow(dead_code)]
 impl ConfirmationOverlay {
+    /// A y/n question: y answers Yes, n answers No.
     pub fn new(message: impl Into<String>) -> Self {
+        Self::with_buttons(message, Vec::new())
+    }
+
+    /// A question answered with one of `buttons`, or cancelled with Esc.
+    pub fn with_buttons(message: impl Into<String>, buttons: Vec<ConfirmButton>) -> Self {
         Self {
             message: message.into(),
+            buttons,
+            choice: None,
             dismissed: false,
-            confirmed: false,
+            cancelled: false,
         }
     }
 
     /// Handle a key press. Returns true if the overlay consumed the key.
     pub fn handle_key(&mut self, key: KeyCode) -> bool {
-        match key {
-            KeyCode::Char('y') | KeyCode::Char('Y') => {
-                self.confirmed = true;
+        let choice = match key {
+            KeyCode::Esc => {
+                self.cancelled = true;
                 self.dismissed = true;
-                true
+                return true;
             }
-            KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => {
-                self.confirmed = false;
-                self.dismissed = true;
-                true
+            KeyCode::Char(c) if self.buttons.is_empty() => match c.to_ascii_lowercase() {
+                'y' => Choice::Yes,
+                'n' => Choice::No,
+                _ => return false,
+            },
+            KeyCode::Char(c) => {
+                let Some(button) = self.buttons.iter().find(|b| b.key.eq_ignore_ascii_case(&c)) else {
+                    return false;
+                };
+                button.choice
             }
-            _ => false,
-        }
+            _ => return false,
+        };
+        self.choice = Some(choice);
+        self.dismissed = true;
+        true
     }
 
     pub fn is_dismissed(&self) -> bool {
         self.dismissed
     }
 
+    /// The answer picked; `None` until answered, or when cancelled.
+    pub fn choice(&self) -> Option<Choice> {
+        self.choice
+    }
+
     pub fn is_confirmed(&self) -> bool {
-        self.confirmed
+        self.choice == Some(Choice::Yes)
+    }
+
+    /// True if the overlay was dismissed with Esc rather than answered.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
     }
 
     pub fn message(&self) -> &str {
@@ -56,17 +117,31 @@ impl ConfirmationOverlay {
         let inner = block.inner(area);
         block.render(area, buf);
 
+        let mut buttons = Vec::new();
+        if self.buttons.is_empty() {
+            buttons.push(Span::styled("[y]", Style::default().fg(Color::Green).bold()));
+            buttons.push(Span::raw(" Confirm  "));
+            buttons.push(Span::styled("[n/Esc]", Style::default().fg(Color::Red).bold()));
+        } else {
+            for button in &self.buttons {
+                let color = match button.choice {
+                    Choice::No | Choice::YesAndClose => Color::Red,
+                    Choice::Yes | Choice::Always => Color::Green,
+                };
+                buttons.push(Span::styled(format!("[{}]", button.key), Style::default().fg(color).bold()));
+                buttons.push(Span::raw(format!(" {}  ", button.label)));
+            }
+            buttons.push(Span::styled("[Esc]", Style::default().fg(Color::Red).bold()));
+        }
+        buttons.push(Span::raw(" Cancel"));
+
         let text = Paragraph::new(vec![
             Line::from(self.message.as_str()),
             Line::from(""),
-            Line::from(vec![
-                Span::styled("[y]", Style::default().fg(Color::Green).bold()),
-                Span::raw(" Confirm  "),
-                Span::styled("[n/Esc]", Style::default().fg(Color::Red).bold()),
-                Span::raw(" Cancel"),
-            ]),
+            Line::from(buttons),
         ])
-        .alignment(Alignment::Center);
+        .alignment(Alignment::Center)
+        .wrap(Wrap { trim: false });
         text.render(inner, buf);
     }
 }
@@ -92,6 +167,7 @@ mod tests {
         overlay.handle_key(KeyCode::Char('n'));
         assert!(overlay.is_dismissed());
         assert!(!overlay.is_confirmed());
+        assert!(!overlay.is_cancelled());
     }
 
     #[test]
@@ -100,6 +176,7 @@ mod tests {
         overlay.handle_key(KeyCode::Esc);
         assert!(overlay.is_dismissed());
         assert!(!overlay.is_confirmed());
+        assert!(overlay.is_cancelled());
     }
 
     #[test]
@@ -154,6 +231,35 @@ mod tests {
         assert!(content.contains("Confirm"), "should contain confirm text");
     }
 
+    #[test]
+    fn test_custom_buttons_return_their_choice() {
+        let buttons = vec![
+            ConfirmButton::new('y', "Yes", Choice::Yes),
+            ConfirmButton::new('a', "Always", Choice::Always),
+            ConfirmButton::new('n', "No", Choice::No),
+        ];
+        let mut overlay = ConfirmationOverlay::with_buttons("Trust this folder?", buttons.clone());
+        assert!(!overlay.handle_key(KeyCode::Char('x')));
+        assert!(overlay.handle_key(KeyCode::Char('A')));
+        assert!(overlay.is_dismissed());
+        assert_eq!(overlay.choice(), Some(Choice::Always));
+        assert!(!overlay.is_confirmed());
+
+        let mut overlay = ConfirmationOverlay::with_buttons("Trust this folder?", buttons);
+        overlay.handle_key(KeyCode::Esc);
+        assert_eq!(overlay.choice(), None);
+        assert!(overlay.is_cancelled());
+
+        let area = Rect::new(0, 0, 60, 10);
+        let mut buf = Buffer::empty(area);
+        let overlay = ConfirmationOverlay::with_buttons(
+            "Delete?",
+            vec![ConfirmButton::new('b', "Branch too", Choice::Always)],
+        );
+        overlay.render_content(area, &mut buf);
+        assert!(buffer_to_string(&buf).contains("[b] Branch too"));
+    }
+
     fn buffer_to_string(buf: &Buffer) -> String {
         let mut s = String::new();
         for y in 0..buf.area.height {
@@ -1,13 +1,19 @@
+pub mod branch_picker;
 pub mod confirmation;
+pub mod push;
 pub mod restart;
 pub mod text_input;
 pub mod text_overlay;
 
 #[allow(unused_imports)]
-pub use confirmation::ConfirmationOverlay;
+pub use branch_picker::BranchPickerOverlay;
+#[allow(unused_imports)]
+pub use confirmation::{Choice, ConfirmButton, ConfirmationOverlay};
 #[allow(unused_imports)]
 pub use text_input::TextInputOverlay;
 #[allow(unused_imports)]
+pub use push::PushOverlay;
+#[allow(unused_imports)]
 pub use restart::RestartOverlay;
 #[allow(unused_imports)]
 pub use text_overlay::TextOverlay;
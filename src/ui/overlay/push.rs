@@ -0,0 +1,244 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::prelude::*;
+use ratatui::widgets::{Block, Borders, Paragraph};
+
+use crate::session::git::util::GhStatus;
+
+/// Push options overlay — shown when the user presses 'P' on a session.
+pub struct PushOverlay {
+    session: String,
+    remote: String,
+    gh: GhStatus,
+    pub squash: bool,
+    message: String,
+    pub force_with_lease: bool,
+    pub draft_body: bool,
+    selected: usize, // 0 = squash, 1 = commit message, 2 = force, 3 = draft, 4 = push button
+    submitted: bool,
+    cancelled: bool,
+}
+
+impl PushOverlay {
+    pub fn new(session: impl Into<String>, remote: impl Into<String>, gh: GhStatus) -> Self {
+        let session = session.into();
+        Self {
+            message: session.clone(),
+            session,
+            remote: remote.into(),
+            gh,
+            squash: false,
+            force_with_lease: false,
+            draft_body: false,
+            selected: 0,
+            submitted: false,
+            cancelled: false,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let editing = self.selected == 1;
+        match key.code {
+            KeyCode::Up | KeyCode::BackTab => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Tab if self.selected < 4 => self.selected += 1,
+            KeyCode::Enter => self.submitted = true,
+            KeyCode::Esc => self.cancelled = true,
+            KeyCode::Backspace if editing => {
+                self.message.pop();
+            }
+            KeyCode::Char(c) if editing => {
+                self.message.push(c);
+                // Editing the message implies wanting the squash
+                self.squash = true;
+            }
+            KeyCode::Char(' ') if self.selected == 0 => self.squash = !self.squash,
+            KeyCode::Char(' ') if self.selected == 2 => {
+                self.force_with_lease = !self.force_with_lease
+            }
+            KeyCode::Char(' ') if self.selected == 3 => self.draft_body = !self.draft_body,
+            KeyCode::Char(' ') => self.submitted = true,
+            KeyCode::Char('y') => self.submitted = true,
+            KeyCode::Char('n') => self.cancelled = true,
+            _ => {}
+        }
+        true
+    }
+
+    /// Append pasted text to the commit message while it's selected.
+    pub fn paste(&mut self, text: &str) {
+        if self.selected == 1 {
+            self.message.push_str(&super::text_input::single_line(text));
+            self.squash = true;
+        }
+    }
+
+    pub fn session(&self) -> &str {
+        &self.session
+    }
+
+    /// Push with git alone and leave opening the PR to the user.
+    pub fn plain_git(&self) -> bool {
+        self.gh != GhStatus::Ready
+    }
+
+    pub fn is_submitted(&self) -> bool {
+        self.submitted
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled
+    }
+
+    /// Message for the squashed commit, or `None` when not squashing.
+    pub fn squash_message(&self) -> Option<&str> {
+        let message = self.message.trim();
+        (self.squash && !message.is_empty()).then_some(message)
+    }
+
+    pub fn render_content(&self, area: Rect, buf: &mut Buffer) {
+        let marker = |idx: usize| if idx == self.selected { " > " } else { "   " };
+        let checkbox = |checked: bool| if checked { "[x]" } else { "[ ]" };
+        let cursor = if self.selected == 1 { "▏" } else { "" };
+        let message_style = if self.squash {
+            Style::default().fg(Color::White)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+
+        let mut lines = vec![
+            Line::raw(format!("Push & create PR for '{}' to {}?", self.session, self.remote)),
+            Line::raw(""),
+            Line::raw(format!("{}{} Squash commits into one", marker(0), checkbox(self.squash))),
+            Line::from(vec![
+                Span::raw(format!("{}    Message: ", marker(1))),
+                Span::styled(format!("{}{}", self.message, cursor), message_style),
+            ]),
+            Line::raw(format!(
+                "{}{} Force with lease{}",
+                marker(2),
+                checkbox(self.force_with_lease || self.squash),
+                if self.squash { " (needed after squashing)" } else { "" }
+            )),
+            Line::raw(format!(
+                "{}{} Let the agent draft the PR description (opens $EDITOR)",
+                marker(3),
+                checkbox(self.draft_body)
+            )),
+            Line::raw(""),
+            Line::raw(format!("{}[ Push ]", marker(4))),
+            Line::raw(""),
+            Line::styled(
+                "↑/↓ navigate · Space toggle · Enter push · Esc cancel",
+                Style::default().fg(Color::DarkGray),
+            ),
+        ];
+        if let Some(hint) = self.gh.hint() {
+            let warning = vec![
+                Line::styled(format!("⚠ {}", hint), Style::default().fg(Color::Yellow)),
+                Line::styled(
+                    "  Will push with git and show a link for opening the PR",
+                    Style::default().fg(Color::DarkGray),
+                ),
+                Line::raw(""),
+            ];
+            lines.splice(2..2, warning);
+        }
+
+        let block = Block::default()
+            .title(" ☸ Push ")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::Yellow));
+
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    fn key(code: KeyCode) -> KeyEvent {
+        KeyEvent::new(code, KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn test_push_defaults_to_plain_push() {
+        let mut overlay = PushOverlay::new("feature", "origin", GhStatus::Ready);
+        overlay.handle_key(key(KeyCode::Char('y')));
+        assert!(overlay.is_submitted());
+        assert_eq!(overlay.squash_message(), None);
+    }
+
+    #[test]
+    fn test_push_squash_with_edited_message() {
+        let mut overlay = PushOverlay::new("feature", "origin", GhStatus::Ready);
+        overlay.handle_key(key(KeyCode::Char(' ')));
+        assert_eq!(overlay.squash_message(), Some("feature"));
+
+        overlay.handle_key(key(KeyCode::Down));
+        for _ in 0.."feature".len() {
+            overlay.handle_key(key(KeyCode::Backspace));
+        }
+        for c in "Add login".chars() {
+            overlay.handle_key(key(KeyCode::Char(c)));
+        }
+        // 'n' and 'y' are text while editing the message
+        assert!(!overlay.is_cancelled());
+        overlay.handle_key(key(KeyCode::Enter));
+        assert!(overlay.is_submitted());
+        assert_eq!(overlay.squash_message(), Some("Add login"));
+    }
+
+    #[test]
+    fn test_push_paste_only_into_message() {
+        let mut overlay = PushOverlay::new("feature", "origin", GhStatus::Ready);
+        overlay.paste("y");
+        assert!(!overlay.is_submitted());
+        assert_eq!(overlay.squash_message(), None);
+
+        overlay.handle_key(key(KeyCode::Down));
+        overlay.paste(": add login\n");
+        assert_eq!(overlay.squash_message(), Some("feature: add login"));
+    }
+
+    #[test]
+    fn test_push_toggle_force_with_lease() {
+        let mut overlay = PushOverlay::new("feature", "origin", GhStatus::Ready);
+        overlay.handle_key(key(KeyCode::Down));
+        overlay.handle_key(key(KeyCode::Down));
+        overlay.handle_key(key(KeyCode::Char(' ')));
+        assert!(overlay.force_with_lease);
+        assert!(!overlay.is_submitted());
+        overlay.handle_key(key(KeyCode::Char(' ')));
+        assert!(!overlay.force_with_lease);
+    }
+
+    #[test]
+    fn test_push_toggle_draft_body() {
+        let mut overlay = PushOverlay::new("feature", "origin", GhStatus::Ready);
+        for _ in 0..3 {
+            overlay.handle_key(key(KeyCode::Down));
+        }
+        overlay.handle_key(key(KeyCode::Char(' ')));
+        assert!(overlay.draft_body);
+        overlay.handle_key(key(KeyCode::Down));
+        overlay.handle_key(key(KeyCode::Char(' ')));
+        assert!(overlay.is_submitted());
+    }
+
+    #[test]
+    fn test_push_without_gh_uses_plain_git() {
+        let overlay = PushOverlay::new("feature", "origin", GhStatus::NotAuthenticated);
+        assert!(overlay.plain_git());
+        assert!(!PushOverlay::new("feature", "origin", GhStatus::Ready).plain_git());
+    }
+
+    #[test]
+    fn test_push_cancel() {
+        let mut overlay = PushOverlay::new("feature", "origin", GhStatus::Ready);
+        overlay.handle_key(key(KeyCode::Char('n')));
+        assert!(overlay.is_cancelled());
+    }
+}
@@ -1,12 +1,39 @@
 use crossterm::event::{KeyCode, KeyEvent};
 use ratatui::prelude::*;
 use ratatui::widgets::*;
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// Default input limit, in graphemes. Session titles use it unless the
+/// config's `title_max_length` says otherwise.
+pub const DEFAULT_MAX_LEN: usize = 64;
+
+/// Limit for free-form inputs like prompts, in graphemes.
+pub const PROMPT_MAX_LEN: usize = 4096;
+
+/// Pasted text as one line: the inputs are single-line, and a newline sent
+/// on to an agent would submit the prompt early.
+pub fn single_line(text: &str) -> String {
+    text.lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Feedback on the input as it's typed: a note to show, or why it can't
+/// be submitted.
+pub type InputCheck = Box<dyn Fn(&str) -> Result<String, String>>;
 
 #[allow(dead_code)]
 pub struct TextInputOverlay {
     title: String,
     input: String,
+    /// Byte offset of the cursor, always on a grapheme boundary.
     cursor_pos: usize,
+    /// Most graphemes the input may hold.
+    max_len: usize,
+    check: Option<InputCheck>,
     submitted: bool,
     cancelled: bool,
 }
@@ -18,16 +45,48 @@ impl TextInputOverlay {
             title: title.into(),
             input: String::new(),
             cursor_pos: 0,
+            max_len: DEFAULT_MAX_LEN,
+            check: None,
             submitted: false,
             cancelled: false,
         }
     }
 
+    /// Accept at most `max_len` graphemes.
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Check the input on every change, showing the result under it;
+    /// Enter does nothing while the check fails.
+    pub fn with_check(
+        mut self,
+        check: impl Fn(&str) -> Result<String, String> + 'static,
+    ) -> Self {
+        self.check = Some(Box::new(check));
+        self
+    }
+
+    /// Replace the check, e.g. once what it needs has been looked up.
+    pub fn set_check(&mut self, check: impl Fn(&str) -> Result<String, String> + 'static) {
+        self.check = Some(Box::new(check));
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    /// The check's verdict on the current input; `Ok("")` without one.
+    pub fn feedback(&self) -> Result<String, String> {
+        self.check.as_ref().map_or(Ok(String::new()), |check| check(&self.input))
+    }
+
     /// Handle a key event. Returns true if the overlay consumed the key.
     pub fn handle_key(&mut self, key: KeyEvent) -> bool {
         match key.code {
             KeyCode::Enter => {
-                self.submitted = true;
+                self.submitted = self.feedback().is_ok();
                 true
             }
             KeyCode::Esc => {
@@ -35,35 +94,81 @@ impl TextInputOverlay {
                 true
             }
             KeyCode::Char(c) => {
-                if self.input.len() < 64 {
-                    self.input.insert(self.cursor_pos, c);
-                    self.cursor_pos += 1;
+                // A combining mark joins the grapheme before it, so it
+                // only counts against the limit when it starts a new one
+                let mut with_c = self.input.clone();
+                with_c.insert(self.cursor_pos, c);
+                if with_c.graphemes(true).count() <= self.max_len {
+                    self.input = with_c;
+                    self.cursor_pos += c.len_utf8();
                 }
                 true
             }
             KeyCode::Backspace => {
-                if self.cursor_pos > 0 {
-                    self.cursor_pos -= 1;
-                    self.input.remove(self.cursor_pos);
+                if let Some(start) = self.prev_boundary() {
+                    self.input.replace_range(start..self.cursor_pos, "");
+                    self.cursor_pos = start;
+                }
+                true
+            }
+            KeyCode::Delete => {
+                if let Some(end) = self.next_boundary() {
+                    self.input.replace_range(self.cursor_pos..end, "");
                 }
                 true
             }
             KeyCode::Left => {
-                if self.cursor_pos > 0 {
-                    self.cursor_pos -= 1;
+                if let Some(start) = self.prev_boundary() {
+                    self.cursor_pos = start;
                 }
                 true
             }
             KeyCode::Right => {
-                if self.cursor_pos < self.input.len() {
-                    self.cursor_pos += 1;
+                if let Some(end) = self.next_boundary() {
+                    self.cursor_pos = end;
                 }
                 true
             }
+            KeyCode::Home => {
+                self.cursor_pos = 0;
+                true
+            }
+            KeyCode::End => {
+                self.cursor_pos = self.input.len();
+                true
+            }
             _ => false,
         }
     }
 
+    /// Insert pasted text at the cursor, as much as fits under the limit.
+    pub fn paste(&mut self, text: &str) {
+        let text = single_line(text);
+        let room = self.max_len.saturating_sub(self.input.graphemes(true).count());
+        let end = text
+            .grapheme_indices(true)
+            .nth(room)
+            .map_or(text.len(), |(i, _)| i);
+        self.input.insert_str(self.cursor_pos, &text[..end]);
+        self.cursor_pos += end;
+    }
+
+    /// Byte offset of the grapheme before the cursor, if any.
+    fn prev_boundary(&self) -> Option<usize> {
+        self.input[..self.cursor_pos]
+            .grapheme_indices(true)
+            .next_back()
+            .map(|(i, _)| i)
+    }
+
+    /// Byte offset just past the grapheme under the cursor, if any.
+    fn next_boundary(&self) -> Option<usize> {
+        self.input[self.cursor_pos..]
+            .graphemes(true)
+            .next()
+            .map(|g| self.cursor_pos + g.len())
+    }
+
     pub fn is_submitted(&self) -> bool {
         self.submitted
     }
@@ -89,14 +194,17 @@ impl TextInputOverlay {
         let inner = block.inner(area);
         block.render(area, buf);
 
-        // Build the input display with a cursor indicator
+        // Build the input display with a cursor indicator over the grapheme
+        // under the cursor, or a space at the end
         let before_cursor = &self.input[..self.cursor_pos];
-        let cursor_char = self.input.get(self.cursor_pos..self.cursor_pos + 1).unwrap_or(" ");
-        let after_cursor = if self.cursor_pos < self.input.len() {
-            &self.input[self.cursor_pos + 1..]
-        } else {
-            ""
+        let end = self.next_boundary().unwrap_or(self.cursor_pos);
+        let cursor_char = match &self.input[self.cursor_pos..end] {
+            "" => " ",
+            // Zero-width graphemes would leave the cursor invisible
+            g if g.width() == 0 => " ",
+            g => g,
         };
+        let after_cursor = &self.input[end..];
 
         let input_line = Line::from(vec![
             Span::raw(before_cursor),
@@ -107,9 +215,17 @@ impl TextInputOverlay {
             Span::raw(after_cursor),
         ]);
 
-        let counter = format!("({}/32)", self.input.len());
-        let text = Paragraph::new(vec![
-            input_line,
+        let counter = format!("({}/{})", self.input.graphemes(true).count(), self.max_len);
+        let mut lines = vec![input_line];
+        match self.feedback() {
+            Ok(note) if note.is_empty() => {}
+            Ok(note) => lines.extend(
+                note.lines()
+                    .map(|line| Line::styled(line.to_string(), Style::default().fg(Color::Gray))),
+            ),
+            Err(e) => lines.push(Line::styled(e, Style::default().fg(Color::Red))),
+        }
+        lines.extend([
             Line::from(Span::styled(
                 counter,
                 Style::default().fg(Color::DarkGray),
@@ -121,6 +237,7 @@ impl TextInputOverlay {
                 Span::raw(" Cancel"),
             ]),
         ]);
+        let text = Paragraph::new(lines).wrap(Wrap { trim: false });
         text.render(inner, buf);
     }
 }
@@ -156,6 +273,27 @@ mod tests {
         assert_eq!(input.input(), "x");
     }
 
+    #[test]
+    fn test_failed_check_blocks_submit() {
+        let mut input = TextInputOverlay::new("Name").with_check(|text| {
+            if text.contains(' ') {
+                Err("no spaces".to_string())
+            } else {
+                Ok(format!("Branch: {}", text))
+            }
+        });
+        input.paste("a b");
+        assert_eq!(input.feedback(), Err("no spaces".to_string()));
+        input.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(!input.is_submitted());
+
+        input.handle_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE));
+        input.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(input.feedback().as_deref(), Ok("Branch: ab"));
+        input.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        assert!(input.is_submitted());
+    }
+
     #[test]
     fn test_text_input_cancel() {
         let mut input = TextInputOverlay::new("Name");
@@ -184,4 +322,87 @@ mod tests {
         input.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
         assert!(input.is_done());
     }
+
+    #[test]
+    fn test_text_input_multibyte_chars() {
+        let mut input = TextInputOverlay::new("Name");
+        for c in "héllo 世界".chars() {
+            input.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(input.input(), "héllo 世界");
+
+        // Edit in the middle of the multi-byte run
+        input.handle_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE));
+        input.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        input.handle_key(KeyEvent::new(KeyCode::Char('の'), KeyModifiers::NONE));
+        assert_eq!(input.input(), "héllo の界");
+
+        input.handle_key(KeyEvent::new(KeyCode::Home, KeyModifiers::NONE));
+        input.handle_key(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE));
+        input.handle_key(KeyEvent::new(KeyCode::Delete, KeyModifiers::NONE));
+        assert_eq!(input.input(), "hllo の界");
+    }
+
+    #[test]
+    fn test_text_input_combining_mark_is_one_grapheme() {
+        let mut input = TextInputOverlay::new("Name").with_max_len(2);
+        for c in ['e', '\u{301}', 'x'] {
+            input.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(input.input(), "e\u{301}x");
+
+        // Backspace twice removes the accented 'e' whole
+        input.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        input.handle_key(KeyEvent::new(KeyCode::Backspace, KeyModifiers::NONE));
+        assert_eq!(input.input(), "");
+    }
+
+    #[test]
+    fn test_text_input_limit_counts_graphemes() {
+        let mut input = TextInputOverlay::new("Name").with_max_len(3);
+        for c in "日本語!".chars() {
+            input.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert_eq!(input.input(), "日本語");
+    }
+
+    #[test]
+    fn test_text_input_renders_wide_cursor() {
+        let mut input = TextInputOverlay::new("Name");
+        for c in "日本".chars() {
+            input.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        input.handle_key(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE));
+        let area = Rect::new(0, 0, 20, 5);
+        let mut buf = Buffer::empty(area);
+        input.render_content(area, &mut buf);
+        assert_eq!(buf[(3, 1)].symbol(), "本");
+        assert_eq!(buf[(3, 1)].bg, Color::White);
+        assert_eq!(buf[(1, 2)].symbol(), "(");
+        let counter: String = (1..8).map(|x| buf[(x, 2)].symbol().to_string()).collect();
+        assert_eq!(counter, "(2/64) ");
+    }
+
+    #[test]
+    fn test_text_input_paste() {
+        let mut input = TextInputOverlay::new("Prompt");
+        input.handle_key(KeyEvent::new(KeyCode::Char('!'), KeyModifiers::NONE));
+        input.handle_key(KeyEvent::new(KeyCode::Home, KeyModifiers::NONE));
+        input.paste("fix the bug\r\n\nin café.rs\n");
+        assert_eq!(input.input(), "fix the bug in café.rs!");
+        assert!(!input.is_submitted(), "pasted newlines don't submit");
+
+        // Typing continues after the pasted text
+        input.handle_key(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE));
+        assert_eq!(input.input(), "fix the bug in café.rs?!");
+    }
+
+    #[test]
+    fn test_text_input_paste_keeps_what_fits() {
+        let mut input = TextInputOverlay::new("Name").with_max_len(4);
+        input.paste("añadir");
+        assert_eq!(input.input(), "añad");
+        input.paste("x");
+        assert_eq!(input.input(), "añad");
+    }
 }
@@ -6,15 +6,21 @@ use ratatui::widgets::*;
 pub struct TextOverlay {
     title: String,
     content: String,
+    /// Lines scrolled past at the top.
+    scroll: u16,
     dismissed: bool,
 }
 
+/// Lines moved by PgUp/PgDn.
+const PAGE: u16 = 10;
+
 #[allow(dead_code)]
 impl TextOverlay {
     pub fn new(title: impl Into<String>, content: impl Into<String>) -> Self {
         Self {
             title: title.into(),
             content: content.into(),
+            scroll: 0,
             dismissed: false,
         }
     }
@@ -26,10 +32,34 @@ impl TextOverlay {
                 self.dismissed = true;
                 true
             }
+            KeyCode::Up | KeyCode::Char('k') => self.scroll_to(self.scroll.saturating_sub(1)),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_to(self.scroll.saturating_add(1)),
+            KeyCode::PageUp => self.scroll_to(self.scroll.saturating_sub(PAGE)),
+            KeyCode::PageDown => self.scroll_to(self.scroll.saturating_add(PAGE)),
+            KeyCode::Home | KeyCode::Char('g') => self.scroll_to(0),
+            KeyCode::End | KeyCode::Char('G') => self.scroll_to(u16::MAX),
             _ => false,
         }
     }
 
+    /// Scroll so `line` is at the top, keeping the last line reachable.
+    fn scroll_to(&mut self, line: u16) -> bool {
+        let last = self.content.lines().count().saturating_sub(1);
+        self.scroll = line.min(u16::try_from(last).unwrap_or(u16::MAX));
+        true
+    }
+
+    /// Replace the text, e.g. once something slow to look up arrives,
+    /// keeping the scroll position.
+    pub fn set_content(&mut self, content: impl Into<String>) {
+        self.content = content.into();
+        self.scroll_to(self.scroll);
+    }
+
+    pub fn scroll(&self) -> u16 {
+        self.scroll
+    }
+
     pub fn is_dismissed(&self) -> bool {
         self.dismissed
     }
@@ -47,15 +77,25 @@ impl TextOverlay {
         let layout = Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).split(inner);
 
         // Content text
-        let content = Paragraph::new(self.content.as_str()).wrap(Wrap { trim: false });
+        let content = Paragraph::new(self.content.as_str())
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        let scrollable =
+            self.scroll > 0 || self.content.lines().count() > layout[0].height as usize;
         content.render(layout[0], buf);
 
         // Footer
-        let footer = Line::from(vec![
+        let mut footer = vec![
             Span::styled("Press ", Style::default().fg(Color::DarkGray)),
             Span::styled("Esc", Style::default().fg(Color::Yellow).bold()),
             Span::styled(" to close", Style::default().fg(Color::DarkGray)),
-        ]);
+        ];
+        if scrollable {
+            footer.push(Span::styled(", ", Style::default().fg(Color::DarkGray)));
+            footer.push(Span::styled("↑/↓", Style::default().fg(Color::Yellow).bold()));
+            footer.push(Span::styled(" to scroll", Style::default().fg(Color::DarkGray)));
+        }
+        let footer = Line::from(footer);
         let footer_paragraph = Paragraph::new(footer).alignment(Alignment::Center);
         footer_paragraph.render(layout[1], buf);
     }
@@ -87,6 +127,26 @@ mod tests {
         assert!(overlay.is_dismissed());
     }
 
+    #[test]
+    fn test_text_overlay_scrolls_within_content() {
+        let mut overlay = TextOverlay::new("Notes", "one\ntwo\nthree");
+        overlay.handle_key(KeyCode::Up);
+        assert_eq!(overlay.scroll(), 0);
+        overlay.handle_key(KeyCode::Down);
+        overlay.handle_key(KeyCode::Char('j'));
+        assert_eq!(overlay.scroll(), 2);
+        // Stops at the last line
+        overlay.handle_key(KeyCode::PageDown);
+        assert_eq!(overlay.scroll(), 2);
+        overlay.handle_key(KeyCode::Char('k'));
+        assert_eq!(overlay.scroll(), 1);
+        overlay.handle_key(KeyCode::Home);
+        assert_eq!(overlay.scroll(), 0);
+        overlay.handle_key(KeyCode::Char('G'));
+        assert_eq!(overlay.scroll(), 2);
+        assert!(!overlay.is_dismissed());
+    }
+
     #[test]
     fn test_text_overlay_other_keys_ignored() {
         let mut overlay = TextOverlay::new("Help", "Some help text");
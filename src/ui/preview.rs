@@ -1,9 +1,16 @@
+use std::collections::VecDeque;
+
 use ratatui::prelude::*;
 use ratatui::widgets::{Block, Borders, Paragraph};
 
+use crate::session::Instance;
+
+/// Default cap on scrollback lines held in memory.
+pub const DEFAULT_MAX_SCROLLBACK: usize = 10_000;
+
 /// Strip ANSI escape sequences from a string.
 /// Handles CSI sequences (ESC[...m) and OSC sequences (ESC]...BEL/ST).
-fn strip_ansi(s: &str) -> String {
+pub fn strip_ansi(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     let mut chars = s.chars().peekable();
     while let Some(c) = chars.next() {
@@ -44,36 +51,106 @@ fn strip_ansi(s: &str) -> String {
     result
 }
 
+/// Bring `lines` in line with `text`, touching only the lines that
+/// changed: a new capture usually differs from the last in a line or two
+/// at the bottom. Returns how many lines were replaced, added or removed.
+fn update_lines(lines: &mut VecDeque<String>, text: &str) -> usize {
+    let mut changed = 0;
+    let mut count = 0;
+    for (i, line) in text.lines().enumerate() {
+        let line = strip_ansi(line);
+        match lines.get_mut(i) {
+            Some(old) if *old == line => {}
+            Some(old) => {
+                *old = line;
+                changed += 1;
+            }
+            None => {
+                lines.push_back(line);
+                changed += 1;
+            }
+        }
+        count = i + 1;
+    }
+    changed += lines.len() - count;
+    lines.truncate(count);
+    changed
+}
+
 /// Renders tmux pane content with scroll support.
 pub struct PreviewPane {
-    normal_content: Vec<String>,
-    content: Vec<String>,
+    // Latest pane content while scrolling; empty otherwise (it lives in
+    // `content` then, so it isn't copied on every update).
+    normal_content: VecDeque<String>,
+    // Displayed lines; in scroll mode at most `max_scrollback` of them
+    content: VecDeque<String>,
+    // Text `content` was last built from, to skip rebuilding unchanged content
+    raw: String,
+    // Scrollback lines above the pane loaded / not loaded yet (scroll mode)
+    loaded_history: usize,
+    unloaded_history: usize,
+    max_scrollback: usize,
     scroll_offset: usize,
     is_scrolling: bool,
     width: u16,
     height: u16,
+    // Which session is shown, in the block title
+    header: String,
+}
+
+/// The selected session's title, branch, program and status, for the
+/// preview's block title.
+pub fn session_header(instance: &Instance) -> String {
+    let mut parts = vec![instance.title.as_str()];
+    if !instance.branch.is_empty() {
+        parts.push(&instance.branch);
+    }
+    parts.push(&instance.program);
+    let status = match instance.transition {
+        Some(transition) => transition.label().to_lowercase(),
+        None => instance.status.to_string(),
+    };
+    format!("{} · {}", parts.join(" · "), status)
 }
 
 impl PreviewPane {
     pub fn new() -> Self {
         Self {
-            normal_content: Vec::new(),
-            content: Vec::new(),
+            normal_content: VecDeque::new(),
+            content: VecDeque::new(),
+            raw: String::new(),
+            loaded_history: 0,
+            unloaded_history: 0,
+            max_scrollback: DEFAULT_MAX_SCROLLBACK,
             scroll_offset: 0,
             is_scrolling: false,
             width: 0,
             height: 0,
+            header: String::new(),
         }
     }
 
-    /// Replace content by splitting text into lines.
-    /// Strips ANSI escape sequences since ratatui renders plain text.
-    /// When not scrolling, updates the displayed content immediately.
+    /// Describe the session being shown in the block title; empty for none.
+    pub fn set_header(&mut self, header: impl Into<String>) {
+        self.header = header.into();
+    }
+
+    /// Replace content by splitting text into lines, updating only the
+    /// lines that changed. Strips ANSI escape sequences since ratatui
+    /// renders plain text. When not scrolling, updates the displayed
+    /// content immediately.
     pub fn set_content(&mut self, text: &str) {
-        self.normal_content = text.lines().map(|l| strip_ansi(l)).collect();
-        if !self.is_scrolling {
-            self.content = self.normal_content.clone();
+        if text == self.raw {
+            return;
         }
+        self.raw.clear();
+        self.raw.push_str(text);
+        let lines = if self.is_scrolling {
+            &mut self.normal_content
+        } else {
+            &mut self.content
+        };
+        update_lines(lines, text);
     }
 
     /// Set the preview to the fallback Ganesha art (shown when no session is selected).
@@ -83,7 +160,8 @@ impl PreviewPane {
 
     /// Show animated Ganesha loading screen with swaying motion and rotating messages.
     /// `tick` controls both the animation frame and the status message.
-    pub fn set_loading(&mut self, tick: usize, session_name: &str) {
+    /// `step` is what creation is busy with, when known.
+    pub fn set_loading(&mut self, tick: usize, session_name: &str, step: Option<&str>) {
         let frames = crate::ui::consts::GANESHA_FRAMES;
         let messages = crate::ui::consts::LOADING_MESSAGES;
 
@@ -98,11 +176,13 @@ impl PreviewPane {
         let spinner_frames = ['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
         let spinner = spinner_frames[tick % spinner_frames.len()];
 
+        let step = step.map(|s| format!(": {}", s)).unwrap_or_default();
         let loading_text = format!(
-            "{}\n\n   {} Creating '{}'\n\n   {}\n",
+            "{}\n\n   {} Creating '{}'{}\n\n   {}\n",
             frame.trim_end(),
             spinner,
             session_name,
+            step,
             msg,
         );
         self.set_content(&loading_text);
@@ -110,12 +190,40 @@ impl PreviewPane {
 
     /// Returns true when there is no content to display.
     pub fn is_empty(&self) -> bool {
-        self.normal_content.is_empty()
+        if self.is_scrolling {
+            self.normal_content.is_empty()
+        } else {
+            self.content.is_empty()
+        }
+    }
+
+    /// Limit the number of lines kept in scroll mode.
+    pub fn set_max_scrollback(&mut self, max_lines: usize) {
+        self.max_scrollback = max_lines.max(1);
     }
 
     /// Enter scroll mode with full history content.
     pub fn enter_scroll_mode(&mut self, full_history: &str) {
-        self.content = full_history.lines().map(|l| strip_ansi(l)).collect();
+        self.enter_scroll_mode_partial(full_history, 0, 0);
+    }
+
+    /// Enter scroll mode with the most recent part of the history.
+    ///
+    /// `loaded` is the number of scrollback lines (above the visible pane)
+    /// included in `recent_history`; `unloaded` is the number of older ones
+    /// still in tmux, fetched with `prepend_history` as the user scrolls up.
+    pub fn enter_scroll_mode_partial(&mut self, recent_history: &str, loaded: usize, unloaded: usize) {
+        let mut history: VecDeque<String> = recent_history.lines().map(strip_ansi).collect();
+        while history.len() > self.max_scrollback {
+            history.pop_front();
+        }
+        self.loaded_history = loaded;
+        self.unloaded_history = unloaded;
+        if !self.is_scrolling {
+            self.normal_content = std::mem::replace(&mut self.content, history);
+        } else {
+            self.content = history;
+        }
         self.is_scrolling = true;
         self.scroll_offset = 0;
     }
@@ -125,6 +233,11 @@ impl PreviewPane {
         self.height = height;
     }
 
+    /// Lines moved by PgUp/PgDn: a screen, keeping one line of context.
+    pub fn page_size(&self) -> usize {
+        (self.height as usize).saturating_sub(1).max(1)
+    }
+
     pub fn scroll_up(&mut self, amount: usize) {
         self.scroll_offset = self.scroll_offset.saturating_add(amount);
         self.clamp_scroll();
@@ -133,12 +246,48 @@ impl PreviewPane {
     pub fn scroll_down(&mut self, amount: usize) {
         self.scroll_offset = self.scroll_offset.saturating_sub(amount);
         if self.scroll_offset == 0 {
-            self.is_scrolling = false;
+            self.reset_scroll();
+        }
+    }
+
+    /// Number of older lines to request next, or 0 when the user isn't near
+    /// the top of what's loaded, everything is loaded, or the cap is reached.
+    pub fn wants_older_history(&self, chunk: usize) -> usize {
+        if !self.is_scrolling || self.unloaded_history == 0 {
+            return 0;
+        }
+        let room = self.max_scrollback.saturating_sub(self.content.len());
+        let top_distance = self
+            .content
+            .len()
+            .saturating_sub(self.scroll_offset + self.height as usize);
+        if top_distance > chunk / 2 {
+            return 0;
         }
+        chunk.min(self.unloaded_history).min(room)
+    }
+
+    /// Prepend `count` older scrollback lines fetched from tmux.
+    pub fn prepend_history(&mut self, older: &str, count: usize) {
+        let lines: Vec<String> = older.lines().map(strip_ansi).collect();
+        for line in lines.into_iter().rev() {
+            self.content.push_front(line);
+        }
+        self.loaded_history += count;
+        self.unloaded_history = self.unloaded_history.saturating_sub(count);
+    }
+
+    /// Number of scrollback lines above the visible pane loaded so far.
+    pub fn loaded_history(&self) -> usize {
+        self.loaded_history
     }
 
     pub fn reset_scroll(&mut self) {
-        self.content = self.normal_content.clone();
+        if self.is_scrolling {
+            self.content = std::mem::take(&mut self.normal_content);
+        }
+        self.loaded_history = 0;
+        self.unloaded_history = 0;
         self.scroll_offset = 0;
         self.is_scrolling = false;
     }
@@ -162,7 +311,12 @@ impl PreviewPane {
 
 impl Widget for &PreviewPane {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let block = Block::default().borders(Borders::ALL).title("Preview");
+        let title = if self.header.is_empty() {
+            "Preview".to_string()
+        } else {
+            format!("Preview: {}", self.header)
+        };
+        let block = Block::default().borders(Borders::ALL).title(title);
         let inner = block.inner(area);
         block.render(area, buf);
 
@@ -182,8 +336,9 @@ impl Widget for &PreviewPane {
         let end = total.saturating_sub(self.scroll_offset);
         let start = end.saturating_sub(visible_height);
 
-        let lines: Vec<Line<'_>> = self.content[start..end]
-            .iter()
+        let lines: Vec<Line<'_>> = self
+            .content
+            .range(start..end)
             .map(|l| Line::from(l.as_str()))
             .collect();
 
@@ -293,6 +448,27 @@ mod tests {
         Widget::render(&preview, area, &mut buf);
     }
 
+    #[test]
+    fn test_header_names_selected_session() {
+        let mut instance = Instance::new(crate::session::InstanceOptions {
+            title: "api".to_string(),
+            path: "/tmp".to_string(),
+            program: "claude".to_string(),
+            ..Default::default()
+        });
+        instance.branch = "gana/api".to_string();
+        instance.status = crate::session::InstanceStatus::Running;
+        assert_eq!(session_header(&instance), "api · gana/api · claude · running");
+
+        let mut preview = PreviewPane::new();
+        preview.set_header(session_header(&instance));
+        let area = Rect::new(0, 0, 60, 3);
+        let mut buf = Buffer::empty(area);
+        Widget::render(&preview, area, &mut buf);
+        let top: String = (0..area.width).map(|x| buf[(x, 0)].symbol().to_string()).collect();
+        assert!(top.contains("Preview: api · gana/api"));
+    }
+
     #[test]
     fn test_enter_scroll_mode_uses_full_history() {
         let mut preview = PreviewPane::new();
@@ -323,6 +499,52 @@ mod tests {
         assert_eq!(preview.content[0], "normal 1");
     }
 
+    #[test]
+    fn test_scrollback_capped() {
+        let mut preview = PreviewPane::new();
+        preview.set_max_scrollback(3);
+        preview.enter_scroll_mode("a\nb\nc\nd\ne");
+        assert_eq!(preview.content.len(), 3);
+        assert_eq!(preview.content[0], "c");
+    }
+
+    #[test]
+    fn test_older_history_loaded_near_top() {
+        let mut preview = PreviewPane::new();
+        preview.set_size(80, 10);
+        let recent: String = (100..140).map(|i| format!("line {}\n", i)).collect();
+        preview.enter_scroll_mode_partial(&recent, 30, 100);
+
+        // Far from the top: nothing to load yet
+        assert_eq!(preview.wants_older_history(20), 0);
+
+        preview.scroll_up(25);
+        let n = preview.wants_older_history(20);
+        assert_eq!(n, 20);
+        let older: String = (80..100).map(|i| format!("line {}\n", i)).collect();
+        preview.prepend_history(&older, n);
+        assert_eq!(preview.content.len(), 60);
+        assert_eq!(preview.content[0], "line 80");
+        // The viewport stays on the same lines
+        assert_eq!(preview.scroll_offset(), 25);
+        assert_eq!(preview.loaded_history(), 50);
+
+        // Leaving scroll mode drops the loaded history
+        preview.reset_scroll();
+        assert_eq!(preview.wants_older_history(20), 0);
+    }
+
+    #[test]
+    fn test_older_history_respects_cap() {
+        let mut preview = PreviewPane::new();
+        preview.set_size(80, 10);
+        preview.set_max_scrollback(30);
+        let recent: String = (0..20).map(|i| format!("line {}\n", i)).collect();
+        preview.enter_scroll_mode_partial(&recent, 20, 1000);
+        preview.scroll_up(10);
+        assert_eq!(preview.wants_older_history(50), 10);
+    }
+
     #[test]
     fn test_set_content_during_scroll_does_not_change_displayed() {
         let mut preview = PreviewPane::new();
@@ -340,4 +562,16 @@ mod tests {
         assert_eq!(preview.content.len(), 3); // now shows updated normal content
         assert_eq!(preview.content[0], "updated 1");
     }
+
+    #[test]
+    fn test_update_lines_touches_only_changes() {
+        let mut lines = VecDeque::new();
+        assert_eq!(update_lines(&mut lines, "a\nb\nc"), 3);
+        assert_eq!(update_lines(&mut lines, "a\nb\nc"), 0);
+        assert_eq!(update_lines(&mut lines, "a\nb\n\x1b[1md\x1b[0m"), 1);
+        assert_eq!(lines, ["a", "b", "d"]);
+        assert_eq!(update_lines(&mut lines, "a\nb\nd\ne"), 1);
+        assert_eq!(update_lines(&mut lines, "a"), 3);
+        assert_eq!(lines, ["a"]);
+    }
 }
@@ -1,38 +1,99 @@
+use std::collections::HashMap;
+
 use ratatui::prelude::*;
 use ratatui::widgets::Tabs;
 
-/// The active tab in the right-hand pane.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A view in the right-hand pane. What each one renders is up to the app;
+/// the order they are shown in is the [`TabbedWindow`]'s.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Tab {
     Preview,
     Diff,
+    Activity,
+}
+
+impl Tab {
+    /// The tabs shown by default, in order.
+    pub const ALL: [Tab; 3] = [Tab::Preview, Tab::Diff, Tab::Activity];
+
+    pub fn title(&self) -> &'static str {
+        match self {
+            Tab::Preview => "Preview",
+            Tab::Diff => "Diff",
+            Tab::Activity => "Activity",
+        }
+    }
 }
 
-/// Manages tab state and renders a tab bar for switching between Preview and Diff.
+/// Manages tab state and renders a tab bar for switching between an
+/// ordered list of tabs.
 pub struct TabbedWindow {
-    active_tab: Tab,
+    tabs: Vec<Tab>,
+    active: usize,
+    // Shown after a tab's title, e.g. "+15 -3" on Diff
+    badges: HashMap<Tab, String>,
 }
 
 impl TabbedWindow {
     pub fn new() -> Self {
+        Self::with_tabs(Tab::ALL.to_vec())
+    }
+
+    /// A window showing `tabs` in order, the first one active.
+    pub fn with_tabs(tabs: Vec<Tab>) -> Self {
         Self {
-            active_tab: Tab::Preview,
+            tabs,
+            active: 0,
+            badges: HashMap::new(),
         }
     }
 
     pub fn active_tab(&self) -> Tab {
-        self.active_tab
+        self.tabs.get(self.active).copied().unwrap_or(Tab::Preview)
+    }
+
+    pub fn next_tab(&mut self) {
+        if !self.tabs.is_empty() {
+            self.active = (self.active + 1) % self.tabs.len();
+        }
     }
 
-    pub fn switch_tab(&mut self) {
-        self.active_tab = match self.active_tab {
-            Tab::Preview => Tab::Diff,
-            Tab::Diff => Tab::Preview,
-        };
+    pub fn previous_tab(&mut self) {
+        if !self.tabs.is_empty() {
+            self.active = (self.active + self.tabs.len() - 1) % self.tabs.len();
+        }
+    }
+
+    /// Show the `number`th tab, counting from 1. Returns false when there
+    /// is no such tab.
+    pub fn select_number(&mut self, number: usize) -> bool {
+        if number == 0 || number > self.tabs.len() {
+            return false;
+        }
+        self.active = number - 1;
+        true
     }
 
+    /// Show `tab`, if it is one of this window's.
     pub fn set_tab(&mut self, tab: Tab) {
-        self.active_tab = tab;
+        if let Some(idx) = self.tabs.iter().position(|t| *t == tab) {
+            self.active = idx;
+        }
+    }
+
+    /// Summarize a tab's content after its title, e.g. the selected
+    /// session's changes on Diff; empty shows the plain title.
+    pub fn set_badge(&mut self, tab: Tab, badge: impl Into<String>) {
+        let badge = badge.into();
+        if badge.is_empty() {
+            self.badges.remove(&tab);
+        } else {
+            self.badges.insert(tab, badge);
+        }
+    }
+
+    pub fn badge(&self, tab: Tab) -> Option<&str> {
+        self.badges.get(&tab).map(String::as_str)
     }
 }
 
@@ -42,14 +103,17 @@ impl Widget for &TabbedWindow {
             return;
         }
 
-        let titles = vec!["Preview", "Diff"];
-        let selected = match self.active_tab {
-            Tab::Preview => 0,
-            Tab::Diff => 1,
-        };
+        let titles: Vec<String> = self
+            .tabs
+            .iter()
+            .map(|tab| match self.badges.get(tab) {
+                Some(badge) => format!("{} {}", tab.title(), badge),
+                None => tab.title().to_string(),
+            })
+            .collect();
 
         let tabs = Tabs::new(titles)
-            .select(selected)
+            .select(self.active)
             .style(Style::default().fg(Color::DarkGray))
             .highlight_style(
                 Style::default()
@@ -77,10 +141,31 @@ mod tests {
         let mut tw = TabbedWindow::new();
         assert_eq!(tw.active_tab(), Tab::Preview);
 
-        tw.switch_tab();
+        tw.next_tab();
         assert_eq!(tw.active_tab(), Tab::Diff);
 
-        tw.switch_tab();
+        tw.next_tab();
+        assert_eq!(tw.active_tab(), Tab::Activity);
+
+        tw.next_tab();
+        assert_eq!(tw.active_tab(), Tab::Preview);
+
+        tw.previous_tab();
+        assert_eq!(tw.active_tab(), Tab::Activity);
+    }
+
+    #[test]
+    fn test_tabbed_window_select_number() {
+        let mut tw = TabbedWindow::with_tabs(vec![Tab::Diff, Tab::Preview]);
+        assert_eq!(tw.active_tab(), Tab::Diff);
+        assert!(tw.select_number(2));
+        assert_eq!(tw.active_tab(), Tab::Preview);
+        assert!(!tw.select_number(3));
+        assert!(!tw.select_number(0));
+        assert_eq!(tw.active_tab(), Tab::Preview);
+
+        // Tabs not in the window can't be shown
+        tw.set_tab(Tab::Activity);
         assert_eq!(tw.active_tab(), Tab::Preview);
     }
 
@@ -106,5 +191,20 @@ mod tests {
             .collect();
         assert!(content.contains("Preview"));
         assert!(content.contains("Diff"));
+        assert!(content.contains("Activity"));
+    }
+
+    #[test]
+    fn test_diff_badge_in_title() {
+        let mut tw = TabbedWindow::new();
+        tw.set_badge(Tab::Diff, "+120 -14");
+        let area = Rect::new(0, 0, 40, 1);
+        let mut buf = Buffer::empty(area);
+        Widget::render(&tw, area, &mut buf);
+
+        let content: String = (0..40)
+            .map(|x| buf.cell((x, 0)).unwrap().symbol().to_string())
+            .collect();
+        assert!(content.contains("Diff +120 -14"));
     }
 }
@@ -1,102 +1,263 @@
 use std::fs;
+use std::io::Read;
 use std::path::Path;
-use std::process::Command;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use serde::Deserialize;
+
+use crate::config::UpdateChannel;
 
 const REPO: &str = "daern91/gana";
 const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-/// Check for updates and auto-install if a newer version is available.
-/// Runs silently — never blocks startup or shows errors to the user.
-/// Returns Some(new_version) if an update was downloaded and will take
-/// effect on next launch.
-pub fn auto_update(config_dir: &Path) -> Option<String> {
-    // Check if we recently checked (at most once per hour)
-    let last_check_file = config_dir.join("last_update_check");
-    if let Ok(metadata) = fs::metadata(&last_check_file) {
-        if let Ok(modified) = metadata.modified() {
-            if modified.elapsed().unwrap_or_default().as_secs() < 3600 {
-                // Check for pending update notification
-                return check_pending_update(config_dir);
+/// Written after a background install; holds the version installed.
+const INSTALLED_FILE: &str = "update_installed";
+
+/// Written when a newer release exists but `auto_update` is off.
+const AVAILABLE_FILE: &str = "update_available";
+
+/// Written when a background check or install fails; holds the error.
+const FAILED_FILE: &str = "update_failed";
+
+/// Timeouts for the release lookup and the download.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Release archives larger than this are refused.
+const MAX_ARCHIVE_BYTES: u64 = 100 * 1024 * 1024;
+
+/// Touched on every check so releases are looked up at most once per hour.
+const LAST_CHECK_FILE: &str = "last_update_check";
+
+/// Releases looked at on the pre-release channel.
+const RELEASES_PAGE: usize = 30;
+
+/// Look for a newer release on `channel` in the background, at most once
+/// per hour.
+///
+/// With `install`, a newer release is downloaded and replaces the running
+/// binary; otherwise it's only noted for [`available_version`]. Never
+/// blocks startup; failures are logged and kept for [`take_failure`].
+/// Returns the version installed by an
+/// earlier run, if any, to announce that it takes effect now.
+pub fn check_on_startup(
+    config_dir: &Path,
+    install: bool,
+    channel: UpdateChannel,
+) -> Option<String> {
+    let installed = take_installed_notice(config_dir);
+
+    let recently_checked = fs::metadata(config_dir.join(LAST_CHECK_FILE))
+        .and_then(|m| m.modified())
+        .is_ok_and(|at| at.elapsed().unwrap_or_default().as_secs() < 3600);
+    if !recently_checked {
+        let config_dir = config_dir.to_path_buf();
+        std::thread::spawn(move || {
+            if let Err(e) = background_check(&config_dir, install, channel) {
+                tracing::warn!("Update check failed: {:#}", e);
+                let _ = fs::write(config_dir.join(FAILED_FILE), format!("{:#}", e));
             }
-        }
+        });
     }
 
-    // Check if a previous update was staged
-    let result = check_pending_update(config_dir);
+    installed
+}
 
-    // Spawn background thread so we never block startup
-    let config_dir_owned = config_dir.to_path_buf();
-    std::thread::spawn(move || {
-        let _ = do_update_check(&config_dir_owned);
-    });
+/// A newer version found by an earlier check while `auto_update` was off.
+pub fn available_version(config_dir: &Path) -> Option<String> {
+    let version = fs::read_to_string(config_dir.join(AVAILABLE_FILE)).ok()?;
+    let version = version.trim();
+    is_newer(version).then(|| version.to_string())
+}
 
-    result
+/// The error from the last failed background check, if not yet shown.
+pub fn take_failure(config_dir: &Path) -> Option<String> {
+    let failed_file = config_dir.join(FAILED_FILE);
+    let error = fs::read_to_string(&failed_file).ok()?;
+    let _ = fs::remove_file(&failed_file);
+    let error = error.trim();
+    (!error.is_empty()).then(|| error.to_string())
 }
 
-/// Check if there's a pending "updated to vX.Y.Z" notification.
-fn check_pending_update(config_dir: &Path) -> Option<String> {
-    let notify_file = config_dir.join("update_installed");
-    if let Ok(version) = fs::read_to_string(&notify_file) {
-        let _ = fs::remove_file(&notify_file);
-        Some(version.trim().to_string())
-    } else {
-        None
+/// Check for a newer release on `channel` and, unless `check_only`,
+/// install it now. Used by `gana update`; errors are reported rather than
+/// swallowed.
+pub fn update_now(
+    config_dir: &Path,
+    check_only: bool,
+    channel: UpdateChannel,
+) -> anyhow::Result<()> {
+    let tag = latest_release_tag(channel)?;
+    let latest = tag.strip_prefix('v').unwrap_or(&tag);
+    if !is_newer(latest) {
+        let _ = fs::remove_file(config_dir.join(AVAILABLE_FILE));
+        println!("gana v{} is up to date.", CURRENT_VERSION);
+        return Ok(());
     }
+    if check_only {
+        println!(
+            "gana v{} is available (installed: v{}). Run `gana update` to install it.",
+            latest, CURRENT_VERSION
+        );
+        return Ok(());
+    }
+
+    println!("Updating gana v{} → v{}…", CURRENT_VERSION, latest);
+    download_and_install(&tag, config_dir)?;
+    let _ = fs::remove_file(config_dir.join(AVAILABLE_FILE));
+    println!("Installed gana v{}.", latest);
+    Ok(())
 }
 
-/// The actual update check + download (runs in background thread).
-fn do_update_check(config_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
-    // Mark that we checked
+/// Take the "updated to vX.Y.Z" notice left by a background install.
+fn take_installed_notice(config_dir: &Path) -> Option<String> {
+    let notify_file = config_dir.join(INSTALLED_FILE);
+    let version = fs::read_to_string(&notify_file).ok()?;
+    let _ = fs::remove_file(&notify_file);
+    Some(version.trim().to_string())
+}
+
+/// The startup check (runs in a background thread).
+fn background_check(
+    config_dir: &Path,
+    install: bool,
+    channel: UpdateChannel,
+) -> anyhow::Result<()> {
     let _ = fs::create_dir_all(config_dir);
-    let _ = fs::write(config_dir.join("last_update_check"), "");
+    let _ = fs::write(config_dir.join(LAST_CHECK_FILE), "");
+    let _ = fs::remove_file(config_dir.join(FAILED_FILE));
 
-    // Get latest release from GitHub API
-    let output = Command::new("curl")
-        .args([
-            "-fsSL",
-            "--max-time", "5",
-            &format!("https://api.github.com/repos/{}/releases/latest", REPO),
-        ])
-        .output()?;
+    let tag = latest_release_tag(channel)?;
+    let latest = tag.strip_prefix('v').unwrap_or(&tag);
+    let available = config_dir.join(AVAILABLE_FILE);
+    if !is_newer(latest) {
+        let _ = fs::remove_file(&available);
+        return Ok(());
+    }
 
-    if !output.status.success() {
-        return Ok(()); // Silently fail
+    if install {
+        download_and_install(&tag, config_dir)?;
+        let _ = fs::write(config_dir.join(INSTALLED_FILE), latest);
+    } else {
+        fs::write(&available, latest)?;
     }
+    Ok(())
+}
+
+/// An HTTP agent that gives up after `timeout`.
+fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::Agent::config_builder()
+        .timeout_global(Some(timeout))
+        .user_agent(format!("gana/{}", CURRENT_VERSION))
+        .build()
+        .into()
+}
 
-    let body = String::from_utf8_lossy(&output.stdout);
+/// Tag of the latest GitHub release on `channel`, e.g. "v0.9.0".
+fn latest_release_tag(channel: UpdateChannel) -> anyhow::Result<String> {
+    // releases/latest never returns a pre-release
+    let url = match channel {
+        UpdateChannel::Stable => format!("https://api.github.com/repos/{}/releases/latest", REPO),
+        UpdateChannel::Prerelease => format!(
+            "https://api.github.com/repos/{}/releases?per_page={}",
+            REPO, RELEASES_PAGE
+        ),
+    };
+    let body = agent(CHECK_TIMEOUT)
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .context("could not fetch the latest release")?;
+    match channel {
+        UpdateChannel::Stable => {
+            parse_tag(&body).context("no tag_name in the release information")
+        }
+        UpdateChannel::Prerelease => newest_tag(&body)?.context("no releases published yet"),
+    }
+}
 
-    // Parse tag_name from JSON (simple extraction, no serde needed)
+/// Extract `tag_name` from the release JSON (simple extraction, no serde
+/// needed).
+fn parse_tag(body: &str) -> Option<String> {
     let tag = body
         .split("\"tag_name\"")
         .nth(1)
         .and_then(|s| s.split('"').nth(1))
         .unwrap_or("");
+    (!tag.is_empty()).then(|| tag.to_string())
+}
 
-    let latest = tag.strip_prefix('v').unwrap_or(tag);
+/// A GitHub release, as far as the updater cares.
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    #[serde(default)]
+    draft: bool,
+    /// Release notes, in markdown.
+    #[serde(default)]
+    body: Option<String>,
+}
 
-    if latest.is_empty() {
-        return Ok(());
-    }
+/// Release notes for `version`, e.g. to show after it was installed.
+pub fn release_notes(version: &str) -> anyhow::Result<String> {
+    let url = format!(
+        "https://api.github.com/repos/{}/releases/tags/v{}",
+        REPO,
+        version.strip_prefix('v').unwrap_or(version)
+    );
+    let body = agent(CHECK_TIMEOUT)
+        .get(&url)
+        .header("Accept", "application/vnd.github+json")
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .with_context(|| format!("could not fetch the notes for v{}", version))?;
+    parse_notes(&body)
+}
+
+/// The notes of a single release, with Windows line endings removed.
+fn parse_notes(body: &str) -> anyhow::Result<String> {
+    let release: Release = serde_json::from_str(body).context("unexpected release information")?;
+    let notes = release.body.unwrap_or_default().replace("\r\n", "\n");
+    let notes = notes.trim();
+    Ok(if notes.is_empty() {
+        format!("No release notes for {}.", release.tag_name)
+    } else {
+        notes.to_string()
+    })
+}
 
-    // Compare versions
-    let current = semver::Version::parse(CURRENT_VERSION).ok();
-    let remote = semver::Version::parse(latest).ok();
+/// The highest version tag in a releases list, pre-releases included.
+/// Drafts and tags that aren't versions are skipped.
+fn newest_tag(body: &str) -> anyhow::Result<Option<String>> {
+    let releases: Vec<Release> =
+        serde_json::from_str(body).context("unexpected release information")?;
+    Ok(releases
+        .into_iter()
+        .filter(|r| !r.draft)
+        .filter_map(|r| {
+            let version =
+                semver::Version::parse(r.tag_name.strip_prefix('v').unwrap_or(&r.tag_name)).ok()?;
+            Some((version, r.tag_name))
+        })
+        .max_by(|a, b| a.0.cmp(&b.0))
+        .map(|(_, tag)| tag))
+}
 
-    match (current, remote) {
-        (Some(cur), Some(rem)) if rem > cur => {
-            // Newer version available — download it
-            if let Err(_) = download_and_install(tag, config_dir) {
-                // Silently fail
-            }
-        }
-        _ => {} // Up to date or parse error
+/// Whether `version` is newer than the running binary.
+fn is_newer(version: &str) -> bool {
+    match (
+        semver::Version::parse(CURRENT_VERSION),
+        semver::Version::parse(version.strip_prefix('v').unwrap_or(version)),
+    ) {
+        (Ok(current), Ok(other)) => other > current,
+        _ => false,
     }
-
-    Ok(())
 }
 
-/// Download the new binary and replace the current one.
-fn download_and_install(tag: &str, config_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+/// Download the release `tag` and replace the current binary.
+fn download_and_install(tag: &str, config_dir: &Path) -> anyhow::Result<()> {
     let target = detect_target();
     let url = format!(
         "https://github.com/{}/releases/download/{}/gana-{}.tar.gz",
@@ -104,73 +265,52 @@ fn download_and_install(tag: &str, config_dir: &Path) -> Result<(), Box<dyn std:
     );
 
     let tmp_dir = config_dir.join("update_tmp");
-    let _ = fs::create_dir_all(&tmp_dir);
-
-    let tarball = tmp_dir.join("gana.tar.gz");
-
-    // Download
-    let status = Command::new("curl")
-        .args([
-            "-fsSL",
-            "--max-time", "30",
-            "-o", &tarball.to_string_lossy(),
-            &url,
-        ])
-        .status()?;
-
-    if !status.success() {
-        let _ = fs::remove_dir_all(&tmp_dir);
-        return Ok(());
-    }
-
-    // Extract
-    let status = Command::new("tar")
-        .args(["-xzf", &tarball.to_string_lossy(), "-C", &tmp_dir.to_string_lossy()])
-        .status()?;
+    fs::create_dir_all(&tmp_dir)?;
+    let result = download_and_replace(&url, &tmp_dir);
+    let _ = fs::remove_dir_all(&tmp_dir);
+    result
+}
 
-    if !status.success() {
-        let _ = fs::remove_dir_all(&tmp_dir);
-        return Ok(());
-    }
+fn download_and_replace(url: &str, tmp_dir: &Path) -> anyhow::Result<()> {
+    let response = agent(DOWNLOAD_TIMEOUT)
+        .get(url)
+        .call()
+        .with_context(|| format!("download of {} failed", url))?;
+    let body = response
+        .into_body()
+        .into_with_config()
+        .limit(MAX_ARCHIVE_BYTES)
+        .reader();
+    unpack(body, tmp_dir).with_context(|| format!("could not extract {}", url))?;
 
     let new_binary = tmp_dir.join("gana");
     if !new_binary.exists() {
-        let _ = fs::remove_dir_all(&tmp_dir);
-        return Ok(());
+        bail!("the release archive has no gana binary");
     }
 
-    // Replace the current binary
-    if let Ok(current_exe) = std::env::current_exe() {
-        let current_exe = current_exe.canonicalize().unwrap_or(current_exe);
-        // Move current to .old, move new to current
-        let backup = current_exe.with_extension("old");
-        let _ = fs::remove_file(&backup);
-        if fs::rename(&current_exe, &backup).is_ok() {
-            if fs::copy(&new_binary, &current_exe).is_ok() {
-                // Make executable
-                #[cfg(unix)]
-                {
-                    use std::os::unix::fs::PermissionsExt;
-                    let _ = fs::set_permissions(
-                        &current_exe,
-                        fs::Permissions::from_mode(0o755),
-                    );
-                }
-                // Write notification for next launch
-                let version = tag.strip_prefix('v').unwrap_or(tag);
-                let _ = fs::write(
-                    config_dir.join("update_installed"),
-                    version,
-                );
-                let _ = fs::remove_file(&backup);
-            } else {
-                // Restore backup
-                let _ = fs::rename(&backup, &current_exe);
-            }
-        }
+    // Move current to .old, copy new to current
+    let current_exe = std::env::current_exe()?;
+    let current_exe = current_exe.canonicalize().unwrap_or(current_exe);
+    let backup = current_exe.with_extension("old");
+    let _ = fs::remove_file(&backup);
+    fs::rename(&current_exe, &backup)
+        .with_context(|| format!("cannot replace {}", current_exe.display()))?;
+    if let Err(e) = fs::copy(&new_binary, &current_exe) {
+        let _ = fs::rename(&backup, &current_exe);
+        return Err(e).with_context(|| format!("cannot write {}", current_exe.display()));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let _ = fs::set_permissions(&current_exe, fs::Permissions::from_mode(0o755));
     }
+    let _ = fs::remove_file(&backup);
+    Ok(())
+}
 
-    let _ = fs::remove_dir_all(&tmp_dir);
+/// Extract a `.tar.gz` stream into `dir`.
+fn unpack(archive: impl Read, dir: &Path) -> anyhow::Result<()> {
+    tar::Archive::new(flate2::read::GzDecoder::new(archive)).unpack(dir)?;
     Ok(())
 }
 
@@ -193,3 +333,84 @@ fn detect_target() -> String {
 
     format!("{}-{}", arch, os)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tag() {
+        let body = r#"{"url": "x", "tag_name": "v1.2.3", "name": "1.2.3"}"#;
+        assert_eq!(parse_tag(body).as_deref(), Some("v1.2.3"));
+        assert_eq!(parse_tag(r#"{"message": "Not Found"}"#), None);
+    }
+
+    #[test]
+    fn test_newest_tag_includes_prereleases() {
+        let body = r#"[
+            {"tag_name": "v0.3.0", "draft": true, "prerelease": false},
+            {"tag_name": "v0.2.0-rc.1", "draft": false, "prerelease": true},
+            {"tag_name": "v0.2.0-beta.2", "draft": false, "prerelease": true},
+            {"tag_name": "nightly", "draft": false, "prerelease": true},
+            {"tag_name": "v0.1.1", "draft": false, "prerelease": false}
+        ]"#;
+        assert_eq!(newest_tag(body).unwrap().as_deref(), Some("v0.2.0-rc.1"));
+        assert_eq!(newest_tag("[]").unwrap(), None);
+        assert!(newest_tag(r#"{"message": "Not Found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_notes() {
+        let body = r###"{"tag_name": "v0.2.0", "body": "## Changes\r\n\r\n- Faster startup\r\n"}"###;
+        assert_eq!(parse_notes(body).unwrap(), "## Changes\n\n- Faster startup");
+        let body = r#"{"tag_name": "v0.2.0", "body": null}"#;
+        assert_eq!(parse_notes(body).unwrap(), "No release notes for v0.2.0.");
+        assert!(parse_notes(r#"{"message": "Not Found"}"#).is_err());
+    }
+
+    #[test]
+    fn test_unpack_release_archive() {
+        let mut builder = tar::Builder::new(flate2::write::GzEncoder::new(
+            Vec::new(),
+            flate2::Compression::default(),
+        ));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, "gana", &b"\x7fELF"[..]).unwrap();
+        let archive = builder.into_inner().unwrap().finish().unwrap();
+
+        let dir = tempfile::TempDir::new().unwrap();
+        unpack(&archive[..], dir.path()).unwrap();
+        assert_eq!(fs::read(dir.path().join("gana")).unwrap(), b"\x7fELF");
+
+        assert!(unpack(&b"not a tarball"[..], dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_take_failure_once() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(take_failure(dir.path()), None);
+
+        fs::write(dir.path().join(FAILED_FILE), "could not fetch the latest release\n").unwrap();
+        assert_eq!(
+            take_failure(dir.path()).as_deref(),
+            Some("could not fetch the latest release")
+        );
+        assert_eq!(take_failure(dir.path()), None);
+    }
+
+    #[test]
+    fn test_available_version_ignores_stale_notes() {
+        let dir = tempfile::TempDir::new().unwrap();
+        assert_eq!(available_version(dir.path()), None);
+
+        fs::write(dir.path().join(AVAILABLE_FILE), "999.0.0\n").unwrap();
+        assert_eq!(available_version(dir.path()).as_deref(), Some("999.0.0"));
+
+        // Already installed (e.g. by a package manager)
+        fs::write(dir.path().join(AVAILABLE_FILE), CURRENT_VERSION).unwrap();
+        assert_eq!(available_version(dir.path()), None);
+    }
+}
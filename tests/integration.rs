@@ -21,7 +21,7 @@ fn test_version_flag() {
         .arg("--version")
         .assert()
         .success()
-        .stdout(predicate::str::contains("gana 0.1.0"));
+        .stdout(predicate::str::contains(format!("gana {}", env!("CARGO_PKG_VERSION"))));
 }
 
 #[test]
@@ -98,5 +98,32 @@ fn test_daemon_subcommand_help() {
         .args(["daemon", "--help"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("config-dir"));
+        .stdout(
+            predicate::str::contains("config-dir")
+                .and(predicate::str::contains("install")),
+        );
+}
+
+#[test]
+fn test_new_subcommand_help() {
+    gana()
+        .args(["new", "--help"])
+        .assert()
+        .success()
+        .stdout(
+            predicate::str::contains("from-issue")
+                .and(predicate::str::contains("prompt-file"))
+                .and(predicate::str::contains("carry-changes"))
+                .and(predicate::str::contains("--branch")),
+        );
+}
+
+#[test]
+fn test_new_requires_title_or_issue() {
+    gana().arg("new").assert().failure();
+}
+
+#[test]
+fn test_statusline_succeeds() {
+    gana().arg("statusline").assert().success();
 }